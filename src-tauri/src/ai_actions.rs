@@ -0,0 +1,114 @@
+// 自定义AI操作
+//
+// 每个操作由设置中的 ai_actions 定义：提示词模板、模型、温度和输出方式。
+// 操作使用当前AI配置（或选中的配置档案）的服务提供方，模型和温度可按操作覆盖。
+
+use crate::ai_config::AIConfig;
+use crate::ai_provider::ChatMessage;
+use crate::error::{AppError, AppResult};
+use crate::settings::{AIAction, AppSettings};
+
+// 操作结果的输出方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    // 写入剪贴板（不添加历史记录）
+    ReplaceClipboard,
+    // 写入剪贴板并粘贴到当前窗口
+    Paste,
+    // 流式模拟键盘输入
+    Type,
+    // 保存为新的历史记录
+    Save,
+}
+
+impl OutputMode {
+    // 由设置中的名称解析，无法识别时写入剪贴板
+    pub fn from_setting(name: &str) -> Self {
+        match name {
+            "paste" => OutputMode::Paste,
+            "type" => OutputMode::Type,
+            "save" => OutputMode::Save,
+            _ => OutputMode::ReplaceClipboard,
+        }
+    }
+}
+
+// 按 id 查找操作
+pub fn find_action(settings: &AppSettings, id: &str) -> AppResult<AIAction> {
+    settings
+        .ai_actions
+        .iter()
+        .find(|action| action.id == id)
+        .cloned()
+        .ok_or_else(|| {
+            AppError::not_found(format!("未知的AI操作: {}", id))
+                .with_key("error.ai_action.not_found")
+                .with_context("id", id)
+        })
+}
+
+// 填充提示词模板，生成发送给模型的消息
+pub fn build_messages(action: &AIAction, text: &str) -> Vec<ChatMessage> {
+    let content = if action.prompt_template.contains("{text}") {
+        action.prompt_template.replace("{text}", text)
+    } else {
+        format!("{}\n\n{}", action.prompt_template, text)
+    };
+    vec![ChatMessage::user(content)]
+}
+
+// 以当前AI配置为基础，应用操作中的模型和温度
+pub fn ai_config_for_action(settings: &AppSettings, action: &AIAction) -> AIConfig {
+    let mut config = crate::ai_config::create_ai_config_from_settings(settings);
+    if !action.model.trim().is_empty() {
+        config.model = action.model.trim().to_string();
+    }
+    config.temperature = action.temperature.clamp(0.0, 2.0);
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(prompt: &str) -> AIAction {
+        AIAction {
+            id: "test".to_string(),
+            name: "测试".to_string(),
+            prompt_template: prompt.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fills_prompt_template() {
+        let messages = build_messages(&action("总结：{text}。"), "内容");
+        assert_eq!(messages[0].content, "总结：内容。");
+
+        let messages = build_messages(&action("请总结"), "内容");
+        assert_eq!(messages[0].content, "请总结\n\n内容");
+    }
+
+    #[test]
+    fn overrides_model_and_temperature() {
+        let settings = AppSettings::default();
+        let mut action = action("{text}");
+        action.temperature = 5.0;
+
+        let config = ai_config_for_action(&settings, &action);
+        assert_eq!(config.model, settings.ai_model);
+        assert_eq!(config.temperature, 2.0);
+
+        action.model = " gpt-4o-mini ".to_string();
+        assert_eq!(ai_config_for_action(&settings, &action).model, "gpt-4o-mini");
+    }
+
+    #[test]
+    fn finds_builtin_actions() {
+        let settings = AppSettings::default();
+        assert!(find_action(&settings, "summarize").is_ok());
+        assert!(find_action(&settings, "missing").is_err());
+        assert_eq!(OutputMode::from_setting("type"), OutputMode::Type);
+        assert_eq!(OutputMode::from_setting(""), OutputMode::ReplaceClipboard);
+    }
+}
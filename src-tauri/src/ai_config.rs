@@ -3,8 +3,16 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+use crate::ai_provider::AIProviderKind;
+use crate::ai_request::RequestExecutor;
+
+// 获取模型列表时最多请求的页数
+const MAX_MODEL_PAGES: usize = 20;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIConfig {
+    // 服务提供方，决定接口格式和认证方式
+    pub provider: AIProviderKind,
     // API密钥，用于身份认证
     pub api_key: String,
     // AI模型名称
@@ -17,17 +25,27 @@ pub struct AIConfig {
     pub temperature: f32,
     // 最大输出token数量
     pub max_tokens: u32,
+    // 请求失败（网络错误、限流、服务端错误）后的最大重试次数
+    pub max_retries: u32,
+    // 同时进行的最大请求数
+    pub max_concurrent_requests: u32,
+    // 每分钟的token预算（按提供方统计），0 表示不限制
+    pub token_budget_per_minute: u32,
 }
 
 impl Default for AIConfig {
     fn default() -> Self {
         Self {
+            provider: AIProviderKind::OpenAi,
             api_key: String::new(),
             model: "Qwen/Qwen2-7B-Instruct".to_string(),
             base_url: "https://api.siliconflow.cn/v1".to_string(),
             timeout_secs: 120,
             temperature: 0.3,
             max_tokens: 2048,
+            max_retries: 3,
+            max_concurrent_requests: 2,
+            token_budget_per_minute: 0,
         }
     }
 }
@@ -43,9 +61,9 @@ impl AIConfig {
         }
     }
 
-    // 验证配置是否有效
+    // 验证配置是否有效（本地服务等不需要密钥的提供方允许密钥为空）
     pub fn is_valid(&self) -> bool {
-        !self.api_key.trim().is_empty()
+        (!self.provider.provider().requires_api_key() || !self.api_key.trim().is_empty())
             && !self.model.trim().is_empty()
             && !self.base_url.trim().is_empty()
             && self.timeout_secs > 0
@@ -58,38 +76,12 @@ impl AIConfig {
     pub fn timeout(&self) -> Duration {
         Duration::from_secs(self.timeout_secs)
     }
-
-    // 获取API完整URL
-    pub fn get_chat_completions_url(&self) -> String {
-        format!("{}/chat/completions", self.base_url.trim_end_matches('/'))
-    }
-
-    // 获取模型列表URL
-    pub fn get_models_url(&self) -> String {
-        format!("{}/models", self.base_url.trim_end_matches('/'))
-    }
-}
-
-// AI模型信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AIModel {
-    pub id: String,
-    pub object: String,
-    pub created: Option<u64>,
-    pub owned_by: Option<String>,
-}
-
-// 模型列表响应
-#[derive(Debug, Deserialize)]
-pub struct ModelsResponse {
-    pub data: Vec<AIModel>,
-    pub object: String,
 }
 
 // AI配置管理器
 pub struct AIConfigManager {
     config: AIConfig,
-    client: Client,
+    executor: RequestExecutor,
 }
 
 impl AIConfigManager {
@@ -104,38 +96,38 @@ impl AIConfigManager {
             .build()
             .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
 
-        Ok(Self { config, client })
+        let executor = RequestExecutor::new(client, &config);
+        Ok(Self { config, executor })
     }
 
-    // 获取可用模型列表
+    // 获取可用模型列表（请求和响应格式由提供方决定，分页的接口逐页获取）
     pub async fn get_available_models(&self) -> Result<Vec<String>, String> {
-        let url = self.config.get_models_url();
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| format!("API请求失败: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API请求失败，状态码: {}", response.status()));
+        let provider = self.config.provider.provider();
+
+        let mut models = Vec::new();
+        let mut page_token: Option<String> = None;
+        for _ in 0..MAX_MODEL_PAGES {
+            let response = self
+                .executor
+                .send(|client| provider.models_request(client, &self.config, page_token.as_deref()), 0)
+                .await
+                .map_err(|e| format!("API请求失败: {}", e))?
+                .response;
+
+            let body: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| format!("解析响应失败: {}", e))?;
+
+            models.extend(provider.parse_models(&body)?);
+            // 翻页标记没有变化时停止，避免服务端异常时反复请求同一页
+            match provider.next_models_page(&body) {
+                Some(next) if page_token.as_deref() != Some(next.as_str()) => page_token = Some(next),
+                _ => break,
+            }
         }
 
-        let models_response: ModelsResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("解析响应失败: {}", e))?;
-
-        let model_ids: Vec<String> = models_response
-            .data
-            .into_iter()
-            .map(|model| model.id)
-            .collect();
-
-        Ok(model_ids)
+        Ok(models)
     }
 
     // 测试配置是否可用
@@ -146,15 +138,34 @@ impl AIConfigManager {
     }
 }
 
-// 从应用设置创建AI配置
+// 从应用设置创建AI配置：选中了有效的配置档案时使用档案，否则使用基础AI设置
 pub fn create_ai_config_from_settings(settings: &crate::settings::AppSettings) -> AIConfig {
+    let active_profile = settings
+        .ai_profiles
+        .iter()
+        .find(|profile| !settings.ai_active_profile.is_empty() && profile.id == settings.ai_active_profile);
+
+    let (provider, api_key, model, base_url) = match active_profile {
+        Some(profile) => (&profile.provider, &profile.api_key, &profile.model, &profile.base_url),
+        None => (
+            &settings.ai_provider,
+            &settings.ai_api_key,
+            &settings.ai_model,
+            &settings.ai_base_url,
+        ),
+    };
+
     AIConfig {
-        api_key: settings.ai_api_key.clone(),
-        model: settings.ai_model.clone(),
-        base_url: settings.ai_base_url.clone(),
+        provider: AIProviderKind::from_setting(provider),
+        api_key: api_key.clone(),
+        model: model.clone(),
+        base_url: base_url.clone(),
         timeout_secs: 120,
         temperature: 0.3,
         max_tokens: 2048,
+        max_retries: settings.ai_max_retries,
+        max_concurrent_requests: settings.ai_max_concurrent_requests,
+        token_budget_per_minute: settings.ai_token_budget_per_minute,
     }
 }
 
@@ -0,0 +1,102 @@
+// Anthropic Messages 接口（/messages + x-api-key），system 消息放在请求体顶层
+
+use reqwest::{Client, RequestBuilder};
+
+use super::{collect_strings, endpoint, sse_data, AIProvider, ChatMessage, StreamEvent};
+use crate::ai_config::AIConfig;
+
+const API_VERSION: &str = "2023-06-01";
+// 模型列表每页的最大数量
+const MODELS_PAGE_SIZE: u32 = 1000;
+
+pub struct AnthropicProvider;
+
+impl AnthropicProvider {
+    fn with_auth(request: RequestBuilder, config: &AIConfig) -> RequestBuilder {
+        request
+            .header("x-api-key", &config.api_key)
+            .header("anthropic-version", API_VERSION)
+            .header("Content-Type", "application/json")
+    }
+}
+
+impl AIProvider for AnthropicProvider {
+    fn chat_request(
+        &self,
+        client: &Client,
+        config: &AIConfig,
+        messages: &[ChatMessage],
+    ) -> RequestBuilder {
+        let system: Vec<&str> = messages
+            .iter()
+            .filter(|m| m.role == "system")
+            .map(|m| m.content.as_str())
+            .collect();
+        let conversation: Vec<&ChatMessage> =
+            messages.iter().filter(|m| m.role != "system").collect();
+
+        let mut body = serde_json::json!({
+            "model": config.model,
+            "messages": conversation,
+            "stream": true,
+            "temperature": config.temperature,
+            "max_tokens": config.max_tokens,
+        });
+        if !system.is_empty() {
+            body["system"] = serde_json::Value::String(system.join("\n\n"));
+        }
+
+        Self::with_auth(client.post(endpoint(config, "messages")), config).json(&body)
+    }
+
+    // 只关心 data 行：content_block_delta 携带文本，message_stop 表示结束
+    fn parse_stream_line(&self, line: &str) -> Result<StreamEvent, String> {
+        let data = match sse_data(line) {
+            Some(data) => data,
+            None => return Ok(StreamEvent::Ignore),
+        };
+
+        let event: serde_json::Value =
+            serde_json::from_str(data).map_err(|e| format!("解析流式响应失败: {}", e))?;
+        match event.get("type").and_then(|v| v.as_str()) {
+            Some("content_block_delta") => {
+                let text = event
+                    .pointer("/delta/text")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                Ok(if text.is_empty() {
+                    StreamEvent::Ignore
+                } else {
+                    StreamEvent::Chunk(text.to_string())
+                })
+            }
+            Some("message_stop") => Ok(StreamEvent::Done),
+            Some("error") => Err(event
+                .pointer("/error/message")
+                .and_then(|v| v.as_str())
+                .unwrap_or("服务返回错误")
+                .to_string()),
+            _ => Ok(StreamEvent::Ignore),
+        }
+    }
+
+    // 每页取最大数量，仍有更多时按上一页最后一个模型的 id 继续
+    fn models_request(&self, client: &Client, config: &AIConfig, page_token: Option<&str>) -> RequestBuilder {
+        let mut request = client.get(endpoint(config, "models")).query(&[("limit", MODELS_PAGE_SIZE)]);
+        if let Some(after_id) = page_token {
+            request = request.query(&[("after_id", after_id)]);
+        }
+        Self::with_auth(request, config)
+    }
+
+    fn parse_models(&self, body: &serde_json::Value) -> Result<Vec<String>, String> {
+        collect_strings(body, "data", "id")
+    }
+
+    fn next_models_page(&self, body: &serde_json::Value) -> Option<String> {
+        if !body.get("has_more").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return None;
+        }
+        body.get("last_id").and_then(|v| v.as_str()).map(|s| s.to_string())
+    }
+}
@@ -0,0 +1,130 @@
+// Gemini 接口（models/{model}:streamGenerateContent + x-goog-api-key），以 SSE 方式流式返回
+
+use reqwest::{Client, RequestBuilder};
+
+use super::{endpoint, sse_data, AIProvider, ChatMessage, StreamEvent};
+use crate::ai_config::AIConfig;
+
+// 模型列表每页的最大数量
+const MODELS_PAGE_SIZE: u32 = 1000;
+
+pub struct GeminiProvider;
+
+impl GeminiProvider {
+    fn with_auth(request: RequestBuilder, config: &AIConfig) -> RequestBuilder {
+        request
+            .header("x-goog-api-key", &config.api_key)
+            .header("Content-Type", "application/json")
+    }
+}
+
+impl AIProvider for GeminiProvider {
+    fn chat_request(
+        &self,
+        client: &Client,
+        config: &AIConfig,
+        messages: &[ChatMessage],
+    ) -> RequestBuilder {
+        let system: Vec<&str> = messages
+            .iter()
+            .filter(|m| m.role == "system")
+            .map(|m| m.content.as_str())
+            .collect();
+        // Gemini 中助手角色名为 model
+        let contents: Vec<serde_json::Value> = messages
+            .iter()
+            .filter(|m| m.role != "system")
+            .map(|m| {
+                serde_json::json!({
+                    "role": if m.role == "assistant" { "model" } else { "user" },
+                    "parts": [{ "text": m.content }],
+                })
+            })
+            .collect();
+
+        let mut body = serde_json::json!({
+            "contents": contents,
+            "generationConfig": {
+                "temperature": config.temperature,
+                "maxOutputTokens": config.max_tokens,
+            },
+        });
+        if !system.is_empty() {
+            body["systemInstruction"] = serde_json::json!({
+                "parts": [{ "text": system.join("\n\n") }],
+            });
+        }
+
+        let url = endpoint(
+            config,
+            &format!("models/{}:streamGenerateContent?alt=sse", config.model),
+        );
+        Self::with_auth(client.post(url), config).json(&body)
+    }
+
+    // 每个 data 行是一个完整的 GenerateContentResponse，连接关闭即表示结束
+    fn parse_stream_line(&self, line: &str) -> Result<StreamEvent, String> {
+        let data = match sse_data(line) {
+            Some(data) => data,
+            None => return Ok(StreamEvent::Ignore),
+        };
+
+        let event: serde_json::Value =
+            serde_json::from_str(data).map_err(|e| format!("解析流式响应失败: {}", e))?;
+        if let Some(message) = event.pointer("/error/message").and_then(|v| v.as_str()) {
+            return Err(message.to_string());
+        }
+
+        let text: String = event
+            .pointer("/candidates/0/content/parts")
+            .and_then(|v| v.as_array())
+            .map(|parts| {
+                parts
+                    .iter()
+                    .filter_map(|part| part.get("text").and_then(|v| v.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(if text.is_empty() {
+            StreamEvent::Ignore
+        } else {
+            StreamEvent::Chunk(text)
+        })
+    }
+
+    // 每页取最大数量，仍有更多时带上 nextPageToken 继续
+    fn models_request(&self, client: &Client, config: &AIConfig, page_token: Option<&str>) -> RequestBuilder {
+        let mut request = client.get(endpoint(config, "models")).query(&[("pageSize", MODELS_PAGE_SIZE)]);
+        if let Some(token) = page_token {
+            request = request.query(&[("pageToken", token)]);
+        }
+        Self::with_auth(request, config)
+    }
+
+    // 只保留支持 generateContent 的模型，名称去掉 "models/" 前缀
+    fn parse_models(&self, body: &serde_json::Value) -> Result<Vec<String>, String> {
+        let models = body
+            .get("models")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| "模型列表响应缺少 models 字段".to_string())?;
+        Ok(models
+            .iter()
+            .filter(|model| {
+                model
+                    .get("supportedGenerationMethods")
+                    .and_then(|v| v.as_array())
+                    .map(|methods| methods.iter().any(|m| m.as_str() == Some("generateContent")))
+                    .unwrap_or(true)
+            })
+            .filter_map(|model| model.get("name").and_then(|v| v.as_str()))
+            .map(|name| name.trim_start_matches("models/").to_string())
+            .collect())
+    }
+
+    fn next_models_page(&self, body: &serde_json::Value) -> Option<String> {
+        body.get("nextPageToken")
+            .and_then(|v| v.as_str())
+            .filter(|token| !token.is_empty())
+            .map(|s| s.to_string())
+    }
+}
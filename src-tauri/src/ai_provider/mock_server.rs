@@ -0,0 +1,111 @@
+// 测试用的本地 HTTP 模拟服务（提供方和请求执行器的测试共用）
+//
+// 按顺序对每个连接返回一个预设响应，并把收到的请求交给测试检查
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+
+// 预设响应：declared_length 大于正文长度时，发送完正文就断开连接
+pub struct MockResponse {
+    status: &'static str,
+    headers: &'static str,
+    content_type: &'static str,
+    body: String,
+    declared_length: Option<usize>,
+}
+
+impl MockResponse {
+    pub fn new(status: &'static str, content_type: &'static str, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: "",
+            content_type,
+            body: body.into(),
+            declared_length: None,
+        }
+    }
+
+    pub fn ok(content_type: &'static str, body: impl Into<String>) -> Self {
+        Self::new("200 OK", content_type, body)
+    }
+
+    // 额外的响应头，每行以 \r\n 结尾
+    pub fn with_headers(mut self, headers: &'static str) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    // 声明的长度比正文长，模拟中途断开的连接
+    pub fn truncated(mut self, declared_length: usize) -> Self {
+        self.declared_length = Some(declared_length);
+        self
+    }
+}
+
+// 收到的请求：请求行、请求头（小写名称）和请求体
+pub struct Captured {
+    pub request_line: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Captured {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// 启动模拟服务，返回服务地址和收到的请求
+pub fn serve(responses: Vec<MockResponse>) -> (String, mpsc::Receiver<Captured>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for response in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut headers = Vec::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((k, v)) = line.split_once(':') {
+                    let k = k.trim().to_ascii_lowercase();
+                    if k == "content-length" {
+                        length = v.trim().parse().unwrap();
+                    }
+                    headers.push((k, v.trim().to_string()));
+                }
+            }
+            let mut buf = vec![0; length];
+            reader.read_exact(&mut buf).unwrap();
+            let _ = tx.send(Captured {
+                request_line: request_line.trim_end().to_string(),
+                headers,
+                body: String::from_utf8_lossy(&buf).to_string(),
+            });
+
+            let mut stream = stream;
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\n{}Content-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.status,
+                response.headers,
+                response.content_type,
+                response.declared_length.unwrap_or(response.body.len()),
+                response.body
+            );
+        }
+    });
+    (format!("http://{}", addr), rx)
+}
@@ -0,0 +1,302 @@
+// AI 服务提供方
+//
+// 不同服务的接口差异（地址、认证头、请求体、流式格式、模型列表）由 AIProvider 实现，
+// 翻译器和配置管理器只负责发送请求、检查状态码并逐行交给提供方解析
+
+mod anthropic;
+mod gemini;
+mod ollama;
+mod openai;
+
+#[cfg(test)]
+pub(crate) mod mock_server;
+
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::ai_config::AIConfig;
+
+// 提供方类型（序列化后的取值保存在设置中）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AIProviderKind {
+    #[default]
+    OpenAi,
+    Anthropic,
+    Ollama,
+    Gemini,
+}
+
+impl AIProviderKind {
+    // 由设置中的字符串解析，无法识别时按 OpenAI 兼容接口处理
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "anthropic" => AIProviderKind::Anthropic,
+            "ollama" => AIProviderKind::Ollama,
+            "gemini" => AIProviderKind::Gemini,
+            _ => AIProviderKind::OpenAi,
+        }
+    }
+
+    // 获取对应的提供方实现
+    pub fn provider(&self) -> &'static dyn AIProvider {
+        match self {
+            AIProviderKind::OpenAi => &openai::OpenAiProvider,
+            AIProviderKind::Anthropic => &anthropic::AnthropicProvider,
+            AIProviderKind::Ollama => &ollama::OllamaProvider,
+            AIProviderKind::Gemini => &gemini::GeminiProvider,
+        }
+    }
+}
+
+// 对话消息（role 为 system / user / assistant）
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+// 流式响应中一行的解析结果
+#[derive(Debug, PartialEq)]
+pub enum StreamEvent {
+    // 新增的文本片段
+    Chunk(String),
+    // 服务端表示输出结束
+    Done,
+    // 与输出无关的行（空行、事件名、心跳等）
+    Ignore,
+}
+
+pub trait AIProvider: Send + Sync {
+    // 是否必须配置 API 密钥
+    fn requires_api_key(&self) -> bool {
+        true
+    }
+
+    // 构造流式对话请求（地址、认证头和请求体）
+    fn chat_request(
+        &self,
+        client: &Client,
+        config: &AIConfig,
+        messages: &[ChatMessage],
+    ) -> RequestBuilder;
+
+    // 解析流式响应的一行，服务端在流中返回的错误以 Err 返回
+    fn parse_stream_line(&self, line: &str) -> Result<StreamEvent, String>;
+
+    // 构造模型列表请求（page_token 为上一页响应中的翻页标记，首页为 None）
+    fn models_request(&self, client: &Client, config: &AIConfig, page_token: Option<&str>) -> RequestBuilder;
+
+    // 从模型列表响应中提取模型名称
+    fn parse_models(&self, body: &serde_json::Value) -> Result<Vec<String>, String>;
+
+    // 模型列表分页时返回下一页的翻页标记，没有更多时返回 None
+    fn next_models_page(&self, _body: &serde_json::Value) -> Option<String> {
+        None
+    }
+}
+
+// 拼接基础地址和路径
+fn endpoint(config: &AIConfig, path: &str) -> String {
+    format!("{}/{}", config.base_url.trim_end_matches('/'), path)
+}
+
+// 取出 SSE 的 data 字段，非 data 行返回 None
+fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(|data| data.trim_start())
+}
+
+// 从 JSON 数组中按字段取出字符串列表
+fn collect_strings(body: &serde_json::Value, list: &str, field: &str) -> Result<Vec<String>, String> {
+    let items = body
+        .get(list)
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| format!("模型列表响应缺少 {} 字段", list))?;
+    Ok(items
+        .iter()
+        .filter_map(|item| item.get(field).and_then(|v| v.as_str()))
+        .map(|s| s.to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_translator::{AITranslator, TranslationConfig};
+    use mock_server::MockResponse;
+
+    fn config(provider: AIProviderKind, base_url: String) -> AIConfig {
+        AIConfig {
+            provider,
+            api_key: "test-key".to_string(),
+            model: "test-model".to_string(),
+            base_url,
+            ..Default::default()
+        }
+    }
+
+    async fn translate(config: AIConfig) -> String {
+        let translator = AITranslator::new(TranslationConfig {
+            ai_config: config,
+            target_language: "en".to_string(),
+            prompt_template: "翻译成{target_language}：".to_string(),
+        })
+        .unwrap();
+        translator.translate("你好").await.unwrap()
+    }
+
+    async fn models(config: AIConfig) -> Vec<String> {
+        crate::ai_config::AIConfigManager::new(config)
+            .unwrap()
+            .get_available_models()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn openai_compatible() {
+        let stream = "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n\
+                      data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n\
+                      data: [DONE]\n\n";
+        let (url, rx) = mock_server::serve(vec![
+            MockResponse::ok("text/event-stream", stream),
+            MockResponse::ok("application/json", r#"{"object":"list","data":[{"id":"gpt-a"},{"id":"gpt-b"}]}"#),
+        ]);
+        let config = config(AIProviderKind::OpenAi, url);
+
+        assert_eq!(translate(config.clone()).await, "Hello");
+        let request = rx.recv().unwrap();
+        assert_eq!(request.request_line, "POST /chat/completions HTTP/1.1");
+        assert_eq!(request.header("authorization"), Some("Bearer test-key"));
+        assert!(request.body.contains("\"stream\":true"));
+
+        assert_eq!(models(config).await, vec!["gpt-a", "gpt-b"]);
+        assert_eq!(rx.recv().unwrap().request_line, "GET /models HTTP/1.1");
+    }
+
+    #[tokio::test]
+    async fn anthropic_messages() {
+        let stream = "event: message_start\n\
+                      data: {\"type\":\"message_start\",\"message\":{}}\n\n\
+                      event: content_block_delta\n\
+                      data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hel\"}}\n\n\
+                      event: content_block_delta\n\
+                      data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"lo\"}}\n\n\
+                      event: message_stop\n\
+                      data: {\"type\":\"message_stop\"}\n\n";
+        let (url, rx) = mock_server::serve(vec![
+            MockResponse::ok("text/event-stream", stream),
+            MockResponse::ok(
+                "application/json",
+                r#"{"data":[{"id":"claude-a","type":"model"}],"has_more":true,"first_id":"claude-a","last_id":"claude-a"}"#,
+            ),
+            MockResponse::ok("application/json", r#"{"data":[{"id":"claude-b","type":"model"}],"has_more":false}"#),
+        ]);
+        let config = config(AIProviderKind::Anthropic, url);
+
+        assert_eq!(translate(config.clone()).await, "Hello");
+        let request = rx.recv().unwrap();
+        assert_eq!(request.request_line, "POST /messages HTTP/1.1");
+        assert_eq!(request.header("x-api-key"), Some("test-key"));
+        assert!(request.header("anthropic-version").is_some());
+        assert!(request.header("authorization").is_none());
+
+        assert_eq!(models(config).await, vec!["claude-a", "claude-b"]);
+        let first_page = rx.recv().unwrap();
+        assert_eq!(first_page.request_line, "GET /models?limit=1000 HTTP/1.1");
+        assert_eq!(first_page.header("x-api-key"), Some("test-key"));
+        assert_eq!(
+            rx.recv().unwrap().request_line,
+            "GET /models?limit=1000&after_id=claude-a HTTP/1.1"
+        );
+    }
+
+    #[tokio::test]
+    async fn ollama_native() {
+        let stream = "{\"message\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"done\":false}\n\
+                      {\"message\":{\"role\":\"assistant\",\"content\":\"lo\"},\"done\":false}\n\
+                      {\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true}\n";
+        let (url, rx) = mock_server::serve(vec![
+            MockResponse::ok("application/x-ndjson", stream),
+            MockResponse::ok("application/json", r#"{"models":[{"name":"llama3:8b"},{"name":"qwen2:7b"}]}"#),
+        ]);
+        // Ollama 不需要 API 密钥
+        let mut config = config(AIProviderKind::Ollama, url);
+        config.api_key.clear();
+        assert!(config.is_valid());
+
+        assert_eq!(translate(config.clone()).await, "Hello");
+        let request = rx.recv().unwrap();
+        assert_eq!(request.request_line, "POST /api/chat HTTP/1.1");
+        assert!(request.header("authorization").is_none());
+
+        assert_eq!(models(config).await, vec!["llama3:8b", "qwen2:7b"]);
+        assert_eq!(rx.recv().unwrap().request_line, "GET /api/tags HTTP/1.1");
+    }
+
+    #[tokio::test]
+    async fn gemini_generate_content() {
+        let stream = "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Hel\"}],\"role\":\"model\"}}]}\r\n\r\n\
+                      data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"lo\"}],\"role\":\"model\"},\"finishReason\":\"STOP\"}]}\r\n\r\n";
+        let (url, rx) = mock_server::serve(vec![
+            MockResponse::ok("text/event-stream", stream),
+            MockResponse::ok(
+                "application/json",
+                r#"{"models":[{"name":"models/gemini-pro","supportedGenerationMethods":["generateContent"]},{"name":"models/embedding-001","supportedGenerationMethods":["embedContent"]}],"nextPageToken":"page-2"}"#,
+            ),
+            MockResponse::ok(
+                "application/json",
+                r#"{"models":[{"name":"models/gemini-flash","supportedGenerationMethods":["generateContent"]}]}"#,
+            ),
+        ]);
+        let config = config(AIProviderKind::Gemini, url);
+
+        assert_eq!(translate(config.clone()).await, "Hello");
+        let request = rx.recv().unwrap();
+        assert_eq!(
+            request.request_line,
+            "POST /models/test-model:streamGenerateContent?alt=sse HTTP/1.1"
+        );
+        assert_eq!(request.header("x-goog-api-key"), Some("test-key"));
+
+        assert_eq!(models(config).await, vec!["gemini-pro", "gemini-flash"]);
+        assert_eq!(rx.recv().unwrap().request_line, "GET /models?pageSize=1000 HTTP/1.1");
+        assert_eq!(
+            rx.recv().unwrap().request_line,
+            "GET /models?pageSize=1000&pageToken=page-2 HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn stream_errors_are_reported() {
+        let anthropic = AIProviderKind::Anthropic.provider();
+        assert!(anthropic
+            .parse_stream_line(r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#)
+            .is_err());
+        let ollama = AIProviderKind::Ollama.provider();
+        assert!(ollama.parse_stream_line(r#"{"error":"model not found"}"#).is_err());
+        assert_eq!(ollama.parse_stream_line("").unwrap(), StreamEvent::Ignore);
+    }
+
+    #[test]
+    fn parses_kind_names() {
+        assert_eq!(AIProviderKind::from_setting("Anthropic"), AIProviderKind::Anthropic);
+        assert_eq!(AIProviderKind::from_setting(""), AIProviderKind::OpenAi);
+    }
+}
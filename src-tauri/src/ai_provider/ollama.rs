@@ -0,0 +1,75 @@
+// Ollama 原生接口（/api/chat），流式响应为每行一个 JSON 对象；本地服务不需要密钥
+
+use reqwest::{Client, RequestBuilder};
+
+use super::{collect_strings, endpoint, AIProvider, ChatMessage, StreamEvent};
+use crate::ai_config::AIConfig;
+
+pub struct OllamaProvider;
+
+impl OllamaProvider {
+    // 配置了密钥时（如经过反向代理）仍以 Bearer 令牌发送
+    fn with_auth(request: RequestBuilder, config: &AIConfig) -> RequestBuilder {
+        if config.api_key.trim().is_empty() {
+            request
+        } else {
+            request.header("Authorization", format!("Bearer {}", config.api_key))
+        }
+    }
+}
+
+impl AIProvider for OllamaProvider {
+    fn requires_api_key(&self) -> bool {
+        false
+    }
+
+    fn chat_request(
+        &self,
+        client: &Client,
+        config: &AIConfig,
+        messages: &[ChatMessage],
+    ) -> RequestBuilder {
+        Self::with_auth(client.post(endpoint(config, "api/chat")), config).json(&serde_json::json!({
+            "model": config.model,
+            "messages": messages,
+            "stream": true,
+            "options": {
+                "temperature": config.temperature,
+                "num_predict": config.max_tokens,
+            },
+        }))
+    }
+
+    fn parse_stream_line(&self, line: &str) -> Result<StreamEvent, String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(StreamEvent::Ignore);
+        }
+
+        let event: serde_json::Value =
+            serde_json::from_str(line).map_err(|e| format!("解析流式响应失败: {}", e))?;
+        if let Some(error) = event.get("error").and_then(|v| v.as_str()) {
+            return Err(error.to_string());
+        }
+
+        let text = event
+            .pointer("/message/content")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        if !text.is_empty() {
+            return Ok(StreamEvent::Chunk(text.to_string()));
+        }
+        if event.get("done").and_then(|v| v.as_bool()) == Some(true) {
+            return Ok(StreamEvent::Done);
+        }
+        Ok(StreamEvent::Ignore)
+    }
+
+    fn models_request(&self, client: &Client, config: &AIConfig, _page_token: Option<&str>) -> RequestBuilder {
+        Self::with_auth(client.get(endpoint(config, "api/tags")), config)
+    }
+
+    fn parse_models(&self, body: &serde_json::Value) -> Result<Vec<String>, String> {
+        collect_strings(body, "models", "name")
+    }
+}
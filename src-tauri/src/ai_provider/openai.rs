@@ -0,0 +1,81 @@
+// OpenAI 兼容接口（/chat/completions + Bearer 令牌），也适用于硅基流动、DeepSeek 等服务
+
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+
+use super::{collect_strings, endpoint, sse_data, AIProvider, ChatMessage, StreamEvent};
+use crate::ai_config::AIConfig;
+
+pub struct OpenAiProvider;
+
+// 流式响应数据
+#[derive(Debug, Deserialize)]
+struct StreamResponse {
+    choices: Option<Vec<Choice>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    delta: Option<Delta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Delta {
+    content: Option<String>,
+}
+
+impl AIProvider for OpenAiProvider {
+    fn chat_request(
+        &self,
+        client: &Client,
+        config: &AIConfig,
+        messages: &[ChatMessage],
+    ) -> RequestBuilder {
+        client
+            .post(endpoint(config, "chat/completions"))
+            .header("Authorization", format!("Bearer {}", config.api_key))
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({
+                "model": config.model,
+                "messages": messages,
+                "stream": true,
+                "temperature": config.temperature,
+                "max_tokens": config.max_tokens,
+            }))
+    }
+
+    fn parse_stream_line(&self, line: &str) -> Result<StreamEvent, String> {
+        let data = match sse_data(line) {
+            Some(data) => data,
+            None => return Ok(StreamEvent::Ignore),
+        };
+        if data == "[DONE]" {
+            return Ok(StreamEvent::Done);
+        }
+
+        let response: StreamResponse =
+            serde_json::from_str(data).map_err(|e| format!("解析流式响应失败: {}", e))?;
+        let text: String = response
+            .choices
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|choice| choice.delta.and_then(|delta| delta.content))
+            .collect();
+        Ok(if text.is_empty() {
+            StreamEvent::Ignore
+        } else {
+            StreamEvent::Chunk(text)
+        })
+    }
+
+    fn models_request(&self, client: &Client, config: &AIConfig, _page_token: Option<&str>) -> RequestBuilder {
+        client
+            .get(endpoint(config, "models"))
+            .header("Authorization", format!("Bearer {}", config.api_key))
+            .header("Content-Type", "application/json")
+    }
+
+    fn parse_models(&self, body: &serde_json::Value) -> Result<Vec<String>, String> {
+        collect_strings(body, "data", "id")
+    }
+}
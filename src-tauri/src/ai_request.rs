@@ -0,0 +1,520 @@
+// AI 请求执行器
+//
+// 包装 reqwest 客户端，翻译器和配置管理器的请求都经由这里发送：
+// - 网络错误、429 和 5xx 按指数退避重试，服务端给出 Retry-After 时按其等待
+// - 限制同时进行的请求数（许可一直持有到响应读取完毕）
+// - 按提供方统计最近一分钟的 token 用量，超出预算时等待窗口释放后再发送
+// 流式输出中途断开后的续传由翻译器处理，等待时间同样由这里计算
+
+use once_cell::sync::Lazy;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::ai_config::AIConfig;
+use crate::ai_provider::AIProviderKind;
+
+// 第一次重试前的等待时间，之后每次翻倍
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+// Retry-After 超过该时长时不再等待，直接返回错误
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+// token 预算的统计窗口
+const BUDGET_WINDOW: Duration = Duration::from_secs(60);
+
+// 全局并发限制
+static CONCURRENCY: Lazy<ConcurrencyLimit> = Lazy::new(ConcurrencyLimit::new);
+
+// 各提供方最近一分钟的 token 用量
+static TOKEN_USAGE: Lazy<Mutex<HashMap<AIProviderKind, TokenWindow>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 请求失败原因
+#[derive(Debug)]
+pub enum RequestFailure {
+    Network(reqwest::Error),
+    Status {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+        body: String,
+    },
+}
+
+impl RequestFailure {
+    // 连接失败、超时、限流和服务端错误可以重试，认证失败等请求本身的问题不重试
+    fn is_retryable(&self) -> bool {
+        match self {
+            RequestFailure::Network(e) => e.is_connect() || e.is_timeout(),
+            RequestFailure::Status { status, .. } => matches!(status.as_u16(), 408 | 429 | 500 | 502 | 503 | 504 | 529),
+        }
+    }
+}
+
+impl std::fmt::Display for RequestFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestFailure::Network(e) => write!(f, "网络请求失败: {}", e),
+            RequestFailure::Status { status, body, .. } => write!(
+                f,
+                "HTTP {} {}: {}",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("Unknown"),
+                body
+            ),
+        }
+    }
+}
+
+// 发送成功的响应，并发许可随响应一起释放
+pub struct ExecutedResponse {
+    pub response: Response,
+    _permit: OwnedSemaphorePermit,
+}
+
+// 某个提供方最近一分钟的 token 用量
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    pub provider: AIProviderKind,
+    pub used_tokens: u32,
+}
+
+#[derive(Clone)]
+pub struct RequestExecutor {
+    client: Client,
+    provider: AIProviderKind,
+    max_retries: u32,
+    max_concurrent: usize,
+    token_budget: u32,
+}
+
+impl RequestExecutor {
+    pub fn new(client: Client, config: &AIConfig) -> Self {
+        Self {
+            client,
+            provider: config.provider,
+            max_retries: config.max_retries,
+            max_concurrent: config.max_concurrent_requests.max(1) as usize,
+            token_budget: config.token_budget_per_minute,
+        }
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    // 发送请求，可重试的失败按退避时间重试；build 每次重试都会重新构造请求
+    pub async fn send(
+        &self,
+        build: impl Fn(&Client) -> RequestBuilder,
+        estimated_tokens: u32,
+    ) -> Result<ExecutedResponse, RequestFailure> {
+        self.reserve_tokens(estimated_tokens).await;
+
+        let mut attempt = 0;
+        loop {
+            let permit = acquire_permit(self.max_concurrent).await;
+
+            let failure = match build(&self.client).send().await {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(ExecutedResponse {
+                        response,
+                        _permit: permit,
+                    });
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let retry_after = response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(parse_retry_after);
+                    // 尝试获取错误响应体以获得更详细的错误信息
+                    let body = response
+                        .text()
+                        .await
+                        .unwrap_or_else(|_| "无法读取错误响应".to_string());
+                    RequestFailure::Status {
+                        status,
+                        retry_after,
+                        body,
+                    }
+                }
+                Err(e) => RequestFailure::Network(e),
+            };
+            drop(permit);
+
+            let Some(delay) = self.retry_delay(&failure, attempt) else {
+                return Err(failure);
+            };
+            attempt += 1;
+            println!(
+                "AI请求失败（{}），{} 毫秒后进行第 {} 次重试",
+                failure,
+                delay.as_millis(),
+                attempt
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    // 第 attempt 次重试前的等待时间，不应再重试时返回 None
+    pub fn retry_delay(&self, failure: &RequestFailure, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_retries || !failure.is_retryable() {
+            return None;
+        }
+
+        match failure {
+            RequestFailure::Status {
+                retry_after: Some(retry_after),
+                ..
+            } => (*retry_after <= MAX_RETRY_AFTER).then_some(*retry_after),
+            _ => Some(backoff_delay(attempt)),
+        }
+    }
+
+    // 记录响应实际消耗的 token（按输出文本估算）
+    pub fn record_output(&self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let mut usage = TOKEN_USAGE.lock().unwrap_or_else(|e| e.into_inner());
+        usage
+            .entry(self.provider)
+            .or_default()
+            .record(estimate_tokens(text), Instant::now());
+    }
+
+    // 预留请求的 token，预算不足时等待最早的用量移出统计窗口
+    async fn reserve_tokens(&self, tokens: u32) {
+        loop {
+            let wait = {
+                let mut usage = TOKEN_USAGE.lock().unwrap_or_else(|e| e.into_inner());
+                usage
+                    .entry(self.provider)
+                    .or_default()
+                    .try_reserve(tokens, self.token_budget, Instant::now())
+            };
+
+            match wait {
+                None => return,
+                Some(wait) => {
+                    println!("{:?} 的 token 预算已用完，等待 {} 秒", self.provider, wait.as_secs());
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+    }
+}
+
+// 第 attempt 次重试的退避时间：基础间隔逐次翻倍，加上最多 25% 的随机抖动
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let delay = BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_DELAY);
+    (delay + delay.mul_f64(fastrand::f64() * 0.25)).min(MAX_DELAY)
+}
+
+// 解析 Retry-After：秒数或 HTTP 日期
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let millis = (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .num_milliseconds()
+        .max(0);
+    Some(Duration::from_millis(millis as u64))
+}
+
+// 估算文本的 token 数：ASCII 约 4 个字符一个 token，其他字符按每个一个 token 计算
+pub fn estimate_tokens(text: &str) -> u32 {
+    let (ascii, other) = text
+        .chars()
+        .fold((0u32, 0u32), |(ascii, other), c| {
+            if c.is_ascii() {
+                (ascii + 1, other)
+            } else {
+                (ascii, other + 1)
+            }
+        });
+    ascii.div_ceil(4) + other
+}
+
+// 各提供方最近一分钟的 token 用量
+pub fn token_usage() -> Vec<TokenUsage> {
+    let now = Instant::now();
+    let mut usage = TOKEN_USAGE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut result: Vec<TokenUsage> = usage
+        .iter_mut()
+        .map(|(provider, window)| {
+            window.prune(now);
+            TokenUsage {
+                provider: *provider,
+                used_tokens: window.used(),
+            }
+        })
+        .collect();
+    result.sort_by_key(|item| item.provider as u8);
+    result
+}
+
+async fn acquire_permit(limit: usize) -> OwnedSemaphorePermit {
+    CONCURRENCY.acquire(limit).await
+}
+
+// 并发上限：始终使用同一个信号量，上限变化时增减许可数量，
+// 这样修改上限前后发出的请求仍受同一个上限约束
+struct ConcurrencyLimit {
+    limit: Mutex<usize>,
+    semaphore: Arc<Semaphore>,
+}
+
+impl ConcurrencyLimit {
+    fn new() -> Self {
+        Self {
+            limit: Mutex::new(0),
+            semaphore: Arc::new(Semaphore::new(0)),
+        }
+    }
+
+    async fn acquire(&self, limit: usize) -> OwnedSemaphorePermit {
+        self.resize(limit);
+        self.semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("并发信号量不会被关闭")
+    }
+
+    // 调低上限时先收回空闲的许可，其余的等进行中的请求释放后再收回
+    fn resize(&self, limit: usize) {
+        let mut current = self.limit.lock().unwrap_or_else(|e| e.into_inner());
+        if limit > *current {
+            self.semaphore.add_permits(limit - *current);
+        } else if limit < *current {
+            let excess = *current - limit;
+            let pending = excess - self.semaphore.forget_permits(excess);
+            if pending > 0 {
+                let semaphore = self.semaphore.clone();
+                tokio::spawn(async move {
+                    if let Ok(permits) = semaphore.acquire_many_owned(pending as u32).await {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+        *current = limit;
+    }
+}
+
+// 滑动窗口内的 token 用量
+#[derive(Default)]
+struct TokenWindow {
+    entries: VecDeque<(Instant, u32)>,
+}
+
+impl TokenWindow {
+    fn prune(&mut self, now: Instant) {
+        while let Some((time, _)) = self.entries.front() {
+            if now.duration_since(*time) < BUDGET_WINDOW {
+                break;
+            }
+            self.entries.pop_front();
+        }
+    }
+
+    fn used(&self) -> u32 {
+        self.entries.iter().map(|(_, tokens)| tokens).sum()
+    }
+
+    fn record(&mut self, tokens: u32, now: Instant) {
+        self.entries.push_back((now, tokens));
+    }
+
+    // 预算足够时记录用量并返回 None，否则返回需要等待的时间
+    // 预算为 0 表示不限制；窗口为空时总是放行，避免单个超出预算的请求永远等待
+    fn try_reserve(&mut self, tokens: u32, budget: u32, now: Instant) -> Option<Duration> {
+        self.prune(now);
+        let used = self.used();
+        if budget == 0 || used == 0 || used.saturating_add(tokens) <= budget {
+            self.record(tokens, now);
+            return None;
+        }
+
+        self.entries
+            .front()
+            .map(|(time, _)| (*time + BUDGET_WINDOW).saturating_duration_since(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_provider::ChatMessage;
+    use crate::ai_translator::{AITranslator, TranslationConfig, TranslationError, TranslationResult};
+    use crate::ai_provider::mock_server::{self, MockResponse};
+
+    fn respond(status: &'static str, headers: &'static str, body: &str) -> MockResponse {
+        MockResponse::new(status, "text/event-stream", body).with_headers(headers)
+    }
+
+    fn sse(parts: &[&str]) -> String {
+        parts
+            .iter()
+            .map(|part| format!("data: {{\"choices\":[{{\"delta\":{{\"content\":\"{}\"}}}}]}}\n\n", part))
+            .collect()
+    }
+
+    fn translator(base_url: String, max_retries: u32) -> AITranslator {
+        AITranslator::new(TranslationConfig {
+            ai_config: AIConfig {
+                api_key: "test-key".to_string(),
+                model: "test-model".to_string(),
+                base_url,
+                max_retries,
+                ..Default::default()
+            },
+            target_language: "en".to_string(),
+            prompt_template: "翻译成{target_language}：".to_string(),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limits_and_server_errors() {
+        let done = format!("{}data: [DONE]\n\n", sse(&["Hel", "lo"]));
+        let (url, rx) = mock_server::serve(vec![
+            respond("429 Too Many Requests", "Retry-After: 0\r\n", "{\"error\":\"rate limited\"}"),
+            respond("503 Service Unavailable", "Retry-After: 0\r\n", "overloaded"),
+            respond("200 OK", "", &done),
+        ]);
+
+        assert_eq!(translator(url, 3).translate("你好").await.unwrap(), "Hello");
+        assert_eq!(rx.try_iter().count(), 3);
+
+        let (url, _rx) = mock_server::serve(vec![
+            respond("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+            respond("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+        ]);
+        assert!(matches!(
+            translator(url, 1).translate("你好").await,
+            Err(TranslationError::RateLimitError)
+        ));
+
+        // 认证失败不重试
+        let (url, rx) = mock_server::serve(vec![respond("401 Unauthorized", "", "")]);
+        assert!(matches!(
+            translator(url, 3).translate("你好").await,
+            Err(TranslationError::AuthenticationError)
+        ));
+        assert_eq!(rx.try_iter().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn resumes_interrupted_stream() {
+        let partial = sse(&["Hel"]);
+        let rest = format!("{}data: [DONE]\n\n", sse(&["lo"]));
+        let (url, rx) = mock_server::serve(vec![
+            respond("200 OK", "", &partial).truncated(partial.len() + 100),
+            respond("200 OK", "", &rest),
+        ]);
+
+        let mut receiver = translator(url, 2).chat_stream(vec![ChatMessage::user("你好")]);
+        let mut text = String::new();
+        while let Some(result) = receiver.recv().await {
+            match result {
+                TranslationResult::Chunk(chunk) => text.push_str(&chunk),
+                TranslationResult::Complete => break,
+                TranslationResult::Error(e) => panic!("{}", e),
+            }
+        }
+        assert_eq!(text, "Hello");
+
+        // 续传请求带上已经输出的内容
+        let messages = |request: mock_server::Captured| {
+            let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+            body["messages"].as_array().unwrap().clone()
+        };
+        assert_eq!(messages(rx.recv().unwrap()).len(), 1);
+        let resumed = messages(rx.recv().unwrap());
+        assert_eq!(resumed.len(), 3);
+        assert_eq!(resumed[1]["role"], "assistant");
+        assert_eq!(resumed[1]["content"], "Hel");
+    }
+
+    #[tokio::test]
+    async fn resizes_concurrency_limit_in_place() {
+        let concurrency = ConcurrencyLimit::new();
+        let first = concurrency.acquire(2).await;
+        let second = concurrency.acquire(2).await;
+        assert_eq!(concurrency.semaphore.available_permits(), 0);
+
+        // 两个请求仍在进行时调低上限，释放后只剩一个许可
+        concurrency.resize(1);
+        drop(first);
+        drop(second);
+        tokio::task::yield_now().await;
+        assert_eq!(concurrency.semaphore.available_permits(), 1);
+
+        let third = concurrency.acquire(3).await;
+        assert_eq!(concurrency.semaphore.available_permits(), 2);
+        drop(third);
+        assert_eq!(concurrency.semaphore.available_permits(), 3);
+    }
+
+    #[test]
+    fn computes_retry_delays() {
+        let config = AIConfig {
+            max_retries: 2,
+            ..Default::default()
+        };
+        let executor = RequestExecutor::new(Client::new(), &config);
+        let status = |code: u16, retry_after: Option<Duration>| RequestFailure::Status {
+            status: StatusCode::from_u16(code).unwrap(),
+            retry_after,
+            body: String::new(),
+        };
+
+        assert_eq!(
+            executor.retry_delay(&status(429, Some(Duration::from_secs(3))), 0),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(executor.retry_delay(&status(429, Some(Duration::from_secs(600))), 0), None);
+        assert_eq!(executor.retry_delay(&status(400, None), 0), None);
+        assert_eq!(executor.retry_delay(&status(503, None), 2), None);
+
+        let delay = executor.retry_delay(&status(503, None), 1).unwrap();
+        assert!(delay >= Duration::from_secs(1) && delay <= Duration::from_millis(1250));
+        assert_eq!(backoff_delay(20), MAX_DELAY);
+
+        assert_eq!(parse_retry_after(" 12 "), Some(Duration::from_secs(12)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn enforces_token_budget() {
+        assert_eq!(estimate_tokens("hello world!"), 3);
+        assert_eq!(estimate_tokens("你好 ab"), 3);
+
+        let start = Instant::now();
+        let mut window = TokenWindow::default();
+        assert_eq!(window.try_reserve(80, 100, start), None);
+        assert_eq!(
+            window.try_reserve(30, 100, start + Duration::from_secs(20)),
+            Some(Duration::from_secs(40))
+        );
+        assert_eq!(window.try_reserve(20, 100, start + Duration::from_secs(20)), None);
+        // 最早的用量移出窗口后放行
+        assert_eq!(window.try_reserve(30, 100, start + Duration::from_secs(60)), None);
+        assert_eq!(window.used(), 50);
+        // 预算为 0 表示不限制
+        assert_eq!(window.try_reserve(1000, 0, start + Duration::from_secs(61)), None);
+    }
+}
@@ -1,9 +1,14 @@
 
 use crate::ai_config::AIConfig;
+use crate::ai_provider::{AIProvider, ChatMessage, StreamEvent};
+use crate::ai_request::{estimate_tokens, RequestExecutor, RequestFailure};
+use crate::offline_translator::OfflineTranslator;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
+// 流式输出中断后续传时追加的提示
+const CONTINUE_PROMPT: &str = "输出在中途中断了，请紧接着上次输出的最后一个字继续，不要重复已经输出的内容，也不要添加任何说明：";
+
 #[derive(Debug, Clone)]
 pub struct TranslationConfig {
     // 通用AI配置
@@ -26,48 +31,6 @@ impl Default for TranslationConfig {
     }
 }
 
-// 翻译请求
-#[derive(Debug, Serialize)]
-struct TranslationRequest {
-    model: String,
-    messages: Vec<Message>,
-    stream: bool,
-    temperature: f32,
-    max_tokens: u32,
-}
-
-// 消息结构
-#[derive(Debug, Serialize)]
-struct Message {
-    role: String,
-    content: String,
-}
-
-// 流式响应数据
-#[derive(Debug, Deserialize)]
-struct StreamResponse {
-    id: Option<String>,
-    object: Option<String>,
-    created: Option<u64>,
-    model: Option<String>,
-    choices: Option<Vec<Choice>>,
-}
-
-// 选择项
-#[derive(Debug, Deserialize)]
-struct Choice {
-    index: Option<u32>,
-    delta: Option<Delta>,
-    finish_reason: Option<String>,
-}
-
-// 增量内容
-#[derive(Debug, Deserialize)]
-struct Delta {
-    role: Option<String>,
-    content: Option<String>,
-}
-
 // 翻译错误类型
 #[derive(Debug)]
 pub enum TranslationError {
@@ -102,6 +65,25 @@ impl From<reqwest::Error> for TranslationError {
     }
 }
 
+impl From<RequestFailure> for TranslationError {
+    fn from(failure: RequestFailure) -> Self {
+        match failure {
+            RequestFailure::Network(e) => TranslationError::NetworkError(e),
+            RequestFailure::Status { status, body, .. } => match status.as_u16() {
+                400 => TranslationError::UnknownError(format!("HTTP 400 Bad Request: {}", body)),
+                401 | 403 => TranslationError::AuthenticationError,
+                429 => TranslationError::RateLimitError,
+                _ => TranslationError::UnknownError(format!(
+                    "HTTP {} {}: {}",
+                    status.as_u16(),
+                    status.canonical_reason().unwrap_or("Unknown"),
+                    body
+                )),
+            },
+        }
+    }
+}
+
 // 翻译结果
 #[derive(Debug)]
 pub enum TranslationResult {
@@ -115,7 +97,7 @@ pub enum TranslationResult {
 
 // 线程安全的AI翻译器，支持并发使用
 pub struct AITranslator {
-    client: Client,
+    executor: RequestExecutor,
     config: TranslationConfig,
 }
 
@@ -135,34 +117,15 @@ impl AITranslator {
             .build()
             .map_err(TranslationError::NetworkError)?;
 
-        Ok(Self { client, config })
+        Ok(Self {
+            executor: RequestExecutor::new(client, &config.ai_config),
+            config,
+        })
     }
 
     // 翻译文本（非流式，返回完整结果）
     pub async fn translate(&self, text: &str) -> Result<String, TranslationError> {
-        let mut receiver = self.translate_stream(text).await?;
-        let mut result = String::new();
-
-        // 收集所有流式响应片段
-        while let Some(translation_result) = receiver.recv().await {
-            match translation_result {
-                TranslationResult::Chunk(chunk) => {
-                    result.push_str(&chunk);
-                }
-                TranslationResult::Complete => {
-                    break;
-                }
-                TranslationResult::Error(e) => {
-                    return Err(e);
-                }
-            }
-        }
-
-        if result.is_empty() {
-            Err(TranslationError::ConfigError("翻译结果为空".to_string()))
-        } else {
-            Ok(result)
-        }
+        collect_stream(self.translate_stream(text).await?).await
     }
 
     // 翻译文本（流式）
@@ -170,37 +133,38 @@ impl AITranslator {
         &self,
         text: &str,
     ) -> Result<mpsc::Receiver<TranslationResult>, TranslationError> {
-        let (tx, rx) = mpsc::channel(100);
-
         let prompt = self
             .config
             .prompt_template
             .replace("{target_language}", &self.config.target_language);
 
-        let request = TranslationRequest {
-            model: self.config.ai_config.model.clone(),
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: format!("{}\n\n{}", prompt, text),
-            }],
-            stream: true,
-            temperature: self.config.ai_config.temperature,
-            max_tokens: self.config.ai_config.max_tokens,
-        };
-
-        let url = self.config.ai_config.get_chat_completions_url();
-        let client = self.client.clone();
-        let api_key = self.config.ai_config.api_key.clone();
+        let messages = vec![ChatMessage::user(format!("{}\n\n{}", prompt, text))];
+        let ai_config = &self.config.ai_config;
 
         // 调试输出
         println!("AI翻译请求:");
-        println!("  URL: {}", url);
-        println!("  Model: {}", request.model);
-        println!("  Content: {}", request.messages[0].content);
-        println!("  API Key: {}...", &api_key[..api_key.len().min(10)]);
+        println!("  Provider: {:?}", ai_config.provider);
+        println!("  URL: {}", ai_config.base_url);
+        println!("  Model: {}", ai_config.model);
+        println!("  Content: {}", messages[0].content);
+        println!("  API Key: {}...", &ai_config.api_key[..ai_config.api_key.len().min(10)]);
+
+        Ok(self.chat_stream(messages))
+    }
+
+    // 发送任意对话消息（流式），翻译和自定义AI操作共用
+    pub fn chat_stream(&self, messages: Vec<ChatMessage>) -> mpsc::Receiver<TranslationResult> {
+        let (tx, rx) = mpsc::channel(100);
+
+        let executor = self.executor.clone();
+        let ai_config = self.config.ai_config.clone();
 
         tokio::spawn(async move {
-            let result = Self::send_stream_request(client, url, api_key, request, tx.clone()).await;
+            // 接收端提前关闭（如取消操作）时立即放弃请求，包括仍在连接中的请求
+            let result = tokio::select! {
+                result = Self::send_stream_request(executor, ai_config, messages, tx.clone()) => result,
+                _ = tx.closed() => return,
+            };
 
             if let Err(e) = result {
                 let _ = tx.send(TranslationResult::Error(e)).await;
@@ -209,104 +173,261 @@ impl AITranslator {
             }
         });
 
-        Ok(rx)
+        rx
     }
 
-    // 发送流式请求
+    // 发送流式请求，按提供方的格式逐行解析响应
+    // 输出中途连接断开时带上已输出的内容重新请求，让模型接着输出
     async fn send_stream_request(
-        client: Client,
-        url: String,
-        api_key: String,
-        request: TranslationRequest,
+        executor: RequestExecutor,
+        ai_config: AIConfig,
+        messages: Vec<ChatMessage>,
         tx: mpsc::Sender<TranslationResult>,
     ) -> Result<(), TranslationError> {
-        use futures_util::StreamExt;
+        let provider = ai_config.provider.provider();
+        let mut output = String::new();
+        let mut interruptions = 0;
 
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-
-            // 尝试获取错误响应体以获得更详细的错误信息
-            let error_body = match response.text().await {
-                Ok(body) => body,
-                Err(_) => "无法读取错误响应".to_string(),
+        loop {
+            let request_messages = if output.is_empty() {
+                messages.clone()
+            } else {
+                continuation_messages(&messages, &output)
             };
-
-            let error = match status.as_u16() {
-                400 => {
-                    TranslationError::UnknownError(format!("HTTP 400 Bad Request: {}", error_body))
+            let estimated_tokens = request_messages
+                .iter()
+                .map(|message| estimate_tokens(&message.content))
+                .sum();
+
+            let executed = executor
+                .send(
+                    |client| provider.chat_request(client, &ai_config, &request_messages),
+                    estimated_tokens,
+                )
+                .await?;
+
+            let received_before = output.len();
+            let result = Self::read_stream(provider, executed.response, &tx, &mut output).await;
+            executor.record_output(&output[received_before..]);
+
+            match result {
+                Err(TranslationError::NetworkError(e)) if interruptions < executor.max_retries() => {
+                    let delay = crate::ai_request::backoff_delay(interruptions);
+                    interruptions += 1;
+                    println!(
+                        "流式响应中断（{}），已输出 {} 字，{} 毫秒后续传",
+                        e,
+                        output.chars().count(),
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
                 }
-                401 => TranslationError::AuthenticationError,
-                429 => TranslationError::RateLimitError,
-                _ => TranslationError::UnknownError(format!(
-                    "HTTP {} {}: {}",
-                    status.as_u16(),
-                    status.canonical_reason().unwrap_or("Unknown"),
-                    error_body
-                )),
-            };
-            return Err(error);
+                result => return result,
+            }
         }
+    }
+
+    // 读取流式响应，收到的片段发送给接收端并追加到 output
+    async fn read_stream(
+        provider: &dyn AIProvider,
+        response: reqwest::Response,
+        tx: &mpsc::Sender<TranslationResult>,
+        output: &mut String,
+    ) -> Result<(), TranslationError> {
+        use futures_util::StreamExt;
 
         let mut stream = response.bytes_stream();
         let mut buffer = String::new();
+        let mut received = false;
+        let mut stream_error = None;
 
         while let Some(chunk_result) = stream.next().await {
             let chunk_bytes = chunk_result?;
             let chunk_str = String::from_utf8_lossy(&chunk_bytes);
             buffer.push_str(&chunk_str);
 
-            // 处理SSE格式的数据
             while let Some(line_end) = buffer.find('\n') {
                 let line = buffer[..line_end].trim().to_string();
                 buffer = buffer[line_end + 1..].to_string();
 
-                if line.starts_with("data: ") {
-                    let data = &line[6..];
-                    if data == "[DONE]" {
-                        break;
+                match provider.parse_stream_line(&line) {
+                    Ok(StreamEvent::Chunk(content)) => {
+                        received = true;
+                        output.push_str(&content);
+                        if tx.send(TranslationResult::Chunk(content)).await.is_err() {
+                            // 接收端已关闭，停止发送
+                            return Ok(());
+                        }
+                    }
+                    Ok(StreamEvent::Done) => return Ok(()),
+                    Ok(StreamEvent::Ignore) => {}
+                    Err(e) => {
+                        // 解析失败，记录错误但继续处理
+                        println!("解析流式响应失败: {} - 数据: {}", e, line);
+                        stream_error = Some(e);
                     }
+                }
+            }
+        }
 
-                    // 尝试解析JSON响应
-                    match serde_json::from_str::<StreamResponse>(data) {
-                        Ok(response) => {
-                            if let Some(choices) = response.choices {
-                                for choice in choices {
-                                    if let Some(delta) = choice.delta {
-                                        if let Some(content) = delta.content {
-                                            if !content.is_empty() {
-                                                if let Err(_) =
-                                                    tx.send(TranslationResult::Chunk(content)).await
-                                                {
-                                                    // 接收端已关闭，停止发送
-                                                    return Ok(());
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            // 解析失败，记录错误但继续处理
-                            println!("解析流式响应失败: {} - 数据: {}", e, data);
-                        }
+        // 最后一行可能没有换行符
+        if let Ok(StreamEvent::Chunk(content)) = provider.parse_stream_line(buffer.trim()) {
+            received = true;
+            output.push_str(&content);
+            let _ = tx.send(TranslationResult::Chunk(content)).await;
+        }
+
+        // 没有任何输出时把流中的错误交给调用方，而不是只得到空结果
+        match stream_error {
+            Some(e) if !received => Err(TranslationError::UnknownError(e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+// 续传请求的消息：原消息之后带上已输出的内容，再要求模型接着输出
+fn continuation_messages(messages: &[ChatMessage], output: &str) -> Vec<ChatMessage> {
+    let mut messages = messages.to_vec();
+    messages.push(ChatMessage::assistant(output));
+    messages.push(ChatMessage::user(CONTINUE_PROMPT));
+    messages
+}
+
+// 收集流式结果为完整文本
+async fn collect_stream(
+    mut receiver: mpsc::Receiver<TranslationResult>,
+) -> Result<String, TranslationError> {
+    let mut result = String::new();
+
+    // 收集所有流式响应片段
+    while let Some(translation_result) = receiver.recv().await {
+        match translation_result {
+            TranslationResult::Chunk(chunk) => {
+                result.push_str(&chunk);
+            }
+            TranslationResult::Complete => {
+                break;
+            }
+            TranslationResult::Error(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    if result.is_empty() {
+        Err(TranslationError::ConfigError("翻译结果为空".to_string()))
+    } else {
+        Ok(result)
+    }
+}
+
+// 翻译器：按设置中的翻译后端使用在线AI或离线翻译，两者输出相同的流式结果
+// AI翻译可使用翻译记忆：命中时直接返回保存的译文，否则在翻译完成后保存结果
+pub enum Translator {
+    Ai { translator: AITranslator, use_memory: bool },
+    Offline(OfflineTranslator),
+}
+
+impl Translator {
+    // 翻译文本（非流式，返回完整结果）
+    pub async fn translate(&self, text: &str) -> Result<String, TranslationError> {
+        collect_stream(self.translate_stream(text).await?).await
+    }
+
+    // 翻译文本（流式）
+    pub async fn translate_stream(
+        &self,
+        text: &str,
+    ) -> Result<mpsc::Receiver<TranslationResult>, TranslationError> {
+        match self {
+            Translator::Ai { translator, use_memory: false } => translator.translate_stream(text).await,
+            Translator::Ai { translator, use_memory: true } => {
+                let key = memory_key(&translator.config, text);
+                match crate::translation_memory::lookup(&key) {
+                    Ok(Some(translation)) => return Ok(single_result(translation)),
+                    Ok(None) => {}
+                    Err(e) => println!("读取翻译记忆失败: {}", e),
+                }
+
+                let receiver = translator.translate_stream(text).await?;
+                Ok(record_to_memory(key, receiver))
+            }
+            Translator::Offline(translator) => translator.translate_stream(text).await,
+        }
+    }
+}
+
+// 翻译记忆的查找键（提示词按替换目标语言后的内容计算摘要）
+fn memory_key(config: &TranslationConfig, text: &str) -> crate::translation_memory::MemoryKey {
+    let prompt = config
+        .prompt_template
+        .replace("{target_language}", &config.target_language);
+    crate::translation_memory::MemoryKey::new(text, &config.target_language, &config.ai_config.model, &prompt)
+}
+
+// 按当前设置计算翻译记忆的查找键，离线翻译不使用翻译记忆
+pub fn memory_key_from_settings(
+    settings: &crate::settings::AppSettings,
+    text: &str,
+) -> Option<crate::translation_memory::MemoryKey> {
+    if is_offline_backend(settings) || !settings.translation_memory_enabled {
+        return None;
+    }
+    Some(memory_key(&config_from_settings(settings), text))
+}
+
+// 以单个片段返回已有的译文
+fn single_result(translation: String) -> mpsc::Receiver<TranslationResult> {
+    let (tx, rx) = mpsc::channel(2);
+    let _ = tx.try_send(TranslationResult::Chunk(translation));
+    let _ = tx.try_send(TranslationResult::Complete);
+    rx
+}
+
+// 转发流式结果，完整结束后保存到翻译记忆（出错或接收端提前关闭时不保存）
+fn record_to_memory(
+    key: crate::translation_memory::MemoryKey,
+    mut receiver: mpsc::Receiver<TranslationResult>,
+) -> mpsc::Receiver<TranslationResult> {
+    let (tx, rx) = mpsc::channel(100);
+
+    tokio::spawn(async move {
+        let mut translation = String::new();
+        while let Some(result) = receiver.recv().await {
+            match &result {
+                TranslationResult::Chunk(chunk) => translation.push_str(chunk),
+                TranslationResult::Complete => {
+                    if let Err(e) = crate::translation_memory::store(&key, &translation) {
+                        println!("保存翻译记忆失败: {}", e);
                     }
                 }
+                TranslationResult::Error(_) => {}
+            }
+            if tx.send(result).await.is_err() {
+                return;
             }
         }
+    });
+
+    rx
+}
 
-        Ok(())
+// 从应用设置创建翻译器
+pub fn create_translator(settings: &crate::settings::AppSettings) -> Result<Translator, TranslationError> {
+    if is_offline_backend(settings) {
+        OfflineTranslator::from_settings(settings).map(Translator::Offline)
+    } else {
+        AITranslator::new(config_from_settings(settings)).map(|translator| Translator::Ai {
+            translator,
+            use_memory: settings.translation_memory_enabled,
+        })
     }
 }
 
+fn is_offline_backend(settings: &crate::settings::AppSettings) -> bool {
+    settings.translation_backend == "offline"
+}
+
 // 从应用设置创建翻译配置
 pub fn config_from_settings(settings: &crate::settings::AppSettings) -> TranslationConfig {
     let ai_config = crate::ai_config::create_ai_config_from_settings(settings);
@@ -320,5 +441,10 @@ pub fn config_from_settings(settings: &crate::settings::AppSettings) -> Translat
 
 // 检查翻译配置是否有效
 pub fn is_translation_config_valid(settings: &crate::settings::AppSettings) -> bool {
-    crate::ai_config::is_ai_config_valid(settings) && !settings.ai_target_language.is_empty()
+    let backend_valid = if is_offline_backend(settings) {
+        crate::offline_translator::is_configured(settings)
+    } else {
+        crate::ai_config::is_ai_config_valid(settings)
+    };
+    backend_valid && !settings.ai_target_language.is_empty()
 }
@@ -0,0 +1,81 @@
+// Server-Sent Events 推送
+//
+// 转发剪贴板监听发出的应用事件，payload 与前端收到的一致
+
+use once_cell::sync::Lazy;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, Once};
+use std::time::Duration;
+use tauri::{AppHandle, Listener};
+
+// 转发给 SSE 订阅者的事件
+const FORWARDED_EVENTS: &[&str] = &["clipboard-item-added", "clipboard-item-moved"];
+
+// 无事件时发送心跳的间隔，用于检测断开的连接
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+static SUBSCRIBERS: Lazy<Mutex<Vec<Sender<String>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+static LISTENERS_REGISTERED: Once = Once::new();
+
+// 注册应用事件监听（只注册一次）
+pub fn register_listeners(app_handle: &AppHandle) {
+    LISTENERS_REGISTERED.call_once(|| {
+        for &name in FORWARDED_EVENTS {
+            app_handle.listen_any(name, move |event| broadcast(name, event.payload()));
+        }
+    });
+}
+
+fn broadcast(name: &str, payload: &str) {
+    let Ok(mut subscribers) = SUBSCRIBERS.lock() else {
+        return;
+    };
+    if subscribers.is_empty() {
+        return;
+    }
+
+    // data 字段不能包含换行，payload 为紧凑 JSON
+    let message = format!("event: {}\ndata: {}\n\n", name, payload);
+    subscribers.retain(|tx| tx.send(message.clone()).is_ok());
+}
+
+fn subscribe() -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        subscribers.push(tx);
+    }
+    rx
+}
+
+// 在当前连接上持续推送事件，直到客户端断开或服务停止
+pub fn stream_events(mut stream: TcpStream, stop: Arc<AtomicBool>) -> Result<(), String> {
+    let rx = subscribe();
+
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream; charset=utf-8\r\nCache-Control: no-store\r\nConnection: keep-alive\r\n\r\n: connected\n\n",
+        )
+        .map_err(|e| format!("发送事件流失败: {}", e))?;
+
+    while !stop.load(Ordering::SeqCst) {
+        let message = match rx.recv_timeout(KEEPALIVE_INTERVAL) {
+            Ok(message) => message,
+            Err(RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if stream
+            .write_all(message.as_bytes())
+            .and_then(|_| stream.flush())
+            .is_err()
+        {
+            // 客户端已断开，发送端会在下次广播时被移除
+            break;
+        }
+    }
+
+    Ok(())
+}
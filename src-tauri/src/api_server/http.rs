@@ -0,0 +1,281 @@
+// 最小化的 HTTP/1.1 解析与响应
+//
+// 每个连接只处理一个请求，响应后关闭连接（SSE 除外）
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Read, Write};
+use std::net::TcpStream;
+
+use crate::error::{AppError, ErrorCode};
+
+// 请求头的最大总长度
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+
+// 请求体的最大长度
+const MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+pub struct Request {
+    pub method: String,
+    // 已解码的路径片段，例如 /api/favorites/abc -> ["api", "favorites", "abc"]
+    pub segments: Vec<String>,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    // 只读取请求行和请求头，请求体在令牌校验通过后由 read_body 读取
+    pub fn read_head(reader: &mut impl BufRead) -> Result<Self, String> {
+        let mut header_bytes = 0;
+
+        let request_line = read_line(reader, &mut header_bytes)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_uppercase();
+        let target = parts.next().ok_or_else(|| "请求行无效".to_string())?;
+
+        let mut headers = HashMap::new();
+        loop {
+            let line = read_line(reader, &mut header_bytes)?;
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let segments = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(decode)
+            .collect();
+        let query = query
+            .split('&')
+            .filter(|s| !s.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode(key), decode(value))
+            })
+            .collect();
+
+        Ok(Self {
+            method,
+            segments,
+            query,
+            headers,
+            body: Vec::new(),
+        })
+    }
+
+    // 按 Content-Length 读取请求体
+    pub fn read_body(&mut self, reader: &mut impl BufRead) -> Result<(), Response> {
+        let content_length = self
+            .header("content-length")
+            .map(|v| {
+                v.parse::<usize>()
+                    .map_err(|_| Response::bad_request("Content-Length 无效"))
+            })
+            .transpose()?
+            .unwrap_or(0);
+        if content_length > MAX_BODY_BYTES {
+            return Err(Response::error(413, "请求体过大"));
+        }
+        let mut body = vec![0; content_length];
+        reader
+            .read_exact(&mut body)
+            .map_err(|e| Response::bad_request(format!("读取请求体失败: {}", e)))?;
+        self.body = body;
+        Ok(())
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|v| v.as_str())
+    }
+
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        self.query.get(name).filter(|v| !v.is_empty()).cloned()
+    }
+
+    pub fn query_usize(&self, name: &str) -> Result<Option<usize>, Response> {
+        self.query_param(name)
+            .map(|v| {
+                v.parse::<usize>()
+                    .map_err(|_| Response::bad_request(format!("参数 {} 无效", name)))
+            })
+            .transpose()
+    }
+
+    // 解析 JSON 请求体
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, Response> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| Response::bad_request(format!("请求体格式无效: {}", e)))
+    }
+}
+
+fn read_line(reader: &mut impl BufRead, total: &mut usize) -> Result<String, String> {
+    let mut line = String::new();
+    let read = reader
+        .by_ref()
+        .take((MAX_HEADER_BYTES - *total) as u64)
+        .read_line(&mut line)
+        .map_err(|e| format!("读取请求失败: {}", e))?;
+    *total += read;
+    if !line.ends_with('\n') {
+        return Err("请求头无效或过大".to_string());
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn decode(s: &str) -> String {
+    let s = s.replace('+', " ");
+    urlencoding::decode(&s).map(|v| v.into_owned()).unwrap_or(s)
+}
+
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    pub fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    pub fn created(body: Value) -> Self {
+        Self { status: 201, body }
+    }
+
+    pub fn no_content() -> Self {
+        Self {
+            status: 204,
+            body: Value::Null,
+        }
+    }
+
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::error(400, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::error(404, message)
+    }
+
+    pub fn write_to(&self, stream: &mut TcpStream) -> Result<(), String> {
+        let body = if self.status == 204 {
+            String::new()
+        } else {
+            serde_json::to_string(&self.body).map_err(|e| format!("序列化响应失败: {}", e))?
+        };
+        let head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason_phrase(self.status),
+            body.len()
+        );
+        stream
+            .write_all(head.as_bytes())
+            .and_then(|_| stream.write_all(body.as_bytes()))
+            .and_then(|_| stream.flush())
+            .map_err(|e| format!("发送响应失败: {}", e))
+    }
+}
+
+// 处理函数返回的 String 错误统一作为 500
+impl From<String> for Response {
+    fn from(message: String) -> Self {
+        Self::error(500, message)
+    }
+}
+
+// 结构化错误按类别映射状态码，响应体附带 code、key 和 context
+impl From<AppError> for Response {
+    fn from(error: AppError) -> Self {
+        let status = match error.code {
+            ErrorCode::NotFound => 404,
+            ErrorCode::InvalidInput => 400,
+            ErrorCode::PermissionDenied => 403,
+            ErrorCode::Locked => 423,
+            ErrorCode::DatabaseNotInitialized => 503,
+            _ => 500,
+        };
+        Self {
+            status,
+            body: json!({
+                "error": error.localized_message(),
+                "code": error.code,
+                "key": error.key,
+                "context": error.context,
+            }),
+        }
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        423 => "Locked",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+// 常数时间比较，避免通过响应时间推测令牌
+pub fn secure_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn reads_head_without_consuming_body() {
+        let raw = "POST /api/favorites?limit=5&q=a+b HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Length: 7\r\n\r\n{\"a\":1}";
+        let mut reader = Cursor::new(raw.as_bytes());
+
+        let mut request = Request::read_head(&mut reader).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.segments, ["api", "favorites"]);
+        assert_eq!(request.query_param("q").as_deref(), Some("a b"));
+        assert_eq!(request.header("host"), Some("127.0.0.1"));
+        assert!(request.body.is_empty());
+        assert_eq!(reader.position() as usize, raw.len() - 7);
+
+        assert!(request.read_body(&mut reader).is_ok());
+        assert_eq!(request.body, b"{\"a\":1}");
+    }
+
+    #[test]
+    fn rejects_oversized_body_before_reading_it() {
+        let raw = format!(
+            "POST /api/favorites HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        let mut reader = Cursor::new(raw.as_bytes());
+        let mut request = Request::read_head(&mut reader).unwrap();
+        let response = request.read_body(&mut reader).err().unwrap();
+        assert_eq!(response.status, 413);
+    }
+}
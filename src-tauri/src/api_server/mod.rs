@@ -0,0 +1,195 @@
+// 本地 HTTP 接口
+//
+// 供启动器、编辑器扩展、自动化脚本等第三方工具读写剪贴板历史和收藏。
+// 默认关闭；启用后仅监听 127.0.0.1，请求需携带令牌：
+//   Authorization: Bearer <令牌>，或查询参数 ?token=<令牌>（用于无法设置请求头的 EventSource）
+// GET /api/events 为 Server-Sent Events 流，转发剪贴板新增/移动事件
+
+mod events;
+mod http;
+mod routes;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::settings::AppSettings;
+use http::{Request, Response};
+
+// 无新连接时检查停止标记的间隔
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// 读取请求的超时时间
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct RunningServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+}
+
+static SERVER: Lazy<Mutex<Option<RunningServer>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+}
+
+// 根据设置启动、重启或停止接口服务
+pub fn apply_settings(app_handle: &AppHandle, settings: &AppSettings) {
+    if !settings.api_server_enabled {
+        stop_server();
+        return;
+    }
+
+    if settings.api_server_token.is_empty() {
+        if let Err(e) = regenerate_token() {
+            println!("生成接口令牌失败: {}", e);
+            return;
+        }
+    }
+
+    let Ok(mut server) = SERVER.lock() else {
+        return;
+    };
+    if let Some(running) = server.as_ref() {
+        if running.port == settings.api_server_port {
+            return;
+        }
+        running.stop.store(true, Ordering::SeqCst);
+        *server = None;
+    }
+
+    match start_server(app_handle.clone(), settings.api_server_port) {
+        Ok(running) => *server = Some(running),
+        Err(e) => println!("{}", e),
+    }
+}
+
+pub fn stop_server() {
+    if let Ok(mut server) = SERVER.lock() {
+        if let Some(running) = server.take() {
+            running.stop.store(true, Ordering::SeqCst);
+            println!("本地接口服务已停止");
+        }
+    }
+}
+
+pub fn get_status() -> ApiServerStatus {
+    let settings = crate::settings::get_global_settings();
+    let running = SERVER.lock().map(|s| s.is_some()).unwrap_or(false);
+    ApiServerStatus {
+        enabled: settings.api_server_enabled,
+        running,
+        port: settings.api_server_port,
+    }
+}
+
+// 重新生成访问令牌，旧令牌立即失效
+pub fn regenerate_token() -> Result<String, String> {
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let mut settings = crate::settings::get_global_settings();
+    settings.api_server_token = token.clone();
+    crate::settings::update_global_settings(settings)?;
+    Ok(token)
+}
+
+fn start_server(app_handle: AppHandle, port: u16) -> Result<RunningServer, String> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("启动本地接口服务失败（端口 {}）: {}", port, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("设置监听模式失败: {}", e))?;
+
+    events::register_listeners(&app_handle);
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    std::thread::spawn(move || {
+        while !thread_stop.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let app_handle = app_handle.clone();
+                    let stop = thread_stop.clone();
+                    std::thread::spawn(move || {
+                        if let Err(e) = handle_connection(stream, port, &app_handle, stop) {
+                            println!("处理接口请求失败: {}", e);
+                        }
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(e) => {
+                    println!("接受接口连接失败: {}", e);
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+            }
+        }
+    });
+
+    println!("本地接口服务已启动: http://127.0.0.1:{}", port);
+    Ok(RunningServer { port, stop })
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    port: u16,
+    app_handle: &AppHandle,
+    stop: Arc<AtomicBool>,
+) -> Result<(), String> {
+    stream
+        .set_nonblocking(false)
+        .and_then(|_| stream.set_read_timeout(Some(READ_TIMEOUT)))
+        .map_err(|e| format!("设置连接失败: {}", e))?;
+
+    // 先校验请求头和令牌，通过后才读取请求体
+    let mut reader = BufReader::new(&stream);
+    let mut request = match Request::read_head(&mut reader) {
+        Ok(request) => request,
+        Err(e) => return Response::bad_request(e).write_to(&mut stream),
+    };
+
+    if let Err(response) = authorize(&request, port) {
+        return response.write_to(&mut stream);
+    }
+
+    if let Err(response) = request.read_body(&mut reader) {
+        return response.write_to(&mut stream);
+    }
+
+    if request.method == "GET" && request.segments == ["api", "events"] {
+        return events::stream_events(stream, stop);
+    }
+
+    routes::handle(&request, app_handle).write_to(&mut stream)
+}
+
+fn authorize(request: &Request, port: u16) -> Result<(), Response> {
+    // 校验 Host，防止网页通过 DNS 重绑定访问本地接口
+    let host_allowed = request.header("host").is_some_and(|host| {
+        let name = host.strip_suffix(&format!(":{}", port)).unwrap_or(host);
+        name == "127.0.0.1" || name.eq_ignore_ascii_case("localhost")
+    });
+    if !host_allowed {
+        return Err(Response::error(403, "不允许的 Host"));
+    }
+
+    let expected = crate::settings::get_global_settings().api_server_token;
+    let provided = request
+        .header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string())
+        .or_else(|| request.query_param("token"));
+
+    match provided {
+        Some(token) if !expected.is_empty() && http::secure_eq(&token, &expected) => Ok(()),
+        _ => Err(Response::error(401, "令牌无效")),
+    }
+}
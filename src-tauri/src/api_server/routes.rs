@@ -0,0 +1,335 @@
+// HTTP 接口路由
+//
+// 剪贴板历史:
+//   GET    /api/history?limit=&offset=&type=
+//   GET    /api/history/search?q=&type=&app=&url=&limit=&offset=
+//   GET    /api/history/{id}
+//   DELETE /api/history/{id}
+//   POST   /api/history/{id}/paste
+// 收藏:
+//   GET    /api/favorites?group=
+//   GET    /api/favorites/search?q=&group=&type=&limit=&offset=
+//   POST   /api/favorites              {title, content, html_content?, group_name?}
+//   GET    /api/favorites/{id}
+//   PUT    /api/favorites/{id}         {title?, content?, group_name?}
+//   DELETE /api/favorites/{id}
+//   POST   /api/favorites/{id}/paste
+// 分组（以名称作为标识）:
+//   GET    /api/groups
+//   POST   /api/groups                 {name, icon?}
+//   PUT    /api/groups/{name}          {name?, icon?}
+//   DELETE /api/groups/{name}
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::http::{Request, Response};
+use crate::database_search::SearchParams;
+use crate::services::group_service::GroupService;
+
+// 默认返回的历史记录数量
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+// 默认分组
+const DEFAULT_GROUP: &str = "全部";
+
+// 默认分组图标
+const DEFAULT_GROUP_ICON: &str = "ti ti-folder";
+
+type RouteResult = Result<Response, Response>;
+
+pub fn handle(request: &Request, app_handle: &AppHandle) -> Response {
+    route(request, app_handle).unwrap_or_else(|response| response)
+}
+
+fn route(request: &Request, app_handle: &AppHandle) -> RouteResult {
+    let segments: Vec<&str> = request.segments.iter().map(|s| s.as_str()).collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["api", "history"]) => list_history(request),
+        ("GET", ["api", "history", "search"]) => search_history(request),
+        ("GET", ["api", "history", id]) => get_history_item(parse_id(id)?),
+        ("DELETE", ["api", "history", id]) => delete_history_item(parse_id(id)?, app_handle),
+        ("POST", ["api", "history", id, "paste"]) => paste(
+            crate::services::paste_service::PasteContentParams {
+                clipboard_id: Some(parse_id(id)?),
+                quick_text_id: None,
+                mime_type: None,
+            },
+            app_handle,
+        ),
+
+        ("GET", ["api", "favorites"]) => list_favorites(request),
+        ("GET", ["api", "favorites", "search"]) => search_favorites(request),
+        ("POST", ["api", "favorites"]) => create_favorite(request, app_handle),
+        ("GET", ["api", "favorites", id]) => {
+            find_favorite(id).map(|item| Response::ok(to_value(&item)))
+        }
+        ("PUT", ["api", "favorites", id]) => update_favorite(id, request, app_handle),
+        ("DELETE", ["api", "favorites", id]) => delete_favorite(id, app_handle),
+        ("POST", ["api", "favorites", id, "paste"]) => {
+            let item = find_favorite(id)?;
+            paste(
+                crate::services::paste_service::PasteContentParams {
+                    clipboard_id: None,
+                    quick_text_id: Some(item.id),
+                    mime_type: None,
+                },
+                app_handle,
+            )
+        }
+
+        ("GET", ["api", "groups"]) => Ok(Response::ok(to_value(&GroupService::get_all_groups()))),
+        ("POST", ["api", "groups"]) => create_group(request, app_handle),
+        ("PUT", ["api", "groups", name]) => update_group(name, request, app_handle),
+        ("DELETE", ["api", "groups", name]) => delete_group(name, app_handle),
+
+        (_, ["api", ..]) => Err(Response::error(405, "不支持的请求")),
+        _ => Err(Response::not_found("接口不存在")),
+    }
+}
+
+fn parse_id(id: &str) -> Result<i64, Response> {
+    id.parse()
+        .map_err(|_| Response::bad_request(format!("无效的项目ID: {}", id)))
+}
+
+fn search_params(request: &Request) -> Result<SearchParams, Response> {
+    Ok(SearchParams {
+        query: request
+            .query_param("q")
+            .ok_or_else(|| Response::bad_request("缺少搜索关键词 q"))?,
+        content_type: request.query_param("type"),
+        group_name: request.query_param("group"),
+        tag: request.query_param("tag"),
+        source_app: request.query_param("app"),
+        source_url: request.query_param("url"),
+        offset: request.query_usize("offset")?,
+        limit: request.query_usize("limit")?,
+    })
+}
+
+// =================== 剪贴板历史 ===================
+
+fn list_history(request: &Request) -> RouteResult {
+    let content_type = request.query_param("type");
+    let offset = request.query_usize("offset")?.unwrap_or(0);
+    let limit = request.query_usize("limit")?.unwrap_or(DEFAULT_PAGE_SIZE);
+
+    let history = match request.query_param("tag") {
+        Some(tag) => crate::content_classifier::get_history_by_tag(&tag)?,
+        None => crate::database::get_clipboard_history(None)?,
+    };
+    let items: Vec<_> = history
+        .into_iter()
+        .filter(|item| {
+            content_type
+                .as_deref()
+                .is_none_or(|t| item.content_type.to_string() == t)
+        })
+        .collect();
+    let total = items.len();
+    let items: Vec<_> = items.into_iter().skip(offset).take(limit).collect();
+
+    Ok(Response::ok(json!({
+        "items": items,
+        "total": total,
+        "offset": offset,
+        "limit": limit,
+    })))
+}
+
+fn search_history(request: &Request) -> RouteResult {
+    let params = search_params(request)?;
+    Ok(Response::ok(to_value(
+        &crate::database_search::search_clipboard(&params)?,
+    )))
+}
+
+fn get_history_item(id: i64) -> RouteResult {
+    match crate::database::get_clipboard_item_by_id(id)? {
+        Some(item) => Ok(Response::ok(to_value(&item))),
+        None => Err(Response::not_found(format!("未找到ID为 {} 的剪贴板项", id))),
+    }
+}
+
+fn delete_history_item(id: i64, app_handle: &AppHandle) -> RouteResult {
+    get_history_item(id)?;
+    crate::database::delete_clipboard_item(id)?;
+    emit_to_main(app_handle, "clipboard-changed");
+    Ok(Response::no_content())
+}
+
+fn paste(
+    params: crate::services::paste_service::PasteContentParams,
+    app_handle: &AppHandle,
+) -> RouteResult {
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| "找不到主窗口".to_string())?;
+    tauri::async_runtime::block_on(crate::services::paste_service::paste_content(
+        params, window,
+    ))?;
+    Ok(Response::no_content())
+}
+
+// =================== 收藏 ===================
+
+#[derive(Deserialize)]
+struct CreateFavoriteBody {
+    title: String,
+    content: String,
+    html_content: Option<String>,
+    group_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UpdateFavoriteBody {
+    title: Option<String>,
+    content: Option<String>,
+    group_name: Option<String>,
+}
+
+fn list_favorites(request: &Request) -> RouteResult {
+    let items = match request.query_param("group") {
+        Some(group) => crate::database::get_favorite_items_by_group(&group)?,
+        None => crate::database::get_all_favorite_items()?,
+    };
+    Ok(Response::ok(to_value(&items)))
+}
+
+fn search_favorites(request: &Request) -> RouteResult {
+    let params = search_params(request)?;
+    Ok(Response::ok(to_value(
+        &crate::database_search::search_favorites(&params)?,
+    )))
+}
+
+fn find_favorite(id: &str) -> Result<crate::database::FavoriteItem, Response> {
+    crate::database::get_all_favorite_items()?
+        .into_iter()
+        .find(|item| item.id == id)
+        .ok_or_else(|| Response::not_found(format!("常用文本 {} 不存在", id)))
+}
+
+fn ensure_group_exists(group_name: &str) -> Result<(), Response> {
+    if group_name != DEFAULT_GROUP && !crate::database::group_exists(group_name)? {
+        return Err(Response::bad_request(format!("分组 {} 不存在", group_name)));
+    }
+    Ok(())
+}
+
+fn create_favorite(request: &Request, app_handle: &AppHandle) -> RouteResult {
+    let body: CreateFavoriteBody = request.json()?;
+    if body.content.is_empty() {
+        return Err(Response::bad_request("内容不能为空"));
+    }
+    let group_name = body.group_name.unwrap_or_else(|| DEFAULT_GROUP.to_string());
+    ensure_group_exists(&group_name)?;
+
+    let item = crate::quick_texts::add_quick_text_with_group_and_html(
+        body.title,
+        body.content,
+        body.html_content,
+        group_name,
+    )?;
+    emit_to_main(app_handle, "refreshQuickTexts");
+    Ok(Response::created(to_value(&item)))
+}
+
+fn update_favorite(id: &str, request: &Request, app_handle: &AppHandle) -> RouteResult {
+    let body: UpdateFavoriteBody = request.json()?;
+    let existing = find_favorite(id)?;
+    let group_name = body.group_name.unwrap_or(existing.group_name);
+    ensure_group_exists(&group_name)?;
+
+    let item = crate::quick_texts::update_quick_text(
+        existing.id,
+        body.title.unwrap_or(existing.title),
+        body.content.unwrap_or(existing.content),
+        Some(group_name),
+    )?;
+    emit_to_main(app_handle, "refreshQuickTexts");
+    Ok(Response::ok(to_value(&item)))
+}
+
+fn delete_favorite(id: &str, app_handle: &AppHandle) -> RouteResult {
+    if !crate::database::favorite_item_exists(id)? {
+        return Err(Response::not_found(format!("常用文本 {} 不存在", id)));
+    }
+    crate::quick_texts::delete_quick_text(id)?;
+    emit_to_main(app_handle, "refreshQuickTexts");
+    Ok(Response::no_content())
+}
+
+// =================== 分组 ===================
+
+#[derive(Deserialize)]
+struct GroupBody {
+    name: Option<String>,
+    icon: Option<String>,
+}
+
+fn find_group(name: &str) -> Result<crate::database::GroupInfo, Response> {
+    GroupService::get_all_groups()
+        .into_iter()
+        .find(|group| group.name == name)
+        .ok_or_else(|| Response::not_found(format!("分组 {} 不存在", name)))
+}
+
+fn create_group(request: &Request, app_handle: &AppHandle) -> RouteResult {
+    let body: GroupBody = request.json()?;
+    let name = body
+        .name
+        .filter(|name| !name.trim().is_empty())
+        .ok_or_else(|| Response::bad_request("分组名称不能为空"))?;
+    if crate::database::group_exists(&name)? {
+        return Err(Response::bad_request(format!("分组 {} 已存在", name)));
+    }
+
+    let group = GroupService::add_group(
+        name,
+        body.icon.unwrap_or_else(|| DEFAULT_GROUP_ICON.to_string()),
+    )?;
+    emit_to_main(app_handle, "refreshQuickTexts");
+    Ok(Response::created(to_value(&group)))
+}
+
+fn update_group(name: &str, request: &Request, app_handle: &AppHandle) -> RouteResult {
+    if name == DEFAULT_GROUP {
+        return Err(Response::bad_request("不能修改默认分组"));
+    }
+    let body: GroupBody = request.json()?;
+    let existing = find_group(name)?;
+
+    let group = GroupService::update_group(
+        existing.name,
+        body.name.unwrap_or_else(|| name.to_string()),
+        body.icon.unwrap_or(existing.icon),
+    )?;
+    emit_to_main(app_handle, "refreshQuickTexts");
+    Ok(Response::ok(to_value(&group)))
+}
+
+fn delete_group(name: &str, app_handle: &AppHandle) -> RouteResult {
+    if name == DEFAULT_GROUP {
+        return Err(Response::bad_request("不能删除默认分组"));
+    }
+    find_group(name)?;
+    GroupService::delete_group(name.to_string())?;
+    emit_to_main(app_handle, "refreshQuickTexts");
+    Ok(Response::no_content())
+}
+
+// =================== 工具函数 ===================
+
+// 通知主窗口刷新数据
+fn emit_to_main(app_handle: &AppHandle, event: &str) {
+    if let Some(main_window) = app_handle.get_webview_window("main") {
+        let _ = main_window.emit(event, ());
+    }
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
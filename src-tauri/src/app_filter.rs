@@ -1,16 +1,6 @@
+use crate::database::ClipboardSource;
 use crate::settings;
 use serde::{Deserialize, Serialize};
-use std::ffi::OsString;
-use std::os::windows::ffi::OsStringExt;
-
-#[cfg(windows)]
-
-#[cfg(windows)]
-use windows::Win32::System::ProcessStatus::{GetModuleFileNameExW, GetProcessImageFileNameW};
-#[cfg(windows)]
-use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ};
-#[cfg(windows)]
-use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppInfo {
@@ -95,25 +85,76 @@ pub fn get_all_windows_info() -> Vec<AppInfo> {
     windows
 }
 
-// 检查当前应用是否在允许列表中
+// Linux：X11 的客户端列表或 Wayland 合成器的窗口树，图标来自 .desktop 文件
+#[cfg(target_os = "linux")]
+pub fn get_all_windows_info() -> Vec<AppInfo> {
+    let icons = crate::linux_window::IconResolver::new();
+
+    let mut windows: Vec<AppInfo> = crate::linux_window::list_windows()
+        .into_iter()
+        .filter(|window| !window.title.trim().is_empty())
+        .map(|window| {
+            let process = window.process_name();
+            AppInfo {
+                icon: icons.icon_for(&window.app_id, &process),
+                path: linux_process_path(&window),
+                name: window.title,
+                process,
+            }
+        })
+        .collect();
+
+    // 按窗口标题排序并去重
+    windows.sort_by(|a: &AppInfo, b: &AppInfo| a.name.cmp(&b.name));
+    windows.dedup_by(|a: &mut AppInfo, b: &mut AppInfo| a.process == b.process && a.name == b.name);
+    windows
+}
+
+#[cfg(target_os = "linux")]
+fn linux_process_path(window: &crate::linux_window::WindowInfo) -> String {
+    window
+        .exe_path()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| String::from("unknown"))
+}
+
+// 前台窗口所属的应用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForegroundApp {
+    // 进程文件名（如 chrome.exe）
+    pub process: String,
+    // 窗口标题
+    pub title: String,
+    // 进程完整路径
+    pub path: String,
+}
+
+impl ForegroundApp {
+    // 转为剪贴板记录的来源（无法获取的字段为空）
+    pub fn into_source(self, url: Option<String>) -> ClipboardSource {
+        let non_empty = |value: String| Some(value).filter(|v| !v.is_empty() && v != "unknown");
+        ClipboardSource {
+            app: non_empty(self.process),
+            path: non_empty(self.path),
+            title: non_empty(self.title),
+            url: url.filter(|url| !url.is_empty()),
+            selection: None,
+        }
+    }
+}
+
+// 获取前台窗口所属的应用
 #[cfg(windows)]
-pub fn is_current_app_allowed() -> bool {
+pub fn get_foreground_app() -> Option<ForegroundApp> {
     use windows::Win32::Foundation::HWND;
     use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId};
     use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ};
     use windows::Win32::System::ProcessStatus::GetModuleFileNameExW;
 
-    let settings = settings::get_global_settings();
-    
-    // 如果未启用应用过滤，则允许所有应用
-    if !settings.app_filter_enabled {
-        return true;
-    }
-
     unsafe {
         let hwnd = GetForegroundWindow();
         if hwnd == HWND(0) {
-            return true; // 无法获取当前应用，默认允许
+            return None;
         }
 
         // 获取进程ID
@@ -121,7 +162,7 @@ pub fn is_current_app_allowed() -> bool {
         GetWindowThreadProcessId(hwnd, Some(&mut process_id));
 
         if process_id == 0 {
-            return true;
+            return None;
         }
 
         // 获取进程路径
@@ -153,70 +194,105 @@ pub fn is_current_app_allowed() -> bool {
             .unwrap_or(&full_path)
             .to_string();
 
-        // 检查是否匹配任何过滤规则
-        let matches_filter = settings.app_filter_list.iter().any(|filter| {
-            let filter_lower = filter.to_lowercase();
-            
-            // 检查进程名
-            if process_filename.to_lowercase().contains(&filter_lower) {
-                return true;
-            }
-            
-            // 检查窗口标题
-            if window_title.to_lowercase().contains(&filter_lower) {
-                return true;
-            }
-            
-            // 检查完整路径
-            if full_path.to_lowercase().contains(&filter_lower) {
-                return true;
-            }
-            
-            false
-        });
-
-        match settings.app_filter_mode.as_str() {
-            "whitelist" => matches_filter, // 白名单模式：只有匹配的应用才允许
-            "blacklist" => !matches_filter, // 黑名单模式：匹配的应用不允许
-            _ => true, // 默认允许
-        }
+        Some(ForegroundApp {
+            process: process_filename,
+            title: window_title,
+            path: full_path,
+        })
     }
 }
 
-// 非Windows平台的占位实现
-#[cfg(not(windows))]
+// Linux：X11 的 _NET_ACTIVE_WINDOW 或 Wayland 合成器的焦点窗口
+#[cfg(target_os = "linux")]
+pub fn get_foreground_app() -> Option<ForegroundApp> {
+    let window = crate::linux_window::foreground_window()?;
+    Some(ForegroundApp {
+        process: window.process_name(),
+        path: linux_process_path(&window),
+        title: window.title,
+    })
+}
+
+// 其他平台的占位实现
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn get_foreground_app() -> Option<ForegroundApp> {
+    None
+}
+
+// 检查当前应用是否在允许列表中
 pub fn is_current_app_allowed() -> bool {
-    true
+    let settings = settings::get_global_settings();
+
+    // 如果未启用应用过滤，则允许所有应用
+    if !settings.app_filter_enabled {
+        return true;
+    }
+
+    // 无法获取当前应用，默认允许
+    let Some(app) = get_foreground_app() else {
+        return true;
+    };
+
+    let matches_filter = matches_filter_list(&[&app.process, &app.title, &app.path], &settings.app_filter_list);
+    applies_filter_mode(&settings.app_filter_mode, matches_filter)
 }
 
+// 读取内容后按完整的来源再次检查，过滤词也可以匹配浏览器复制时的网页地址
+pub fn is_source_allowed(source: &ClipboardSource) -> bool {
+    let settings = settings::get_global_settings();
+
+    if !settings.app_filter_enabled || source.is_empty() {
+        return true;
+    }
 
+    let fields: Vec<&str> = [&source.app, &source.title, &source.path, &source.url]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    let matches_filter = matches_filter_list(&fields, &settings.app_filter_list);
+    applies_filter_mode(&settings.app_filter_mode, matches_filter)
+}
+
+fn applies_filter_mode(mode: &str, matches_filter: bool) -> bool {
+    match mode {
+        "whitelist" => matches_filter, // 白名单模式：只有匹配的应用才允许
+        "blacklist" => !matches_filter, // 黑名单模式：匹配的应用不允许
+        _ => true, // 默认允许
+    }
+}
+
+// 检查是否匹配任何过滤规则（进程名、窗口标题、完整路径或网页地址包含过滤词）
+fn matches_filter_list(fields: &[&str], filters: &[String]) -> bool {
+    let fields: Vec<String> = fields.iter().map(|field| field.to_lowercase()).collect();
+
+    filters.iter().any(|filter| {
+        let filter_lower = filter.to_lowercase();
+        fields.iter().any(|field| field.contains(&filter_lower))
+    })
+}
 
 #[tauri::command]
 pub fn get_all_windows_info_cmd() -> Result<Vec<AppInfo>, String> {
-    #[cfg(windows)]
+    #[cfg(any(windows, target_os = "linux"))]
     {
         Ok(get_all_windows_info())
     }
     
-    #[cfg(not(windows))]
+    #[cfg(not(any(windows, target_os = "linux")))]
     {
         Ok(Vec::new())
     }
 }
 
 
-// 非Windows平台的占位实现
+// 非Windows平台通过前台应用获取
 #[cfg(not(windows))]
 pub fn get_active_window_process_name() -> Option<String> {
-    None
+    get_foreground_app().map(|app| app.process)
 }
 
 #[cfg(not(windows))]
 pub fn get_active_window_title() -> Option<String> {
-    None
+    get_foreground_app().map(|app| app.title)
 }
-
-#[cfg(not(windows))]
-pub fn is_current_app_allowed() -> bool {
-    true
-}
\ No newline at end of file
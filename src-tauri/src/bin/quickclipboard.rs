@@ -0,0 +1,7 @@
+// QuickClipboard 命令行工具
+
+fn main() {
+    std::process::exit(quickclipboard_lib::cli::run(
+        std::env::args().skip(1).collect(),
+    ));
+}
@@ -0,0 +1,502 @@
+// 剪贴板记录规则
+//
+// 新内容写入历史之前按顺序匹配用户定义的规则，第一条命中的规则决定如何记录。
+// 规则的所有非空条件都满足才算命中：
+// - 进程名：完全匹配或 * / ? 通配符，不区分大小写；规则不带扩展名时也匹配 xxx.exe
+// - 窗口标题正则、来源网页地址正则、内容正则
+// - 内容类型（text / rich_text / image / file / link）
+// 命中后可组合多种处理方式：不记录、不播放音效、自动过期、只保留纯文本、
+// 同时保存到收藏分组、触发复制时翻译
+
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+use crate::database::{ClipboardSource, ContentType};
+use crate::error::{AppError, AppResult};
+use crate::settings::CaptureRule;
+
+// 用于匹配规则的样本：来源应用和内容
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CaptureSample {
+    pub process: String,
+    pub title: String,
+    // 浏览器复制时的来源网页地址
+    pub url: String,
+    pub content: String,
+    // 为空时按内容检测
+    pub content_type: String,
+}
+
+impl CaptureSample {
+    // 以内容来源和剪贴板内容构造样本
+    pub fn from_source(source: &ClipboardSource, content: &str, html_content: Option<&str>) -> Self {
+        Self {
+            process: source.app.clone().unwrap_or_default(),
+            title: source.title.clone().unwrap_or_default(),
+            url: source.url.clone().unwrap_or_default(),
+            content: content.to_string(),
+            content_type: crate::database::detect_content_type(content, html_content).to_string(),
+        }
+    }
+
+    fn content_type(&self) -> String {
+        if self.content_type.is_empty() {
+            crate::database::detect_content_type(&self.content, None).to_string()
+        } else {
+            ContentType::from_string(&self.content_type).to_string()
+        }
+    }
+}
+
+// 命中规则后的处理方式
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureDecision {
+    pub skip: bool,
+    pub mute: bool,
+    pub expire_minutes: Option<u32>,
+    pub plain_text: bool,
+    pub favorite_group: Option<String>,
+    pub translate: bool,
+}
+
+impl CaptureDecision {
+    fn from_rule(rule: &CaptureRule) -> Self {
+        Self {
+            skip: rule.skip,
+            mute: rule.mute,
+            expire_minutes: (rule.expire_minutes > 0).then_some(rule.expire_minutes),
+            plain_text: rule.plain_text,
+            favorite_group: Some(rule.favorite_group.trim().to_string()).filter(|group| !group.is_empty()),
+            translate: rule.translate,
+        }
+    }
+
+    // 自动过期的时间戳
+    pub fn expires_at(&self) -> Option<i64> {
+        self.expire_minutes
+            .map(|minutes| chrono::Local::now().timestamp() + minutes as i64 * 60)
+    }
+}
+
+// 单个条件的检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionCheck {
+    // process / title / url / contentType / content
+    pub condition: String,
+    // 规则中的取值
+    pub expected: String,
+    // 样本中的取值（内容条件不返回原文）
+    pub actual: String,
+    pub matched: bool,
+}
+
+// 单条规则的匹配说明
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleExplanation {
+    pub index: usize,
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub matched: bool,
+    pub conditions: Vec<ConditionCheck>,
+}
+
+// 规则测试结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestResult {
+    // 生效的规则序号，没有规则命中时为空
+    pub matched_index: Option<usize>,
+    pub decision: Option<CaptureDecision>,
+    pub rules: Vec<RuleExplanation>,
+}
+
+// 编译后的规则
+struct CompiledRule {
+    rule: CaptureRule,
+    process: Option<Regex>,
+    title: Option<Regex>,
+    url: Option<Regex>,
+    content: Option<Regex>,
+    content_types: Vec<String>,
+}
+
+impl CompiledRule {
+    // name 为界面显示的规则名称，用于错误说明
+    fn compile(rule: &CaptureRule, name: &str) -> AppResult<Self> {
+        Self::compile_patterns(rule)
+            .map_err(|e| e.prefixed(&format!("规则 {} 无效", name)).with_context("rule", name))
+    }
+
+    fn compile_patterns(rule: &CaptureRule) -> AppResult<Self> {
+        let process = match rule.process.trim() {
+            "" => None,
+            pattern => Some(glob_to_regex(pattern)?),
+        };
+
+        Ok(Self {
+            rule: rule.clone(),
+            process,
+            title: compile_pattern(&rule.title_pattern).map_err(|e| invalid_pattern("title", "窗口标题正则", e))?,
+            url: compile_pattern(&rule.url_pattern).map_err(|e| invalid_pattern("url", "网页地址正则", e))?,
+            content: compile_pattern(&rule.content_pattern).map_err(|e| invalid_pattern("content", "内容正则", e))?,
+            content_types: rule
+                .content_types
+                .iter()
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect(),
+        })
+    }
+
+    // 逐项检查条件（按顺序：进程名、窗口标题、网页地址、内容类型、内容）
+    fn check(&self, sample: &CaptureSample) -> Vec<ConditionCheck> {
+        let mut checks = Vec::new();
+
+        if let Some(process) = &self.process {
+            checks.push(ConditionCheck {
+                condition: "process".to_string(),
+                expected: self.rule.process.trim().to_string(),
+                actual: sample.process.clone(),
+                matched: matches_process(process, &sample.process),
+            });
+        }
+
+        if let Some(title) = &self.title {
+            checks.push(ConditionCheck {
+                condition: "title".to_string(),
+                expected: self.rule.title_pattern.clone(),
+                actual: sample.title.clone(),
+                matched: title.is_match(&sample.title),
+            });
+        }
+
+        if let Some(url) = &self.url {
+            checks.push(ConditionCheck {
+                condition: "url".to_string(),
+                expected: self.rule.url_pattern.clone(),
+                actual: sample.url.clone(),
+                matched: url.is_match(&sample.url),
+            });
+        }
+
+        if !self.content_types.is_empty() {
+            let content_type = sample.content_type();
+            checks.push(ConditionCheck {
+                condition: "contentType".to_string(),
+                expected: self.content_types.join(", "),
+                matched: self.content_types.contains(&content_type),
+                actual: content_type,
+            });
+        }
+
+        if let Some(content) = &self.content {
+            checks.push(ConditionCheck {
+                condition: "content".to_string(),
+                expected: self.rule.content_pattern.clone(),
+                actual: format!("{} 字符", sample.content.chars().count()),
+                matched: content.is_match(&sample.content),
+            });
+        }
+
+        checks
+    }
+
+    fn matches(&self, sample: &CaptureSample) -> bool {
+        self.process
+            .as_ref()
+            .is_none_or(|process| matches_process(process, &sample.process))
+            && self.title.as_ref().is_none_or(|title| title.is_match(&sample.title))
+            && self.url.as_ref().is_none_or(|url| url.is_match(&sample.url))
+            && (self.content_types.is_empty() || self.content_types.contains(&sample.content_type()))
+            && self
+                .content
+                .as_ref()
+                .is_none_or(|content| content.is_match(&sample.content))
+    }
+}
+
+// 规则的显示名称，未命名的规则用序号表示
+fn display_name(rule: &CaptureRule, index: usize) -> String {
+    if rule.name.is_empty() {
+        format!("#{}", index + 1)
+    } else {
+        rule.name.clone()
+    }
+}
+
+// 规则中的匹配条件无效（field 为条件名，供前端定位输入框）
+fn invalid_pattern(field: &str, label: &str, error: regex::Error) -> AppError {
+    AppError::invalid_input(format!("{}无效: {}", label, error))
+        .with_key("error.capture_rule.invalid_pattern")
+        .with_context("field", field)
+        .with_context("detail", error)
+}
+
+fn compile_pattern(pattern: &str) -> Result<Option<Regex>, regex::Error> {
+    if pattern.is_empty() {
+        return Ok(None);
+    }
+    Regex::new(pattern).map(Some)
+}
+
+// 通配符转为整段匹配、不区分大小写的正则
+fn glob_to_regex(pattern: &str) -> AppResult<Regex> {
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+
+    RegexBuilder::new(&regex)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| invalid_pattern("process", "进程名", e))
+}
+
+// 进程名匹配，Windows 的进程名带 .exe，规则中可以省略
+fn matches_process(pattern: &Regex, process: &str) -> bool {
+    if pattern.is_match(process) {
+        return true;
+    }
+    let lower = process.to_lowercase();
+    lower
+        .strip_suffix(".exe")
+        .is_some_and(|stem| pattern.is_match(stem))
+}
+
+// 当前设置中的规则及编译结果，设置加载或修改时更新，避免每次剪贴板变化都重新编译正则
+struct LoadedRules {
+    source: Vec<CaptureRule>,
+    compiled: Arc<Vec<CompiledRule>>,
+}
+
+static LOADED_RULES: Lazy<RwLock<Option<LoadedRules>>> = Lazy::new(|| RwLock::new(None));
+
+// 编译启用的规则（无效的规则跳过）
+fn compile_rules(rules: &[CaptureRule]) -> Vec<CompiledRule> {
+    rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| rule.enabled)
+        .filter_map(|(index, rule)| match CompiledRule::compile(rule, &display_name(rule, index)) {
+            Ok(compiled) => Some(compiled),
+            Err(e) => {
+                println!("已跳过无效的记录规则: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+// 设置加载或修改后调用，规则没有变化时不重新编译
+pub fn load(rules: &[CaptureRule]) {
+    let mut loaded = LOADED_RULES.write().unwrap_or_else(|e| e.into_inner());
+    if loaded.as_ref().is_some_and(|loaded| loaded.source.as_slice() == rules) {
+        return;
+    }
+    *loaded = Some(LoadedRules {
+        source: rules.to_vec(),
+        compiled: Arc::new(compile_rules(rules)),
+    });
+}
+
+fn loaded_rules() -> Arc<Vec<CompiledRule>> {
+    if let Some(loaded) = LOADED_RULES.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return loaded.compiled.clone();
+    }
+    load(&crate::settings::get_global_settings().capture_rules);
+    loaded_rules()
+}
+
+// 按顺序匹配规则，返回第一条命中规则的处理方式
+fn evaluate(rules: &[CompiledRule], sample: &CaptureSample) -> Option<CaptureDecision> {
+    rules
+        .iter()
+        .find(|compiled| compiled.matches(sample))
+        .map(|compiled| CaptureDecision::from_rule(&compiled.rule))
+}
+
+// 按当前设置匹配剪贴板内容
+pub fn decide(source: &ClipboardSource, content: &str, html_content: Option<&str>) -> Option<CaptureDecision> {
+    let rules = loaded_rules();
+    if rules.is_empty() {
+        return None;
+    }
+    evaluate(&rules, &CaptureSample::from_source(source, content, html_content))
+}
+
+// 测试样本命中的规则，说明每条规则各个条件的匹配情况（供设置界面调试规则）
+pub fn test_rules(rules: &[CaptureRule], sample: &CaptureSample) -> AppResult<RuleTestResult> {
+    let mut matched_index = None;
+    let mut explanations = Vec::new();
+
+    for (index, rule) in rules.iter().enumerate() {
+        let name = display_name(rule, index);
+        let compiled = CompiledRule::compile(rule, &name)?;
+
+        let conditions = compiled.check(sample);
+        let matched = rule.enabled && conditions.iter().all(|check| check.matched);
+        if matched && matched_index.is_none() {
+            matched_index = Some(index);
+        }
+
+        explanations.push(RuleExplanation {
+            index,
+            id: rule.id.clone(),
+            name,
+            enabled: rule.enabled,
+            matched,
+            conditions,
+        });
+    }
+
+    Ok(RuleTestResult {
+        matched_index,
+        decision: matched_index.map(|index| CaptureDecision::from_rule(&rules[index])),
+        rules: explanations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate_rules(rules: &[CaptureRule], sample: &CaptureSample) -> Option<CaptureDecision> {
+        evaluate(&compile_rules(rules), sample)
+    }
+
+    fn sample(process: &str, title: &str, content: &str) -> CaptureSample {
+        CaptureSample {
+            process: process.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = vec![
+            CaptureRule {
+                name: "密码管理器".to_string(),
+                process: "keepass*".to_string(),
+                skip: true,
+                ..Default::default()
+            },
+            CaptureRule {
+                name: "终端里的链接".to_string(),
+                process: "wezterm-gui".to_string(),
+                content_types: vec!["link".to_string()],
+                favorite_group: " 链接 ".to_string(),
+                mute: true,
+                ..Default::default()
+            },
+            CaptureRule {
+                name: "网银".to_string(),
+                url_pattern: r"^https://bank\.example\.com/".to_string(),
+                skip: true,
+                ..Default::default()
+            },
+            CaptureRule {
+                name: "浏览器".to_string(),
+                title_pattern: "(?i)mozilla firefox$".to_string(),
+                expire_minutes: 30,
+                plain_text: true,
+                translate: true,
+                ..Default::default()
+            },
+        ];
+
+        let skip = evaluate_rules(&rules, &sample("KeePassXC.exe", "数据库", "secret")).unwrap();
+        assert!(skip.skip);
+
+        let link = evaluate_rules(&rules, &sample("WezTerm-gui.exe", "bash", "https://example.com")).unwrap();
+        assert_eq!(link.favorite_group.as_deref(), Some("链接"));
+        assert!(link.mute && !link.skip);
+        assert!(evaluate_rules(&rules, &sample("wezterm-gui", "bash", "ls -la")).is_none());
+
+        let browser = evaluate_rules(&rules, &sample("firefox", "Docs — Mozilla Firefox", "hello")).unwrap();
+        assert_eq!(browser.expire_minutes, Some(30));
+        assert!(browser.plain_text && browser.translate);
+
+        // 来源网页地址条件
+        let bank = CaptureSample {
+            url: "https://bank.example.com/account".to_string(),
+            ..sample("firefox", "账户 — Mozilla Firefox", "123456")
+        };
+        assert!(evaluate_rules(&rules, &bank).unwrap().skip);
+
+        // 停用的规则不参与匹配
+        let mut disabled = rules.clone();
+        disabled[0].enabled = false;
+        assert!(evaluate_rules(&disabled, &sample("keepassxc", "", "secret")).is_none());
+    }
+
+    #[test]
+    fn explains_rule_matches() {
+        let rules = vec![
+            CaptureRule {
+                name: "代码".to_string(),
+                process: "code".to_string(),
+                content_pattern: r"^\s*fn ".to_string(),
+                ..Default::default()
+            },
+            CaptureRule {
+                process: "code".to_string(),
+                content_types: vec!["text".to_string()],
+                skip: true,
+                ..Default::default()
+            },
+        ];
+
+        let result = test_rules(&rules, &sample("Code.exe", "main.rs", "let x = 1;")).unwrap();
+        assert_eq!(result.matched_index, Some(1));
+        assert!(result.decision.unwrap().skip);
+        assert_eq!(result.rules[0].conditions.len(), 2);
+        assert!(result.rules[0].conditions[0].matched);
+        assert!(!result.rules[0].conditions[1].matched);
+        assert_eq!(result.rules[1].name, "#2");
+        assert_eq!(result.rules[1].conditions[1].actual, "text");
+
+        let invalid = vec![CaptureRule {
+            name: "坏规则".to_string(),
+            title_pattern: "(".to_string(),
+            ..Default::default()
+        }];
+        let error = test_rules(&invalid, &sample("", "", "")).unwrap_err();
+        assert_eq!(error.code, crate::error::ErrorCode::InvalidInput);
+        assert_eq!(error.context.get("rule").map(String::as_str), Some("坏规则"));
+        assert_eq!(error.context.get("field").map(String::as_str), Some("title"));
+        assert!(evaluate_rules(&invalid, &sample("", "", "")).is_none());
+    }
+
+    #[test]
+    fn compiles_rules_once_until_they_change() {
+        let mut rules = vec![CaptureRule {
+            name: "密码管理器".to_string(),
+            process: "keepassxc".to_string(),
+            skip: true,
+            ..Default::default()
+        }];
+        load(&rules);
+        let first = loaded_rules();
+        load(&rules);
+        assert!(Arc::ptr_eq(&first, &loaded_rules()));
+        assert!(evaluate(&first, &sample("KeePassXC.exe", "", "secret")).unwrap().skip);
+
+        rules[0].enabled = false;
+        load(&rules);
+        assert!(loaded_rules().is_empty());
+    }
+}
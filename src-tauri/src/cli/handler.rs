@@ -0,0 +1,144 @@
+// 命令行操作的执行
+//
+// 运行中实例收到的请求和直接访问数据库时都通过这里执行，app_handle 为 None 表示直接模式
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::protocol::CliCommand;
+use crate::database::FavoriteItem;
+
+// 默认列出的历史记录数量
+const DEFAULT_LIST_LIMIT: usize = 20;
+
+pub fn execute(command: CliCommand, app_handle: Option<&AppHandle>) -> Result<Value, String> {
+    match command {
+        CliCommand::List {
+            limit,
+            content_type,
+        } => {
+            let items: Vec<_> = crate::database::get_clipboard_history(None)?
+                .into_iter()
+                .filter(|item| {
+                    content_type
+                        .as_deref()
+                        .is_none_or(|t| item.content_type.to_string() == t)
+                })
+                .take(limit.unwrap_or(DEFAULT_LIST_LIMIT))
+                .collect();
+            to_value(&items)
+        }
+        CliCommand::Search {
+            query,
+            favorites,
+            content_type,
+            group,
+            source_app,
+            source_url,
+            limit,
+        } => {
+            let params = crate::database_search::SearchParams {
+                query,
+                content_type,
+                group_name: group,
+                tag: None,
+                source_app,
+                source_url,
+                offset: None,
+                limit,
+            };
+            if favorites {
+                to_value(&crate::database_search::search_favorites(&params)?)
+            } else {
+                to_value(&crate::database_search::search_clipboard(&params)?)
+            }
+        }
+        CliCommand::Get { id } => match crate::database::get_clipboard_item_by_id(id)? {
+            Some(item) => to_value(&item),
+            None => Err(format!("未找到ID为 {} 的剪贴板项", id)),
+        },
+        CliCommand::Push { content, html } => push_item(content, html, app_handle),
+        CliCommand::CopyFavorite { title } => {
+            let item = find_favorite(&title)?;
+            crate::clipboard_content::set_clipboard_content_with_html(
+                item.content.clone(),
+                item.html_content.clone(),
+            )?;
+            notify_clipboard_changed(app_handle);
+            Ok(json!({ "id": item.id, "title": item.title }))
+        }
+        CliCommand::PasteFavorite { title } => {
+            let app_handle =
+                app_handle.ok_or_else(|| "粘贴需要 QuickClipboard 正在运行".to_string())?;
+            let item = find_favorite(&title)?;
+            let window = app_handle
+                .get_webview_window("main")
+                .ok_or_else(|| "找不到主窗口".to_string())?;
+
+            let params = crate::services::paste_service::PasteContentParams {
+                clipboard_id: None,
+                quick_text_id: Some(item.id.clone()),
+                mime_type: None,
+            };
+            tauri::async_runtime::block_on(crate::services::paste_service::paste_content(
+                params, window,
+            ))?;
+            Ok(json!({ "id": item.id, "title": item.title }))
+        }
+        CliCommand::Export { path } => {
+            tauri::async_runtime::block_on(crate::data_manager::export_data(
+                &path,
+                crate::data_manager::ExportOptions {},
+            ))?;
+            Ok(json!({ "path": path }))
+        }
+        CliCommand::Import { path, merge } => {
+            let mode = if merge {
+                crate::data_manager::ImportMode::Merge
+            } else {
+                crate::data_manager::ImportMode::Replace
+            };
+            tauri::async_runtime::block_on(crate::data_manager::import_data(
+                &path,
+                crate::data_manager::ImportOptions { mode },
+            ))?;
+
+            if let Some(app_handle) = app_handle {
+                notify_clipboard_changed(Some(app_handle));
+                if let Some(main_window) = app_handle.get_webview_window("main") {
+                    let _ = main_window.emit("refreshQuickTexts", ());
+                }
+            }
+            Ok(json!({ "path": path, "merge": merge }))
+        }
+    }
+}
+
+// 添加剪贴板项目（已存在时移动到最前面），遵循敏感内容检测设置
+fn push_item(
+    content: String,
+    html: Option<String>,
+    app_handle: Option<&AppHandle>,
+) -> Result<Value, String> {
+    let (id, is_new) = crate::clipboard_history::add_item_with_filter(content, html)?;
+    notify_clipboard_changed(app_handle);
+    Ok(json!({ "id": id, "is_new": is_new }))
+}
+
+// 按标题查找收藏（标题重复时返回排序最靠前的一项）
+fn find_favorite(title: &str) -> Result<FavoriteItem, String> {
+    crate::database::find_favorite_items_by_title(title)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("未找到标题为 \"{}\" 的收藏", title))
+}
+
+fn notify_clipboard_changed(app_handle: Option<&AppHandle>) {
+    if let Some(main_window) = app_handle.and_then(|app| app.get_webview_window("main")) {
+        let _ = main_window.emit("clipboard-changed", ());
+    }
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("序列化结果失败: {}", e))
+}
@@ -0,0 +1,432 @@
+// 命令行工具
+//
+// 优先连接正在运行的 QuickClipboard 实例执行命令；实例未运行时直接访问数据库。
+// 默认输出纯文本（制表符分隔，便于管道处理），--json 输出 JSON。
+
+pub mod handler;
+mod output;
+pub mod protocol;
+pub mod server;
+
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use std::time::Duration;
+
+use protocol::{CliCommand, CliRequest, CliResponse};
+
+// 数据已加密且实例未运行时，从该环境变量读取解锁口令
+const PASSPHRASE_ENV: &str = "QUICKCLIPBOARD_PASSPHRASE";
+
+// 连接运行中实例的超时时间
+const CONNECT_TIMEOUT_MS: u64 = 1000;
+
+// 纯文本输出中预览内容的最大字符数
+const PREVIEW_CHARS: usize = 80;
+
+const USAGE: &str = "用法: quickclipboard [--json] [--direct] <命令> [参数]
+
+命令:
+  list [--limit N] [--type TYPE]        列出剪贴板历史
+  search <关键词> [--favorites] [--type TYPE] [--group 分组] [--limit N]
+         [--app 进程名] [--url 网址]   搜索剪贴板历史或收藏（--app/--url 按来源筛选历史）
+  get <id>                              输出剪贴板项目的完整内容
+  push [--html FILE]                    从标准输入读取内容并添加到历史记录
+  copy <标题>                           将收藏复制到剪贴板
+  paste <标题>                          粘贴收藏到当前窗口（需要 QuickClipboard 正在运行）
+  export <文件.zip>                     导出数据
+  import <文件.zip> [--merge]           导入数据（默认替换，--merge 合并）
+
+选项:
+  --json      以 JSON 格式输出
+  --direct    不连接运行中的实例，直接访问数据库
+  -h, --help  显示帮助
+
+TYPE 可选: text, rich_text, image, file, link
+数据已加密且 QuickClipboard 未运行时，通过环境变量 QUICKCLIPBOARD_PASSPHRASE 提供口令";
+
+// 命令行参数
+struct CliArgs {
+    command: CliCommand,
+    json: bool,
+    direct: bool,
+}
+
+// 命令行入口，返回进程退出码
+pub fn run(args: Vec<String>) -> i32 {
+    let mut out = output::redirect_logs_to_stderr();
+
+    let args = match parse_args(args) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            let _ = writeln!(out, "{}", USAGE);
+            return 0;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let result = execute(&args);
+    match result.and_then(|data| print_result(&mut out, &args, &data)) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            1
+        }
+    }
+}
+
+// 执行命令：优先发送给运行中的实例
+fn execute(args: &CliArgs) -> Result<Value, String> {
+    if !args.direct {
+        if let Some(stream) = connect_running_instance() {
+            return send_request(stream, &args.command);
+        }
+    }
+
+    if args.command.requires_running_app() {
+        return Err("该命令需要 QuickClipboard 正在运行".to_string());
+    }
+
+    init_direct_mode()?;
+    handler::execute(args.command.clone(), None)
+}
+
+fn connect_running_instance() -> Option<(TcpStream, String)> {
+    let info = protocol::read_instance_info()?;
+    let addr = SocketAddr::from(([127, 0, 0, 1], info.port));
+    let stream =
+        TcpStream::connect_timeout(&addr, Duration::from_millis(CONNECT_TIMEOUT_MS)).ok()?;
+    Some((stream, info.token))
+}
+
+fn send_request(
+    (mut stream, token): (TcpStream, String),
+    command: &CliCommand,
+) -> Result<Value, String> {
+    let request = CliRequest {
+        token,
+        command: command.clone(),
+    };
+    let mut body = serde_json::to_string(&request).map_err(|e| format!("序列化请求失败: {}", e))?;
+    body.push('\n');
+    stream
+        .write_all(body.as_bytes())
+        .map_err(|e| format!("发送请求失败: {}", e))?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+
+    serde_json::from_str::<CliResponse>(&line)
+        .map_err(|e| format!("解析响应失败: {}", e))?
+        .into_result()
+}
+
+// 直接访问数据库（实例未运行时）
+fn init_direct_mode() -> Result<(), String> {
+    crate::encryption::init();
+    crate::database::initialize_database().map_err(|e| format!("初始化数据库失败: {}", e))?;
+
+    if crate::encryption::is_locked() {
+        let passphrase = std::env::var(PASSPHRASE_ENV).map_err(|_| {
+            format!(
+                "数据已加密，请启动 QuickClipboard 并解锁，或通过环境变量 {} 提供口令",
+                PASSPHRASE_ENV
+            )
+        })?;
+        crate::encryption::unlock(&passphrase)?;
+    }
+
+    Ok(())
+}
+
+// =================== 参数解析 ===================
+
+fn parse_args(args: Vec<String>) -> Result<Option<CliArgs>, String> {
+    let mut json = false;
+    let mut direct = false;
+    let mut positional: Vec<String> = Vec::new();
+    let mut options: Vec<(String, Option<String>)> = Vec::new();
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--json" => json = true,
+            "--direct" => direct = true,
+            "--favorites" | "--merge" => options.push((arg, None)),
+            "--limit" | "--type" | "--group" | "--html" | "--app" | "--url" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("参数 {} 缺少取值", arg))?;
+                options.push((arg, Some(value)));
+            }
+            _ if arg.starts_with("--") => return Err(format!("未知参数: {}", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let option = |name: &str| -> Option<String> {
+        options
+            .iter()
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| value.clone())
+    };
+    let flag = |name: &str| options.iter().any(|(key, _)| key == name);
+    let limit = option("--limit")
+        .map(|v| v.parse::<usize>().map_err(|_| format!("无效的数量: {}", v)))
+        .transpose()?;
+
+    let mut positional = positional.into_iter();
+    let name = match positional.next() {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+    let mut argument = |what: &str| -> Result<String, String> {
+        positional
+            .next()
+            .ok_or_else(|| format!("命令 {} 缺少{}", name, what))
+    };
+
+    let command = match name.as_str() {
+        "list" => CliCommand::List {
+            limit,
+            content_type: option("--type"),
+        },
+        "search" => CliCommand::Search {
+            query: argument("关键词")?,
+            favorites: flag("--favorites"),
+            content_type: option("--type"),
+            group: option("--group"),
+            source_app: option("--app"),
+            source_url: option("--url"),
+            limit,
+        },
+        "get" => {
+            let id = argument("项目ID")?;
+            CliCommand::Get {
+                id: id.parse().map_err(|_| format!("无效的项目ID: {}", id))?,
+            }
+        }
+        "push" => {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|e| format!("读取标准输入失败: {}", e))?;
+            let html = option("--html")
+                .map(|path| {
+                    std::fs::read_to_string(&path).map_err(|e| format!("读取HTML文件失败: {}", e))
+                })
+                .transpose()?;
+            CliCommand::Push { content, html }
+        }
+        "copy" => CliCommand::CopyFavorite {
+            title: argument("收藏标题")?,
+        },
+        "paste" => CliCommand::PasteFavorite {
+            title: argument("收藏标题")?,
+        },
+        "export" => CliCommand::Export {
+            path: absolute_path(&argument("导出文件路径")?)?,
+        },
+        "import" => CliCommand::Import {
+            path: absolute_path(&argument("导入文件路径")?)?,
+            merge: flag("--merge"),
+        },
+        _ => return Err(format!("未知命令: {}", name)),
+    };
+
+    Ok(Some(CliArgs {
+        command,
+        json,
+        direct,
+    }))
+}
+
+// 运行中的实例工作目录不同，文件路径需要转换为绝对路径
+fn absolute_path(path: &str) -> Result<String, String> {
+    let path = Path::new(path);
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map_err(|e| format!("获取当前目录失败: {}", e))?
+            .join(path)
+    };
+    Ok(absolute.to_string_lossy().to_string())
+}
+
+// =================== 输出 ===================
+
+fn print_result(out: &mut dyn Write, args: &CliArgs, data: &Value) -> Result<(), String> {
+    let text = if args.json {
+        format!(
+            "{}\n",
+            serde_json::to_string_pretty(data).map_err(|e| format!("序列化结果失败: {}", e))?
+        )
+    } else {
+        format_plain(&args.command, data)
+    };
+
+    out.write_all(text.as_bytes())
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("输出结果失败: {}", e))
+}
+
+fn format_plain(command: &CliCommand, data: &Value) -> String {
+    match command {
+        CliCommand::List { .. } => format_item_lines(data.as_array()),
+        CliCommand::Search { favorites, .. } => {
+            let items: Vec<Value> = data["items"]
+                .as_array()
+                .map(|hits| hits.iter().map(|hit| hit["item"].clone()).collect())
+                .unwrap_or_default();
+            if *favorites {
+                format_favorite_lines(&items)
+            } else {
+                format_item_lines(Some(&items))
+            }
+        }
+        // 原样输出内容，便于重定向到文件
+        CliCommand::Get { .. } => data["content"].as_str().unwrap_or_default().to_string(),
+        CliCommand::Push { .. } => format!("{}\n", data["id"]),
+        CliCommand::CopyFavorite { .. } | CliCommand::PasteFavorite { .. } => String::new(),
+        CliCommand::Export { .. } => {
+            format!("已导出到 {}\n", data["path"].as_str().unwrap_or_default())
+        }
+        CliCommand::Import { .. } => "导入完成\n".to_string(),
+    }
+}
+
+// 每行：ID、类型、内容预览
+fn format_item_lines(items: Option<&Vec<Value>>) -> String {
+    items
+        .map(|items| {
+            items
+                .iter()
+                .map(|item| {
+                    format!(
+                        "{}\t{}\t{}\n",
+                        item["id"],
+                        item["content_type"].as_str().unwrap_or_default(),
+                        preview(item["content"].as_str().unwrap_or_default())
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// 每行：标题、分组、内容预览
+fn format_favorite_lines(items: &[Value]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            format!(
+                "{}\t{}\t{}\n",
+                preview(item["title"].as_str().unwrap_or_default()),
+                item["group_name"].as_str().unwrap_or_default(),
+                preview(item["content"].as_str().unwrap_or_default())
+            )
+        })
+        .collect()
+}
+
+// 单行预览（合并空白并截断）
+fn preview(text: &str) -> String {
+    let single_line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if single_line.chars().count() > PREVIEW_CHARS {
+        let truncated: String = single_line.chars().take(PREVIEW_CHARS - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        single_line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<CliArgs>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    #[test]
+    fn parses_global_flags_and_list_options() {
+        let args = parse(&["--json", "list", "--limit", "5", "--type", "image", "--direct"])
+            .unwrap()
+            .unwrap();
+        assert!(args.json);
+        assert!(args.direct);
+        assert!(matches!(
+            args.command,
+            CliCommand::List { limit: Some(5), content_type: Some(ref t) } if t == "image"
+        ));
+    }
+
+    #[test]
+    fn parses_search_options() {
+        let args = parse(&["search", "hello", "--favorites", "--group", "工作", "--app", "code.exe"])
+            .unwrap()
+            .unwrap();
+        assert!(!args.json);
+        match args.command {
+            CliCommand::Search {
+                query,
+                favorites,
+                group,
+                source_app,
+                source_url,
+                limit,
+                ..
+            } => {
+                assert_eq!(query, "hello");
+                assert!(favorites);
+                assert_eq!(group.as_deref(), Some("工作"));
+                assert_eq!(source_app.as_deref(), Some("code.exe"));
+                assert_eq!(source_url, None);
+                assert_eq!(limit, None);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_get_and_import_arguments() {
+        let args = parse(&["get", "42"]).unwrap().unwrap();
+        assert!(matches!(args.command, CliCommand::Get { id: 42 }));
+
+        let args = parse(&["import", "backup.zip", "--merge"]).unwrap().unwrap();
+        match args.command {
+            CliCommand::Import { path, merge } => {
+                assert!(Path::new(&path).is_absolute());
+                assert!(path.ends_with("backup.zip"));
+                assert!(merge);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn returns_usage_for_help_or_missing_command() {
+        assert!(parse(&[]).unwrap().is_none());
+        assert!(parse(&["--json"]).unwrap().is_none());
+        assert!(parse(&["list", "--help"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse(&["frobnicate"]).is_err());
+        assert!(parse(&["list", "--verbose"]).is_err());
+        assert!(parse(&["list", "--limit"]).is_err());
+        assert!(parse(&["list", "--limit", "many"]).is_err());
+        assert!(parse(&["get"]).is_err());
+        assert!(parse(&["get", "abc"]).is_err());
+        assert!(parse(&["copy"]).is_err());
+    }
+}
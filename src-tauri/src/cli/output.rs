@@ -0,0 +1,46 @@
+// 命令行输出
+//
+// 直接访问数据库时，内部模块会向标准输出打印运行日志。
+// 执行前将标准输出重定向到标准错误，命令结果写入原始的标准输出，保证管道中只有命令结果。
+
+use std::io::Write;
+
+// 将日志重定向到标准错误，返回写入命令结果的原始标准输出
+pub fn redirect_logs_to_stderr() -> Box<dyn Write> {
+    let _ = std::io::stdout().flush();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::FromRawFd;
+
+        // SAFETY: 复制并替换进程自身的标准输出描述符，新描述符由返回的 File 独占
+        unsafe {
+            let saved = libc::dup(libc::STDOUT_FILENO);
+            if saved >= 0 && libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) >= 0 {
+                return Box::new(std::fs::File::from_raw_fd(saved));
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::io::FromRawHandle;
+        use windows::Win32::System::Console::{
+            GetStdHandle, SetStdHandle, STD_ERROR_HANDLE, STD_OUTPUT_HANDLE,
+        };
+
+        // SAFETY: 替换进程自身的标准输出句柄，原句柄由返回的 File 独占
+        unsafe {
+            if let (Ok(stdout), Ok(stderr)) = (
+                GetStdHandle(STD_OUTPUT_HANDLE),
+                GetStdHandle(STD_ERROR_HANDLE),
+            ) {
+                if !stdout.is_invalid() && SetStdHandle(STD_OUTPUT_HANDLE, stderr).is_ok() {
+                    return Box::new(std::fs::File::from_raw_handle(stdout.0 as _));
+                }
+            }
+        }
+    }
+
+    Box::new(std::io::stdout())
+}
@@ -0,0 +1,191 @@
+// 命令行与运行中实例之间的通信协议
+//
+// 运行中的实例在 127.0.0.1 的随机端口监听，端口和访问令牌写入默认数据目录的 cli_instance.json。
+// 每个连接发送一行 JSON 请求，返回一行 JSON 响应。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 实例信息文件名
+const INSTANCE_FILE_NAME: &str = "cli_instance.json";
+
+// 命令行支持的操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum CliCommand {
+    // 列出剪贴板历史
+    List {
+        limit: Option<usize>,
+        content_type: Option<String>,
+    },
+    // 搜索剪贴板历史或收藏
+    Search {
+        query: String,
+        favorites: bool,
+        content_type: Option<String>,
+        group: Option<String>,
+        // 按来源应用和网页地址筛选（仅剪贴板历史）
+        #[serde(default)]
+        source_app: Option<String>,
+        #[serde(default)]
+        source_url: Option<String>,
+        limit: Option<usize>,
+    },
+    // 获取剪贴板项目完整内容
+    Get {
+        id: i64,
+    },
+    // 添加新的剪贴板项目
+    Push {
+        content: String,
+        html: Option<String>,
+    },
+    // 将收藏复制到剪贴板
+    CopyFavorite {
+        title: String,
+    },
+    // 粘贴收藏到当前窗口
+    PasteFavorite {
+        title: String,
+    },
+    // 导出数据
+    Export {
+        path: String,
+    },
+    // 导入数据
+    Import {
+        path: String,
+        merge: bool,
+    },
+}
+
+impl CliCommand {
+    // 是否只能由运行中的实例执行
+    pub fn requires_running_app(&self) -> bool {
+        matches!(self, CliCommand::PasteFavorite { .. })
+    }
+}
+
+// 请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CliRequest {
+    pub token: String,
+    #[serde(flatten)]
+    pub command: CliCommand,
+}
+
+// 响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CliResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CliResponse {
+    pub fn from_result(result: Result<Value, String>) -> Self {
+        match result {
+            Ok(data) => Self {
+                ok: true,
+                data: Some(data),
+                error: None,
+            },
+            Err(e) => Self {
+                ok: false,
+                data: None,
+                error: Some(e),
+            },
+        }
+    }
+
+    pub fn into_result(self) -> Result<Value, String> {
+        if self.ok {
+            Ok(self.data.unwrap_or(Value::Null))
+        } else {
+            Err(self.error.unwrap_or_else(|| "未知错误".to_string()))
+        }
+    }
+}
+
+// 运行中实例的连接信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceInfo {
+    pub port: u16,
+    pub token: String,
+    pub pid: u32,
+}
+
+// 实例信息文件路径（始终位于默认数据目录，与设置文件相同）
+fn instance_file_path() -> Result<PathBuf, String> {
+    Ok(crate::settings::AppSettings::get_default_data_directory()?.join(INSTANCE_FILE_NAME))
+}
+
+pub fn read_instance_info() -> Option<InstanceInfo> {
+    let content = fs::read_to_string(instance_file_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn write_instance_info(info: &InstanceInfo) -> Result<(), String> {
+    let path = instance_file_path()?;
+    let content = serde_json::to_string(info).map_err(|e| format!("序列化实例信息失败: {}", e))?;
+    write_private_file(&path, content.as_bytes()).map_err(|e| format!("写入实例信息失败: {}", e))
+}
+
+// 写入只允许当前用户读取的文件：先以 0600 权限创建临时文件，写完后替换目标文件，
+// 令牌不会以默认权限出现在磁盘上
+fn write_private_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let temp_path = path.with_extension("tmp");
+    let _ = fs::remove_file(&temp_path);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&temp_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&temp_path, path)
+}
+
+pub fn remove_instance_info() {
+    if let Ok(path) = instance_file_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_private_file_atomically() {
+        let dir = std::env::temp_dir().join(format!("qc-cli-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(INSTANCE_FILE_NAME);
+
+        write_private_file(&path, b"first").unwrap();
+        write_private_file(&path, b"second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert!(!path.with_extension("tmp").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,101 @@
+// 运行中实例的命令行服务
+//
+// 仅监听 127.0.0.1，请求必须携带实例信息文件中的令牌
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::protocol::{self, CliRequest, CliResponse, InstanceInfo};
+
+// 单个请求的最大长度（push 的内容可能较大）
+const MAX_REQUEST_BYTES: u64 = 64 * 1024 * 1024;
+
+// 读取请求的超时时间，避免连接后不发送数据的客户端一直占用线程
+const READ_TIMEOUT_SECS: u64 = 10;
+
+static SERVER_STARTED: AtomicBool = AtomicBool::new(false);
+
+// 启动命令行服务
+pub fn start_cli_server(app_handle: AppHandle) {
+    if SERVER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let listener = match TcpListener::bind("127.0.0.1:0") {
+        Ok(listener) => listener,
+        Err(e) => {
+            println!("启动命令行服务失败: {}", e);
+            return;
+        }
+    };
+
+    let port = match listener.local_addr() {
+        Ok(addr) => addr.port(),
+        Err(e) => {
+            println!("获取命令行服务端口失败: {}", e);
+            return;
+        }
+    };
+
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let info = InstanceInfo {
+        port,
+        token: token.clone(),
+        pid: std::process::id(),
+    };
+    if let Err(e) = protocol::write_instance_info(&info) {
+        println!("{}", e);
+        return;
+    }
+
+    println!("命令行服务已启动: 127.0.0.1:{}", port);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let app_handle = app_handle.clone();
+                    let token = token.clone();
+                    std::thread::spawn(move || {
+                        if let Err(e) = handle_connection(stream, &token, &app_handle) {
+                            println!("处理命令行请求失败: {}", e);
+                        }
+                    });
+                }
+                Err(e) => println!("接受命令行连接失败: {}", e),
+            }
+        }
+    });
+}
+
+fn handle_connection(stream: TcpStream, token: &str, app_handle: &AppHandle) -> Result<(), String> {
+    stream
+        .set_read_timeout(Some(Duration::from_secs(READ_TIMEOUT_SECS)))
+        .map_err(|e| format!("设置读取超时失败: {}", e))?;
+    let mut writer = stream
+        .try_clone()
+        .map_err(|e| format!("复制连接失败: {}", e))?;
+
+    let mut line = String::new();
+    BufReader::new(stream.take(MAX_REQUEST_BYTES))
+        .read_line(&mut line)
+        .map_err(|e| format!("读取请求失败: {}", e))?;
+
+    let response = match serde_json::from_str::<CliRequest>(&line) {
+        Ok(request) if request.token == token => {
+            CliResponse::from_result(super::handler::execute(request.command, Some(app_handle)))
+        }
+        Ok(_) => CliResponse::from_result(Err("令牌无效".to_string())),
+        Err(e) => CliResponse::from_result(Err(format!("请求格式无效: {}", e))),
+    };
+
+    let mut body =
+        serde_json::to_string(&response).map_err(|e| format!("序列化响应失败: {}", e))?;
+    body.push('\n');
+    writer
+        .write_all(body.as_bytes())
+        .map_err(|e| format!("发送响应失败: {}", e))
+}
@@ -4,6 +4,8 @@ use image::{ImageBuffer, Rgba};
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
 
+use crate::clipboard_representations::{self as representations, Representation, RepresentationData};
+
 const CF_DIB: u32 = 8;
 
 pub static CLIPBOARD_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
@@ -97,19 +99,7 @@ pub fn set_windows_clipboard_image_with_file(
             return Err("清空剪贴板失败".into());
         }
 
-        let mut dib: Vec<u8> = Vec::with_capacity(40 + bgra.len());
-        dib.extend_from_slice(&(40u32).to_le_bytes());
-        dib.extend_from_slice(&(width as i32).to_le_bytes());
-        dib.extend_from_slice(&(-(height as i32)).to_le_bytes());
-        dib.extend_from_slice(&(1u16).to_le_bytes());
-        dib.extend_from_slice(&(32u16).to_le_bytes());
-        dib.extend_from_slice(&(0u32).to_le_bytes());
-        dib.extend_from_slice(&(0u32).to_le_bytes());
-        dib.extend_from_slice(&(0i32).to_le_bytes());
-        dib.extend_from_slice(&(0i32).to_le_bytes());
-        dib.extend_from_slice(&(0u32).to_le_bytes());
-        dib.extend_from_slice(&(0u32).to_le_bytes());
-        dib.extend_from_slice(bgra);
+        let dib = build_dib(bgra, width, height);
         match GlobalAlloc(GMEM_MOVEABLE, dib.len()) {
             Ok(hmem_dib) if !hmem_dib.0.is_null() => {
                 let ptr = GlobalLock(hmem_dib);
@@ -157,6 +147,25 @@ pub fn set_windows_clipboard_image_with_file(
     Ok(())
 }
 
+// 32位自上而下的 DIB（BITMAPINFOHEADER + BGRA 像素）
+#[cfg(windows)]
+fn build_dib(bgra: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mut dib: Vec<u8> = Vec::with_capacity(40 + bgra.len());
+    dib.extend_from_slice(&(40u32).to_le_bytes());
+    dib.extend_from_slice(&(width as i32).to_le_bytes());
+    dib.extend_from_slice(&(-(height as i32)).to_le_bytes());
+    dib.extend_from_slice(&(1u16).to_le_bytes());
+    dib.extend_from_slice(&(32u16).to_le_bytes());
+    dib.extend_from_slice(&(0u32).to_le_bytes());
+    dib.extend_from_slice(&(0u32).to_le_bytes());
+    dib.extend_from_slice(&(0i32).to_le_bytes());
+    dib.extend_from_slice(&(0i32).to_le_bytes());
+    dib.extend_from_slice(&(0u32).to_le_bytes());
+    dib.extend_from_slice(&(0u32).to_le_bytes());
+    dib.extend_from_slice(bgra);
+    dib
+}
+
 #[cfg(windows)]
 struct ClipboardGuard;
 
@@ -419,6 +428,23 @@ pub fn set_clipboard_content_no_history_with_html(content: String, html_content:
     set_clipboard_content_with_html_internal(content, html_content, false)
 }
 
+// 设置文本内容（可附带HTML）
+// Linux 原生后端可用时由其接管选区，arboard 释放实例后剪贴板内容可能随之丢失
+fn set_text_content(text: &str, html: Option<&str>) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if crate::linux_clipboard::is_available() {
+        let html = html.map(fix_image_urls);
+        return crate::linux_clipboard::write_text(text, html.as_deref());
+    }
+    let _ = html;
+    match Clipboard::new() {
+        Ok(mut clipboard) => clipboard
+            .set_text(text.to_string())
+            .map_err(|e| format!("设置剪贴板文本失败: {}", e)),
+        Err(e) => Err(format!("获取剪贴板失败: {}", e)),
+    }
+}
+
 // 内部函数：设置剪贴板内容（包含HTML格式）
 fn set_clipboard_content_with_html_internal(content: String, html_content: Option<String>, add_to_history: bool) -> Result<(), String> {
     if content.starts_with("data:image/") {
@@ -426,22 +452,15 @@ fn set_clipboard_content_with_html_internal(content: String, html_content: Optio
     } else if content.starts_with("image:") {
         return set_clipboard_content_internal(content, add_to_history);
     } else {
+        #[cfg(windows)]
         if let Some(html) = &html_content {
-            #[cfg(windows)]
-            {
-                set_windows_clipboard_both_formats(&content, html)?;
-            }
+            set_windows_clipboard_both_formats(&content, html)?;
         } else {
             // 只有纯文本
-            match Clipboard::new() {
-                Ok(mut clipboard) => {
-                    clipboard
-                        .set_text(content.clone())
-                        .map_err(|e| format!("设置剪贴板文本失败: {}", e))?;
-                }
-                Err(e) => return Err(format!("获取剪贴板失败: {}", e)),
-            }
+            set_text_content(&content, None)?;
         }
+        #[cfg(not(windows))]
+        set_text_content(&content, html_content.as_deref())?;
     }
     if add_to_history {
         println!("剪贴板内容已设置，将由监听器自动添加到历史记录");
@@ -454,7 +473,13 @@ fn set_clipboard_content_with_html_internal(content: String, html_content: Optio
 fn set_clipboard_content_internal(content: String, add_to_history: bool) -> Result<(), String> {
     if content.starts_with("data:image/") {
         let (bgra, png_bytes, width, height) = data_url_to_bgra_and_png(&content)?;
+        #[cfg(windows)]
         set_windows_clipboard_image(&bgra, &png_bytes, width, height)?;
+        #[cfg(target_os = "linux")]
+        {
+            let _ = (bgra, width, height);
+            crate::linux_clipboard::write_png(&png_bytes)?;
+        }
     } else if content.starts_with("image:") {
         let image_id = content.strip_prefix("image:").unwrap_or("");
 
@@ -466,26 +491,26 @@ fn set_clipboard_content_internal(content: String, add_to_history: bool) -> Resu
             .map_err(|e| format!("获取图片管理器锁失败: {}", e))?;
 
         let (bgra, png_bytes, width, height) = manager.get_image_bgra_and_png(image_id)?;
-        let file_path = manager.get_image_file_path(image_id)?;
+        // 加密的图片文件无法被其他程序读取，只写入图片数据
+        let file_path = manager.get_plain_image_file_path(image_id)?;
         drop(manager);
 
+        #[cfg(windows)]
         set_windows_clipboard_image_with_file(
             &bgra,
             &png_bytes,
             width,
             height,
-            Some(&file_path),
+            file_path.as_deref(),
         )?;
+        #[cfg(target_os = "linux")]
+        {
+            let _ = (bgra, width, height, file_path);
+            crate::linux_clipboard::write_png(&png_bytes)?;
+        }
         return Ok(());
     } else {
-        match Clipboard::new() {
-            Ok(mut clipboard) => {
-                clipboard
-                    .set_text(content.clone())
-                    .map_err(|e| format!("设置剪贴板文本失败: {}", e))?;
-            }
-            Err(e) => return Err(format!("获取剪贴板失败: {}", e)),
-        }
+        set_text_content(&content, None)?;
     }
     if add_to_history {
         println!("剪贴板内容已设置，将由监听器自动添加到历史记录");
@@ -493,3 +518,201 @@ fn set_clipboard_content_internal(content: String, add_to_history: bool) -> Resu
 
     Ok(())
 }
+
+// =================== 多格式写入 ===================
+
+// 将记录的多种格式一次性写入剪贴板
+pub fn set_clipboard_representations(representations: &[Representation]) -> Result<(), String> {
+    if representations.is_empty() {
+        return Err("没有可写入剪贴板的格式".to_string());
+    }
+
+    #[cfg(windows)]
+    return set_windows_clipboard_representations(representations);
+
+    #[cfg(target_os = "linux")]
+    if crate::linux_clipboard::is_available() {
+        return set_linux_clipboard_representations(representations);
+    }
+
+    // 没有原生剪贴板支持时只能写入文本
+    #[allow(unreachable_code)]
+    match representations
+        .iter()
+        .find(|r| r.mime_type == representations::MIME_TEXT)
+        .and_then(|r| r.as_text())
+    {
+        Some(text) => set_text_content(text, None),
+        None => Err("当前平台只支持写入文本格式".to_string()),
+    }
+}
+
+// 图片格式的 PNG 字节（图片记录引用的图片从图片管理器读取）
+fn representation_png(representation: &Representation) -> Result<Vec<u8>, String> {
+    if let Some(image_id) = representation.image_id() {
+        let image_manager = crate::image_manager::get_image_manager()?;
+        let manager = image_manager
+            .lock()
+            .map_err(|e| format!("获取图片管理器锁失败: {}", e))?;
+        return Ok(manager.read_image_png(image_id)?);
+    }
+    match &representation.data {
+        RepresentationData::Bytes(bytes) => Ok(bytes.clone()),
+        RepresentationData::Text(_) => Err("图片格式数据无效".to_string()),
+    }
+}
+
+// 文件格式中的文件路径
+fn representation_file_paths(representation: &Representation) -> Vec<String> {
+    representation
+        .as_text()
+        .and_then(|text| text.strip_prefix("files:"))
+        .and_then(|json| serde_json::from_str::<crate::file_handler::FileClipboardData>(json).ok())
+        .map(|data| data.files.into_iter().map(|f| f.path).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(windows)]
+fn set_windows_clipboard_representations(representations: &[Representation]) -> Result<(), String> {
+    use windows::core::w;
+    use windows::Win32::Foundation::{HANDLE, HWND};
+    use windows::Win32::System::DataExchange::{
+        EmptyClipboard, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+    };
+    use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+
+    const CF_UNICODETEXT: u32 = 13;
+
+    // 打开剪贴板前准备好全部数据（读取图片需要访问数据库）
+    let mut payloads: Vec<(u32, Vec<u8>)> = Vec::new();
+    let mut file_paths: Vec<String> = Vec::new();
+    unsafe {
+        for representation in representations {
+            match representation.mime_type.as_str() {
+                representations::MIME_TEXT => {
+                    let text = representation.as_text().unwrap_or_default();
+                    let wide: Vec<u8> = text
+                        .encode_utf16()
+                        .chain(std::iter::once(0))
+                        .flat_map(|unit| unit.to_le_bytes())
+                        .collect();
+                    payloads.push((CF_UNICODETEXT, wide));
+                }
+                representations::MIME_HTML => {
+                    let html = create_windows_html_format(representation.as_text().unwrap_or_default());
+                    let mut bytes = html.into_bytes();
+                    bytes.push(0);
+                    payloads.push((RegisterClipboardFormatW(w!("HTML Format")), bytes));
+                }
+                representations::MIME_RTF => {
+                    let mut bytes = representation.as_text().unwrap_or_default().as_bytes().to_vec();
+                    bytes.push(0);
+                    payloads.push((RegisterClipboardFormatW(w!("Rich Text Format")), bytes));
+                }
+                representations::MIME_PNG => {
+                    let png = representation_png(representation)?;
+                    let img = image::load_from_memory(&png)
+                        .map_err(|e| format!("解析PNG失败: {}", e))?
+                        .to_rgba8();
+                    let (width, height) = img.dimensions();
+                    let bgra: Vec<u8> = img
+                        .pixels()
+                        .flat_map(|px| {
+                            let [r, g, b, a] = px.0;
+                            [b, g, r, a]
+                        })
+                        .collect();
+                    payloads.push((CF_DIB, build_dib(&bgra, width, height)));
+                    payloads.push((RegisterClipboardFormatW(w!("PNG")), png));
+                }
+                representations::MIME_FILES => {
+                    file_paths = representation_file_paths(representation);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let _lock = CLIPBOARD_LOCK.lock().map_err(|e| format!("获取剪贴板锁失败: {}", e))?;
+
+    unsafe {
+        if OpenClipboard(HWND(0)).is_err() {
+            return Err("打开剪贴板失败".into());
+        }
+        let _guard = ClipboardGuard;
+
+        if EmptyClipboard().is_err() {
+            return Err("清空剪贴板失败".into());
+        }
+
+        for (format, bytes) in payloads {
+            if format == 0 || bytes.is_empty() {
+                continue;
+            }
+            let hmem = match GlobalAlloc(GMEM_MOVEABLE, bytes.len()) {
+                Ok(hmem) if !hmem.0.is_null() => hmem,
+                _ => return Err("分配剪贴板内存失败".to_string()),
+            };
+            let ptr = GlobalLock(hmem);
+            if ptr.is_null() {
+                return Err("锁定剪贴板内存失败".to_string());
+            }
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr as *mut u8, bytes.len());
+            let _ = GlobalUnlock(hmem);
+            if SetClipboardData(format, HANDLE(hmem.0 as isize)).is_err() {
+                eprintln!("设置剪贴板格式 {} 失败", format);
+            }
+        }
+
+        if !file_paths.is_empty() {
+            set_clipboard_hdrop_internal(&file_paths)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_linux_clipboard_representations(representations: &[Representation]) -> Result<(), String> {
+    use crate::linux_clipboard::formats;
+
+    let mut contents: crate::linux_clipboard::MimeContents = Vec::new();
+    for representation in representations {
+        match representation.mime_type.as_str() {
+            representations::MIME_TEXT => {
+                let text = representation.as_text().unwrap_or_default();
+                for mime in formats::TEXT_MIME_TYPES {
+                    contents.push((mime.to_string(), text.as_bytes().to_vec()));
+                }
+            }
+            representations::MIME_HTML => {
+                let html = fix_image_urls(representation.as_text().unwrap_or_default());
+                contents.push((formats::MIME_HTML.to_string(), html.into_bytes()));
+            }
+            representations::MIME_RTF => {
+                let rtf = representation.as_text().unwrap_or_default();
+                for mime in formats::RTF_MIME_TYPES {
+                    contents.push((mime.to_string(), rtf.as_bytes().to_vec()));
+                }
+            }
+            representations::MIME_PNG => {
+                contents.push((formats::MIME_PNG.to_string(), representation_png(representation)?));
+            }
+            representations::MIME_FILES => {
+                let paths = representation_file_paths(representation);
+                if !paths.is_empty() {
+                    contents.push((
+                        formats::MIME_GNOME_COPIED_FILES.to_string(),
+                        formats::build_gnome_copied_files("copy", &paths).into_bytes(),
+                    ));
+                    contents.push((
+                        formats::MIME_URI_LIST.to_string(),
+                        formats::build_uri_list(&paths).into_bytes(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    crate::linux_clipboard::write_contents(contents)
+}
@@ -47,17 +47,18 @@ pub fn add_to_history(text: String) {
 
 
 
-// 添加到历史记录并返回是否真正添加了新内容，支持HTML内容，控制是否移动重复内容
-pub fn add_to_history_with_check_and_move_html(text: String, html_content: Option<String>, move_duplicates: bool) -> bool {
+// 添加到历史记录，支持HTML内容，控制是否移动重复内容
+// 返回新增或被移动到最前面的项目ID，未改变历史记录时返回 None
+pub fn add_to_history_with_check_and_move_html(text: String, html_content: Option<String>, move_duplicates: bool) -> Option<i64> {
     if !MONITORING_ENABLED.load(Ordering::Relaxed) {
         println!("剪贴板监听已禁用，跳过添加历史记录");
-        return false;
+        return None;
     }
 
     // 过滤空白内容：检查去除空白字符后是否为空
     if text.trim().is_empty() {
         println!("跳过空白内容，不添加到历史记录");
-        return false;
+        return None;
     }
 
     // 检查是否已存在相同内容
@@ -67,22 +68,23 @@ pub fn add_to_history_with_check_and_move_html(text: String, html_content: Optio
                 // 移动到最前面（复制操作）
                 if let Err(e) = database::move_clipboard_item_to_front(existing_id) {
                     println!("移动剪贴板项目到前面失败: {}", e);
-                    return false;
+                    return None;
                 }
-                true // 移动了位置，算作添加了新内容
+                Some(existing_id) // 移动了位置，算作添加了新内容
             } else {
                 // 不移动重复内容（粘贴操作）
-                false
+                None
             }
         }
         Ok(None) => {
             // 新文本：使用智能添加函数根据内容类型自动判断
-            let result = database::add_clipboard_item_smart(text, html_content);
-            
-            if let Err(e) = result {
-                println!("添加剪贴板历史失败: {}", e);
-                return false;
-            }
+            let id = match database::add_clipboard_item_smart(text, html_content) {
+                Ok(id) => id,
+                Err(e) => {
+                    println!("添加剪贴板历史失败: {}", e);
+                    return None;
+                }
+            };
 
             // 限制历史记录数量
             let limit = *HISTORY_LIMIT.read().unwrap();
@@ -90,11 +92,14 @@ pub fn add_to_history_with_check_and_move_html(text: String, html_content: Optio
                 println!("限制剪贴板历史数量失败: {}", e);
             }
 
-            true // 添加了新内容
+            // 为新内容分类
+            crate::content_classifier::classify_pending_in_background();
+
+            Some(id) // 添加了新内容
         }
         Err(e) => {
             println!("检查剪贴板项目是否存在失败: {}", e);
-            false
+            None
         }
     }
 }
@@ -109,11 +114,11 @@ pub fn move_to_front_if_exists(text: String) -> bool {
 
     match database::clipboard_item_exists(&text) {
         Ok(Some(existing_id)) => {
-            // 获取当前历史记录以检查是否已经在第一位
-            match database::get_clipboard_history(Some(1)) {
-                Ok(items) => {
+            // 获取最近一次复制的项目以检查是否已经在第一位
+            match database::get_latest_clipboard_item() {
+                Ok(latest) => {
                     // 如果已经是第一位，不需要移动
-                    if !items.is_empty() && items[0].content == text {
+                    if latest.is_some_and(|item| item.content == text) {
                         return false;
                     }
 
@@ -138,6 +143,51 @@ pub fn move_to_front_if_exists(text: String) -> bool {
     }
 }
 
+// 添加由用户主动提交的内容（命令行、文本转换等），已存在时移动到最前面
+// 遵循敏感内容检测设置，返回项目ID和是否为新增
+pub fn add_item_with_filter(content: String, html_content: Option<String>) -> Result<(i64, bool), String> {
+    use crate::sensitive_detector::FilterDecision;
+
+    if content.trim().is_empty() {
+        return Err("内容为空".to_string());
+    }
+
+    let (content, html_content, expires_at) =
+        match crate::sensitive_detector::filter_clipboard_content(content, html_content) {
+            FilterDecision::Pass { content, html_content } => (content, html_content, None),
+            FilterDecision::Store {
+                content,
+                html_content,
+                expires_at,
+                ..
+            } => (content, html_content, expires_at),
+            FilterDecision::Skip { detectors } => {
+                return Err(format!(
+                    "内容包含敏感信息（{}），已按设置跳过记录",
+                    detectors.join(", ")
+                ));
+            }
+        };
+
+    let (id, is_new) = match database::clipboard_item_exists(&content)? {
+        Some(id) => {
+            database::move_clipboard_item_to_front(id)?;
+            (id, false)
+        }
+        None => {
+            let id = database::add_clipboard_item_smart(content, html_content)?;
+            database::limit_clipboard_history(get_history_limit())?;
+            (id, true)
+        }
+    };
+
+    if expires_at.is_some() {
+        database::set_clipboard_item_expiry(id, expires_at)?;
+    }
+
+    Ok((id, is_new))
+}
+
 // 获取历史记录数量限制
 pub fn get_history_limit() -> usize {
     *HISTORY_LIMIT.read().unwrap()
@@ -156,6 +206,23 @@ pub fn set_history_limit(limit: usize) {
     }
 }
 
+// 设置项目的置顶状态
+// 取消置顶后项目重新计入数量限制，超出的部分在下次添加内容时清理
+pub fn set_pinned(id: i64, pinned: bool) -> Result<(), String> {
+    if !database::set_clipboard_item_pinned(id, pinned)? {
+        return Err(format!("剪贴板项目 {} 不存在", id));
+    }
+    Ok(())
+}
+
+// 切换项目的置顶状态，返回新的状态
+pub fn toggle_pinned(id: i64) -> Result<bool, String> {
+    let pinned = database::is_clipboard_item_pinned(id)?
+        .ok_or_else(|| format!("剪贴板项目 {} 不存在", id))?;
+    set_pinned(id, !pinned)?;
+    Ok(!pinned)
+}
+
 // 移动单个项目到指定位置
 pub fn move_item(from_index: usize, to_index: usize) -> Result<(), String> {
     let items =
@@ -169,6 +236,14 @@ pub fn move_item(from_index: usize, to_index: usize) -> Result<(), String> {
         return Err(format!("目标索引 {} 超出范围", to_index));
     }
 
+    // 置顶的项目总是排在前面，只能在同一区域内移动
+    let pinned_count = items.iter().filter(|item| item.pinned).count();
+    let to_index = if items[from_index].pinned {
+        to_index.min(pinned_count.saturating_sub(1))
+    } else {
+        to_index.max(pinned_count)
+    };
+
     if from_index == to_index {
         return Ok(());
     }
@@ -230,7 +305,27 @@ pub fn clear_all() -> Result<(), String> {
 
 // 清理未使用的图片文件（孤儿图片）
 pub fn cleanup_orphaned_images() {
-    // 收集所有正在使用的图片ID
+    // 数据未解锁时无法读取引用关系，跳过清理以免误删图片
+    if crate::encryption::is_locked() {
+        return;
+    }
+
+    let used_image_ids = collect_used_image_ids();
+
+    // 调用图片管理器清理未使用的图片
+    if let Ok(image_manager) = get_image_manager() {
+        if let Ok(manager) = image_manager.lock() {
+            if let Err(e) = manager.cleanup_unused_images(&used_image_ids) {
+                println!("清理未使用的图片失败: {}", e);
+            } else {
+                println!("已清理未使用的图片，保留 {} 个正在使用的图片", used_image_ids.len());
+            }
+        }
+    }
+}
+
+// 收集剪贴板历史和收藏中正在使用的图片ID
+pub fn collect_used_image_ids() -> Vec<String> {
     let mut used_image_ids = Vec::new();
     
     // 从剪贴板历史中收集图片ID
@@ -267,21 +362,12 @@ pub fn cleanup_orphaned_images() {
             }
         }
     }
-    
-    // 调用图片管理器清理未使用的图片
-    if let Ok(image_manager) = get_image_manager() {
-        if let Ok(manager) = image_manager.lock() {
-            if let Err(e) = manager.cleanup_unused_images(&used_image_ids) {
-                println!("清理未使用的图片失败: {}", e);
-            } else {
-                println!("已清理未使用的图片，保留 {} 个正在使用的图片", used_image_ids.len());
-            }
-        }
-    }
+
+    used_image_ids
 }
 
 // 从HTML内容中提取所有图片ID
-fn extract_image_ids_from_html(html: &str, image_ids: &mut Vec<String>) {
+pub fn extract_image_ids_from_html(html: &str, image_ids: &mut Vec<String>) {
     // 也匹配 onerror 等属性中的图片ID
     let patterns = [
         r#"src="image-id:([a-f0-9]+)""#,
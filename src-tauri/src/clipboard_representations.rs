@@ -0,0 +1,297 @@
+// 剪贴板记录的多格式表示
+//
+// 程序复制内容时通常同时提供多种格式（纯文本、HTML、RTF、图片、文件列表），
+// 每种格式作为剪贴板记录的一个子行保存在 clipboard_representations 表中：MIME 类型、文本或字节、大小。
+// 粘贴时一次性恢复全部格式，也可以只粘贴指定的格式。
+// clipboard 表的 content / html_content 仍是记录的主格式，用于列表展示、搜索和去重。
+//
+// 图片记录自身的 image/png 表示引用图片管理器中的文件（文本为 image:<id>），不重复保存；
+// 其他记录附带的图片以 PNG 字节保存。没有子行的记录（旧版本数据、导入或同步的记录）按主格式推导。
+
+use rusqlite::{params, Result as SqliteResult};
+use serde::Serialize;
+
+use crate::error::{AppError, AppResult};
+
+pub const MIME_TEXT: &str = "text/plain";
+pub const MIME_HTML: &str = "text/html";
+pub const MIME_RTF: &str = "text/rtf";
+pub const MIME_PNG: &str = "image/png";
+// 文件列表，数据与剪贴板记录的 content 相同（files: 开头的 JSON）
+pub const MIME_FILES: &str = "application/x-quickclipboard-files";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepresentationData {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Representation {
+    pub mime_type: String,
+    pub data: RepresentationData,
+}
+
+impl Representation {
+    pub fn text(mime_type: &str, text: String) -> Self {
+        Self {
+            mime_type: mime_type.to_string(),
+            data: RepresentationData::Text(text),
+        }
+    }
+
+    pub fn bytes(mime_type: &str, bytes: Vec<u8>) -> Self {
+        Self {
+            mime_type: mime_type.to_string(),
+            data: RepresentationData::Bytes(bytes),
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match &self.data {
+            RepresentationData::Text(text) => Some(text),
+            RepresentationData::Bytes(_) => None,
+        }
+    }
+
+    // 引用的图片ID（图片记录的 image/png 表示）
+    pub fn image_id(&self) -> Option<&str> {
+        if self.mime_type != MIME_PNG {
+            return None;
+        }
+        self.as_text()?.strip_prefix("image:")
+    }
+
+    // 数据大小（字节），引用的图片按图片文件大小计算
+    pub fn size(&self) -> i64 {
+        if let Some(image_id) = self.image_id() {
+            return crate::image_manager::get_image_manager()
+                .ok()
+                .and_then(|manager| manager.lock().ok().map(|m| m.get_image_file_size(image_id)))
+                .unwrap_or(0) as i64;
+        }
+        match &self.data {
+            RepresentationData::Text(text) => text.len() as i64,
+            RepresentationData::Bytes(bytes) => bytes.len() as i64,
+        }
+    }
+}
+
+// 提供给前端选择粘贴格式的信息（不包含数据）
+#[derive(Debug, Clone, Serialize)]
+pub struct RepresentationInfo {
+    pub mime_type: String,
+    pub size: i64,
+    // 是否为记录的主格式
+    pub primary: bool,
+}
+
+// 按剪贴板记录的主格式推导表示（第一个为主格式）
+pub fn derive_from_content(content: &str, html_content: Option<&str>) -> Vec<Representation> {
+    if content.starts_with("image:") {
+        return vec![Representation::text(MIME_PNG, content.to_string())];
+    }
+    if content.starts_with("files:") {
+        return vec![Representation::text(MIME_FILES, content.to_string())];
+    }
+
+    let mut representations = vec![Representation::text(MIME_TEXT, content.to_string())];
+    if let Some(html) = html_content.filter(|html| !html.trim().is_empty()) {
+        representations.push(Representation::text(MIME_HTML, html.to_string()));
+    }
+    representations
+}
+
+// 合并主格式和额外捕获的格式（同一 MIME 类型以先出现的为准）
+pub fn merge(primary: Vec<Representation>, extras: Vec<Representation>) -> Vec<Representation> {
+    let mut merged = primary;
+    for extra in extras {
+        if !merged.iter().any(|r| r.mime_type == extra.mime_type) {
+            merged.push(extra);
+        }
+    }
+    merged
+}
+
+// =================== 数据库操作 ===================
+
+// 保存记录的全部表示（替换已有的子行）
+pub fn save(item_id: i64, representations: &[Representation]) -> AppResult<()> {
+    // 先计算大小：引用图片的大小需要访问图片管理器，不能在持有数据库锁时进行
+    let sizes: Vec<i64> = representations.iter().map(|r| r.size()).collect();
+
+    crate::database::with_connection(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM clipboard_representations WHERE item_id = ?1",
+            params![item_id],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO clipboard_representations (item_id, mime_type, text_data, blob_data, size)
+                 VALUES (?1, ?2, qc_encrypt(?3), qc_encrypt_blob(?4), ?5)",
+            )?;
+            for (representation, size) in representations.iter().zip(sizes) {
+                let (text, bytes) = match &representation.data {
+                    RepresentationData::Text(text) => (Some(text.as_str()), None),
+                    RepresentationData::Bytes(bytes) => (None, Some(bytes.as_slice())),
+                };
+                stmt.execute(params![item_id, representation.mime_type, text, bytes, size])?;
+            }
+        }
+        tx.commit()
+    })
+}
+
+// 保存捕获到的表示：主格式按数据库中的记录推导（与展示、搜索一致），再附加其他格式
+pub fn save_captured(item_id: i64, extras: Vec<Representation>) -> AppResult<()> {
+    let item = crate::database::get_clipboard_item_by_id(item_id)?
+        .ok_or_else(|| AppError::clipboard_item_not_found(item_id))?;
+    let primary = derive_from_content(&item.content, item.html_content.as_deref());
+    save(item_id, &merge(primary, extras))
+}
+
+// 记录内容被编辑后，其他格式已不再对应，只保留按新内容推导的表示
+pub fn reset(item_id: i64) -> AppResult<()> {
+    crate::database::with_connection(|conn| {
+        conn.execute(
+            "DELETE FROM clipboard_representations WHERE item_id = ?1",
+            params![item_id],
+        )?;
+        Ok(())
+    })
+}
+
+// 读取记录的全部表示（没有子行时按主格式推导）
+pub fn load(item_id: i64) -> AppResult<Vec<Representation>> {
+    let stored = crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT mime_type, qc_decrypt(text_data), qc_decrypt_blob(blob_data)
+             FROM clipboard_representations WHERE item_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![item_id], |row| {
+            let mime_type: String = row.get(0)?;
+            let text: Option<String> = row.get(1)?;
+            let bytes: Option<Vec<u8>> = row.get(2)?;
+            Ok(Representation {
+                mime_type,
+                data: match text {
+                    Some(text) => RepresentationData::Text(text),
+                    None => RepresentationData::Bytes(bytes.unwrap_or_default()),
+                },
+            })
+        })?;
+        rows.collect::<SqliteResult<Vec<_>>>()
+    })?;
+
+    if !stored.is_empty() {
+        return Ok(stored);
+    }
+
+    let item = crate::database::get_clipboard_item_by_id(item_id)?
+        .ok_or_else(|| AppError::clipboard_item_not_found(item_id))?;
+    Ok(derive_from_content(&item.content, item.html_content.as_deref()))
+}
+
+// 读取指定格式
+pub fn load_one(item_id: i64, mime_type: &str) -> AppResult<Representation> {
+    load(item_id)?
+        .into_iter()
+        .find(|r| r.mime_type == mime_type)
+        .ok_or_else(|| {
+            AppError::not_found(format!("该记录没有 {} 格式", mime_type))
+                .with_key("error.clipboard.format_not_found")
+                .with_context("id", item_id)
+                .with_context("mime_type", mime_type)
+        })
+}
+
+// 列出记录的全部格式和大小（迁移的旧记录大小未知，读取时补全）
+pub fn list(item_id: i64) -> AppResult<Vec<RepresentationInfo>> {
+    let stored = crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT mime_type, size FROM clipboard_representations WHERE item_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![item_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        rows.collect::<SqliteResult<Vec<_>>>()
+    })?;
+
+    if stored.is_empty() || stored.iter().any(|(_, size)| *size < 0) {
+        let sizes: Vec<(String, i64)> = load(item_id)?
+            .iter()
+            .map(|r| (r.mime_type.clone(), r.size()))
+            .collect();
+        if !stored.is_empty() {
+            update_sizes(item_id, &sizes)?;
+        }
+        return Ok(to_infos(sizes));
+    }
+
+    Ok(to_infos(stored))
+}
+
+fn to_infos(entries: Vec<(String, i64)>) -> Vec<RepresentationInfo> {
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, (mime_type, size))| RepresentationInfo {
+            mime_type,
+            size,
+            primary: index == 0,
+        })
+        .collect()
+}
+
+fn update_sizes(item_id: i64, sizes: &[(String, i64)]) -> AppResult<()> {
+    crate::database::with_connection(|conn| {
+        for (mime_type, size) in sizes {
+            conn.execute(
+                "UPDATE clipboard_representations SET size = ?1 WHERE item_id = ?2 AND mime_type = ?3",
+                params![size, item_id, mime_type],
+            )?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_primary_formats() {
+        let reps = derive_from_content("hello", Some("<b>hello</b>"));
+        assert_eq!(
+            reps,
+            vec![
+                Representation::text(MIME_TEXT, "hello".to_string()),
+                Representation::text(MIME_HTML, "<b>hello</b>".to_string()),
+            ]
+        );
+        assert_eq!(derive_from_content("hello", Some("  ")).len(), 1);
+
+        let image = derive_from_content("image:abc", None);
+        assert_eq!(image[0].mime_type, MIME_PNG);
+        assert_eq!(image[0].image_id(), Some("abc"));
+
+        assert_eq!(derive_from_content("files:{}", None)[0].mime_type, MIME_FILES);
+    }
+
+    #[test]
+    fn merge_keeps_primary_first() {
+        let merged = merge(
+            derive_from_content("hello", None),
+            vec![
+                Representation::text(MIME_TEXT, "other".to_string()),
+                Representation::text(MIME_RTF, "{\\rtf1 hello}".to_string()),
+                Representation::bytes(MIME_PNG, vec![1, 2, 3]),
+            ],
+        );
+        let mimes: Vec<&str> = merged.iter().map(|r| r.mime_type.as_str()).collect();
+        assert_eq!(mimes, vec![MIME_TEXT, MIME_RTF, MIME_PNG]);
+        assert_eq!(merged[0].as_text(), Some("hello"));
+        assert_eq!(merged[2].size(), 3);
+    }
+}
@@ -6,6 +6,7 @@ use tauri::Manager;
 use crate::admin_privileges;
 use crate::clipboard_history::{self, ClipboardItem};
 use crate::database::{FavoriteItem, GroupInfo};
+use crate::error::{AppError, AppResult};
 use std::sync::atomic::Ordering;
 
 #[derive(Deserialize)]
@@ -168,7 +169,7 @@ pub fn disable_mouse_monitoring_command() -> Result<(), String> {
 
 // 设置开机自启动
 #[tauri::command]
-pub fn set_startup_launch(enabled: bool) -> Result<(), String> {
+pub fn set_startup_launch(enabled: bool) -> AppResult<()> {
     crate::settings::SettingsService::set_startup_launch(enabled)
 }
 
@@ -179,6 +180,14 @@ pub fn set_history_limit(limit: usize) -> Result<(), String> {
     Ok(())
 }
 
+// 立即按当前保留策略清理历史记录
+#[tauri::command]
+pub fn run_retention_sweep(app: tauri::AppHandle) -> Result<crate::retention::RetentionReport, String> {
+    let settings = crate::settings::get_global_settings();
+    let policy = crate::retention::RetentionPolicy::from_settings(&settings);
+    crate::retention::run_sweep_and_notify(&app, &policy)
+}
+
 // =================== 拖拽排序相关命令 ===================
 
 // 移动剪贴板项目到指定位置
@@ -247,14 +256,14 @@ pub async fn open_text_editor_window(app: tauri::AppHandle) -> Result<(), String
 
 // 获取设置
 #[tauri::command]
-pub fn get_settings() -> Result<serde_json::Value, String> {
+pub fn get_settings() -> AppResult<serde_json::Value> {
     let settings = crate::settings::get_global_settings();
     Ok(crate::settings::SettingsConverter::to_json(&settings))
 }
 
 // 重新加载设置
 #[tauri::command]
-pub fn reload_settings() -> Result<serde_json::Value, String> {
+pub fn reload_settings() -> AppResult<serde_json::Value> {
     let fresh_settings = crate::settings::SettingsStorage::load_or_default();
     
     if let Err(e) = crate::settings::update_global_settings(fresh_settings.clone()) {
@@ -269,7 +278,7 @@ pub fn reload_settings() -> Result<serde_json::Value, String> {
 pub fn save_settings(
     app_handle: tauri::AppHandle,
     settings: serde_json::Value,
-) -> Result<(), String> {
+) -> AppResult<()> {
     crate::settings::SettingsService::save_settings(app_handle, settings)
 }
 
@@ -364,13 +373,13 @@ pub fn get_sound_status() -> Result<serde_json::Value, String> {
 
 // 获取图片文件路径
 #[tauri::command]
-pub fn get_image_file_path(content: String) -> Result<String, String> {
+pub fn get_image_file_path(content: String) -> AppResult<String> {
     crate::services::image_service::ImageService::get_image_file_path(content)
 }
 
 // 保存图片到指定路径
 #[tauri::command]
-pub fn save_image_to_file(content: String, file_path: String) -> Result<(), String> {
+pub fn save_image_to_file(content: String, file_path: String) -> AppResult<()> {
     crate::services::image_service::ImageService::save_image_to_file(content, file_path)
 }
 
@@ -388,16 +397,233 @@ pub async fn cancel_preview() -> Result<(), String> {
 
 // 删除剪贴板项目
 #[tauri::command]
-pub fn delete_clipboard_item(id: i64) -> Result<(), String> {
+pub fn delete_clipboard_item(id: i64) -> AppResult<()> {
     crate::database::delete_clipboard_item(id)
 }
 
+// 置顶剪贴板项目
+#[tauri::command]
+pub fn pin_clipboard_item(app: tauri::AppHandle, id: i64) -> Result<(), String> {
+    crate::services::clipboard_service::ClipboardService::set_pinned(&app, id, true)
+}
+
+// 取消置顶剪贴板项目
+#[tauri::command]
+pub fn unpin_clipboard_item(app: tauri::AppHandle, id: i64) -> Result<(), String> {
+    crate::services::clipboard_service::ClipboardService::set_pinned(&app, id, false)
+}
+
+// 切换剪贴板项目的置顶状态，返回新的状态
+#[tauri::command]
+pub fn toggle_clipboard_item_pin(app: tauri::AppHandle, id: i64) -> Result<bool, String> {
+    crate::services::clipboard_service::ClipboardService::toggle_pinned(&app, id)
+}
+
+// 切换当前预览项的置顶状态
+#[tauri::command]
+pub fn toggle_preview_item_pin(app: tauri::AppHandle) -> Result<(), String> {
+    crate::services::preview_service::PreviewService::toggle_current_item_pin(&app)
+}
+
+// =================== 全文搜索命令 ===================
+
+// 全文搜索剪贴板历史
+#[tauri::command]
+pub fn search_clipboard(
+    params: crate::database_search::SearchParams,
+) -> AppResult<crate::database_search::SearchPage<crate::database_search::ClipboardSearchHit>> {
+    crate::database_search::search_clipboard(&params)
+}
+
+// 全文搜索收藏
+#[tauri::command]
+pub fn search_favorites(
+    params: crate::database_search::SearchParams,
+) -> AppResult<crate::database_search::SearchPage<crate::database_search::FavoriteSearchHit>> {
+    crate::database_search::search_favorites(&params)
+}
+
+// 重建全文搜索索引
+#[tauri::command]
+pub fn rebuild_search_index() -> AppResult<()> {
+    crate::database_search::rebuild_search_index()
+}
+
+// =================== 敏感内容检测命令 ===================
+
+// 获取内置敏感内容检测器列表
+#[tauri::command]
+pub fn get_builtin_sensitive_detectors() -> Vec<crate::sensitive_detector::BuiltinDetectorInfo> {
+    crate::sensitive_detector::BUILTIN_DETECTORS.to_vec()
+}
+
+// 测试文本命中的敏感内容规则（可传入尚未保存的自定义规则）
+#[tauri::command]
+pub fn test_sensitive_detection(
+    text: String,
+    rules: Option<Vec<crate::settings::SensitiveRule>>,
+) -> Result<Vec<crate::sensitive_detector::SensitiveMatch>, String> {
+    let mut settings = crate::settings::get_global_settings();
+    if let Some(rules) = rules {
+        settings.sensitive_custom_rules = rules;
+    }
+    crate::sensitive_detector::test_detection(&text, &settings)
+}
+
+// 测试样本命中的剪贴板记录规则（可传入尚未保存的规则）
+#[tauri::command]
+pub fn test_capture_rules(
+    sample: crate::capture_rules::CaptureSample,
+    rules: Option<Vec<crate::settings::CaptureRule>>,
+) -> AppResult<crate::capture_rules::RuleTestResult> {
+    let rules = rules.unwrap_or_else(|| crate::settings::get_global_settings().capture_rules);
+    crate::capture_rules::test_rules(&rules, &sample)
+}
+
+// =================== 片段模板命令 ===================
+
+// 预览片段模板（不弹出输入框，inputs 可提供 {{input:名称}} 的取值）
+#[tauri::command]
+pub fn preview_snippet_template(
+    content: String,
+    inputs: Option<std::collections::HashMap<String, String>>,
+) -> Result<crate::snippet_template::TemplatePreview, String> {
+    crate::snippet_template::preview(&content, inputs.unwrap_or_default())
+}
+
+// =================== 数据加密命令 ===================
+
+// 获取数据加密状态
+#[tauri::command]
+pub fn get_encryption_status() -> crate::encryption::EncryptionStatus {
+    crate::encryption::get_status()
+}
+
+// 使用口令解锁加密数据
+#[tauri::command]
+pub async fn unlock_encryption(app: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || crate::encryption::unlock(&passphrase))
+        .await
+        .map_err(|e| format!("解锁任务执行失败: {}", e))??;
+    crate::encryption::notify_status_changed(&app);
+    // 锁定期间新增的记录在解锁后分类
+    crate::content_classifier::classify_pending_in_background();
+    Ok(())
+}
+
+// 锁定加密数据
+#[tauri::command]
+pub fn lock_encryption(app: tauri::AppHandle) -> Result<(), String> {
+    crate::encryption::lock()?;
+    crate::encryption::notify_status_changed(&app);
+    Ok(())
+}
+
+// 启用数据加密（加密现有数据）
+#[tauri::command]
+pub async fn enable_encryption(app: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || crate::encryption::enable(&passphrase))
+        .await
+        .map_err(|e| format!("加密任务执行失败: {}", e))??;
+    crate::encryption::notify_status_changed(&app);
+    Ok(())
+}
+
+// 关闭数据加密（解密现有数据）
+#[tauri::command]
+pub async fn disable_encryption(app: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || crate::encryption::disable(&passphrase))
+        .await
+        .map_err(|e| format!("解密任务执行失败: {}", e))??;
+    crate::encryption::notify_status_changed(&app);
+    Ok(())
+}
+
+// 修改加密口令
+#[tauri::command]
+pub async fn change_encryption_passphrase(
+    old_passphrase: String,
+    new_passphrase: String,
+) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::encryption::change_passphrase(&old_passphrase, &new_passphrase)
+    })
+    .await
+    .map_err(|e| format!("修改口令任务执行失败: {}", e))?
+}
+
+// =================== 本地 HTTP 接口命令 ===================
+
+// 获取本地接口服务状态
+#[tauri::command]
+pub fn get_api_server_status() -> crate::api_server::ApiServerStatus {
+    crate::api_server::get_status()
+}
+
+// 重新生成本地接口访问令牌
+#[tauri::command]
+pub fn regenerate_api_server_token() -> Result<String, String> {
+    crate::api_server::regenerate_token()
+}
+
+// =================== 多设备同步命令 ===================
+
+// 立即同步一次
+#[tauri::command]
+pub async fn sync_now(app: tauri::AppHandle) -> Result<crate::sync::SyncReport, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::sync::sync_now(&app))
+        .await
+        .map_err(|e| format!("同步任务失败: {}", e))?
+}
+
+// 获取同步状态
+#[tauri::command]
+pub fn get_sync_status() -> crate::sync::SyncStatus {
+    crate::sync::get_status()
+}
+
 // 更新剪贴板项目内容
 #[tauri::command]
-pub fn update_clipboard_item(id: i64, content: String) -> Result<(), String> {
+pub fn update_clipboard_item(id: i64, content: String) -> AppResult<()> {
     crate::database::update_clipboard_item(id, content)
 }
 
+// 获取剪贴板项目保存的全部格式（供选择粘贴格式）
+#[tauri::command]
+pub fn get_clipboard_item_formats(
+    id: i64,
+) -> AppResult<Vec<crate::clipboard_representations::RepresentationInfo>> {
+    crate::clipboard_representations::list(id)
+}
+
+// =================== 内容分类命令 ===================
+
+// 获取剪贴板项目的分类标签
+#[tauri::command]
+pub fn get_clipboard_item_tags(id: i64) -> AppResult<Vec<crate::content_classifier::ItemTag>> {
+    crate::content_classifier::get_item_tags(id)
+}
+
+// 获取各分类标签的记录数量（供历史记录筛选）
+#[tauri::command]
+pub fn get_clipboard_tag_counts() -> AppResult<Vec<crate::content_classifier::TagCount>> {
+    crate::content_classifier::get_tag_counts()
+}
+
+// 按分类标签筛选剪贴板历史（代码可用 code:语言 筛选）
+#[tauri::command]
+pub fn get_clipboard_history_by_tag(tag: String) -> AppResult<Vec<ClipboardItem>> {
+    crate::content_classifier::get_history_by_tag(&tag)
+}
+
+// 获取剪贴板项目可用的类型相关操作
+#[tauri::command]
+pub fn get_clipboard_item_actions(
+    id: i64,
+) -> AppResult<Vec<crate::content_classifier::ContentAction>> {
+    crate::content_classifier::get_item_actions(id)
+}
+
 // 清空剪贴板历史
 #[tauri::command]
 pub fn clear_clipboard_history() -> Result<(), String> {
@@ -461,7 +687,7 @@ pub fn get_main_window_state() -> Result<serde_json::Value, String> {
 
 // 更新主题设置
 #[tauri::command]
-pub fn update_theme_setting(theme: String) -> Result<(), String> {
+pub fn update_theme_setting(theme: String) -> AppResult<()> {
     let mut settings = crate::settings::get_global_settings();
     settings.theme = theme;
     crate::settings::update_global_settings(settings)?;
@@ -516,9 +742,9 @@ pub fn send_startup_notification(app: tauri::AppHandle) -> Result<(), String> {
 
     let admin_status = admin_privileges::get_admin_status();
     let status_text = if admin_status.is_admin {
-        "（管理员模式）"
+        crate::i18n::t("notification.startup.admin")
     } else {
-        ""
+        String::new()
     };
 
     // 获取当前设置的快捷键
@@ -529,9 +755,9 @@ pub fn send_startup_notification(app: tauri::AppHandle) -> Result<(), String> {
         app_settings.toggle_shortcut.clone()
     };
 
-    let notification_body = format!(
-        "QuickClipboard 已启动{}\n按 {} 打开剪贴板",
-        status_text, shortcut_key
+    let notification_body = crate::i18n::t_with(
+        "notification.startup.body",
+        &[("admin", &status_text), ("shortcut", &shortcut_key)],
     );
 
     match app
@@ -556,49 +782,118 @@ pub fn send_startup_notification(app: tauri::AppHandle) -> Result<(), String> {
 
 // 测试AI翻译配置
 #[tauri::command]
-pub async fn test_ai_translation() -> Result<String, String> {
+pub async fn test_ai_translation() -> AppResult<String> {
     crate::services::translation_service::test_ai_translation().await
 }
 
 // 取消正在进行的翻译
 #[tauri::command]
-pub fn cancel_translation() -> Result<(), String> {
+pub fn cancel_translation() -> AppResult<()> {
     crate::services::translation_service::cancel_translation()
 }
 
+// 对历史记录或常用文本执行自定义AI操作
+#[tauri::command]
+pub async fn run_ai_action(
+    params: crate::services::ai_action_service::AIActionParams,
+    window: WebviewWindow,
+) -> AppResult<String> {
+    crate::services::ai_action_service::run_ai_action(params, window).await
+}
+
+// 取消正在进行的AI操作（不指定 request_id 时取消全部）
+#[tauri::command]
+pub fn cancel_ai_action(request_id: Option<u64>) -> AppResult<()> {
+    crate::services::ai_action_service::cancel_ai_action(request_id)
+}
+
+// =================== 翻译记忆命令 ===================
+
+// 列出翻译记忆
+#[tauri::command]
+pub fn list_translation_memory(
+    query: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> AppResult<Vec<crate::translation_memory::TranslationMemoryEntry>> {
+    crate::translation_memory::list(query.as_deref(), limit.unwrap_or(100), offset.unwrap_or(0))
+}
+
+// 模糊查找相似原文的译文（默认使用当前目标语言）
+#[tauri::command]
+pub fn lookup_translation_memory(
+    text: String,
+    target_language: Option<String>,
+    min_similarity: Option<f64>,
+    limit: Option<usize>,
+) -> AppResult<Vec<crate::translation_memory::TranslationMemoryMatch>> {
+    let target_language =
+        target_language.unwrap_or_else(|| crate::settings::get_global_settings().ai_target_language);
+    crate::translation_memory::lookup_fuzzy(
+        &text,
+        &target_language,
+        min_similarity
+            .unwrap_or(crate::translation_memory::DEFAULT_MIN_SIMILARITY)
+            .clamp(0.0, 1.0),
+        limit.unwrap_or(10),
+    )
+}
+
+// 修改翻译记忆的译文或确认状态
+#[tauri::command]
+pub fn update_translation_memory_entry(
+    id: i64,
+    translation: Option<String>,
+    confirmed: Option<bool>,
+) -> AppResult<()> {
+    crate::translation_memory::update_entry(id, translation, confirmed)
+}
+
+// 删除翻译记忆
+#[tauri::command]
+pub fn delete_translation_memory_entry(id: i64) -> AppResult<()> {
+    crate::translation_memory::delete_entry(id)
+}
+
+// 清空翻译记忆
+#[tauri::command]
+pub fn clear_translation_memory() -> AppResult<usize> {
+    crate::translation_memory::clear()
+}
+
 // 启用AI翻译取消快捷键
 #[tauri::command]
-pub fn enable_ai_translation_cancel_shortcut() -> Result<(), String> {
+pub fn enable_ai_translation_cancel_shortcut() -> AppResult<()> {
     crate::services::translation_service::enable_ai_translation_cancel_shortcut()
 }
 
 // 禁用AI翻译取消快捷键
 #[tauri::command]
-pub fn disable_ai_translation_cancel_shortcut() -> Result<(), String> {
+pub fn disable_ai_translation_cancel_shortcut() -> AppResult<()> {
     crate::services::translation_service::disable_ai_translation_cancel_shortcut()
 }
 
 // 翻译文本并直接粘贴（非流式）
 #[tauri::command]
-pub async fn translate_and_paste_text(text: String) -> Result<(), String> {
+pub async fn translate_and_paste_text(text: String) -> AppResult<()> {
     crate::services::translation_service::translate_and_paste_text(text).await
 }
 
 // 翻译文本并流式输入
 #[tauri::command]
-pub async fn translate_and_input_text(text: String) -> Result<(), String> {
+pub async fn translate_and_input_text(text: String) -> AppResult<()> {
     crate::services::translation_service::translate_and_input_text(text).await
 }
 
 // 智能翻译文本（根据设置选择流式输入或直接粘贴）
 #[tauri::command]
-pub async fn translate_text_smart(text: String) -> Result<(), String> {
+pub async fn translate_text_smart(text: String) -> AppResult<()> {
     crate::services::translation_service::translate_text_smart(text).await
 }
 
 // 复制时翻译并直接输入到目标位置
 #[tauri::command]
-pub async fn translate_and_input_on_copy(text: String) -> Result<(), String> {
+pub async fn translate_and_input_on_copy(text: String) -> AppResult<()> {
     crate::services::translation_service::translate_and_input_on_copy(text).await
 }
 
@@ -610,7 +905,7 @@ pub fn is_currently_pasting() -> bool {
 
 // 检查AI翻译配置是否有效
 #[tauri::command]
-pub fn check_ai_translation_config() -> Result<bool, String> {
+pub fn check_ai_translation_config() -> AppResult<bool> {
     crate::services::translation_service::check_ai_translation_config()
 }
 
@@ -620,22 +915,22 @@ pub fn check_ai_translation_config() -> Result<bool, String> {
 pub async fn copy_files_to_directory(
     files: Vec<String>,
     target_dir: String,
-) -> Result<Vec<String>, String> {
+) -> AppResult<Vec<String>> {
     crate::services::file_operation_service::FileOperationService::copy_files_to_directory(files, target_dir).await
 }
 
 #[tauri::command]
-pub async fn get_file_info(path: String) -> Result<crate::file_handler::FileInfo, String> {
+pub async fn get_file_info(path: String) -> AppResult<crate::file_handler::FileInfo> {
     crate::services::file_operation_service::FileOperationService::get_file_info(path).await
 }
 
 #[tauri::command]
-pub async fn get_clipboard_files() -> Result<Vec<String>, String> {
+pub async fn get_clipboard_files() -> AppResult<Vec<String>> {
     crate::services::file_operation_service::FileOperationService::get_clipboard_files().await
 }
 
 #[tauri::command]
-pub async fn set_clipboard_files(files: Vec<String>) -> Result<(), String> {
+pub async fn set_clipboard_files(files: Vec<String>) -> AppResult<()> {
     crate::services::file_operation_service::FileOperationService::set_clipboard_files(files).await
 }
 
@@ -645,6 +940,12 @@ pub async fn get_available_ai_models() -> Result<Vec<String>, String> {
     crate::services::ai_service::AIService::get_available_ai_models().await
 }
 
+// 获取各服务提供方最近一分钟的token用量
+#[tauri::command]
+pub fn get_ai_token_usage() -> Vec<crate::ai_request::TokenUsage> {
+    crate::services::ai_service::AIService::get_ai_token_usage()
+}
+
 // 测试AI配置
 #[tauri::command]
 pub async fn test_ai_config() -> Result<bool, String> {
@@ -668,12 +969,12 @@ pub async fn test_ai_config() -> Result<bool, String> {
 
 // 打开文件位置
 #[tauri::command]
-pub async fn open_file_location(file_path: String) -> Result<(), String> {
+pub async fn open_file_location(file_path: String) -> AppResult<()> {
     crate::services::file_operation_service::FileOperationService::open_file_location(file_path).await
 }
 // 使用默认程序打开文件
 #[tauri::command]
-pub async fn open_file_with_default_program(file_path: String) -> Result<(), String> {
+pub async fn open_file_with_default_program(file_path: String) -> AppResult<()> {
     crate::services::file_operation_service::FileOperationService::open_file_with_default_program(file_path).await
 }
 
@@ -686,9 +987,99 @@ pub async fn paste_content(
     crate::services::paste_service::paste_content(params, window).await
 }
 
+// =================== 粘贴队列命令 ===================
+
+// 获取粘贴队列状态
+#[tauri::command]
+pub fn get_paste_queue() -> crate::paste_queue::PasteQueueState {
+    crate::paste_queue::state()
+}
+
+// 开启或关闭粘贴队列（关闭时清空队列）
+#[tauri::command]
+pub fn set_paste_queue_enabled(app: tauri::AppHandle, enabled: bool) {
+    crate::paste_queue::set_enabled(&app, enabled);
+}
+
+// 设置粘贴队列的取出顺序：fifo / lifo
+#[tauri::command]
+pub fn set_paste_queue_order(app: tauri::AppHandle, order: String) -> AppResult<()> {
+    crate::paste_queue::set_order(&app, crate::paste_queue::QueueOrder::parse(&order)?)
+}
+
+// 粘贴队列中的下一项
+#[tauri::command]
+pub async fn paste_next_from_queue(window: WebviewWindow) -> Result<(), String> {
+    if !crate::paste_queue::is_enabled() {
+        return Err("粘贴队列未开启".to_string());
+    }
+    let params = crate::services::paste_service::PasteContentParams {
+        clipboard_id: None,
+        quick_text_id: None,
+        mime_type: None,
+    };
+    crate::services::paste_service::paste_content(params, window).await
+}
+
+// 从粘贴队列中移除一项（index 为按取出顺序的位置）
+#[tauri::command]
+pub fn remove_paste_queue_item(app: tauri::AppHandle, index: usize) -> AppResult<()> {
+    crate::paste_queue::remove(&app, index)
+}
+
+// 清空粘贴队列
+#[tauri::command]
+pub fn clear_paste_queue(app: tauri::AppHandle) {
+    crate::paste_queue::clear(&app);
+}
+
+// 合并粘贴队列中的文本放到剪贴板，返回合并结果
+#[tauri::command]
+pub fn merge_paste_queue(app: tauri::AppHandle, separator: Option<String>) -> AppResult<String> {
+    crate::paste_queue::merge(&app, separator)
+}
+
+// 获取可用的文本转换
+#[tauri::command]
+pub fn get_available_transforms() -> Vec<crate::transforms::TransformInfo> {
+    crate::transforms::TRANSFORMS.to_vec()
+}
+
+// 预览文本转换结果
+#[tauri::command]
+pub fn transform_content(
+    params: crate::services::transform_service::TransformParams,
+) -> Result<String, String> {
+    crate::services::transform_service::transform_content(&params)
+}
+
+// 转换后粘贴
+#[tauri::command]
+pub async fn paste_transformed_content(
+    params: crate::services::transform_service::TransformParams,
+    window: WebviewWindow,
+) -> Result<(), String> {
+    crate::services::transform_service::paste_transformed_content(params, window).await
+}
+
+// 转换后保存为新的历史记录
+#[tauri::command]
+pub fn save_transformed_content(
+    app: tauri::AppHandle,
+    params: crate::services::transform_service::TransformParams,
+) -> Result<i64, String> {
+    use tauri::Emitter;
+
+    let id = crate::services::transform_service::save_transformed_content(&params)?;
+    if let Some(main_window) = app.get_webview_window("main") {
+        let _ = main_window.emit("clipboard-changed", ());
+    }
+    Ok(id)
+}
+
 // 读取图片文件并返回base64数据
 #[tauri::command]
-pub fn read_image_file(file_path: String) -> Result<String, String> {
+pub fn read_image_file(file_path: String) -> AppResult<String> {
     crate::services::file_operation_service::FileOperationService::read_image_file(file_path)
 }
 
@@ -699,7 +1090,7 @@ pub fn read_image_file(file_path: String) -> Result<String, String> {
 pub async fn export_data(
     export_path: String,
     options: crate::data_manager::ExportOptions,
-) -> Result<(), String> {
+) -> AppResult<()> {
     crate::data_manager::export_data(&export_path, options).await
 }
 
@@ -708,7 +1099,7 @@ pub async fn export_data(
 pub async fn import_data(
     import_path: String,
     options: crate::data_manager::ImportOptions,
-) -> Result<(), String> {
+) -> AppResult<()> {
     crate::data_manager::import_data(&import_path, options).await
 }
 
@@ -721,25 +1112,25 @@ pub async fn restart_app(app: tauri::AppHandle) -> Result<(), String> {
 
 // 清空剪贴板历史（数据管理）
 #[tauri::command]
-pub async fn clear_clipboard_history_dm() -> Result<(), String> {
+pub async fn clear_clipboard_history_dm() -> AppResult<()> {
     crate::data_manager::clear_clipboard_history().await
 }
 
 // 重置所有数据
 #[tauri::command]
-pub async fn reset_all_data() -> Result<(), String> {
+pub async fn reset_all_data() -> AppResult<()> {
     crate::data_manager::reset_all_data().await
 }
 
 // 恢复默认配置
 #[tauri::command]
-pub async fn reset_settings_to_default() -> Result<(), String> {
+pub async fn reset_settings_to_default() -> AppResult<()> {
     crate::data_manager::reset_settings_to_default().await
 }
 
 // 获取应用数据目录
 #[tauri::command]
-pub fn get_app_data_dir() -> Result<String, String> {
+pub fn get_app_data_dir() -> AppResult<String> {
     crate::services::system_service::SystemService::get_app_data_dir()
 }
 
@@ -753,17 +1144,17 @@ pub fn is_portable_mode() -> bool {
 
 // 获取存储信息
 #[tauri::command]
-pub fn get_storage_info() -> Result<crate::settings::StorageInfo, String> {
+pub fn get_storage_info() -> AppResult<crate::settings::StorageInfo> {
     let settings = crate::settings::get_global_settings();
     settings.get_storage_info()
 }
 
 // 设置自定义存储位置
 #[tauri::command]
-pub async fn set_custom_storage_location(new_path: String, app: tauri::AppHandle) -> Result<(), String> {
+pub async fn set_custom_storage_location(new_path: String, app: tauri::AppHandle) -> AppResult<()> {
     // 便携版模式下禁止更改存储位置
     if crate::settings::SettingsStorage::is_portable_mode() {
-        return Err("便携版模式下无法更改数据存储位置".to_string());
+        return Err(portable_storage_locked());
     }
     
     let mut settings = crate::settings::get_global_settings();
@@ -773,10 +1164,10 @@ pub async fn set_custom_storage_location(new_path: String, app: tauri::AppHandle
 
 // 重置为默认存储位置
 #[tauri::command]
-pub async fn reset_to_default_storage_location(app: tauri::AppHandle) -> Result<(), String> {
+pub async fn reset_to_default_storage_location(app: tauri::AppHandle) -> AppResult<()> {
     // 便携版模式下禁止更改存储位置
     if crate::settings::SettingsStorage::is_portable_mode() {
-        return Err("便携版模式下无法更改数据存储位置".to_string());
+        return Err(portable_storage_locked());
     }
     
     let mut settings = crate::settings::get_global_settings();
@@ -784,22 +1175,27 @@ pub async fn reset_to_default_storage_location(app: tauri::AppHandle) -> Result<
     crate::settings::update_global_settings(settings)
 }
 
+fn portable_storage_locked() -> AppError {
+    AppError::new(crate::error::ErrorCode::PermissionDenied, "便携版模式下无法更改数据存储位置")
+        .with_key("error.settings.portable_storage")
+}
+
 // 打开存储文件夹
 #[tauri::command]
-pub async fn open_storage_folder() -> Result<(), String> {
+pub async fn open_storage_folder() -> AppResult<()> {
     let settings = crate::settings::get_global_settings();
     crate::settings::SettingsStorage::open_folder(&settings)
 }
 
 // 保存窗口位置
 #[tauri::command]
-pub fn save_window_position(x: i32, y: i32) -> Result<(), String> {
+pub fn save_window_position(x: i32, y: i32) -> AppResult<()> {
     crate::settings::save_window_position(x, y)
 }
 
 // 保存窗口大小
 #[tauri::command]
-pub fn save_window_size(width: u32, height: u32) -> Result<(), String> {
+pub fn save_window_size(width: u32, height: u32) -> AppResult<()> {
     crate::settings::save_window_size(width, height)
 }
 
@@ -810,14 +1206,14 @@ pub fn refresh_file_icons(app_handle: tauri::AppHandle) -> Result<(), String> {
 
 // 获取保存的窗口位置
 #[tauri::command]
-pub fn get_saved_window_position() -> Result<Option<(i32, i32)>, String> {
+pub fn get_saved_window_position() -> AppResult<Option<(i32, i32)>> {
     let settings = crate::settings::get_global_settings();
     Ok(settings.saved_window_position)
 }
 
 // 获取保存的窗口大小
 #[tauri::command]
-pub fn get_saved_window_size() -> Result<Option<(u32, u32)>, String> {
+pub fn get_saved_window_size() -> AppResult<Option<(u32, u32)>> {
     let settings = crate::settings::get_global_settings();
     Ok(settings.saved_window_size)
 }
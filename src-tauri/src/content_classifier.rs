@@ -0,0 +1,1214 @@
+// 剪贴板内容分类
+//
+// 在五种基础内容类型之外，为文本内容打上更细的标签：代码（附带语言猜测）、JSON、XML/HTML 源码、
+// 邮箱、电话号码、颜色值、文件路径、IP 地址、UUID、Markdown、数学表达式、日期。
+// 一条记录可以有多个标签（例如带代码块的 Markdown），标签保存在 clipboard_tags 表中，
+// 用于历史记录筛选和提供针对类型的操作。
+//
+// 新记录和同步、导入的记录由 classify_pending 在后台补充分类；
+// 分类规则变化时递增 CLASSIFIER_VERSION，旧版本分类的记录会被重新分类。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::{params, Result as SqliteResult};
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::database::{ClipboardItem, ContentType};
+use crate::error::{AppError, AppResult};
+
+pub const CLASSIFIER_VERSION: i64 = 1;
+
+// 只分析内容的前一部分，避免超长文本拖慢复制
+const MAX_ANALYZE_BYTES: usize = 64 * 1024;
+// JSON 需要完整解析，超过此大小不再判断
+const MAX_JSON_BYTES: usize = 1024 * 1024;
+// 邮箱、电话、颜色等单值类型的最大长度
+const MAX_VALUE_LENGTH: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentTag {
+    Code,
+    Json,
+    Xml,
+    Email,
+    Phone,
+    Color,
+    FilePath,
+    IpAddress,
+    Uuid,
+    Markdown,
+    Math,
+    Date,
+}
+
+impl ContentTag {
+    pub const ALL: [ContentTag; 12] = [
+        ContentTag::Code,
+        ContentTag::Json,
+        ContentTag::Xml,
+        ContentTag::Email,
+        ContentTag::Phone,
+        ContentTag::Color,
+        ContentTag::FilePath,
+        ContentTag::IpAddress,
+        ContentTag::Uuid,
+        ContentTag::Markdown,
+        ContentTag::Math,
+        ContentTag::Date,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentTag::Code => "code",
+            ContentTag::Json => "json",
+            ContentTag::Xml => "xml",
+            ContentTag::Email => "email",
+            ContentTag::Phone => "phone",
+            ContentTag::Color => "color",
+            ContentTag::FilePath => "file_path",
+            ContentTag::IpAddress => "ip_address",
+            ContentTag::Uuid => "uuid",
+            ContentTag::Markdown => "markdown",
+            ContentTag::Math => "math",
+            ContentTag::Date => "date",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tag| tag.as_str() == s)
+    }
+}
+
+// 分类结果：标签和附加信息（代码语言、XML/HTML、颜色格式、IP 版本、计算结果、标准化日期）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TagMatch {
+    pub tag: &'static str,
+    pub detail: Option<String>,
+}
+
+impl TagMatch {
+    fn new(tag: ContentTag, detail: Option<String>) -> Self {
+        Self {
+            tag: tag.as_str(),
+            detail,
+        }
+    }
+}
+
+// 对文本内容分类（图片和文件记录没有标签）
+pub fn classify(content: &str) -> Vec<TagMatch> {
+    if content.starts_with("image:") || content.starts_with("files:") {
+        return Vec::new();
+    }
+
+    let text = content.trim();
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    let mut tags = Vec::new();
+
+    // 单值类型：整段内容就是一个值
+    if text.len() <= MAX_VALUE_LENGTH && !text.contains('\n') {
+        let date = detect_date(text);
+        let ip = detect_ip(text);
+
+        if is_email(text) {
+            tags.push(TagMatch::new(ContentTag::Email, None));
+        }
+        if let Some(format) = detect_color_format(text) {
+            tags.push(TagMatch::new(ContentTag::Color, Some(format.to_string())));
+        }
+        if is_uuid(text) {
+            tags.push(TagMatch::new(ContentTag::Uuid, None));
+        }
+        let is_phone = date.is_none() && ip.is_none() && is_phone(text);
+        if is_phone {
+            tags.push(TagMatch::new(ContentTag::Phone, None));
+        }
+        if date.is_none() && ip.is_none() && !is_phone && is_math_expression(text) {
+            let result = evaluate_math(text).ok().map(format_number);
+            tags.push(TagMatch::new(ContentTag::Math, result));
+        }
+        if let Some(version) = ip {
+            tags.push(TagMatch::new(ContentTag::IpAddress, Some(version.to_string())));
+        }
+        if let Some(date) = date {
+            tags.push(TagMatch::new(ContentTag::Date, Some(date)));
+        }
+    }
+
+    if is_file_paths(text) {
+        tags.push(TagMatch::new(ContentTag::FilePath, None));
+    }
+
+    // 结构化文本
+    if text.len() <= MAX_JSON_BYTES && is_json(text) {
+        tags.push(TagMatch::new(ContentTag::Json, None));
+        return tags;
+    }
+
+    let sample = truncate_to_boundary(text, MAX_ANALYZE_BYTES);
+    if let Some(kind) = detect_markup(sample) {
+        tags.push(TagMatch::new(ContentTag::Xml, Some(kind.to_string())));
+        return tags;
+    }
+
+    let is_markdown = is_markdown(sample);
+    if is_markdown {
+        tags.push(TagMatch::new(ContentTag::Markdown, None));
+    }
+
+    // Markdown 中的代码块按代码块标注的语言；否则按特征猜测语言
+    let language = if is_markdown {
+        fenced_code_language(sample)
+    } else {
+        guess_code_language(sample)
+    };
+    if let Some(language) = language {
+        tags.push(TagMatch::new(ContentTag::Code, Some(language)));
+    }
+
+    tags
+}
+
+fn truncate_to_boundary(text: &str, max_len: usize) -> &str {
+    if text.len() <= max_len {
+        return text;
+    }
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+// =================== 链接 ===================
+
+// 常见顶级域名（无协议、无路径的裸域名只按此列表判断，避免把 main.rs 之类的文件名当作链接）
+static WWW_HOST_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?i)www\.(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\.)+[a-z]{2,24}(?::\d{1,5})?(?:/\S*)?$")
+        .unwrap()
+});
+
+// 检查字符串是否为URL
+// 没有协议头时只接受 www. 开头的主机名，避免把 main.rs、foo.cc 之类的文件名当成网址
+pub fn is_url(text: &str) -> bool {
+    let text = text.trim();
+    if text.is_empty() || text.split_whitespace().count() != 1 {
+        return false;
+    }
+
+    let lower = text.to_ascii_lowercase();
+    ["http://", "https://", "ftp://", "ftps://"]
+        .iter()
+        .any(|scheme| lower.starts_with(scheme))
+        || WWW_HOST_RE.is_match(text)
+}
+
+// =================== 单值类型 ===================
+
+static EMAIL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:mailto:)?[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}$")
+        .unwrap()
+});
+
+fn is_email(text: &str) -> bool {
+    EMAIL_RE.is_match(text)
+}
+
+static PHONE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\+?\(?[0-9][0-9 ().-]{5,}[0-9]$").unwrap());
+
+// 电话号码：7-15 位数字；纯数字时只接受 11 位手机号，其他情况需要带 + 或分隔符
+fn is_phone(text: &str) -> bool {
+    if !PHONE_RE.is_match(text) {
+        return false;
+    }
+    let digits = text.chars().filter(|c| c.is_ascii_digit()).count();
+    if !(7..=15).contains(&digits) {
+        return false;
+    }
+    if digits == text.len() {
+        return digits == 11 && text.starts_with('1');
+    }
+    // 小数不是电话号码
+    text.starts_with('+') || !text.contains('.') || text.matches('.').count() > 1
+}
+
+static HEX_COLOR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^#(?:[0-9a-fA-F]{3,4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$").unwrap()
+});
+static RGB_COLOR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?i)rgba?\(\s*([\d.]+%?)\s*[,\s]\s*([\d.]+%?)\s*[,\s]\s*([\d.]+%?)\s*(?:[,/]\s*([\d.]+%?)\s*)?\)$")
+        .unwrap()
+});
+static HSL_COLOR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?i)hsla?\(\s*([\d.]+)(?:deg)?\s*[,\s]\s*([\d.]+)%\s*[,\s]\s*([\d.]+)%\s*(?:[,/]\s*([\d.]+%?)\s*)?\)$")
+        .unwrap()
+});
+
+fn detect_color_format(text: &str) -> Option<&'static str> {
+    if HEX_COLOR_RE.is_match(text) {
+        Some("hex")
+    } else if RGB_COLOR_RE.is_match(text) && parse_color(text).is_some() {
+        Some("rgb")
+    } else if HSL_COLOR_RE.is_match(text) && parse_color(text).is_some() {
+        Some("hsl")
+    } else {
+        None
+    }
+}
+
+// 解析颜色值为 RGBA
+pub fn parse_color(text: &str) -> Option<[u8; 4]> {
+    let text = text.trim();
+
+    if HEX_COLOR_RE.is_match(text) {
+        let hex = &text[1..];
+        let expanded: String = if hex.len() <= 4 {
+            hex.chars().flat_map(|c| [c, c]).collect()
+        } else {
+            hex.to_string()
+        };
+        let channel = |i: usize| u8::from_str_radix(&expanded[i * 2..i * 2 + 2], 16).ok();
+        let alpha = if expanded.len() == 8 { channel(3)? } else { 255 };
+        return Some([channel(0)?, channel(1)?, channel(2)?, alpha]);
+    }
+
+    if let Some(captures) = RGB_COLOR_RE.captures(text) {
+        let channel = |value: &str| -> Option<u8> {
+            let v = match value.strip_suffix('%') {
+                Some(percent) => percent.parse::<f64>().ok()? * 2.55,
+                None => value.parse::<f64>().ok()?,
+            };
+            (0.0..=255.0).contains(&v).then(|| v.round() as u8)
+        };
+        let alpha = match captures.get(4) {
+            Some(a) => parse_alpha(a.as_str())?,
+            None => 255,
+        };
+        return Some([channel(&captures[1])?, channel(&captures[2])?, channel(&captures[3])?, alpha]);
+    }
+
+    if let Some(captures) = HSL_COLOR_RE.captures(text) {
+        let h = captures[1].parse::<f64>().ok()? % 360.0;
+        let s = captures[2].parse::<f64>().ok()? / 100.0;
+        let l = captures[3].parse::<f64>().ok()? / 100.0;
+        if s > 1.0 || l > 1.0 {
+            return None;
+        }
+        let alpha = match captures.get(4) {
+            Some(a) => parse_alpha(a.as_str())?,
+            None => 255,
+        };
+        let [r, g, b] = hsl_to_rgb(h, s, l);
+        return Some([r, g, b, alpha]);
+    }
+
+    None
+}
+
+fn parse_alpha(value: &str) -> Option<u8> {
+    let alpha = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok()? / 100.0,
+        None => value.parse::<f64>().ok()?,
+    };
+    (0.0..=1.0).contains(&alpha).then(|| (alpha * 255.0).round() as u8)
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> [u8; 3] {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let to_u8 = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    [to_u8(r), to_u8(g), to_u8(b)]
+}
+
+static UUID_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\{?[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\}?$")
+        .unwrap()
+});
+
+fn is_uuid(text: &str) -> bool {
+    UUID_RE.is_match(text)
+}
+
+// IP 地址（可带端口或 CIDR 前缀），返回 ipv4 / ipv6
+fn detect_ip(text: &str) -> Option<&'static str> {
+    let version = |ip: IpAddr| if ip.is_ipv4() { "ipv4" } else { "ipv6" };
+
+    if let Ok(ip) = text.parse::<IpAddr>() {
+        return Some(version(ip));
+    }
+    if let Ok(addr) = text.parse::<SocketAddr>() {
+        return Some(version(addr.ip()));
+    }
+    let (address, prefix) = text.split_once('/')?;
+    let ip = address.parse::<IpAddr>().ok()?;
+    let prefix = prefix.parse::<u8>().ok()?;
+    let max_prefix = if ip.is_ipv4() { 32 } else { 128 };
+    (prefix <= max_prefix).then(|| version(ip))
+}
+
+// 日期或日期时间，返回标准化的 ISO 8601 表示
+fn detect_date(text: &str) -> Option<String> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Some(dt.to_rfc3339());
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(text) {
+        return Some(dt.to_rfc3339());
+    }
+
+    const DATETIME_FORMATS: &[&str] = &[
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y/%m/%d %H:%M:%S",
+        "%Y/%m/%d %H:%M",
+        "%Y年%m月%d日 %H:%M:%S",
+        "%Y年%m月%d日 %H:%M",
+    ];
+    for format in DATETIME_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(dt.format("%Y-%m-%dT%H:%M:%S").to_string());
+        }
+    }
+
+    const DATE_FORMATS: &[&str] = &[
+        "%Y-%m-%d",
+        "%Y/%m/%d",
+        "%Y.%m.%d",
+        "%Y年%m月%d日",
+        "%d.%m.%Y",
+        "%m/%d/%Y",
+        "%B %d, %Y",
+        "%b %d, %Y",
+        "%d %B %Y",
+        "%d %b %Y",
+    ];
+    for format in DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(text, format) {
+            return Some(date.format("%Y-%m-%d").to_string());
+        }
+    }
+
+    None
+}
+
+// =================== 数学表达式 ===================
+
+static MATH_CHARS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[\d\s.+\-*/×÷^%()]+$").unwrap());
+static MATH_OPERATOR_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[\d)]\s*[+\-*/×÷^%]\s*[\d(.]").unwrap());
+
+// 只包含数字和运算符，且至少有一个二元运算
+fn is_math_expression(text: &str) -> bool {
+    MATH_CHARS_RE.is_match(text) && MATH_OPERATOR_RE.is_match(text) && evaluate_math(text).is_ok()
+}
+
+// 计算数学表达式（支持 + - * / % ^ 和括号，× ÷ 视为乘除）
+pub fn evaluate_math(expression: &str) -> Result<f64, String> {
+    let tokens: Vec<char> = expression
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '×' => '*',
+            '÷' => '/',
+            c => c,
+        })
+        .collect();
+
+    let mut parser = MathParser { tokens, pos: 0 };
+    let value = parser.expression()?;
+    if parser.pos != parser.tokens.len() {
+        return Err("表达式格式无效".to_string());
+    }
+    if !value.is_finite() {
+        return Err("计算结果无效".to_string());
+    }
+    Ok(value)
+}
+
+// 格式化计算结果：整数不带小数，其余最多保留10位小数
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    let formatted = format!("{:.10}", value);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+struct MathParser {
+    tokens: Vec<char>,
+    pos: usize,
+}
+
+impl MathParser {
+    fn peek(&self) -> Option<char> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    // 加减
+    fn expression(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        while let Some(op) = self.peek().filter(|c| *c == '+' || *c == '-') {
+            self.pos += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    // 乘除取余
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.power()?;
+        while let Some(op) = self.peek().filter(|c| matches!(c, '*' | '/' | '%')) {
+            self.pos += 1;
+            let rhs = self.power()?;
+            value = match op {
+                '*' => value * rhs,
+                _ if rhs == 0.0 => return Err("除数不能为0".to_string()),
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Ok(value)
+    }
+
+    // 乘方（右结合）
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.unary()?;
+        if self.peek() == Some('^') {
+            self.pos += 1;
+            let exponent = self.power()?;
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(-self.unary()?)
+            }
+            Some('+') => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<f64, String> {
+        if self.peek() == Some('(') {
+            self.pos += 1;
+            let value = self.expression()?;
+            if self.peek() != Some(')') {
+                return Err("括号不匹配".to_string());
+            }
+            self.pos += 1;
+            return Ok(value);
+        }
+
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || c == '.')
+        {
+            self.pos += 1;
+        }
+        let number: String = self.tokens[start..self.pos].iter().collect();
+        number
+            .parse::<f64>()
+            .map_err(|_| "表达式格式无效".to_string())
+    }
+}
+
+// =================== 文件路径 ===================
+
+// 检查字符串是否为文件路径（每行一个，至少80%的行像文件路径）
+pub fn is_file_paths(text: &str) -> bool {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() || lines.len() > 1000 {
+        return false;
+    }
+
+    let valid_path_count = lines.iter().filter(|line| is_file_path(line)).count();
+    valid_path_count > 0 && (valid_path_count as f32 / lines.len() as f32) >= 0.8
+}
+
+fn is_file_path(line: &str) -> bool {
+    let bytes = line.as_bytes();
+
+    // Windows绝对路径格式: C:\path\to\file
+    if bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\' {
+        return true;
+    }
+
+    // UNC路径格式: \\server\share\path
+    if line.starts_with("\\\\") && line.len() > 2 {
+        return true;
+    }
+
+    // Unix绝对路径或主目录路径格式: /path/to/file、~/path
+    ((line.starts_with('/') && !line.starts_with("//")) || line.starts_with("~/"))
+        && line.len() > 2
+        && !line.contains(' ')
+}
+
+// =================== 结构化文本 ===================
+
+fn is_json(text: &str) -> bool {
+    let looks_like_json = (text.starts_with('{') && text.ends_with('}'))
+        || (text.starts_with('[') && text.ends_with(']'));
+    looks_like_json && serde_json::from_str::<serde_json::Value>(text).is_ok()
+}
+
+static MARKUP_TAG_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"<[A-Za-z][\w:.-]*(?:\s[^<>]*)?/?>").unwrap());
+static HTML_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)<!doctype\s+html|<(?:html|head|body|div|span|p|a|ul|ol|li|table|tr|td|br|img|script|style|h[1-6])\b")
+        .unwrap()
+});
+
+// XML/HTML 源码，返回 xml / html
+fn detect_markup(text: &str) -> Option<&'static str> {
+    if !text.starts_with('<') || !text.ends_with('>') {
+        return None;
+    }
+    let is_markup = text.starts_with("<?xml")
+        || text.to_ascii_lowercase().starts_with("<!doctype")
+        || (MARKUP_TAG_RE.is_match(text) && (text.contains("</") || text.contains("/>")));
+    if !is_markup {
+        return None;
+    }
+    Some(if HTML_RE.is_match(text) { "html" } else { "xml" })
+}
+
+static MARKDOWN_BLOCK_RES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        r"(?m)^#{1,6}\s+\S",                               // 标题
+        r"(?m)^\s*(?:[-*+]|\d+\.)\s+\S",                   // 列表
+        r"(?m)^```",                                       // 代码块
+        r"(?m)^>\s",                                       // 引用
+        r"(?m)^\|?\s*:?-{3,}:?\s*(?:\|\s*:?-{3,}:?\s*)+\|?\s*$", // 表格
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).unwrap())
+    .collect()
+});
+static MARKDOWN_INLINE_RES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        r"\[[^\]\n]+\]\([^)\s]+\)", // 链接
+        r"\*\*[^*\n]+\*\*|__[^_\n]+__", // 粗体
+        r"`[^`\n]+`",               // 行内代码
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).unwrap())
+    .collect()
+});
+
+// Markdown：至少两种不同的语法特征，且其中至少一种是块级语法
+fn is_markdown(text: &str) -> bool {
+    let block = MARKDOWN_BLOCK_RES.iter().filter(|re| re.is_match(text)).count();
+    let inline = MARKDOWN_INLINE_RES.iter().filter(|re| re.is_match(text)).count();
+    block > 0 && block + inline >= 2
+}
+
+static FENCE_LANGUAGE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^```\s*([A-Za-z][\w+#-]*)").unwrap());
+
+fn fenced_code_language(text: &str) -> Option<String> {
+    FENCE_LANGUAGE_RE
+        .captures(text)
+        .map(|captures| captures[1].to_ascii_lowercase())
+}
+
+// =================== 代码语言猜测 ===================
+
+struct LanguageRules {
+    name: &'static str,
+    patterns: Vec<(Regex, u32)>,
+}
+
+fn rules(name: &'static str, patterns: &[(&str, u32)]) -> LanguageRules {
+    LanguageRules {
+        name,
+        patterns: patterns
+            .iter()
+            .map(|(pattern, weight)| (Regex::new(&format!("(?m){}", pattern)).unwrap(), *weight))
+            .collect(),
+    }
+}
+
+// 每种语言的特征和权重，同分时靠前的语言优先
+static LANGUAGES: Lazy<Vec<LanguageRules>> = Lazy::new(|| {
+    vec![
+        rules("rust", &[
+            (r"\bfn\s+\w+\s*[<(]", 3),
+            (r"\blet\s+mut\b", 3),
+            (r"\bpub\s+(?:fn|struct|enum|mod|trait)\b", 3),
+            (r"\buse\s+\w+(?:::\w+)+", 3),
+            (r"\bimpl\b[^\n]*\{", 2),
+            (r"&mut\s", 2),
+            (r"\w!\(", 1),
+            (r"->\s*\w", 1),
+        ]),
+        rules("python", &[
+            (r"^\s*def\s+\w+\s*\([^\n]*\)\s*(?:->[^\n]*)?:\s*$", 3),
+            (r"^\s*class\s+\w+(?:\([^\n]*\))?:\s*$", 3),
+            (r"^\s*elif\b", 3),
+            (r"^\s*(?:from\s+[\w.]+\s+)?import\s+\w+", 2),
+            (r"\bself\.\w+", 2),
+            (r"\bprint\(", 1),
+            (r"\b(?:None|True|False)\b", 1),
+        ]),
+        rules("javascript", &[
+            (r"\bconsole\.\w+\(", 3),
+            (r"\brequire\(|\bmodule\.exports\b|\bexport\s+default\b", 3),
+            (r"\b(?:const|let)\s+\w+\s*=", 2),
+            (r"\bfunction\s*\w*\s*\(", 2),
+            (r"===|!==", 2),
+            (r"=>", 1),
+        ]),
+        rules("typescript", &[
+            (r"\binterface\s+\w+\s*\{", 3),
+            (r"\b\w+\s*:\s*(?:string|number|boolean|any|void|unknown)\b", 3),
+            (r"\btype\s+\w+\s*=", 2),
+            (r"\b(?:const|let)\s+\w+\s*=", 1),
+            (r"=>", 1),
+        ]),
+        rules("java", &[
+            (r"\bpublic\s+(?:static\s+)?(?:class|void|final)\b", 3),
+            (r"\bSystem\.out\.print", 3),
+            (r"@Override\b", 3),
+            (r"\bimport\s+java\.", 3),
+            (r"\bprivate\s+\w+(?:<[^>]*>)?\s+\w+\s*[;=]", 2),
+        ]),
+        rules("csharp", &[
+            (r"\busing\s+System(?:\.\w+)*;", 3),
+            (r"\bConsole\.Write", 3),
+            (r"\{\s*get;\s*(?:set;)?\s*\}", 3),
+            (r"\bnamespace\s+[\w.]+", 2),
+            (r"\bvar\s+\w+\s*=", 1),
+        ]),
+        rules("cpp", &[
+            (r"\bstd::", 3),
+            (r"\bcout\s*<<|\bcin\s*>>", 3),
+            (r"\btemplate\s*<", 3),
+            (r"#include\s*<(?:iostream|vector|string|map|memory)>", 3),
+        ]),
+        rules("c", &[
+            (r"^\s*#include\s*[<\x22]", 3),
+            (r"\bint\s+main\s*\(", 3),
+            (r"\bprintf\s*\(", 2),
+            (r"\bmalloc\s*\(|\bsizeof\s*\(", 2),
+            (r"\bstruct\s+\w+\s*\{", 1),
+        ]),
+        rules("go", &[
+            (r"^\s*package\s+\w+\s*$", 3),
+            (r"\bfunc\s+(?:\([^)]*\)\s*)?\w+\s*\(", 3),
+            (r"\bfmt\.\w+\(", 3),
+            (r"\berr\s*!=\s*nil\b", 3),
+            (r":=", 2),
+        ]),
+        rules("sql", &[
+            (r"(?i)\binsert\s+into\b", 3),
+            (r"(?i)\bupdate\s+\w+\s+set\b", 3),
+            (r"(?i)\bcreate\s+(?:table|index|view)\b", 3),
+            (r"(?i)\bselect\s+(?:\*|distinct\b|[\w.]+\s*,)", 2),
+            (r"(?i)\bselect\b[\s\S]+\bfrom\b", 2),
+            (r"(?i)\b(?:inner|left|right)\s+join\b", 2),
+            (r"(?i)\bwhere\b", 1),
+        ]),
+        rules("shell", &[
+            (r"^#!\s*/", 3),
+            (r"\bexport\s+\w+=", 3),
+            (r"\|\s*(?:grep|awk|sed|xargs)\b", 3),
+            (r"^\s*\$\s+\w+", 2),
+            (r"\b(?:sudo|apt|apt-get|brew|npm|cargo|git|docker|kubectl|pip)\s+\w+", 2),
+            (r"^\s*(?:fi|done|esac)\s*$", 2),
+            (r"\becho\s+", 1),
+        ]),
+        rules("css", &[
+            (r"@media\b|@import\b|@keyframes\b", 3),
+            (r"^\s*[.#]?[\w-]+(?:[\s,>+~]+[.#]?[\w:-]+)*\s*\{\s*$", 2),
+            (r"^\s*[\w-]+\s*:\s*[^;\n]+;\s*$", 2),
+            (r"!important", 2),
+        ]),
+        rules("php", &[
+            (r"<\?php", 5),
+            (r"\becho\s+\$", 2),
+            (r"\$\w+\s*=", 1),
+            (r"->\w+\(", 1),
+        ]),
+        rules("ruby", &[
+            (r"\.each\s+do\s*\|", 3),
+            (r"^\s*def\s+\w+[?!]?(?:\([^\n]*\))?\s*$", 2),
+            (r"^\s*end\s*$", 2),
+            (r"\bputs\s+", 2),
+            (r"\brequire\s+['\x22]", 2),
+            (r"@\w+\s*=", 1),
+        ]),
+    ]
+});
+
+// 行尾或行首具有代码特征的行所占比例
+fn code_line_ratio(text: &str) -> f32 {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.is_empty() {
+        return 0.0;
+    }
+    let code_lines = lines
+        .iter()
+        .filter(|line| {
+            let trimmed = line.trim_end();
+            trimmed.ends_with([';', '{', '}', ')', ':', ',', '(', '['])
+                || line.starts_with("    ")
+                || line.starts_with('\t')
+        })
+        .count();
+    code_lines as f32 / lines.len() as f32
+}
+
+// 按特征得分猜测代码语言，不像代码时返回 None
+pub fn guess_code_language(text: &str) -> Option<String> {
+    let scores: Vec<(&str, u32)> = LANGUAGES
+        .iter()
+        .map(|language| {
+            let score = language
+                .patterns
+                .iter()
+                .filter(|(re, _)| re.is_match(text))
+                .map(|(_, weight)| weight)
+                .sum();
+            (language.name, score)
+        })
+        .collect();
+
+    let (mut best, best_score) = scores
+        .iter()
+        .copied()
+        .fold(("", 0), |acc, item| if item.1 > acc.1 { item } else { acc });
+
+    // TypeScript 是 JavaScript 的超集：JavaScript 得分最高但带有类型注解时视为 TypeScript
+    let typescript = scores.iter().find(|(name, _)| *name == "typescript").map_or(0, |s| s.1);
+    if best == "javascript" && typescript >= 3 {
+        best = "typescript";
+    }
+
+    let confident = best_score >= 5 || (best_score >= 3 && code_line_ratio(text) >= 0.3);
+    confident.then(|| best.to_string())
+}
+
+// =================== 类型相关操作 ===================
+
+// 针对内容类型提供的操作
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentAction {
+    // transform：文本转换的ID（可预览、粘贴或保存转换结果）
+    // open：使用系统默认程序打开 target（链接、邮件、电话、文件）
+    pub kind: &'static str,
+    pub id: &'static str,
+    pub name: &'static str,
+    pub target: Option<String>,
+}
+
+impl ContentAction {
+    fn transform(id: &'static str) -> Option<Self> {
+        let info = crate::transforms::TRANSFORMS.iter().find(|t| t.id == id)?;
+        Some(Self {
+            kind: "transform",
+            id: info.id,
+            name: info.name,
+            target: None,
+        })
+    }
+
+    fn open(id: &'static str, name: &'static str, target: String) -> Self {
+        Self {
+            kind: "open",
+            id,
+            name,
+            target: Some(target),
+        }
+    }
+}
+
+// 根据内容和标签生成可用的操作
+pub fn actions_for(content: &str, content_type: &ContentType, tags: &[TagMatch]) -> Vec<ContentAction> {
+    let text = content.trim();
+    let mut actions = Vec::new();
+
+    if *content_type == ContentType::Link {
+        let url = if text.contains("://") {
+            text.to_string()
+        } else {
+            format!("https://{}", text)
+        };
+        actions.push(ContentAction::open("open_link", "打开链接", url));
+    }
+
+    for tag in tags {
+        let Some(kind) = ContentTag::from_name(tag.tag) else {
+            continue;
+        };
+        match kind {
+            ContentTag::Json => {
+                actions.extend(ContentAction::transform("json_pretty"));
+                actions.extend(ContentAction::transform("json_minify"));
+            }
+            ContentTag::Xml if tag.detail.as_deref() == Some("html") => {
+                actions.extend(ContentAction::transform("html_to_text"));
+            }
+            ContentTag::Email => {
+                let address = text.strip_prefix("mailto:").unwrap_or(text);
+                actions.push(ContentAction::open("send_email", "发送邮件", format!("mailto:{}", address)));
+            }
+            ContentTag::Phone => {
+                let number: String = text
+                    .chars()
+                    .filter(|c| c.is_ascii_digit() || *c == '+')
+                    .collect();
+                actions.push(ContentAction::open("call_phone", "拨打电话", format!("tel:{}", number)));
+            }
+            ContentTag::Color => {
+                if tag.detail.as_deref() != Some("hex") {
+                    actions.extend(ContentAction::transform("color_to_hex"));
+                }
+                if tag.detail.as_deref() != Some("rgb") {
+                    actions.extend(ContentAction::transform("color_to_rgb"));
+                }
+            }
+            ContentTag::FilePath => {
+                if let Some(path) = text.lines().map(str::trim).find(|line| is_file_path(line)) {
+                    actions.push(ContentAction::open("open_file", "打开文件", path.to_string()));
+                    actions.push(ContentAction::open(
+                        "open_file_location",
+                        "打开所在位置",
+                        path.to_string(),
+                    ));
+                }
+            }
+            ContentTag::Uuid => {
+                actions.extend(ContentAction::transform("uppercase"));
+                actions.extend(ContentAction::transform("lowercase"));
+            }
+            ContentTag::Math => {
+                actions.extend(ContentAction::transform("evaluate_math"));
+            }
+            _ => {}
+        }
+    }
+
+    actions
+}
+
+// =================== 数据库操作 ===================
+
+// 记录的标签（供前端展示）
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemTag {
+    pub tag: String,
+    pub detail: Option<String>,
+}
+
+// 标签及对应的记录数量（供历史记录筛选）
+#[derive(Debug, Clone, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+}
+
+const CLASSIFY_BATCH_SIZE: usize = 200;
+
+// 为尚未分类（或按旧版本规则分类）的剪贴板记录分类，返回处理的记录数
+// 加密数据锁定时无法读取内容，解锁后再分类
+pub fn classify_pending() -> AppResult<usize> {
+    if crate::encryption::is_locked() {
+        return Ok(0);
+    }
+
+    let mut total = 0;
+    loop {
+        let pending: Vec<(i64, String, i64)> = crate::database::with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, qc_decrypt(content), updated_at FROM clipboard
+                 WHERE tags_version < ?1 ORDER BY id LIMIT ?2",
+            )?;
+            let rows = stmt.query_map(params![CLASSIFIER_VERSION, CLASSIFY_BATCH_SIZE as i64], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?;
+            rows.collect::<SqliteResult<Vec<_>>>()
+        })?;
+
+        let batch_len = pending.len();
+        let classified: Vec<(i64, i64, Vec<TagMatch>)> = pending
+            .into_iter()
+            .map(|(id, content, updated_at)| (id, updated_at, classify(&content)))
+            .collect();
+
+        let saved = crate::database::with_connection(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut saved = 0;
+            for (id, updated_at, tags) in &classified {
+                // 分类期间内容被修改的记录留到下次处理
+                let changed = tx.execute(
+                    "UPDATE clipboard SET tags_version = ?1 WHERE id = ?2 AND updated_at = ?3",
+                    params![CLASSIFIER_VERSION, id, updated_at],
+                )?;
+                if changed == 0 {
+                    continue;
+                }
+                tx.execute("DELETE FROM clipboard_tags WHERE item_id = ?1", params![id])?;
+                for tag in tags {
+                    tx.execute(
+                        "INSERT OR REPLACE INTO clipboard_tags (item_id, tag, detail) VALUES (?1, ?2, qc_encrypt(?3))",
+                        params![id, tag.tag, tag.detail],
+                    )?;
+                }
+                saved += 1;
+            }
+            tx.commit()?;
+            Ok(saved)
+        })?;
+
+        total += saved;
+        if batch_len < CLASSIFY_BATCH_SIZE || saved == 0 {
+            break;
+        }
+    }
+
+    Ok(total)
+}
+
+static BACKGROUND_RUNNING: AtomicBool = AtomicBool::new(false);
+static BACKGROUND_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+// 在后台线程中分类（新记录、编辑记录时调用，不阻塞写入）
+// 已有后台分类在运行时只做标记，由该线程结束前再处理一轮，避免连续复制时反复创建线程
+pub fn classify_pending_in_background() {
+    BACKGROUND_REQUESTED.store(true, Ordering::SeqCst);
+    if BACKGROUND_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(|| loop {
+        while BACKGROUND_REQUESTED.swap(false, Ordering::SeqCst) {
+            match classify_pending() {
+                Ok(0) => {}
+                Ok(count) => println!("已为 {} 条剪贴板记录分类", count),
+                Err(e) => println!("剪贴板内容分类失败: {}", e),
+            }
+        }
+        BACKGROUND_RUNNING.store(false, Ordering::SeqCst);
+        // 释放标记后又有新请求且没有其他线程接手时继续处理
+        if !BACKGROUND_REQUESTED.load(Ordering::SeqCst) || BACKGROUND_RUNNING.swap(true, Ordering::SeqCst) {
+            break;
+        }
+    });
+}
+
+// 获取记录的标签
+pub fn get_item_tags(item_id: i64) -> AppResult<Vec<ItemTag>> {
+    crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT tag, qc_decrypt(detail) FROM clipboard_tags WHERE item_id = ?1 ORDER BY rowid",
+        )?;
+        let rows = stmt.query_map(params![item_id], |row| {
+            Ok(ItemTag {
+                tag: row.get(0)?,
+                detail: row.get(1)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+// 统计每个标签的记录数量
+pub fn get_tag_counts() -> AppResult<Vec<TagCount>> {
+    crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT tag, COUNT(*) FROM clipboard_tags GROUP BY tag ORDER BY COUNT(*) DESC, tag",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(TagCount {
+                tag: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+// 按标签筛选剪贴板历史（代码标签可用 code:rust 的形式同时按语言筛选）
+pub fn get_history_by_tag(tag: &str) -> AppResult<Vec<ClipboardItem>> {
+    let (tag, detail) = match tag.split_once(':') {
+        Some((tag, detail)) => (tag, Some(detail)),
+        None => (tag, None),
+    };
+
+    crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM clipboard
+             WHERE id IN (
+                 SELECT item_id FROM clipboard_tags
+                 WHERE tag = ?1 AND (?2 IS NULL OR qc_decrypt(detail) = ?2)
+             )
+             ORDER BY pinned DESC, item_order, updated_at DESC",
+            crate::database::CLIPBOARD_ITEM_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![tag, detail], crate::database::clipboard_item_from_row)?;
+        rows.collect()
+    })
+}
+
+// 获取记录可用的类型相关操作
+pub fn get_item_actions(item_id: i64) -> AppResult<Vec<ContentAction>> {
+    let item = crate::database::get_clipboard_item_by_id(item_id)?
+        .ok_or_else(|| AppError::clipboard_item_not_found(item_id))?;
+    let tags = classify(&item.content);
+    Ok(actions_for(&item.content, &item.content_type, &tags))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags_of(text: &str) -> Vec<(&'static str, Option<String>)> {
+        classify(text).into_iter().map(|t| (t.tag, t.detail)).collect()
+    }
+
+    fn has(text: &str, tag: &str) -> bool {
+        classify(text).iter().any(|t| t.tag == tag)
+    }
+
+    #[test]
+    fn test_single_value_tags() {
+        assert_eq!(tags_of("user@example.com"), vec![("email", None)]);
+        assert_eq!(tags_of("#ff8800"), vec![("color", Some("hex".to_string()))]);
+        assert!(has("rgb(255, 136, 0)", "color"));
+        assert!(has("hsl(32, 100%, 50%)", "color"));
+        assert!(!has("rgb(300, 0, 0)", "color"));
+        assert!(has("550e8400-e29b-41d4-a716-446655440000", "uuid"));
+        assert_eq!(tags_of("192.168.1.10"), vec![("ip_address", Some("ipv4".to_string()))]);
+        assert!(has("[::1]:8080", "ip_address"));
+        assert!(has("10.0.0.0/8", "ip_address"));
+        assert!(has("+86 138 1234 5678", "phone"));
+        assert!(has("13812345678", "phone"));
+        assert!(!has("20241017", "phone"));
+        assert!(!has("3.1415926", "phone"));
+    }
+
+    #[test]
+    fn test_date_and_math() {
+        assert_eq!(tags_of("2024-10-17"), vec![("date", Some("2024-10-17".to_string()))]);
+        assert_eq!(
+            tags_of("2024年10月17日"),
+            vec![("date", Some("2024-10-17".to_string()))]
+        );
+        assert!(has("2024-10-17T08:30:00+08:00", "date"));
+        assert_eq!(tags_of("(1 + 2) * 3"), vec![("math", Some("9".to_string()))]);
+        assert_eq!(tags_of("2 ^ 10 / 4"), vec![("math", Some("256".to_string()))]);
+        assert_eq!(tags_of("1 ÷ 4"), vec![("math", Some("0.25".to_string()))]);
+        assert!(!has("42", "math"));
+        assert!(!has("-5", "math"));
+        assert!(evaluate_math("1 / 0").is_err());
+        assert!(evaluate_math("(1 + 2").is_err());
+    }
+
+    #[test]
+    fn test_structured_text() {
+        assert_eq!(tags_of(r#"{"a": [1, 2]}"#), vec![("json", None)]);
+        assert!(!has("{not json}", "json"));
+        assert_eq!(
+            tags_of("<note><to>Tove</to></note>"),
+            vec![("xml", Some("xml".to_string()))]
+        );
+        assert_eq!(
+            tags_of("<div class=\"a\"><p>hi</p></div>"),
+            vec![("xml", Some("html".to_string()))]
+        );
+        assert!(has("C:\\Users\\me\\file.txt", "file_path"));
+        assert!(has("/usr/local/bin\n/etc/hosts", "file_path"));
+
+        let markdown = "# 标题\n\n- 第一项\n- 第二项\n\n```rust\nfn main() {}\n```\n";
+        assert_eq!(
+            tags_of(markdown),
+            vec![("markdown", None), ("code", Some("rust".to_string()))]
+        );
+        assert!(!has("- 只有一个列表项", "markdown"));
+    }
+
+    #[test]
+    fn test_code_language() {
+        let cases = [
+            ("use std::io;\n\nfn main() {\n    let mut s = String::new();\n}", "rust"),
+            ("def add(a, b):\n    return a + b", "python"),
+            ("const x = require('fs');\nconsole.log(x);", "javascript"),
+            ("interface User {\n  name: string;\n}\nconst u: User = { name: 'a' };", "typescript"),
+            ("package main\n\nfunc main() {\n\tfmt.Println(\"hi\")\n}", "go"),
+            ("SELECT id, name FROM users WHERE id = 1;", "sql"),
+            ("#include <stdio.h>\nint main() {\n  printf(\"hi\");\n}", "c"),
+            ("#!/bin/bash\nexport PATH=$HOME/bin\nls | grep foo", "shell"),
+            (".button {\n  color: red;\n  margin: 0 !important;\n}", "css"),
+        ];
+        for (code, language) in cases {
+            assert_eq!(guess_code_language(code).as_deref(), Some(language), "{}", code);
+        }
+        assert_eq!(guess_code_language("今天天气不错，我们去公园散步吧。"), None);
+        assert_eq!(guess_code_language("Please select a file from the list."), None);
+    }
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://example.com/a?b=1"));
+        assert!(is_url("ftp://files.example.org"));
+        assert!(is_url("www.example.xyz"));
+        assert!(is_url("WWW.example.com:8080/docs"));
+        assert!(!is_url("example.com"));
+        assert!(!is_url("example.dev/docs"));
+        assert!(!is_url("main.rs"));
+        assert!(!is_url("readme.md"));
+        assert!(!is_url("foo.cc"));
+        assert!(!is_url("x.ai"));
+        assert!(!is_url("src/main.go"));
+        assert!(!is_url("www.rs"));
+        assert!(!is_url("hello world.com"));
+    }
+
+    #[test]
+    fn test_actions() {
+        let tags = classify("#ff8800");
+        let ids: Vec<&str> = actions_for("#ff8800", &ContentType::Text, &tags)
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(ids, vec!["color_to_rgb"]);
+
+        let tags = classify("user@example.com");
+        let actions = actions_for("user@example.com", &ContentType::Text, &tags);
+        assert_eq!(actions[0].target.as_deref(), Some("mailto:user@example.com"));
+
+        let color = parse_color("hsl(0, 100%, 50%)").unwrap();
+        assert_eq!(color, [255, 0, 0, 255]);
+        assert_eq!(parse_color("#0f08").unwrap(), [0, 255, 0, 136]);
+    }
+}
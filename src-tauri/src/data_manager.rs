@@ -5,6 +5,8 @@ use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use zip::{write::FileOptions, ZipArchive, ZipWriter};
 
+use crate::error::{AppError, AppResult};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ExportOptions {
     // 导出所有数据
@@ -31,19 +33,22 @@ pub struct ExportMetadata {
     pub images_folder: bool,
     #[serde(default)]
     pub exclude_image_cache: bool,
+    // 导出的数据是否已加密（需要同时导出密钥文件，导入后使用原口令解锁）
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 // 获取应用数据目录
-pub fn get_app_data_dir() -> Result<PathBuf, String> {
+pub fn get_app_data_dir() -> AppResult<PathBuf> {
     crate::settings::get_data_directory()
 }
 
 // 导出数据到ZIP文件
-pub async fn export_data(export_path: &str, _options: ExportOptions) -> Result<(), String> {
+pub async fn export_data(export_path: &str, _options: ExportOptions) -> AppResult<()> {
     let app_data_dir = get_app_data_dir()?;
 
     // 创建ZIP文件
-    let file = fs::File::create(export_path).map_err(|e| format!("创建导出文件失败: {}", e))?;
+    let file = fs::File::create(export_path).map_err(|e| AppError::from_io("创建导出文件失败", &e))?;
     let mut zip = ZipWriter::new(file);
     let zip_options = FileOptions::<()>::default()
         .compression_method(zip::CompressionMethod::Deflated)
@@ -57,10 +62,11 @@ pub async fn export_data(export_path: &str, _options: ExportOptions) -> Result<(
         settings_file: false,
         images_folder: false,
         exclude_image_cache: true, 
+        encrypted: false,
     };
 
-    // 导出数据库文件（排除 image_data 表）
-    let db_path = crate::database::get_database_path().map_err(|e| format!("获取数据库路径失败: {}", e))?;
+    // 导出数据库文件（排除 image_data 表和全文索引，索引在导入后重建）
+    let db_path = crate::database::get_database_path().map_err(|e| e.prefixed("获取数据库路径失败"))?;
     if db_path.exists() {
         // 创建临时数据库
         let temp_db_path = app_data_dir.join("temp_export.db");
@@ -74,13 +80,20 @@ pub async fn export_data(export_path: &str, _options: ExportOptions) -> Result<(
 
     // 导出设置文件（始终从默认目录读取）
     let default_data_dir = crate::settings::AppSettings::get_default_data_directory()
-        .map_err(|e| format!("获取默认数据目录失败: {}", e))?;
+        .map_err(|e| e.prefixed("获取默认数据目录失败"))?;
     let settings_path = default_data_dir.join("settings.json");
     if settings_path.exists() {
         add_file_to_zip(&mut zip, &settings_path, "settings.json", zip_options)?;
         metadata.settings_file = true;
     }
 
+    // 导出密钥文件（数据以密文导出，密钥文件中只有被口令保护的数据密钥）
+    let key_file_path = app_data_dir.join(crate::encryption::KEY_FILE_NAME);
+    if key_file_path.exists() {
+        add_file_to_zip(&mut zip, &key_file_path, crate::encryption::KEY_FILE_NAME, zip_options)?;
+        metadata.encrypted = true;
+    }
+
     // 导出图片文件夹
     let images_dir = app_data_dir.join("clipboard_images");
     if images_dir.exists() {
@@ -90,27 +103,27 @@ pub async fn export_data(export_path: &str, _options: ExportOptions) -> Result<(
 
     // 添加元数据文件
     zip.start_file("metadata.json", zip_options)
-        .map_err(|e| format!("创建元数据文件失败: {}", e))?;
+        .map_err(|e| AppError::io(format!("创建元数据文件失败: {}", e)))?;
 
     let metadata_json = serde_json::to_string_pretty(&metadata)
-        .map_err(|e| format!("序列化元数据失败: {}", e))?;
+        .map_err(|e| AppError::internal(format!("序列化元数据失败: {}", e)))?;
 
     zip.write_all(metadata_json.as_bytes())
-        .map_err(|e| format!("写入元数据失败: {}", e))?;
+        .map_err(|e| AppError::from_io("写入元数据失败", &e))?;
 
     zip.finish()
-        .map_err(|e| format!("完成ZIP文件创建失败: {}", e))?;
+        .map_err(|e| AppError::io(format!("完成ZIP文件创建失败: {}", e)))?;
 
     Ok(())
 }
 
 // 导入数据从ZIP文件
-pub async fn import_data(import_path: &str, options: ImportOptions) -> Result<(), String> {
+pub async fn import_data(import_path: &str, options: ImportOptions) -> AppResult<()> {
     let app_data_dir = get_app_data_dir()?;
 
     // 打开ZIP文件
-    let file = fs::File::open(import_path).map_err(|e| format!("打开导入文件失败: {}", e))?;
-    let mut archive = ZipArchive::new(file).map_err(|e| format!("读取ZIP文件失败: {}", e))?;
+    let file = fs::File::open(import_path).map_err(|e| AppError::from_io("打开导入文件失败", &e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| AppError::io(format!("读取ZIP文件失败: {}", e)))?;
 
     // 读取元数据
     let _metadata = read_metadata_from_zip(&mut archive)?;
@@ -130,53 +143,55 @@ pub async fn import_data(import_path: &str, options: ImportOptions) -> Result<()
         }
     }
 
-    // 重新初始化数据库以确保所有表都存在
-    crate::database::reinitialize_database()
-        .map_err(|e| format!("重新初始化数据库失败: {}", e))?;
+    // 重新初始化数据库，导入的旧版本数据库会在此执行结构迁移
+    crate::database::reinitialize_database()?;
+
+    // 导入的密钥文件与当前不同时需要重新解锁；导入的明文数据会被加密
+    crate::encryption::reload_key_file()?;
 
     Ok(())
 }
 
 // 清空剪贴板历史
-pub async fn clear_clipboard_history() -> Result<(), String> {
-    crate::clipboard_history::clear_all().map_err(|e| format!("清空剪贴板历史失败: {}", e))
+pub async fn clear_clipboard_history() -> AppResult<()> {
+    crate::clipboard_history::clear_all().map_err(|e| AppError::from(e).prefixed("清空剪贴板历史失败"))
 }
 
 // 重置所有数据
-pub async fn reset_all_data() -> Result<(), String> {
+pub async fn reset_all_data() -> AppResult<()> {
     let app_data_dir = get_app_data_dir()?;
 
     // 备份当前数据
     backup_current_data(&app_data_dir).await?;
 
     // 清空数据库
-    crate::database::clear_all_data().map_err(|e| format!("清空数据库失败: {}", e))?;
+    crate::database::clear_all_data().map_err(|e| e.prefixed("清空数据库失败"))?;
 
     // 删除设置文件（始终从默认目录删除）
     let default_data_dir = crate::settings::AppSettings::get_default_data_directory()
-        .map_err(|e| format!("获取默认数据目录失败: {}", e))?;
+        .map_err(|e| e.prefixed("获取默认数据目录失败"))?;
     let settings_path = default_data_dir.join("settings.json");
     if settings_path.exists() {
-        fs::remove_file(&settings_path).map_err(|e| format!("删除设置文件失败: {}", e))?;
+        fs::remove_file(&settings_path).map_err(|e| AppError::from_io("删除设置文件失败", &e))?;
     }
 
     // 删除图片文件夹
     let images_dir = app_data_dir.join("clipboard_images");
     if images_dir.exists() {
-        fs::remove_dir_all(&images_dir).map_err(|e| format!("删除图片文件夹失败: {}", e))?;
+        fs::remove_dir_all(&images_dir).map_err(|e| AppError::from_io("删除图片文件夹失败", &e))?;
     }
 
     Ok(())
 }
 
 // 恢复默认配置（仅恢复设置，不影响数据）
-pub async fn reset_settings_to_default() -> Result<(), String> {
+pub async fn reset_settings_to_default() -> AppResult<()> {
     let default_data_dir = crate::settings::AppSettings::get_default_data_directory()
-        .map_err(|e| format!("获取默认数据目录失败: {}", e))?;
+        .map_err(|e| e.prefixed("获取默认数据目录失败"))?;
     let settings_path = default_data_dir.join("settings.json");
     
     if settings_path.exists() {
-        fs::remove_file(&settings_path).map_err(|e| format!("删除设置文件失败: {}", e))?;
+        fs::remove_file(&settings_path).map_err(|e| AppError::from_io("删除设置文件失败", &e))?;
     }
 
     Ok(())
@@ -185,28 +200,28 @@ pub async fn reset_settings_to_default() -> Result<(), String> {
 // =================== 辅助函数 ===================
 
 // 导出数据库但排除 image_data 表
-fn export_database_without_image_data(source_db: &Path, target_db: &Path) -> Result<(), String> {
+fn export_database_without_image_data(source_db: &Path, target_db: &Path) -> AppResult<()> {
     use rusqlite::Connection;
 
     if target_db.exists() {
-        fs::remove_file(target_db).map_err(|e| format!("删除临时数据库失败: {}", e))?;
+        fs::remove_file(target_db).map_err(|e| AppError::from_io("删除临时数据库失败", &e))?;
     }
 
     let source_conn = Connection::open(source_db)
-        .map_err(|e| format!("打开源数据库失败: {}", e))?;
+        .map_err(|e| AppError::from_sqlite("打开源数据库失败", e))?;
 
     let target_conn = Connection::open(target_db)
-        .map_err(|e| format!("创建目标数据库失败: {}", e))?;
+        .map_err(|e| AppError::from_sqlite("创建目标数据库失败", e))?;
 
     let mut stmt = source_conn
-        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' AND name != 'image_data'")
-        .map_err(|e| format!("查询表名失败: {}", e))?;
+        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' AND name != 'image_data' AND name NOT LIKE '%_fts' AND name NOT LIKE '%_fts_%'")
+        .map_err(|e| AppError::from_sqlite("查询表名失败", e))?;
     
     let table_names: Vec<String> = stmt
         .query_map([], |row| row.get(0))
-        .map_err(|e| format!("读取表名失败: {}", e))?
+        .map_err(|e| AppError::from_sqlite("读取表名失败", e))?
         .collect::<Result<Vec<String>, _>>()
-        .map_err(|e| format!("处理表名失败: {}", e))?;
+        .map_err(|e| AppError::from_sqlite("处理表名失败", e))?;
     
     drop(stmt);
 
@@ -217,53 +232,60 @@ fn export_database_without_image_data(source_db: &Path, target_db: &Path) -> Res
                 [&table_name],
                 |row| row.get(0),
             )
-            .map_err(|e| format!("获取表 {} 的创建语句失败: {}", table_name, e))?;
+            .map_err(|e| AppError::from_sqlite(&format!("获取表 {} 的创建语句失败", table_name), e))?;
 
         target_conn
             .execute(&create_sql, [])
-            .map_err(|e| format!("创建表 {} 失败: {}", table_name, e))?;
+            .map_err(|e| AppError::from_sqlite(&format!("创建表 {} 失败", table_name), e))?;
 
         target_conn
             .execute(&format!("ATTACH DATABASE '{}' AS source_db", source_db.display()), [])
-            .map_err(|e| format!("附加源数据库失败: {}", e))?;
+            .map_err(|e| AppError::from_sqlite("附加源数据库失败", e))?;
         
         target_conn
             .execute(&format!("INSERT INTO {} SELECT * FROM source_db.{}", table_name, table_name), [])
-            .map_err(|e| format!("复制表 {} 数据失败: {}", table_name, e))?;
+            .map_err(|e| AppError::from_sqlite(&format!("复制表 {} 数据失败", table_name), e))?;
         
         target_conn
             .execute("DETACH DATABASE source_db", [])
-            .map_err(|e| format!("分离源数据库失败: {}", e))?;
+            .map_err(|e| AppError::from_sqlite("分离源数据库失败", e))?;
     }
     
     // 复制索引
     let mut idx_stmt = source_conn
-        .prepare("SELECT sql FROM sqlite_master WHERE type='index' AND sql IS NOT NULL AND tbl_name != 'image_data'")
-        .map_err(|e| format!("查询索引失败: {}", e))?;
+        .prepare("SELECT sql FROM sqlite_master WHERE type='index' AND sql IS NOT NULL AND tbl_name != 'image_data' AND tbl_name NOT LIKE '%_fts%'")
+        .map_err(|e| AppError::from_sqlite("查询索引失败", e))?;
     
     let index_sqls: Vec<String> = idx_stmt
         .query_map([], |row| row.get(0))
-        .map_err(|e| format!("读取索引失败: {}", e))?
+        .map_err(|e| AppError::from_sqlite("读取索引失败", e))?
         .collect::<Result<Vec<String>, _>>()
-        .map_err(|e| format!("处理索引失败: {}", e))?;
+        .map_err(|e| AppError::from_sqlite("处理索引失败", e))?;
     
     drop(idx_stmt);
     
     for index_sql in index_sqls {
         let _ = target_conn.execute(&index_sql, []);
     }
+
+    // 保留数据库结构版本，导入时只执行缺少的迁移
+    let schema_version = crate::database_migration::get_schema_version(&source_conn)
+        .map_err(|e| AppError::from_sqlite("读取数据库结构版本失败", e))?;
+    target_conn
+        .pragma_update(None, "user_version", schema_version)
+        .map_err(|e| AppError::from_sqlite("写入数据库结构版本失败", e))?;
     
     Ok(())
 }
 
 // 备份当前数据
-async fn backup_current_data(app_data_dir: &Path) -> Result<(), String> {
+async fn backup_current_data(app_data_dir: &Path) -> AppResult<()> {
     let backup_dir = app_data_dir.join("backups");
     let timestamp = Local::now().format("%Y%m%d_%H%M%S");
     let backup_path = backup_dir.join(format!("backup_{}.zip", timestamp));
 
     // 创建备份目录
-    fs::create_dir_all(&backup_dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    fs::create_dir_all(&backup_dir).map_err(|e| AppError::from_io("创建备份目录失败", &e))?;
 
     // 导出当前数据作为备份
     let backup_options = ExportOptions {};
@@ -273,24 +295,28 @@ async fn backup_current_data(app_data_dir: &Path) -> Result<(), String> {
 }
 
 // 从ZIP文件读取元数据
-fn read_metadata_from_zip(archive: &mut ZipArchive<fs::File>) -> Result<ExportMetadata, String> {
+fn read_metadata_from_zip(archive: &mut ZipArchive<fs::File>) -> AppResult<ExportMetadata> {
     let mut file = archive
         .by_name("metadata.json")
-        .map_err(|_| "找不到元数据文件".to_string())?;
+        .map_err(|_| AppError::invalid_input("找不到元数据文件").with_key("error.import.missing_metadata"))?;
 
     let mut contents = String::new();
     file.read_to_string(&mut contents)
-        .map_err(|e| format!("读取元数据文件失败: {}", e))?;
+        .map_err(|e| AppError::from_io("读取元数据文件失败", &e))?;
 
-    serde_json::from_str(&contents).map_err(|e| format!("解析元数据失败: {}", e))
+    serde_json::from_str(&contents).map_err(|e| {
+        AppError::invalid_input(format!("解析元数据失败: {}", e))
+            .with_key("error.import.invalid_metadata")
+            .with_context("detail", e)
+    })
 }
 
 // 提取所有文件（替换模式）
-fn extract_all_files(archive: &mut ZipArchive<fs::File>, app_data_dir: &Path) -> Result<(), String> {
+fn extract_all_files(archive: &mut ZipArchive<fs::File>, app_data_dir: &Path) -> AppResult<()> {
     for i in 0..archive.len() {
         let mut file = archive
             .by_index(i)
-            .map_err(|e| format!("读取ZIP文件项失败: {}", e))?;
+            .map_err(|e| AppError::io(format!("读取ZIP文件项失败: {}", e)))?;
 
         let file_name = file.name().to_string();
 
@@ -308,21 +334,21 @@ fn extract_all_files(archive: &mut ZipArchive<fs::File>, app_data_dir: &Path) ->
 
         // 创建父目录
         if let Some(parent) = output_path.parent() {
-            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+            fs::create_dir_all(parent).map_err(|e| AppError::from_io("创建目录失败", &e))?;
         }
 
         // 写入文件
         let mut output_file = fs::File::create(&output_path)
-            .map_err(|e| format!("创建文件失败: {}", e))?;
+            .map_err(|e| AppError::from_io("创建文件失败", &e))?;
 
         std::io::copy(&mut file, &mut output_file)
-            .map_err(|e| format!("复制文件失败: {}", e))?;
+            .map_err(|e| AppError::from_io("复制文件失败", &e))?;
     }
 
     Ok(())
 }
 
-fn extract_settings_file(archive: &mut ZipArchive<fs::File>) -> Result<(), String> {
+fn extract_settings_file(archive: &mut ZipArchive<fs::File>) -> AppResult<()> {
     let mut settings_file = match archive.by_name("settings.json") {
         Ok(file) => file,
         Err(_) => {
@@ -332,37 +358,48 @@ fn extract_settings_file(archive: &mut ZipArchive<fs::File>) -> Result<(), Strin
 
     // 获取默认数据目录（设置文件始终保存在默认目录）
     let default_data_dir = crate::settings::AppSettings::get_default_data_directory()
-        .map_err(|e| format!("获取默认数据目录失败: {}", e))?;
+        .map_err(|e| e.prefixed("获取默认数据目录失败"))?;
     
     let target_settings_path = default_data_dir.join("settings.json");
 
     // 确保目录存在
     if let Some(parent) = target_settings_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("创建设置目录失败: {}", e))?;
+        fs::create_dir_all(parent).map_err(|e| AppError::from_io("创建设置目录失败", &e))?;
     }
 
     // 写入设置文件
     let mut output_file = fs::File::create(&target_settings_path)
-        .map_err(|e| format!("创建设置文件失败: {}", e))?;
+        .map_err(|e| AppError::from_io("创建设置文件失败", &e))?;
 
     std::io::copy(&mut settings_file, &mut output_file)
-        .map_err(|e| format!("复制设置文件失败: {}", e))?;
+        .map_err(|e| AppError::from_io("复制设置文件失败", &e))?;
 
     Ok(())
 }
 
 // 合并导入数据（合并模式）
 // 合并数据内容（数据库记录、图片），不覆盖用户设置
-async fn merge_import_data(archive: &mut ZipArchive<fs::File>, app_data_dir: &Path) -> Result<(), String> {
+async fn merge_import_data(archive: &mut ZipArchive<fs::File>, app_data_dir: &Path) -> AppResult<()> {
     // 先提取到临时目录
     let temp_dir = app_data_dir.join("temp_import");
     if temp_dir.exists() {
-        fs::remove_dir_all(&temp_dir).map_err(|e| format!("清理临时目录失败: {}", e))?;
+        fs::remove_dir_all(&temp_dir).map_err(|e| AppError::from_io("清理临时目录失败", &e))?;
     }
-    fs::create_dir_all(&temp_dir).map_err(|e| format!("创建临时目录失败: {}", e))?;
+    fs::create_dir_all(&temp_dir).map_err(|e| AppError::from_io("创建临时目录失败", &e))?;
 
     extract_all_files(archive, &temp_dir)?;
 
+    // 加密的数据只能合并到使用同一数据密钥的数据中
+    let temp_key_file = temp_dir.join(crate::encryption::KEY_FILE_NAME);
+    if temp_key_file.exists() {
+        let import_key_id = crate::encryption::read_key_id(&temp_key_file)?;
+        if crate::encryption::current_key_id().as_deref() != Some(import_key_id.as_str()) {
+            let _ = fs::remove_dir_all(&temp_dir);
+            return Err(AppError::invalid_input("导入的数据使用了不同的加密密钥，无法合并，请使用替换模式导入")
+                .with_key("error.import.key_mismatch"));
+        }
+    }
+
     // 合并数据库
     let temp_db_path = temp_dir.join("quickclipboard.db");
     if temp_db_path.exists() {
@@ -378,30 +415,98 @@ async fn merge_import_data(archive: &mut ZipArchive<fs::File>, app_data_dir: &Pa
     }
 
     // 清理临时目录
-    fs::remove_dir_all(&temp_dir).map_err(|e| format!("清理临时目录失败: {}", e))?;
+    fs::remove_dir_all(&temp_dir).map_err(|e| AppError::from_io("清理临时目录失败", &e))?;
 
     Ok(())
 }
 
 // 合并数据库
-async fn merge_database(temp_db_path: &Path) -> Result<(), String> {
+async fn merge_database(temp_db_path: &Path) -> AppResult<()> {
     // 不需要单独打开临时数据库，直接使用ATTACH
 
+    // 拒绝合并由更新版本程序导出的数据库
+    {
+        let temp_conn = rusqlite::Connection::open(temp_db_path)
+            .map_err(|e| AppError::from_sqlite("打开导入数据库失败", e))?;
+        let version = crate::database_migration::get_schema_version(&temp_conn)
+            .map_err(|e| AppError::from_sqlite("读取导入数据库结构版本失败", e))?;
+        let current = crate::database_migration::current_schema_version();
+        if version > current {
+            return Err(AppError::invalid_input(format!(
+                "导入数据的结构版本 ({}) 高于当前程序支持的版本 ({})，请升级 QuickClipboard 后再导入",
+                version, current
+            ))
+            .with_key("error.import.unsupported_version")
+            .with_context("version", version)
+            .with_context("supported", current));
+        }
+    }
+
     // 确保主数据库已初始化
     crate::database::initialize_database()
-        .map_err(|e| format!("初始化主数据库失败: {}", e))?;
+        .map_err(|e| AppError::from_sqlite("初始化主数据库失败", e))?;
 
     // 获取主数据库连接并执行合并操作
     let result = crate::database::with_connection(|main_conn| {
         // 简单粗暴的方法：直接用ATTACH DATABASE合并
         main_conn.execute(&format!("ATTACH DATABASE '{}' AS temp_db", temp_db_path.display()), [])?;
 
-        // 合并剪贴板数据
-        let _ = main_conn.execute(
-            "INSERT OR IGNORE INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at)
-             SELECT content, html_content, content_type, image_id, item_order, created_at, updated_at FROM temp_db.clipboard",
-            []
-        );
+        // 合并剪贴板数据（旧版本导出的数据没有来源列和置顶状态列）
+        let has_column = |column: &str| -> bool {
+            main_conn
+                .query_row(
+                    "SELECT COUNT(*) > 0 FROM pragma_table_info('clipboard', 'temp_db') WHERE name = ?1",
+                    [column],
+                    |row| row.get(0),
+                )
+                .unwrap_or(false)
+        };
+        let mut optional_columns = String::new();
+        if has_column("source_app") {
+            optional_columns.push_str(", source_app, source_path, source_title, source_url");
+        }
+        if has_column("pinned") {
+            optional_columns.push_str(", pinned");
+        }
+        // 逐条插入以取得新记录的 id，多格式表示的 item_id 需要换成新 id
+        let has_representations: bool = main_conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM temp_db.sqlite_master WHERE type = 'table' AND name = 'clipboard_representations'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+        let tx = main_conn.unchecked_transaction()?;
+        let old_ids: Vec<i64> = {
+            let mut stmt = tx.prepare("SELECT id FROM temp_db.clipboard ORDER BY id")?;
+            let ids = stmt.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+            ids
+        };
+        {
+            let mut insert_item = tx.prepare(&format!(
+                "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at{0})
+                 SELECT content, html_content, content_type, image_id, item_order, created_at, updated_at{0}
+                 FROM temp_db.clipboard WHERE id = ?1",
+                optional_columns
+            ))?;
+            let mut insert_representations = if has_representations {
+                Some(tx.prepare(
+                    "INSERT OR IGNORE INTO clipboard_representations (item_id, mime_type, text_data, blob_data, size)
+                     SELECT ?2, mime_type, text_data, blob_data, size
+                     FROM temp_db.clipboard_representations WHERE item_id = ?1 ORDER BY id",
+                )?)
+            } else {
+                None
+            };
+            for old_id in old_ids {
+                insert_item.execute([old_id])?;
+                let new_id = tx.last_insert_rowid();
+                if let Some(stmt) = insert_representations.as_mut() {
+                    stmt.execute([old_id, new_id])?;
+                }
+            }
+        }
+        tx.commit()?;
 
         // 合并常用文本数据
         let _ = main_conn.execute(
@@ -422,26 +527,26 @@ async fn merge_database(temp_db_path: &Path) -> Result<(), String> {
         Ok(())
     });
 
-    result.map_err(|e| format!("数据库合并失败: {}", e))
+    result.map_err(|e| e.prefixed("数据库合并失败"))
 }
 
 // 合并图片目录
-fn merge_images_directory(source_dir: &Path, target_dir: &Path) -> Result<(), String> {
+fn merge_images_directory(source_dir: &Path, target_dir: &Path) -> AppResult<()> {
     if !target_dir.exists() {
-        fs::create_dir_all(target_dir).map_err(|e| format!("创建图片目录失败: {}", e))?;
+        fs::create_dir_all(target_dir).map_err(|e| AppError::from_io("创建图片目录失败", &e))?;
     }
 
     copy_dir_recursively(source_dir, target_dir)
 }
 
 // 递归复制目录
-fn copy_dir_recursively(source: &Path, target: &Path) -> Result<(), String> {
+fn copy_dir_recursively(source: &Path, target: &Path) -> AppResult<()> {
     if !target.exists() {
-        fs::create_dir_all(target).map_err(|e| format!("创建目录失败: {}", e))?;
+        fs::create_dir_all(target).map_err(|e| AppError::from_io("创建目录失败", &e))?;
     }
 
-    for entry in fs::read_dir(source).map_err(|e| format!("读取源目录失败: {}", e))? {
-        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+    for entry in fs::read_dir(source).map_err(|e| AppError::from_io("读取源目录失败", &e))? {
+        let entry = entry.map_err(|e| AppError::from_io("读取目录项失败", &e))?;
         let source_path = entry.path();
         let target_path = target.join(entry.file_name());
 
@@ -451,7 +556,7 @@ fn copy_dir_recursively(source: &Path, target: &Path) -> Result<(), String> {
             // 只复制不存在的文件（避免覆盖）
             if !target_path.exists() {
                 fs::copy(&source_path, &target_path)
-                    .map_err(|e| format!("复制文件失败: {}", e))?;
+                    .map_err(|e| AppError::from_io("复制文件失败", &e))?;
             }
         }
     }
@@ -465,13 +570,13 @@ fn add_file_to_zip(
     file_path: &Path,
     zip_path: &str,
     options: FileOptions<()>,
-) -> Result<(), String> {
+) -> AppResult<()> {
     zip.start_file(zip_path, options)
-        .map_err(|e| format!("创建ZIP文件项失败: {}", e))?;
+        .map_err(|e| AppError::io(format!("创建ZIP文件项失败: {}", e)))?;
 
-    let file_content = fs::read(file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let file_content = fs::read(file_path).map_err(|e| AppError::from_io("读取文件失败", &e))?;
     zip.write_all(&file_content)
-        .map_err(|e| format!("写入ZIP文件失败: {}", e))?;
+        .map_err(|e| AppError::from_io("写入ZIP文件失败", &e))?;
 
     Ok(())
 }
@@ -482,9 +587,9 @@ fn add_directory_to_zip(
     dir_path: &Path,
     zip_prefix: &str,
     options: FileOptions<()>,
-) -> Result<(), String> {
-    for entry in fs::read_dir(dir_path).map_err(|e| format!("读取目录失败: {}", e))? {
-        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+) -> AppResult<()> {
+    for entry in fs::read_dir(dir_path).map_err(|e| AppError::from_io("读取目录失败", &e))? {
+        let entry = entry.map_err(|e| AppError::from_io("读取目录项失败", &e))?;
         let path = entry.path();
         let name = entry.file_name();
         let zip_path = format!("{}/{}", zip_prefix, name.to_string_lossy());
@@ -21,6 +21,7 @@ impl DataMigrationService {
             "quickclipboard.db",
             "clipboard_images", 
             "backups",
+            crate::encryption::KEY_FILE_NAME,
         ];
         
         for item in &items_to_migrate {
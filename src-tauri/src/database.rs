@@ -1,9 +1,11 @@
 use once_cell::sync::Lazy;
-use rusqlite::{params, Connection, Result as SqliteResult};
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+use crate::error::{AppError, AppResult};
+
 // 对于文本内容，超过此限制会被截断
 const MAX_CONTENT_LENGTH_FOR_DISPLAY: usize = 10000;
 
@@ -12,7 +14,7 @@ pub static DB_CONNECTION: Lazy<Arc<Mutex<Option<Connection>>>> =
     Lazy::new(|| Arc::new(Mutex::new(None)));
 
 // 动态获取数据库文件路径
-pub fn get_database_path() -> Result<PathBuf, String> {
+pub fn get_database_path() -> AppResult<PathBuf> {
     let data_dir = crate::settings::get_data_directory()?;
     Ok(data_dir.join("quickclipboard.db"))
 }
@@ -82,6 +84,51 @@ pub struct ClipboardItem {
     pub item_order: i32,
     pub created_at: i64,
     pub updated_at: i64,
+    // 复制时的来源应用，旧记录和无法获取时为空
+    #[serde(default)]
+    pub source: Option<ClipboardSource>,
+    // 置顶的项目排在最前面，不受数量限制、保留策略和清空历史影响
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+// 剪贴板内容的来源（复制时的前台窗口，浏览器复制时还有网页地址）
+// 窗口标题和网页地址与内容一样按需加密，进程名和路径用于筛选，不加密
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardSource {
+    // 进程名（如 chrome.exe）
+    pub app: Option<String>,
+    // 可执行文件路径
+    pub path: Option<String>,
+    // 窗口标题
+    pub title: Option<String>,
+    // 网页地址
+    pub url: Option<String>,
+    // 来源选区（Linux 主选区为 primary，常规剪贴板为空）
+    pub selection: Option<String>,
+}
+
+impl ClipboardSource {
+    pub fn is_empty(&self) -> bool {
+        self.app.is_none()
+            && self.path.is_none()
+            && self.title.is_none()
+            && self.url.is_none()
+            && self.selection.is_none()
+    }
+
+    // 从查询结果中读取来源（从 start 开始依次为 source_app、source_path、source_title、source_url、source_selection）
+    pub(crate) fn from_row(row: &rusqlite::Row, start: usize) -> SqliteResult<Option<Self>> {
+        let source = Self {
+            app: row.get(start)?,
+            path: row.get(start + 1)?,
+            title: row.get(start + 2)?,
+            url: row.get(start + 3)?,
+            selection: row.get(start + 4)?,
+        };
+        Ok((!source.is_empty()).then_some(source))
+    }
 }
 
 impl ClipboardItem {
@@ -98,6 +145,8 @@ impl ClipboardItem {
             item_order: 0,
             created_at: timestamp,
             updated_at: timestamp,
+            source: None,
+            pinned: false,
         }
     }
 
@@ -114,6 +163,8 @@ impl ClipboardItem {
             item_order: 0,
             created_at: timestamp,
             updated_at: timestamp,
+            source: None,
+            pinned: false,
         }
     }
 
@@ -130,6 +181,8 @@ impl ClipboardItem {
             item_order: 0,
             created_at: timestamp,
             updated_at: timestamp,
+            source: None,
+            pinned: false,
         }
     }
 
@@ -146,6 +199,8 @@ impl ClipboardItem {
             item_order: 0,
             created_at: timestamp,
             updated_at: timestamp,
+            source: None,
+            pinned: false,
         }
     }
 
@@ -162,6 +217,8 @@ impl ClipboardItem {
             item_order: 0,
             created_at: timestamp,
             updated_at: timestamp,
+            source: None,
+            pinned: false,
         }
     }
 
@@ -261,10 +318,17 @@ pub fn initialize_database() -> SqliteResult<()> {
     })?;
     println!("初始化数据库: {:?}", db_path);
 
-    let conn = Connection::open(&db_path)?;
+    let mut conn = Connection::open(&db_path)?;
+
+    // 注册全文索引和数据加密使用的SQL函数
+    crate::database_search::register_search_functions(&conn)?;
+    crate::encryption::register_sql_functions(&conn)?;
+
+    // 执行数据库结构迁移（数据库版本高于程序支持的版本时拒绝打开）
+    crate::database_migration::run_migrations(&mut conn, &db_path)?;
 
-    // 创建表
-    create_tables(&conn)?;
+    // 检查全文索引
+    crate::database_search::ensure_search_index(&conn)?;
 
     // 存储连接
     let mut db_conn = DB_CONNECTION.lock().unwrap();
@@ -274,90 +338,9 @@ pub fn initialize_database() -> SqliteResult<()> {
     Ok(())
 }
 
-// 创建数据库表
-fn create_tables(conn: &Connection) -> SqliteResult<()> {
-    // 剪贴板表（重命名并统一字段）
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS clipboard (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            content TEXT NOT NULL,
-            html_content TEXT,
-            content_type TEXT NOT NULL DEFAULT 'text',
-            image_id TEXT,
-            item_order INTEGER NOT NULL DEFAULT 0,
-            created_at INTEGER NOT NULL,
-            updated_at INTEGER NOT NULL
-        )",
-        [],
-    )?;
-
-    // 收藏表（原常用文本表，通过group_name引用groups表）
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS favorites (
-            id TEXT PRIMARY KEY,
-            title TEXT NOT NULL,
-            content TEXT NOT NULL,
-            html_content TEXT,
-            content_type TEXT NOT NULL DEFAULT 'text',
-            image_id TEXT,
-            group_name TEXT NOT NULL DEFAULT '全部',
-            item_order INTEGER NOT NULL DEFAULT 0,
-            created_at INTEGER NOT NULL,
-            updated_at INTEGER NOT NULL
-        )",
-        [],
-    )?;
-
-    // 分组表（支持空分组）
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS groups (
-            name TEXT PRIMARY KEY,
-            icon TEXT NOT NULL DEFAULT 'ti ti-folder',
-            order_index INTEGER NOT NULL DEFAULT 0,
-            created_at INTEGER NOT NULL,
-            updated_at INTEGER NOT NULL
-        )",
-        [],
-    )?;
-
-    // 创建索引
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_clipboard_created ON clipboard(created_at DESC)",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_favorites_group ON favorites(group_name, item_order)",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_favorites_updated ON favorites(updated_at DESC)",
-        [],
-    )?;
-
-    // 图片数据表（存储原始BGRA数据）
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS image_data (
-            image_id TEXT PRIMARY KEY,
-            width INTEGER NOT NULL,
-            height INTEGER NOT NULL,
-            bgra_data BLOB NOT NULL,
-            png_data BLOB NOT NULL,
-            created_at INTEGER NOT NULL
-        )",
-        [],
-    )?;
-
-    Ok(())
-}
-
-
 // 关闭数据库连接
-pub fn close_database_connection() -> Result<(), String> {
-    let mut db_conn = DB_CONNECTION
-        .lock()
-        .map_err(|e| format!("获取数据库锁失败: {}", e))?;
+pub fn close_database_connection() -> AppResult<()> {
+    let mut db_conn = lock_connection()?;
     
     if let Some(conn) = db_conn.take() {
         drop(conn);
@@ -370,36 +353,37 @@ pub fn close_database_connection() -> Result<(), String> {
 }
 
 // 重新初始化数据库连接
-pub fn reinitialize_database() -> Result<(), String> {
+pub fn reinitialize_database() -> AppResult<()> {
     // 先关闭现有连接
     close_database_connection()?;
     
     // 清除连接池中的连接
     {
-        let mut db_conn = DB_CONNECTION
-            .lock()
-            .map_err(|e| format!("获取数据库锁失败: {}", e))?;
+        let mut db_conn = lock_connection()?;
         *db_conn = None;
     }
     
     // 重新初始化
-    initialize_database()
-        .map_err(|e| format!("重新初始化数据库失败: {}", e))
+    initialize_database().map_err(|e| AppError::from_sqlite("重新初始化数据库失败", e))
+}
+
+// 获取数据库连接锁
+fn lock_connection() -> AppResult<std::sync::MutexGuard<'static, Option<Connection>>> {
+    DB_CONNECTION
+        .lock()
+        .map_err(|e| AppError::database(format!("获取数据库锁失败: {}", e)))
 }
 
 // 执行数据库操作的辅助函数
-pub fn with_connection<F, R>(f: F) -> Result<R, String>
+pub fn with_connection<F, R>(f: F) -> AppResult<R>
 where
     F: FnOnce(&Connection) -> SqliteResult<R>,
 {
-    let conn_arc = DB_CONNECTION.clone();
-    let conn_guard = conn_arc
-        .lock()
-        .map_err(|e| format!("获取数据库锁失败: {}", e))?;
+    let conn_guard = lock_connection()?;
 
     match conn_guard.as_ref() {
-        Some(conn) => f(conn).map_err(|e| format!("数据库操作失败: {}", e)),
-        None => Err("数据库未初始化".to_string()),
+        Some(conn) => f(conn).map_err(AppError::from),
+        None => Err(AppError::database_not_initialized()),
     }
 }
 
@@ -425,78 +409,17 @@ pub fn detect_content_type(content: &str, html: Option<&str>) -> ContentType {
     }
     
     // 检查是否为URL
-    if is_url(content) {
+    if crate::content_classifier::is_url(content) {
         return ContentType::Link;
     }
     // 默认为纯文本
     ContentType::Text
 }
 
-// 检查字符串是否为URL
-fn is_url(text: &str) -> bool {
-    let text = text.trim();
-    text.starts_with("http://") || 
-    text.starts_with("https://") || 
-    text.starts_with("ftp://") || 
-    text.starts_with("ftps://") ||
-    (text.contains('.') && text.split_whitespace().count() == 1 && 
-     (text.ends_with(".com") || text.ends_with(".org") || text.ends_with(".net") || 
-      text.ends_with(".edu") || text.ends_with(".gov") || text.ends_with(".cn") ||
-      text.ends_with(".io") || text.ends_with(".dev")))
-}
-
-// 检查字符串是否为文件路径
-fn is_file_paths(text: &str) -> bool {
-    let text = text.trim();
-    
-    // 只检查简单的文件路径模式，避免误判
-    let lines: Vec<&str> = text.lines().collect();
-    if lines.is_empty() {
-        return false;
-    }
-    
-    let mut valid_path_count = 0;
-    let total_lines = lines.len();
-    
-    // 检查每一行是否像文件路径
-    for line in &lines {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        
-        // Windows绝对路径格式: C:\path\to\file
-        if line.len() >= 3 && 
-           line.chars().nth(1) == Some(':') && 
-           line.chars().nth(2) == Some('\\') &&
-           line.chars().nth(0).map_or(false, |c| c.is_ascii_alphabetic()) {
-            valid_path_count += 1;
-            continue;
-        }
-        
-        // UNC路径格式: \\server\share\path
-        if line.starts_with("\\\\") && line.len() > 2 {
-            valid_path_count += 1;
-            continue;
-        }
-        
-        // Unix绝对路径格式: /path/to/file
-        if line.starts_with('/') && line.len() > 1 && !line.contains(' ') {
-            valid_path_count += 1;
-            continue;
-        }
-    }
-    
-    // 需要至少80%的行看起来像文件路径，且至少有1个有效路径
-    total_lines > 0 && 
-    valid_path_count > 0 &&
-    (valid_path_count as f32 / total_lines as f32) >= 0.8
-}
-
 // =================== 剪贴板历史数据库操作 ===================
 
 // 智能添加剪贴板项目（根据内容自动检测类型）
-pub fn add_clipboard_item_smart(content: String, html: Option<String>) -> Result<i64, String> {
+pub fn add_clipboard_item_smart(content: String, html: Option<String>) -> AppResult<i64> {
     let content_type = detect_content_type(&content, html.as_deref());
     
     match content_type {
@@ -521,13 +444,15 @@ pub fn add_clipboard_item_smart(content: String, html: Option<String>) -> Result
                     item_order: 0,
                     created_at: chrono::Local::now().timestamp(),
                     updated_at: chrono::Local::now().timestamp(),
+                    source: None,
+                    pinned: false,
                 };
                 
                 with_connection(|conn| {
                     let new_order = get_new_clipboard_order(conn);
                     
                     conn.execute(
-                        "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (qc_encrypt(?1), qc_encrypt(?2), ?3, ?4, ?5, ?6, ?7)",
                         params![item.content, item.html_content, item.content_type.to_string(), item.image_id, new_order, item.created_at, item.updated_at],
                     )?;
             
@@ -568,14 +493,14 @@ fn get_new_clipboard_order(conn: &Connection) -> i32 {
 }
 
 // 添加剪贴板项目
-pub fn add_clipboard_item(content: String) -> Result<i64, String> {
+pub fn add_clipboard_item(content: String) -> AppResult<i64> {
     let item = ClipboardItem::new_text(content);
 
     with_connection(|conn| {
         let new_order = get_new_clipboard_order(conn);
         
         conn.execute(
-            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (qc_encrypt(?1), qc_encrypt(?2), ?3, ?4, ?5, ?6, ?7)",
             params![item.content, item.html_content, item.content_type.to_string(), item.image_id, new_order, item.created_at, item.updated_at],
         )?;
 
@@ -585,7 +510,7 @@ pub fn add_clipboard_item(content: String) -> Result<i64, String> {
 
 
 // 添加富文本剪贴板项目
-pub fn add_clipboard_rich_text(content: String, html: String) -> Result<i64, String> {
+pub fn add_clipboard_rich_text(content: String, html: String) -> AppResult<i64> {
     // 在存储前统一处理HTML中的所有图片URL，转换为dataURL
     let processed_html = crate::database_image_utils::normalize_html_images(&html);
     let item = ClipboardItem::new_rich_text(content, processed_html);
@@ -594,7 +519,7 @@ pub fn add_clipboard_rich_text(content: String, html: String) -> Result<i64, Str
         let new_order = get_new_clipboard_order(conn);
         
         conn.execute(
-            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (qc_encrypt(?1), qc_encrypt(?2), ?3, ?4, ?5, ?6, ?7)",
             params![item.content, item.html_content, item.content_type.to_string(), item.image_id, new_order, item.created_at, item.updated_at],
         )?;
 
@@ -603,14 +528,14 @@ pub fn add_clipboard_rich_text(content: String, html: String) -> Result<i64, Str
 }
 
 // 添加图片剪贴板项目
-pub fn add_clipboard_image(image_id: String) -> Result<i64, String> {
+pub fn add_clipboard_image(image_id: String) -> AppResult<i64> {
     let item = ClipboardItem::new_image(image_id);
 
     with_connection(|conn| {
         let new_order = get_new_clipboard_order(conn);
         
         conn.execute(
-            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (qc_encrypt(?1), qc_encrypt(?2), ?3, ?4, ?5, ?6, ?7)",
             params![item.content, item.html_content, item.content_type.to_string(), item.image_id, new_order, item.created_at, item.updated_at],
         )?;
 
@@ -619,14 +544,14 @@ pub fn add_clipboard_image(image_id: String) -> Result<i64, String> {
 }
 
 // 添加文件剪贴板项目
-pub fn add_clipboard_file(file_paths: Vec<String>) -> Result<i64, String> {
+pub fn add_clipboard_file(file_paths: Vec<String>) -> AppResult<i64> {
     let item = ClipboardItem::new_file(file_paths);
 
     with_connection(|conn| {
         let new_order = get_new_clipboard_order(conn);
         
         conn.execute(
-            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (qc_encrypt(?1), qc_encrypt(?2), ?3, ?4, ?5, ?6, ?7)",
             params![item.content, item.html_content, item.content_type.to_string(), item.image_id, new_order, item.created_at, item.updated_at],
         )?;
 
@@ -635,14 +560,14 @@ pub fn add_clipboard_file(file_paths: Vec<String>) -> Result<i64, String> {
 }
 
 // 添加链接剪贴板项目
-pub fn add_clipboard_link(url: String) -> Result<i64, String> {
+pub fn add_clipboard_link(url: String) -> AppResult<i64> {
     let item = ClipboardItem::new_link(url);
 
     with_connection(|conn| {
         let new_order = get_new_clipboard_order(conn);
         
         conn.execute(
-            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (qc_encrypt(?1), qc_encrypt(?2), ?3, ?4, ?5, ?6, ?7)",
             params![item.content, item.html_content, item.content_type.to_string(), item.image_id, new_order, item.created_at, item.updated_at],
         )?;
 
@@ -679,65 +604,86 @@ fn truncate_string_for_display(s: String, max_len: usize) -> String {
     truncated
 }
 
-// 获取剪贴板历史（按更新时间倒序，支持拖拽排序）
-pub fn get_clipboard_history(limit: Option<usize>) -> Result<Vec<ClipboardItem>, String> {
-    with_connection(|conn| {
-        let sql = if let Some(limit) = limit {
-            // 如果限制数量非常大（≥999999），直接无限制
-            if limit >= 999999 {
-                "SELECT id, content, html_content, content_type, image_id, item_order, created_at, updated_at FROM clipboard ORDER BY item_order, updated_at DESC".to_string()
+// 按内容类型截断用于展示的内容（图片和文件类型不截断）
+pub(crate) fn truncate_for_display(
+    content_type: &ContentType,
+    content: String,
+    html_content: Option<String>,
+) -> (String, Option<String>) {
+    match content_type {
+        ContentType::Text | ContentType::RichText | ContentType::Link => {
+            // 截断content
+            let truncated_content = if content.len() > MAX_CONTENT_LENGTH_FOR_DISPLAY {
+                truncate_string_for_display(content, MAX_CONTENT_LENGTH_FOR_DISPLAY)
             } else {
-                format!("SELECT id, content, html_content, content_type, image_id, item_order, created_at, updated_at FROM clipboard ORDER BY item_order, updated_at DESC LIMIT {}", limit)
-            }
-        } else {
-            "SELECT id, content, html_content, content_type, image_id, item_order, created_at, updated_at FROM clipboard ORDER BY item_order, updated_at DESC".to_string()
-        };
+                content
+            };
 
-        let mut stmt = conn.prepare(&sql)?;
-        let rows = stmt.query_map([], |row| {
-            let content: String = row.get(1)?;
-            let html_content: Option<String> = row.get(2).ok();
-            let content_type = ContentType::from_string(&row.get::<_, String>(3).unwrap_or_default());
-
-            let (truncated_content, truncated_html) = match content_type {
-                ContentType::Text | ContentType::RichText | ContentType::Link => {
-                    // 截断content
-                    let truncated_content = if content.len() > MAX_CONTENT_LENGTH_FOR_DISPLAY {
-                        truncate_string_for_display(content, MAX_CONTENT_LENGTH_FOR_DISPLAY)
-                    } else {
-                        content
-                    };
-                    
-                    // 截断html_content
-                    let truncated_html = if let Some(html) = html_content {
-                        if html.len() > MAX_CONTENT_LENGTH_FOR_DISPLAY {
-                            Some(truncate_string_for_display(html, MAX_CONTENT_LENGTH_FOR_DISPLAY))
-                        } else {
-                            Some(html)
-                        }
-                    } else {
-                        None
-                    };
-                    
-                    (truncated_content, truncated_html)
-                },
-                // 图片和文件类型不截断
-                ContentType::Image | ContentType::File => {
-                    (content, html_content)
+            // 截断html_content
+            let truncated_html = html_content.map(|html| {
+                if html.len() > MAX_CONTENT_LENGTH_FOR_DISPLAY {
+                    truncate_string_for_display(html, MAX_CONTENT_LENGTH_FOR_DISPLAY)
+                } else {
+                    html
                 }
-            };
-            
-            Ok(ClipboardItem {
-                id: row.get(0)?,
-                content: truncated_content,
-                html_content: truncated_html,
-                content_type,
-                image_id: row.get(4)?,
-                item_order: row.get(5)?,
-                created_at: row.get(6)?,
-                updated_at: row.get(7)?,
-            })
-        })?;
+            });
+
+            (truncated_content, truncated_html)
+        }
+        ContentType::Image | ContentType::File => (content, html_content),
+    }
+}
+
+// 剪贴板历史查询的列，与 clipboard_item_from_row 的读取顺序对应
+pub(crate) const CLIPBOARD_ITEM_COLUMNS: &str = "id, qc_decrypt(content), qc_decrypt(html_content), content_type, image_id, item_order, created_at, updated_at, source_app, source_path, qc_decrypt(source_title), qc_decrypt(source_url), source_selection, pinned";
+
+// 读取一行剪贴板历史（内容按显示长度截断）
+pub(crate) fn clipboard_item_from_row(row: &rusqlite::Row) -> SqliteResult<ClipboardItem> {
+    let item = full_clipboard_item_from_row(row)?;
+    let (content, html_content) = truncate_for_display(&item.content_type, item.content, item.html_content);
+    Ok(ClipboardItem {
+        content,
+        html_content,
+        ..item
+    })
+}
+
+// 读取一行剪贴板历史（完整内容）
+fn full_clipboard_item_from_row(row: &rusqlite::Row) -> SqliteResult<ClipboardItem> {
+    Ok(ClipboardItem {
+        id: row.get(0)?,
+        content: row.get(1)?,
+        html_content: row.get(2).ok(),
+        content_type: ContentType::from_string(&row.get::<_, String>(3).unwrap_or_default()),
+        image_id: row.get(4)?,
+        item_order: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        source: ClipboardSource::from_row(row, 8)?,
+        pinned: row.get(13)?,
+    })
+}
+
+// 获取剪贴板历史（置顶的项目在前，其余按更新时间倒序，支持拖拽排序）
+// 置顶的项目不计入数量限制
+pub fn get_clipboard_history(limit: Option<usize>) -> AppResult<Vec<ClipboardItem>> {
+    with_connection(|conn| {
+        let mut sql = format!(
+            "SELECT {} FROM clipboard ORDER BY pinned DESC, item_order, updated_at DESC",
+            CLIPBOARD_ITEM_COLUMNS
+        );
+        // 如果限制数量非常大（≥999999），直接无限制
+        if let Some(limit) = limit.filter(|&limit| limit < 999999) {
+            let pinned_count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM clipboard WHERE pinned = 1",
+                [],
+                |row| row.get(0),
+            )?;
+            sql.push_str(&format!(" LIMIT {}", limit as i64 + pinned_count));
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], clipboard_item_from_row)?;
 
         let mut items = Vec::new();
         for row in rows {
@@ -748,11 +694,26 @@ pub fn get_clipboard_history(limit: Option<usize>) -> Result<Vec<ClipboardItem>,
     })
 }
 
+// 获取最近一次复制的剪贴板项目（不考虑置顶状态）
+pub fn get_latest_clipboard_item() -> AppResult<Option<ClipboardItem>> {
+    with_connection(|conn| {
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM clipboard ORDER BY item_order, updated_at DESC LIMIT 1",
+                CLIPBOARD_ITEM_COLUMNS
+            ),
+            [],
+            clipboard_item_from_row,
+        )
+        .optional()
+    })
+}
+
 // 检查剪贴板项目是否存在
-pub fn clipboard_item_exists(content: &str) -> Result<Option<i64>, String> {
+pub fn clipboard_item_exists(content: &str) -> AppResult<Option<i64>> {
     with_connection(|conn| {
         let mut stmt = conn.prepare(
-            "SELECT id FROM clipboard WHERE content = ?1 ORDER BY created_at DESC LIMIT 1",
+            "SELECT id FROM clipboard WHERE content = qc_encrypt(?1) ORDER BY created_at DESC LIMIT 1",
         )?;
         let mut rows = stmt.query_map([content], |row| Ok(row.get::<_, i64>(0)?))?;
 
@@ -764,8 +725,20 @@ pub fn clipboard_item_exists(content: &str) -> Result<Option<i64>, String> {
     })
 }
 
+// 根据ID获取剪贴板项目（完整内容，不截断）
+pub fn get_clipboard_item_by_id(id: i64) -> AppResult<Option<ClipboardItem>> {
+    with_connection(|conn| {
+        conn.query_row(
+            &format!("SELECT {} FROM clipboard WHERE id = ?1", CLIPBOARD_ITEM_COLUMNS),
+            params![id],
+            full_clipboard_item_from_row,
+        )
+        .optional()
+    })
+}
+
 // 移动剪贴板项目到最前面（使用item_order排序）
-pub fn move_clipboard_item_to_front(id: i64) -> Result<(), String> {
+pub fn move_clipboard_item_to_front(id: i64) -> AppResult<()> {
     let now = chrono::Local::now();
     let new_timestamp = now.timestamp();
 
@@ -788,7 +761,7 @@ pub fn move_clipboard_item_to_front(id: i64) -> Result<(), String> {
 }
 
 // 删除剪贴板项目
-pub fn delete_clipboard_item(id: i64) -> Result<(), String> {
+pub fn delete_clipboard_item(id: i64) -> AppResult<()> {
     with_connection(|conn| {
         conn.execute("DELETE FROM clipboard WHERE id = ?1", params![id])?;
         Ok(())
@@ -801,22 +774,72 @@ pub fn delete_clipboard_item(id: i64) -> Result<(), String> {
 }
 
 // 更新剪贴板项目内容
-pub fn update_clipboard_item(id: i64, new_content: String) -> Result<(), String> {
+pub fn update_clipboard_item(id: i64, new_content: String) -> AppResult<()> {
     let now = chrono::Local::now().timestamp();
     
     with_connection(|conn| {
         conn.execute(
-            "UPDATE clipboard SET content = ?1, updated_at = ?2 WHERE id = ?3",
+            "UPDATE clipboard SET content = qc_encrypt(?1), updated_at = ?2, tags_version = 0 WHERE id = ?3",
             params![new_content, now, id],
         )?;
         Ok(())
+    })?;
+
+    // 编辑后其他格式已不再对应新内容，并按新内容重新分类
+    crate::clipboard_representations::reset(id)?;
+    crate::content_classifier::classify_pending_in_background();
+    Ok(())
+}
+
+// 设置剪贴板项目的自动过期时间（None 表示不过期）
+pub fn set_clipboard_item_expiry(id: i64, expires_at: Option<i64>) -> AppResult<()> {
+    with_connection(|conn| {
+        conn.execute(
+            "UPDATE clipboard SET expires_at = ?1 WHERE id = ?2",
+            params![expires_at, id],
+        )?;
+        Ok(())
     })
 }
 
-// 清空剪贴板历史
-pub fn clear_clipboard_history() -> Result<(), String> {
+// 记录剪贴板项目的来源（重复复制时以最新一次为准）
+pub fn set_clipboard_item_source(id: i64, source: &ClipboardSource) -> AppResult<()> {
     with_connection(|conn| {
-        conn.execute("DELETE FROM clipboard", [])?;
+        conn.execute(
+            "UPDATE clipboard SET source_app = ?1, source_path = ?2, source_title = qc_encrypt(?3), source_url = qc_encrypt(?4), source_selection = ?5 WHERE id = ?6",
+            params![source.app, source.path, source.title, source.url, source.selection, id],
+        )?;
+        Ok(())
+    })
+}
+
+// 设置剪贴板项目的置顶状态，返回项目是否存在
+pub fn set_clipboard_item_pinned(id: i64, pinned: bool) -> AppResult<bool> {
+    with_connection(|conn| {
+        let changed = conn.execute(
+            "UPDATE clipboard SET pinned = ?1 WHERE id = ?2",
+            params![pinned, id],
+        )?;
+        Ok(changed > 0)
+    })
+}
+
+// 获取剪贴板项目的置顶状态（项目不存在时返回 None）
+pub fn is_clipboard_item_pinned(id: i64) -> AppResult<Option<bool>> {
+    with_connection(|conn| {
+        conn.query_row(
+            "SELECT pinned FROM clipboard WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+    })
+}
+
+// 清空剪贴板历史（保留置顶的项目）
+pub fn clear_clipboard_history() -> AppResult<()> {
+    with_connection(|conn| {
+        conn.execute("DELETE FROM clipboard WHERE pinned = 0", [])?;
         Ok(())
     })?;
     
@@ -826,30 +849,31 @@ pub fn clear_clipboard_history() -> Result<(), String> {
 }
 
 // 限制剪贴板历史数量
-pub fn limit_clipboard_history(max_count: usize) -> Result<(), String> {
+pub fn limit_clipboard_history(max_count: usize) -> AppResult<()> {
     if max_count >= 999999 {
         return Ok(());
     }
     
-    with_connection(|conn| {
-        // 删除超出限制的记录（保留item_order最小的记录）
-        conn.execute(
-            "DELETE FROM clipboard WHERE id NOT IN (
-                SELECT id FROM clipboard ORDER BY item_order, updated_at DESC LIMIT ?1
-            )",
-            params![max_count],
-        )?;
-        Ok(())
-    })?;
+    with_connection(|conn| delete_over_history_limit(conn, max_count))?;
     
     crate::clipboard_history::cleanup_orphaned_images();
     
     Ok(())
 }
 
+// 删除超出限制的记录（保留item_order最小的记录），置顶的项目不计入数量也不会被删除
+pub(crate) fn delete_over_history_limit(conn: &Connection, max_count: usize) -> SqliteResult<usize> {
+    conn.execute(
+        "DELETE FROM clipboard WHERE pinned = 0 AND id NOT IN (
+            SELECT id FROM clipboard WHERE pinned = 0 ORDER BY item_order, updated_at DESC LIMIT ?1
+        )",
+        params![max_count],
+    )
+}
+
 // 批量更新剪贴板项目的时间戳（用于重新排序）
 // 通过ID重新排序剪贴板项目（使用item_order字段）
-pub fn reorder_clipboard_items_by_ids(ids: &[i64]) -> Result<(), String> {
+pub fn reorder_clipboard_items_by_ids(ids: &[i64]) -> AppResult<()> {
     with_connection(|conn| {
         let tx = conn.unchecked_transaction()?;
 
@@ -870,10 +894,10 @@ pub fn reorder_clipboard_items_by_ids(ids: &[i64]) -> Result<(), String> {
 // =================== 收藏项目数据库操作 ===================
 
 // 添加收藏项目
-pub fn add_favorite_item(item: &FavoriteItem) -> Result<(), String> {
+pub fn add_favorite_item(item: &FavoriteItem) -> AppResult<()> {
     with_connection(|conn| {
         conn.execute(
-                    "INSERT INTO favorites (id, title, content, html_content, content_type, image_id, group_name, item_order, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    "INSERT INTO favorites (id, title, content, html_content, content_type, image_id, group_name, item_order, created_at, updated_at) VALUES (?1, qc_encrypt(?2), qc_encrypt(?3), qc_encrypt(?4), ?5, ?6, ?7, ?8, ?9, ?10)",
         params![item.id, item.title, item.content, item.html_content, item.content_type.to_string(), item.image_id, item.group_name, item.item_order, item.created_at, item.updated_at],
         )?;
         Ok(())
@@ -881,10 +905,10 @@ pub fn add_favorite_item(item: &FavoriteItem) -> Result<(), String> {
 }
 
 // 获取所有收藏项目
-pub fn get_all_favorite_items() -> Result<Vec<FavoriteItem>, String> {
+pub fn get_all_favorite_items() -> AppResult<Vec<FavoriteItem>> {
     with_connection(|conn| {
         let mut stmt = conn.prepare(
-            "SELECT f.id, f.title, f.content, f.html_content, f.content_type, f.image_id, f.group_name, f.item_order, f.created_at, f.updated_at 
+            "SELECT f.id, qc_decrypt(f.title), qc_decrypt(f.content), qc_decrypt(f.html_content), f.content_type, f.image_id, f.group_name, f.item_order, f.created_at, f.updated_at 
              FROM favorites f 
              LEFT JOIN groups g ON f.group_name = g.name 
              ORDER BY COALESCE(g.order_index, 999999), f.item_order, f.updated_at DESC"
@@ -895,33 +919,7 @@ pub fn get_all_favorite_items() -> Result<Vec<FavoriteItem>, String> {
             let html_content: Option<String> = row.get(3)?;
             let content_type = ContentType::from_string(&row.get::<_, String>(4).unwrap_or_default());
 
-            let (truncated_content, truncated_html) = match content_type {
-                ContentType::Text | ContentType::RichText | ContentType::Link => {
-                    // 截断content
-                    let truncated_content = if content.len() > MAX_CONTENT_LENGTH_FOR_DISPLAY {
-                        truncate_string_for_display(content, MAX_CONTENT_LENGTH_FOR_DISPLAY)
-                    } else {
-                        content
-                    };
-                    
-                    // 截断html_content
-                    let truncated_html = if let Some(html) = html_content {
-                        if html.len() > MAX_CONTENT_LENGTH_FOR_DISPLAY {
-                            Some(truncate_string_for_display(html, MAX_CONTENT_LENGTH_FOR_DISPLAY))
-                        } else {
-                            Some(html)
-                        }
-                    } else {
-                        None
-                    };
-                    
-                    (truncated_content, truncated_html)
-                },
-                // 图片和文件类型不截断
-                ContentType::Image | ContentType::File => {
-                    (content, html_content)
-                }
-            };
+            let (truncated_content, truncated_html) = truncate_for_display(&content_type, content, html_content);
             
             Ok(FavoriteItem {
                 id: row.get(0)?,
@@ -947,10 +945,10 @@ pub fn get_all_favorite_items() -> Result<Vec<FavoriteItem>, String> {
 }
 
 // 按分组获取收藏项目
-pub fn get_favorite_items_by_group(group_name: &str) -> Result<Vec<FavoriteItem>, String> {
+pub fn get_favorite_items_by_group(group_name: &str) -> AppResult<Vec<FavoriteItem>> {
     with_connection(|conn| {
         let mut stmt = conn.prepare(
-            "SELECT id, title, content, html_content, content_type, image_id, group_name, item_order, created_at, updated_at FROM favorites WHERE group_name = ?1 ORDER BY item_order, updated_at DESC"
+            "SELECT id, qc_decrypt(title), qc_decrypt(content), qc_decrypt(html_content), content_type, image_id, group_name, item_order, created_at, updated_at FROM favorites WHERE group_name = ?1 ORDER BY item_order, updated_at DESC"
         )?;
 
         let rows = stmt.query_map([group_name], |row| {
@@ -958,30 +956,7 @@ pub fn get_favorite_items_by_group(group_name: &str) -> Result<Vec<FavoriteItem>
             let html_content: Option<String> = row.get(3)?;
             let content_type = ContentType::from_string(&row.get::<_, String>(4).unwrap_or_default());
 
-            let (truncated_content, truncated_html) = match content_type {
-                ContentType::Text | ContentType::RichText | ContentType::Link => {
-                    let truncated_content = if content.len() > MAX_CONTENT_LENGTH_FOR_DISPLAY {
-                        truncate_string_for_display(content, MAX_CONTENT_LENGTH_FOR_DISPLAY)
-                    } else {
-                        content
-                    };
-                    
-                    let truncated_html = if let Some(html) = html_content {
-                        if html.len() > MAX_CONTENT_LENGTH_FOR_DISPLAY {
-                            Some(truncate_string_for_display(html, MAX_CONTENT_LENGTH_FOR_DISPLAY))
-                        } else {
-                            Some(html)
-                        }
-                    } else {
-                        None
-                    };
-                    
-                    (truncated_content, truncated_html)
-                },
-                ContentType::Image | ContentType::File => {
-                    (content, html_content)
-                }
-            };
+            let (truncated_content, truncated_html) = truncate_for_display(&content_type, content, html_content);
             
             Ok(FavoriteItem {
                 id: row.get(0)?,
@@ -1007,10 +982,10 @@ pub fn get_favorite_items_by_group(group_name: &str) -> Result<Vec<FavoriteItem>
 }
 
 // 更新收藏项目
-pub fn update_favorite_item(item: &FavoriteItem) -> Result<(), String> {
+pub fn update_favorite_item(item: &FavoriteItem) -> AppResult<()> {
     with_connection(|conn| {
         conn.execute(
-            "UPDATE favorites SET title = ?1, content = ?2, html_content = ?3, content_type = ?4, image_id = ?5, group_name = ?6, item_order = ?7, updated_at = ?8 WHERE id = ?9",
+            "UPDATE favorites SET title = qc_encrypt(?1), content = qc_encrypt(?2), html_content = qc_encrypt(?3), content_type = ?4, image_id = ?5, group_name = ?6, item_order = ?7, updated_at = ?8 WHERE id = ?9",
             params![item.title, item.content, item.html_content, item.content_type.to_string(), item.image_id, item.group_name, item.item_order, item.updated_at, item.id],
         )?;
         Ok(())
@@ -1018,7 +993,7 @@ pub fn update_favorite_item(item: &FavoriteItem) -> Result<(), String> {
 }
 
 // 删除收藏项目
-pub fn delete_favorite_item(id: &str) -> Result<(), String> {
+pub fn delete_favorite_item(id: &str) -> AppResult<()> {
     with_connection(|conn| {
         conn.execute("DELETE FROM favorites WHERE id = ?1", params![id])?;
         Ok(())
@@ -1026,7 +1001,7 @@ pub fn delete_favorite_item(id: &str) -> Result<(), String> {
 }
 
 // 检查收藏项目是否存在
-pub fn favorite_item_exists(id: &str) -> Result<bool, String> {
+pub fn favorite_item_exists(id: &str) -> AppResult<bool> {
     with_connection(|conn| {
         let count: i64 = conn.query_row(
             "SELECT COUNT(*) FROM favorites WHERE id = ?1",
@@ -1037,8 +1012,34 @@ pub fn favorite_item_exists(id: &str) -> Result<bool, String> {
     })
 }
 
+// 按标题查找收藏项目（完整内容，不截断）
+pub fn find_favorite_items_by_title(title: &str) -> AppResult<Vec<FavoriteItem>> {
+    with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, qc_decrypt(title), qc_decrypt(content), qc_decrypt(html_content), content_type, image_id, group_name, item_order, created_at, updated_at FROM favorites WHERE title = qc_encrypt(?1) ORDER BY item_order, updated_at DESC"
+        )?;
+
+        let rows = stmt.query_map([title], |row| {
+            Ok(FavoriteItem {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                content: row.get(2)?,
+                html_content: row.get(3)?,
+                content_type: ContentType::from_string(&row.get::<_, String>(4).unwrap_or_default()),
+                image_id: row.get(5)?,
+                group_name: row.get(6)?,
+                item_order: row.get(7)?,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+            })
+        })?;
+
+        rows.collect()
+    })
+}
+
 // 批量更新收藏项目的排序
-pub fn reorder_favorite_items(items: &[FavoriteItem]) -> Result<(), String> {
+pub fn reorder_favorite_items(items: &[FavoriteItem]) -> AppResult<()> {
     with_connection(|conn| {
         let tx = conn.unchecked_transaction()?;
 
@@ -1058,7 +1059,7 @@ pub fn reorder_favorite_items(items: &[FavoriteItem]) -> Result<(), String> {
 // =================== 分组信息查询操作 ===================
 
 // 获取所有分组信息（合并groups表和favorites表的数据）
-pub fn get_all_groups() -> Result<Vec<GroupInfo>, String> {
+pub fn get_all_groups() -> AppResult<Vec<GroupInfo>> {
     with_connection(|conn| {
         let mut groups = Vec::new();
         
@@ -1089,7 +1090,7 @@ pub fn get_all_groups() -> Result<Vec<GroupInfo>, String> {
 }
 
 // 更新分组信息（批量更新指定分组的所有项目）
-pub fn update_group_info(old_name: &str, new_name: &str, new_icon: &str, new_order: i32) -> Result<(), String> {
+pub fn update_group_info(old_name: &str, new_name: &str, new_icon: &str, new_order: i32) -> AppResult<()> {
     let now = chrono::Local::now().timestamp();
     
     with_connection(|conn| {
@@ -1109,7 +1110,7 @@ pub fn update_group_info(old_name: &str, new_name: &str, new_icon: &str, new_ord
 }
 
 // 删除分组（将分组下的所有项目移动到全部）
-pub fn delete_group_items(group_name: &str) -> Result<(), String> {
+pub fn delete_group_items(group_name: &str) -> AppResult<()> {
     with_connection(|conn| {
         let tx = conn.unchecked_transaction()?;
         
@@ -1125,7 +1126,7 @@ pub fn delete_group_items(group_name: &str) -> Result<(), String> {
 }
 
 // 检查分组是否存在
-pub fn group_exists(group_name: &str) -> Result<bool, String> {
+pub fn group_exists(group_name: &str) -> AppResult<bool> {
     with_connection(|conn| {
         let count: i64 = conn.query_row(
             "SELECT COUNT(*) FROM groups WHERE name = ?1",
@@ -1137,17 +1138,14 @@ pub fn group_exists(group_name: &str) -> Result<bool, String> {
 }
 
 // 清空所有数据
-pub fn clear_all_data() -> Result<(), String> {
-    let conn_arc = DB_CONNECTION.clone();
-    let mut conn_guard = conn_arc
-        .lock()
-        .map_err(|e| format!("获取数据库锁失败: {}", e))?;
+pub fn clear_all_data() -> AppResult<()> {
+    let mut conn_guard = lock_connection()?;
 
     match conn_guard.as_mut() {
         Some(conn) => {
             let tx = conn
                 .transaction()
-                .map_err(|e| format!("创建事务失败: {}", e))?;
+                .map_err(|e| AppError::from_sqlite("创建事务失败", e))?;
 
             // 安全地清空所有表（如果表存在的话）
             let tables = vec!["clipboard", "favorites", "groups", "image_data"];
@@ -1169,7 +1167,10 @@ pub fn clear_all_data() -> Result<(), String> {
 
                 if table_exists {
                     tx.execute(&format!("DELETE FROM {}", table), [])
-                        .map_err(|e| format!("清空表 {} 失败: {}", table, e))?;
+                        .map_err(|e| {
+                            AppError::from_sqlite(&format!("清空表 {} 失败", table), e)
+                                .with_context("table", table)
+                        })?;
                 }
             }
 
@@ -1186,24 +1187,21 @@ pub fn clear_all_data() -> Result<(), String> {
 
             if sequence_exists {
                 tx.execute("DELETE FROM sqlite_sequence WHERE name IN ('clipboard', 'favorites')", [])
-                    .map_err(|e| format!("重置自增ID失败: {}", e))?;
+                    .map_err(|e| AppError::from_sqlite("重置自增ID失败", e))?;
             }
 
-            tx.commit().map_err(|e| format!("提交事务失败: {}", e))?;
+            tx.commit().map_err(|e| AppError::from_sqlite("提交事务失败", e))?;
             Ok(())
         }
-        None => Err("数据库未初始化".to_string()),
+        None => Err(AppError::database_not_initialized()),
     }
 }
 
 // =================== 分组管理操作 ===================
 
 // 创建分组
-pub fn create_group(name: &str, icon: &str) -> Result<(), String> {
-    let conn_arc = DB_CONNECTION.clone();
-    let mut conn_guard = conn_arc
-        .lock()
-        .map_err(|e| format!("获取数据库锁失败: {}", e))?;
+pub fn create_group(name: &str, icon: &str) -> AppResult<()> {
+    let mut conn_guard = lock_connection()?;
 
     match conn_guard.as_mut() {
         Some(conn) => {
@@ -1212,19 +1210,21 @@ pub fn create_group(name: &str, icon: &str) -> Result<(), String> {
                 "SELECT COUNT(*) FROM groups WHERE name = ?1",
                 params![name],
                 |row| row.get(0),
-            ).map_err(|e| format!("检查分组名称失败: {}", e))?;
+            ).map_err(|e| AppError::from_sqlite("检查分组名称失败", e))?;
             
             if count > 0 {
-                return Err(format!("分组名称 '{}' 已存在，请使用其他名称", name));
+                return Err(AppError::invalid_input(format!("分组名称 '{}' 已存在，请使用其他名称", name))
+                    .with_key("error.group.already_exists")
+                    .with_context("name", name));
             }
             
             let now = chrono::Utc::now().timestamp();
             conn.execute(
                 "INSERT INTO groups (name, icon, order_index, created_at, updated_at) VALUES (?1, ?2, 0, ?3, ?4)",
                 params![name, icon, now, now],
-            ).map_err(|e| format!("创建分组失败: {}", e))?;
+            ).map_err(|e| AppError::from_sqlite("创建分组失败", e))?;
             Ok(())
         }
-        None => Err("数据库未初始化".to_string()),
+        None => Err(AppError::database_not_initialized()),
     }
 }
@@ -0,0 +1,617 @@
+// 数据库结构迁移模块
+//
+// 使用 PRAGMA user_version 记录数据库结构版本，按顺序在事务中执行迁移步骤
+
+use rusqlite::{Connection, Result as SqliteResult};
+use std::path::{Path, PathBuf};
+
+// 单个迁移步骤
+pub struct Migration {
+    // 迁移完成后的结构版本号（从1开始递增）
+    pub version: i32,
+    // 迁移说明
+    pub description: &'static str,
+    // 是否为破坏性迁移（删除/重建表、删除列等），执行前会备份数据库文件
+    pub destructive: bool,
+    // 迁移操作（在事务中执行）
+    pub up: fn(&Connection) -> SqliteResult<()>,
+}
+
+// 所有迁移步骤，必须按版本号升序排列，已发布的迁移不可修改
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "创建基础表（剪贴板、收藏、分组、图片数据）",
+        destructive: false,
+        up: migrate_v1_base_tables,
+    },
+    Migration {
+        version: 2,
+        description: "创建全文搜索索引",
+        destructive: false,
+        up: migrate_v2_search_index,
+    },
+    Migration {
+        version: 3,
+        description: "剪贴板记录增加自动过期时间",
+        destructive: false,
+        up: migrate_v3_clipboard_expiry,
+    },
+    Migration {
+        version: 4,
+        description: "创建多设备同步状态表",
+        destructive: false,
+        up: migrate_v4_sync_state,
+    },
+    Migration {
+        version: 5,
+        description: "剪贴板记录的多格式表示",
+        destructive: false,
+        up: migrate_v5_clipboard_representations,
+    },
+    Migration {
+        version: 6,
+        description: "剪贴板内容分类标签",
+        destructive: false,
+        up: migrate_v6_clipboard_tags,
+    },
+    Migration {
+        version: 7,
+        description: "AI翻译记忆",
+        destructive: false,
+        up: migrate_v7_translation_memory,
+    },
+    Migration {
+        version: 8,
+        description: "剪贴板记录的来源应用和网页地址",
+        destructive: false,
+        up: migrate_v8_clipboard_source,
+    },
+    Migration {
+        version: 9,
+        description: "剪贴板记录的置顶状态",
+        destructive: false,
+        up: migrate_v9_clipboard_pinned,
+    },
+    Migration {
+        version: 10,
+        description: "剪贴板记录的来源选区",
+        destructive: false,
+        up: migrate_v10_clipboard_source_selection,
+    },
+    Migration {
+        version: 11,
+        description: "收藏全文索引改为按行号关联",
+        destructive: false,
+        up: migrate_v11_favorites_search_keys,
+    },
+];
+
+// 当前程序支持的数据库结构版本
+pub fn current_schema_version() -> i32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+// 读取数据库结构版本
+pub fn get_schema_version(conn: &Connection) -> SqliteResult<i32> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+}
+
+// 执行所有待执行的迁移
+pub fn run_migrations(conn: &mut Connection, db_path: &Path) -> SqliteResult<()> {
+    apply_migrations(conn, db_path, MIGRATIONS)
+}
+
+// 按顺序执行 migrations 中高于当前结构版本的步骤
+fn apply_migrations(
+    conn: &mut Connection,
+    db_path: &Path,
+    migrations: &[Migration],
+) -> SqliteResult<()> {
+    let current_version = get_schema_version(conn)?;
+    let target_version = migrations.last().map(|m| m.version).unwrap_or(0);
+
+    // 拒绝打开由更新版本程序写入的数据库，避免破坏数据
+    if current_version > target_version {
+        return Err(migration_error(format!(
+            "数据库结构版本 ({}) 高于当前程序支持的版本 ({})，请升级 QuickClipboard 后再打开",
+            current_version, target_version
+        )));
+    }
+
+    let pending: Vec<&Migration> = migrations
+        .iter()
+        .filter(|m| m.version > current_version)
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    println!(
+        "数据库结构需要从版本 {} 升级到版本 {}",
+        current_version, target_version
+    );
+
+    // 存在破坏性迁移时先备份数据库文件
+    if pending.iter().any(|m| m.destructive) {
+        let backup_path = backup_database(conn, db_path, current_version)?;
+        println!("已在迁移前备份数据库: {:?}", backup_path);
+    }
+
+    for migration in pending {
+        println!(
+            "执行数据库迁移 v{}: {}",
+            migration.version, migration.description
+        );
+
+        let tx = conn.transaction()?;
+        (migration.up)(&tx).map_err(|e| {
+            migration_error(format!(
+                "数据库迁移 v{} ({}) 失败: {}",
+                migration.version, migration.description, e
+            ))
+        })?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+    }
+
+    println!("数据库结构已升级到版本 {}", target_version);
+    Ok(())
+}
+
+// 检查数据库文件是否由更新版本的程序写入，返回其结构版本
+pub fn newer_schema_version() -> Option<i32> {
+    let db_path = crate::database::get_database_path().ok()?;
+    if !db_path.exists() {
+        return None;
+    }
+
+    let conn = Connection::open_with_flags(
+        &db_path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )
+    .ok()?;
+    let version = get_schema_version(&conn).ok()?;
+
+    if version > current_schema_version() {
+        Some(version)
+    } else {
+        None
+    }
+}
+
+// 使用 VACUUM INTO 生成数据库的一致性快照作为备份
+fn backup_database(conn: &Connection, db_path: &Path, version: i32) -> SqliteResult<PathBuf> {
+    let backup_dir = db_path
+        .parent()
+        .map(|p| p.join("backups"))
+        .unwrap_or_else(|| PathBuf::from("backups"));
+
+    std::fs::create_dir_all(&backup_dir)
+        .map_err(|e| migration_error(format!("创建备份目录失败: {}", e)))?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let backup_path = backup_dir.join(format!("quickclipboard_v{}_{}.db", version, timestamp));
+
+    conn.execute(
+        "VACUUM INTO ?1",
+        [backup_path.to_string_lossy().to_string()],
+    )?;
+
+    Ok(backup_path)
+}
+
+fn migration_error(message: String) -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+        Some(message),
+    )
+}
+
+// =================== 迁移辅助函数 ===================
+
+// 检查表中是否存在指定列
+pub fn column_exists(conn: &Connection, table: &str, column: &str) -> SqliteResult<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<SqliteResult<Vec<String>>>()?;
+    Ok(columns.iter().any(|c| c == column))
+}
+
+// 列不存在时添加列（导入的数据库可能已经包含该列）
+pub fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> SqliteResult<()> {
+    if !column_exists(conn, table, column)? {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// =================== 迁移步骤 ===================
+
+// v1: 基础表结构（旧版本数据库已存在这些表，使用 IF NOT EXISTS 保证可重复执行）
+fn migrate_v1_base_tables(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "-- 剪贴板表
+        CREATE TABLE IF NOT EXISTS clipboard (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            content TEXT NOT NULL,
+            html_content TEXT,
+            content_type TEXT NOT NULL DEFAULT 'text',
+            image_id TEXT,
+            item_order INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        -- 收藏表（通过group_name引用groups表）
+        CREATE TABLE IF NOT EXISTS favorites (
+            id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            content TEXT NOT NULL,
+            html_content TEXT,
+            content_type TEXT NOT NULL DEFAULT 'text',
+            image_id TEXT,
+            group_name TEXT NOT NULL DEFAULT '全部',
+            item_order INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        -- 分组表（支持空分组）
+        CREATE TABLE IF NOT EXISTS groups (
+            name TEXT PRIMARY KEY,
+            icon TEXT NOT NULL DEFAULT 'ti ti-folder',
+            order_index INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_clipboard_created ON clipboard(created_at DESC);
+        CREATE INDEX IF NOT EXISTS idx_favorites_group ON favorites(group_name, item_order);
+        CREATE INDEX IF NOT EXISTS idx_favorites_updated ON favorites(updated_at DESC);
+
+        -- 图片数据表（存储原始BGRA数据）
+        CREATE TABLE IF NOT EXISTS image_data (
+            image_id TEXT PRIMARY KEY,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            bgra_data BLOB NOT NULL,
+            png_data BLOB NOT NULL,
+            created_at INTEGER NOT NULL
+        );",
+    )
+}
+
+// v2: 全文搜索索引
+fn migrate_v2_search_index(conn: &Connection) -> SqliteResult<()> {
+    crate::database_search::create_search_tables(conn)
+}
+
+// v3: 敏感内容自动过期时间（为空表示不过期）
+fn migrate_v3_clipboard_expiry(conn: &Connection) -> SqliteResult<()> {
+    add_column_if_missing(conn, "clipboard", "expires_at", "INTEGER")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_clipboard_expires ON clipboard(expires_at) WHERE expires_at IS NOT NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+// v4: 多设备同步状态
+// sync_records 记录每条数据上次同步时的摘要和版本（时间戳+设备），用于生成本地变更和按记录合并
+// sync_cursors 记录每个远端设备已合并到的日志文件
+fn migrate_v4_sync_state(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS sync_records (
+            kind TEXT NOT NULL,
+            record_key TEXT NOT NULL,
+            digest TEXT NOT NULL,
+            updated_at INTEGER NOT NULL,
+            device_id TEXT NOT NULL,
+            deleted INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (kind, record_key)
+        );
+
+        CREATE TABLE IF NOT EXISTS sync_cursors (
+            device_id TEXT PRIMARY KEY,
+            last_segment TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS sync_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );",
+    )
+}
+
+// v5: 剪贴板记录的多格式表示（每种格式一行，删除记录时一并删除）
+// 旧记录按主格式迁移：文本类为 text/plain（富文本另加 text/html），图片引用图片文件，文件为文件列表
+// 数据按原样复制（已加密的数据仍为密文），大小记为 -1，首次读取时补全
+fn migrate_v5_clipboard_representations(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS clipboard_representations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL,
+            mime_type TEXT NOT NULL,
+            text_data TEXT,
+            blob_data BLOB,
+            size INTEGER NOT NULL DEFAULT -1,
+            UNIQUE (item_id, mime_type)
+        );
+
+        CREATE TRIGGER IF NOT EXISTS clipboard_representations_delete AFTER DELETE ON clipboard BEGIN
+            DELETE FROM clipboard_representations WHERE item_id = old.id;
+        END;
+
+        INSERT OR IGNORE INTO clipboard_representations (item_id, mime_type, text_data)
+        SELECT id, 'text/plain', content FROM clipboard
+        WHERE content_type IN ('text', 'rich_text', 'link') ORDER BY id;
+
+        INSERT OR IGNORE INTO clipboard_representations (item_id, mime_type, text_data)
+        SELECT id, 'text/html', html_content FROM clipboard
+        WHERE content_type IN ('text', 'rich_text', 'link')
+          AND html_content IS NOT NULL AND trim(html_content) != '' ORDER BY id;
+
+        INSERT OR IGNORE INTO clipboard_representations (item_id, mime_type, text_data)
+        SELECT id, 'image/png', content FROM clipboard WHERE content_type = 'image' ORDER BY id;
+
+        INSERT OR IGNORE INTO clipboard_representations (item_id, mime_type, text_data)
+        SELECT id, 'application/x-quickclipboard-files', content FROM clipboard
+        WHERE content_type = 'file' ORDER BY id;",
+    )
+}
+
+// v6: 剪贴板内容分类标签（每个标签一行，删除记录时一并删除）
+// tags_version 记录分类时的规则版本，旧记录为 0，由程序在后台分类（数据可能已加密，无法在迁移中处理）
+fn migrate_v6_clipboard_tags(conn: &Connection) -> SqliteResult<()> {
+    add_column_if_missing(conn, "clipboard", "tags_version", "INTEGER NOT NULL DEFAULT 0")?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS clipboard_tags (
+            item_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            detail TEXT,
+            PRIMARY KEY (item_id, tag)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_clipboard_tags_tag ON clipboard_tags(tag);
+        CREATE INDEX IF NOT EXISTS idx_clipboard_tags_version ON clipboard(tags_version);
+
+        CREATE TRIGGER IF NOT EXISTS clipboard_tags_delete AFTER DELETE ON clipboard BEGIN
+            DELETE FROM clipboard_tags WHERE item_id = old.id;
+        END;",
+    )
+}
+
+// v7: AI翻译记忆（按规范化原文、目标语言、模型和提示词摘要去重）
+// 原文和译文与剪贴板内容一样按需加密；source_length 为原文字符数，用于模糊匹配时预先筛选
+fn migrate_v7_translation_memory(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS translation_memory (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source_text TEXT NOT NULL,
+            source_length INTEGER NOT NULL,
+            target_language TEXT NOT NULL,
+            model TEXT NOT NULL,
+            prompt_hash TEXT NOT NULL,
+            translation TEXT NOT NULL,
+            confirmed INTEGER NOT NULL DEFAULT 0,
+            hit_count INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            UNIQUE (source_text, target_language, model, prompt_hash)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_translation_memory_target
+            ON translation_memory(target_language, source_length);",
+    )
+}
+
+// v8: 剪贴板记录的来源（进程名、可执行文件路径、窗口标题、网页地址），旧记录为空
+fn migrate_v8_clipboard_source(conn: &Connection) -> SqliteResult<()> {
+    add_column_if_missing(conn, "clipboard", "source_app", "TEXT")?;
+    add_column_if_missing(conn, "clipboard", "source_path", "TEXT")?;
+    add_column_if_missing(conn, "clipboard", "source_title", "TEXT")?;
+    add_column_if_missing(conn, "clipboard", "source_url", "TEXT")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_clipboard_source_app ON clipboard(source_app COLLATE NOCASE)",
+        [],
+    )?;
+    Ok(())
+}
+
+// v9: 置顶的剪贴板记录排在最前面，不受数量限制、保留策略和清空历史影响
+fn migrate_v9_clipboard_pinned(conn: &Connection) -> SqliteResult<()> {
+    add_column_if_missing(conn, "clipboard", "pinned", "INTEGER NOT NULL DEFAULT 0")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_clipboard_pinned ON clipboard(pinned DESC, item_order)",
+        [],
+    )?;
+    Ok(())
+}
+
+// v10: 来源选区（Linux 主选区记录为 primary，与常规复制区分），旧记录为空
+fn migrate_v10_clipboard_source_selection(conn: &Connection) -> SqliteResult<()> {
+    add_column_if_missing(conn, "clipboard", "source_selection", "TEXT")
+}
+
+// v11: 收藏索引原来按 fav_id 列关联，编辑和删除收藏时需要扫描整个索引，改为由映射表分配行号
+// 索引可由收藏表重建：这里只重建表结构，内容由 ensure_search_index 在迁移后补齐
+fn migrate_v11_favorites_search_keys(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "DROP TRIGGER IF EXISTS favorites_fts_insert;
+        DROP TRIGGER IF EXISTS favorites_fts_delete;
+        DROP TRIGGER IF EXISTS favorites_fts_update;
+        DROP TABLE IF EXISTS favorites_fts;
+        DROP TABLE IF EXISTS favorites_fts_keys;",
+    )?;
+    crate::database_search::create_search_tables(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 临时数据库文件，备份写在同目录的 backups 下，测试结束时整个目录删除
+    struct TempDb {
+        dir: PathBuf,
+        path: PathBuf,
+    }
+
+    impl TempDb {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("qc-migration-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("quickclipboard.db");
+            TempDb { dir, path }
+        }
+
+        fn open(&self) -> Connection {
+            Connection::open(&self.path).unwrap()
+        }
+
+        fn backups(&self) -> Vec<PathBuf> {
+            match std::fs::read_dir(self.dir.join("backups")) {
+                Ok(entries) => entries.map(|e| e.unwrap().path()).collect(),
+                Err(_) => Vec::new(),
+            }
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    // 引入结构版本之前的数据库：只有基础表，user_version 为 0
+    fn create_baseline_schema(conn: &Connection) {
+        conn.execute_batch(
+            "CREATE TABLE clipboard (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                content TEXT NOT NULL,
+                html_content TEXT,
+                content_type TEXT NOT NULL DEFAULT 'text',
+                image_id TEXT,
+                item_order INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE favorites (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                content TEXT NOT NULL,
+                html_content TEXT,
+                content_type TEXT NOT NULL DEFAULT 'text',
+                image_id TEXT,
+                group_name TEXT NOT NULL DEFAULT '全部',
+                item_order INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE groups (
+                name TEXT PRIMARY KEY,
+                icon TEXT NOT NULL DEFAULT 'ti ti-folder',
+                order_index INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            INSERT INTO clipboard (content, html_content, content_type, created_at, updated_at)
+                VALUES ('hello', '<b>hello</b>', 'rich_text', 1, 1);",
+        )
+        .unwrap();
+    }
+
+    fn recreate_clipboard(conn: &Connection) -> SqliteResult<()> {
+        conn.execute_batch(
+            "CREATE TABLE clipboard_new (id INTEGER PRIMARY KEY, content TEXT NOT NULL);
+            INSERT INTO clipboard_new (id, content) SELECT id, content FROM clipboard;
+            DROP TABLE clipboard;
+            ALTER TABLE clipboard_new RENAME TO clipboard;",
+        )
+    }
+
+    #[test]
+    fn migrates_baseline_schema_to_current_version() {
+        let db = TempDb::new();
+        let mut conn = db.open();
+        create_baseline_schema(&conn);
+
+        run_migrations(&mut conn, &db.path).unwrap();
+
+        assert_eq!(get_schema_version(&conn).unwrap(), current_schema_version());
+        for column in ["expires_at", "tags_version", "source_app", "source_url", "pinned", "source_selection"] {
+            assert!(column_exists(&conn, "clipboard", column).unwrap(), "{}", column);
+        }
+        let (content, pinned): (String, i64) = conn
+            .query_row("SELECT content, pinned FROM clipboard", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((content.as_str(), pinned), ("hello", 0));
+        let representations: i64 = conn
+            .query_row("SELECT COUNT(*) FROM clipboard_representations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(representations, 2);
+        // 没有破坏性迁移时不备份
+        assert!(db.backups().is_empty());
+
+        // 再次执行不做任何改动
+        run_migrations(&mut conn, &db.path).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), current_schema_version());
+    }
+
+    #[test]
+    fn backs_up_before_destructive_migration() {
+        let db = TempDb::new();
+        let mut conn = db.open();
+        create_baseline_schema(&conn);
+        let migrations = [
+            Migration {
+                version: 1,
+                description: "基础表",
+                destructive: false,
+                up: migrate_v1_base_tables,
+            },
+            Migration {
+                version: 2,
+                description: "重建剪贴板表",
+                destructive: true,
+                up: recreate_clipboard,
+            },
+        ];
+
+        apply_migrations(&mut conn, &db.path, &migrations).unwrap();
+
+        assert_eq!(get_schema_version(&conn).unwrap(), 2);
+        assert!(!column_exists(&conn, "clipboard", "html_content").unwrap());
+        let backups = db.backups();
+        assert_eq!(backups.len(), 1);
+        // 备份是迁移前的数据库
+        let backup = Connection::open(&backups[0]).unwrap();
+        assert_eq!(get_schema_version(&backup).unwrap(), 0);
+        assert!(column_exists(&backup, "clipboard", "html_content").unwrap());
+    }
+
+    #[test]
+    fn refuses_newer_schema_version() {
+        let db = TempDb::new();
+        let mut conn = db.open();
+        create_baseline_schema(&conn);
+        let newer = current_schema_version() + 1;
+        conn.pragma_update(None, "user_version", newer).unwrap();
+
+        assert!(run_migrations(&mut conn, &db.path).is_err());
+        assert_eq!(get_schema_version(&conn).unwrap(), newer);
+        assert!(!column_exists(&conn, "clipboard", "pinned").unwrap());
+    }
+}
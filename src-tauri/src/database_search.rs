@@ -0,0 +1,1009 @@
+// 全文搜索模块
+//
+// 基于 SQLite FTS5 为剪贴板历史和收藏建立全文索引，索引通过触发器与原表保持同步
+
+use crate::database::{truncate_for_display, ClipboardItem, ClipboardSource, ContentType, FavoriteItem};
+use crate::error::AppResult;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{params, Connection, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+// CJK字符分隔符（不可见分隔符，unicode61 分词器会将其视为分隔符）
+const SEGMENT_MARK: char = '\u{2063}';
+
+// 摘要高亮标记（私有区字符，输出前替换为 <mark> 标签）
+const HIGHLIGHT_START: char = '\u{E000}';
+const HIGHLIGHT_END: char = '\u{E001}';
+
+// 默认每页数量
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+// 搜索参数
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchParams {
+    // 搜索关键词
+    pub query: String,
+    // 按内容类型过滤
+    pub content_type: Option<String>,
+    // 按分组过滤（仅收藏）
+    pub group_name: Option<String>,
+    // 按内容分类标签过滤（仅剪贴板历史）
+    #[serde(default)]
+    pub tag: Option<String>,
+    // 按来源应用的进程名过滤，不区分大小写（仅剪贴板历史）
+    #[serde(default)]
+    pub source_app: Option<String>,
+    // 按来源网页地址包含的文本过滤，如域名（仅剪贴板历史）
+    #[serde(default)]
+    pub source_url: Option<String>,
+    // 分页偏移
+    pub offset: Option<usize>,
+    // 每页数量
+    pub limit: Option<usize>,
+}
+
+// 剪贴板搜索结果项
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipboardSearchHit {
+    pub item: ClipboardItem,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+// 收藏搜索结果项
+#[derive(Debug, Clone, Serialize)]
+pub struct FavoriteSearchHit {
+    pub item: FavoriteItem,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+// 分页搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchPage<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+// =================== 索引结构 ===================
+
+// 注册索引使用的SQL函数（每个连接都需要注册）
+pub fn register_search_functions(conn: &Connection) -> SqliteResult<()> {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+
+    // 加密后的内容不建立索引，避免明文写入索引表
+    conn.create_scalar_function("qc_fts_content", 2, flags, |ctx| {
+        let content: Option<String> = ctx.get(0)?;
+        let content_type: Option<String> = ctx.get(1)?;
+        Ok(match content {
+            Some(content) if !crate::encryption::is_encrypted_text(&content) => {
+                index_text_for_content(&content, content_type.as_deref().unwrap_or("text"))
+            }
+            _ => String::new(),
+        })
+    })?;
+
+    conn.create_scalar_function("qc_fts_text", 1, flags, |ctx| {
+        let text: Option<String> = ctx.get(0)?;
+        Ok(match text {
+            Some(text) if !crate::encryption::is_encrypted_text(&text) => segment_text(&text),
+            _ => String::new(),
+        })
+    })?;
+
+    conn.create_scalar_function("qc_fts_html", 1, flags, |ctx| {
+        let html: Option<String> = ctx.get(0)?;
+        Ok(match html {
+            Some(html) if !html.trim().is_empty() && !crate::encryption::is_encrypted_text(&html) => segment_text(
+                &crate::utils::content_utils::html_to_plain_text(&html),
+            ),
+            _ => String::new(),
+        })
+    })?;
+
+    Ok(())
+}
+
+// 创建全文索引表和同步触发器
+pub fn create_search_tables(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS clipboard_fts USING fts5(
+            content,
+            html_text,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        -- 收藏以文本 id 为主键，VACUUM 可能改变其 rowid，索引行号由映射表分配
+        CREATE TABLE IF NOT EXISTS favorites_fts_keys (
+            id INTEGER PRIMARY KEY,
+            fav_id TEXT NOT NULL UNIQUE
+        );
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS favorites_fts USING fts5(
+            title,
+            content,
+            html_text,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS clipboard_fts_insert AFTER INSERT ON clipboard BEGIN
+            INSERT INTO clipboard_fts(rowid, content, html_text)
+            VALUES (new.id, qc_fts_content(new.content, new.content_type), qc_fts_html(new.html_content));
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS clipboard_fts_delete AFTER DELETE ON clipboard BEGIN
+            DELETE FROM clipboard_fts WHERE rowid = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS clipboard_fts_update
+        AFTER UPDATE OF content, html_content, content_type ON clipboard BEGIN
+            DELETE FROM clipboard_fts WHERE rowid = old.id;
+            INSERT INTO clipboard_fts(rowid, content, html_text)
+            VALUES (new.id, qc_fts_content(new.content, new.content_type), qc_fts_html(new.html_content));
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS favorites_fts_insert AFTER INSERT ON favorites BEGIN
+            INSERT OR IGNORE INTO favorites_fts_keys(fav_id) VALUES (new.id);
+            INSERT INTO favorites_fts(rowid, title, content, html_text)
+            VALUES ((SELECT id FROM favorites_fts_keys WHERE fav_id = new.id),
+                    qc_fts_text(new.title), qc_fts_content(new.content, new.content_type), qc_fts_html(new.html_content));
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS favorites_fts_delete AFTER DELETE ON favorites BEGIN
+            DELETE FROM favorites_fts WHERE rowid = (SELECT id FROM favorites_fts_keys WHERE fav_id = old.id);
+            DELETE FROM favorites_fts_keys WHERE fav_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS favorites_fts_update
+        AFTER UPDATE OF id, title, content, html_content, content_type ON favorites BEGIN
+            DELETE FROM favorites_fts WHERE rowid = (SELECT id FROM favorites_fts_keys WHERE fav_id = old.id);
+            UPDATE favorites_fts_keys SET fav_id = new.id WHERE fav_id = old.id;
+            INSERT OR IGNORE INTO favorites_fts_keys(fav_id) VALUES (new.id);
+            INSERT INTO favorites_fts(rowid, title, content, html_text)
+            VALUES ((SELECT id FROM favorites_fts_keys WHERE fav_id = new.id),
+                    qc_fts_text(new.title), qc_fts_content(new.content, new.content_type), qc_fts_html(new.html_content));
+        END;",
+    )
+}
+
+// 检查全文索引与数据是否一致（旧数据库或导入的数据库可能没有索引），不一致时重建
+pub fn ensure_search_index(conn: &Connection) -> SqliteResult<()> {
+    let clipboard_count: i64 =
+        conn.query_row("SELECT COUNT(*) FROM clipboard", [], |row| row.get(0))?;
+    let clipboard_fts_count: i64 =
+        conn.query_row("SELECT COUNT(*) FROM clipboard_fts", [], |row| row.get(0))?;
+    let favorites_count: i64 =
+        conn.query_row("SELECT COUNT(*) FROM favorites", [], |row| row.get(0))?;
+    let favorites_fts_count: i64 =
+        conn.query_row("SELECT COUNT(*) FROM favorites_fts", [], |row| row.get(0))?;
+
+    if clipboard_count != clipboard_fts_count || favorites_count != favorites_fts_count {
+        println!("全文索引与数据不一致，正在重建索引...");
+        rebuild_search_index_with(conn)?;
+    }
+
+    Ok(())
+}
+
+// 使用指定连接重建全文索引
+pub(crate) fn rebuild_search_index_with(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "DELETE FROM clipboard_fts;
+        INSERT INTO clipboard_fts(rowid, content, html_text)
+            SELECT id, qc_fts_content(content, content_type), qc_fts_html(html_content) FROM clipboard;
+        DELETE FROM favorites_fts;
+        DELETE FROM favorites_fts_keys;
+        INSERT INTO favorites_fts_keys(fav_id) SELECT id FROM favorites;
+        INSERT INTO favorites_fts(rowid, title, content, html_text)
+            SELECT k.id, qc_fts_text(f.title), qc_fts_content(f.content, f.content_type), qc_fts_html(f.html_content)
+            FROM favorites f JOIN favorites_fts_keys k ON k.fav_id = f.id;
+        INSERT INTO clipboard_fts(clipboard_fts) VALUES('optimize');
+        INSERT INTO favorites_fts(favorites_fts) VALUES('optimize');",
+    )
+}
+
+// 重建全文索引
+pub fn rebuild_search_index() -> AppResult<()> {
+    crate::database::with_connection(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        rebuild_search_index_with(&tx)?;
+        tx.commit()
+    })
+}
+
+// =================== 搜索 ===================
+
+// 搜索剪贴板历史
+pub fn search_clipboard(params: &SearchParams) -> AppResult<SearchPage<ClipboardSearchHit>> {
+    // 启用数据加密后索引中没有内容，改为解密后逐条匹配
+    if crate::encryption::is_enabled() {
+        return search_clipboard_decrypted(params);
+    }
+
+    crate::database::with_connection(|conn| query_clipboard(conn, params))
+}
+
+// 在全文索引中查询剪贴板历史
+fn query_clipboard(conn: &Connection, params: &SearchParams) -> SqliteResult<SearchPage<ClipboardSearchHit>> {
+    let (offset, limit) = page_bounds(params);
+
+    let match_query = match build_match_query(&params.query) {
+        Some(q) => q,
+        None => return Ok(empty_page(offset, limit)),
+    };
+    let content_type = params.content_type.clone().filter(|t| !t.is_empty());
+    let tag = params.tag.clone().filter(|t| !t.is_empty());
+    let (source_app, source_url) = source_filters(params);
+
+    let total: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM clipboard_fts
+         JOIN clipboard c ON c.id = clipboard_fts.rowid
+         WHERE clipboard_fts MATCH ?1 AND (?2 IS NULL OR c.content_type = ?2)
+           AND (?3 IS NULL OR c.id IN (SELECT item_id FROM clipboard_tags WHERE tag = ?3))
+           AND (?4 IS NULL OR c.source_app = ?4 COLLATE NOCASE)
+           AND (?5 IS NULL OR instr(lower(c.source_url), lower(?5)) > 0)",
+        params![match_query, content_type, tag, source_app, source_url],
+        |row| row.get(0),
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.content, c.html_content, c.content_type, c.image_id, c.item_order, c.created_at, c.updated_at,
+                snippet(clipboard_fts, -1, ?4, ?5, '…', 24),
+                bm25(clipboard_fts) AS rank,
+                c.source_app, c.source_path, c.source_title, c.source_url, c.source_selection, c.pinned
+         FROM clipboard_fts
+         JOIN clipboard c ON c.id = clipboard_fts.rowid
+         WHERE clipboard_fts MATCH ?1 AND (?2 IS NULL OR c.content_type = ?2)
+           AND (?7 IS NULL OR c.id IN (SELECT item_id FROM clipboard_tags WHERE tag = ?7))
+           AND (?8 IS NULL OR c.source_app = ?8 COLLATE NOCASE)
+           AND (?9 IS NULL OR instr(lower(c.source_url), lower(?9)) > 0)
+         ORDER BY rank, c.item_order
+         LIMIT ?3 OFFSET ?6",
+    )?;
+
+    let rows = stmt.query_map(
+        params![
+            match_query,
+            content_type,
+            limit as i64,
+            HIGHLIGHT_START.to_string(),
+            HIGHLIGHT_END.to_string(),
+            offset as i64,
+            tag,
+            source_app,
+            source_url
+        ],
+        |row| {
+            let content_type = ContentType::from_string(&row.get::<_, String>(3)?);
+            let (content, html_content) =
+                truncate_for_display(&content_type, row.get(1)?, row.get(2)?);
+
+            Ok(ClipboardSearchHit {
+                item: ClipboardItem {
+                    id: row.get(0)?,
+                    content,
+                    html_content,
+                    content_type,
+                    image_id: row.get(4)?,
+                    item_order: row.get(5)?,
+                    created_at: row.get(6)?,
+                    updated_at: row.get(7)?,
+                    source: ClipboardSource::from_row(row, 10)?,
+                    pinned: row.get(15)?,
+                },
+                snippet: render_snippet(&row.get::<_, String>(8)?),
+                rank: row.get(9)?,
+            })
+        },
+    )?;
+
+    let items = rows.collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(SearchPage {
+        items,
+        total,
+        offset,
+        limit,
+    })
+}
+
+// 搜索收藏
+pub fn search_favorites(params: &SearchParams) -> AppResult<SearchPage<FavoriteSearchHit>> {
+    if crate::encryption::is_enabled() {
+        return search_favorites_decrypted(params);
+    }
+
+    crate::database::with_connection(|conn| query_favorites(conn, params))
+}
+
+// 在全文索引中查询收藏
+fn query_favorites(conn: &Connection, params: &SearchParams) -> SqliteResult<SearchPage<FavoriteSearchHit>> {
+    let (offset, limit) = page_bounds(params);
+
+    let match_query = match build_match_query(&params.query) {
+        Some(q) => q,
+        None => return Ok(empty_page(offset, limit)),
+    };
+    let content_type = params.content_type.clone().filter(|t| !t.is_empty());
+    let group_name = params
+        .group_name
+        .clone()
+        .filter(|g| !g.is_empty() && g != "全部");
+
+    let total: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM favorites_fts
+         JOIN favorites_fts_keys k ON k.id = favorites_fts.rowid
+         JOIN favorites f ON f.id = k.fav_id
+         WHERE favorites_fts MATCH ?1
+           AND (?2 IS NULL OR f.content_type = ?2)
+           AND (?3 IS NULL OR f.group_name = ?3)",
+        params![match_query, content_type, group_name],
+        |row| row.get(0),
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT f.id, f.title, f.content, f.html_content, f.content_type, f.image_id, f.group_name, f.item_order, f.created_at, f.updated_at,
+                snippet(favorites_fts, -1, ?5, ?6, '…', 24),
+                bm25(favorites_fts, 2.0, 1.0, 1.0) AS rank
+         FROM favorites_fts
+         JOIN favorites_fts_keys k ON k.id = favorites_fts.rowid
+         JOIN favorites f ON f.id = k.fav_id
+         WHERE favorites_fts MATCH ?1
+           AND (?2 IS NULL OR f.content_type = ?2)
+           AND (?3 IS NULL OR f.group_name = ?3)
+         ORDER BY rank, f.item_order
+         LIMIT ?4 OFFSET ?7",
+    )?;
+
+    let rows = stmt.query_map(
+        params![
+            match_query,
+            content_type,
+            group_name,
+            limit as i64,
+            HIGHLIGHT_START.to_string(),
+            HIGHLIGHT_END.to_string(),
+            offset as i64
+        ],
+        |row| {
+            let content_type = ContentType::from_string(&row.get::<_, String>(4)?);
+            let (content, html_content) =
+                truncate_for_display(&content_type, row.get(2)?, row.get(3)?);
+
+            Ok(FavoriteSearchHit {
+                item: FavoriteItem {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content,
+                    html_content,
+                    content_type,
+                    image_id: row.get(5)?,
+                    group_name: row.get(6)?,
+                    item_order: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                },
+                snippet: render_snippet(&row.get::<_, String>(10)?),
+                rank: row.get(11)?,
+            })
+        },
+    )?;
+
+    let items = rows.collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(SearchPage {
+        items,
+        total,
+        offset,
+        limit,
+    })
+}
+
+// =================== 加密模式下的搜索 ===================
+
+// 逐条解密并匹配剪贴板历史
+fn search_clipboard_decrypted(
+    params: &SearchParams,
+) -> AppResult<SearchPage<ClipboardSearchHit>> {
+    let (offset, limit) = page_bounds(params);
+    let terms = match parse_query(&params.query) {
+        Some(terms) => terms,
+        None => return Ok(empty_page(offset, limit)),
+    };
+    let content_type = params.content_type.clone().filter(|t| !t.is_empty());
+    let tag = params.tag.clone().filter(|t| !t.is_empty());
+    let (source_app, source_url) = source_filters(params);
+
+    let items: Vec<ClipboardItem> = crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, qc_decrypt(content), qc_decrypt(html_content), content_type, image_id, item_order, created_at, updated_at,
+                    source_app, source_path, qc_decrypt(source_title), qc_decrypt(source_url), source_selection, pinned
+             FROM clipboard
+             WHERE (?1 IS NULL OR content_type = ?1)
+               AND (?2 IS NULL OR id IN (SELECT item_id FROM clipboard_tags WHERE tag = ?2))
+               AND (?3 IS NULL OR source_app = ?3 COLLATE NOCASE)
+               AND (?4 IS NULL OR instr(lower(qc_decrypt(source_url)), lower(?4)) > 0)
+             ORDER BY pinned DESC, item_order, updated_at DESC",
+        )?;
+        let rows = stmt.query_map(params![content_type, tag, source_app, source_url], |row| {
+            Ok(ClipboardItem {
+                id: row.get(0)?,
+                content: row.get(1)?,
+                html_content: row.get(2)?,
+                content_type: ContentType::from_string(&row.get::<_, String>(3)?),
+                image_id: row.get(4)?,
+                item_order: row.get(5)?,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
+                source: ClipboardSource::from_row(row, 8)?,
+                pinned: row.get(13)?,
+            })
+        })?;
+        rows.collect()
+    })?;
+
+    let mut hits = Vec::new();
+    for item in items {
+        let text = searchable_text(
+            &[&item.content],
+            &item.content_type,
+            item.html_content.as_deref(),
+        );
+        if !terms.matches(&text) {
+            continue;
+        }
+
+        let snippet = terms.snippet(&text);
+        let (content, html_content) =
+            truncate_for_display(&item.content_type, item.content, item.html_content);
+        hits.push(ClipboardSearchHit {
+            item: ClipboardItem {
+                content,
+                html_content,
+                ..item
+            },
+            snippet,
+            rank: 0.0,
+        });
+    }
+
+    Ok(paginate(hits, offset, limit))
+}
+
+// 逐条解密并匹配收藏
+fn search_favorites_decrypted(
+    params: &SearchParams,
+) -> AppResult<SearchPage<FavoriteSearchHit>> {
+    let (offset, limit) = page_bounds(params);
+    let terms = match parse_query(&params.query) {
+        Some(terms) => terms,
+        None => return Ok(empty_page(offset, limit)),
+    };
+    let content_type = params.content_type.clone().filter(|t| !t.is_empty());
+    let group_name = params
+        .group_name
+        .clone()
+        .filter(|g| !g.is_empty() && g != "全部");
+
+    let items: Vec<FavoriteItem> = crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, qc_decrypt(title), qc_decrypt(content), qc_decrypt(html_content), content_type, image_id, group_name, item_order, created_at, updated_at
+             FROM favorites
+             WHERE (?1 IS NULL OR content_type = ?1) AND (?2 IS NULL OR group_name = ?2)
+             ORDER BY item_order, updated_at DESC",
+        )?;
+        let rows = stmt.query_map(params![content_type, group_name], |row| {
+            Ok(FavoriteItem {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                content: row.get(2)?,
+                html_content: row.get(3)?,
+                content_type: ContentType::from_string(&row.get::<_, String>(4)?),
+                image_id: row.get(5)?,
+                group_name: row.get(6)?,
+                item_order: row.get(7)?,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+            })
+        })?;
+        rows.collect()
+    })?;
+
+    let mut hits = Vec::new();
+    for item in items {
+        let text = searchable_text(
+            &[&item.title, &item.content],
+            &item.content_type,
+            item.html_content.as_deref(),
+        );
+        if !terms.matches(&text) {
+            continue;
+        }
+
+        let snippet = terms.snippet(&text);
+        let (content, html_content) =
+            truncate_for_display(&item.content_type, item.content, item.html_content);
+        hits.push(FavoriteSearchHit {
+            item: FavoriteItem {
+                content,
+                html_content,
+                ..item
+            },
+            snippet,
+            rank: 0.0,
+        });
+    }
+
+    Ok(paginate(hits, offset, limit))
+}
+
+// 拼接参与匹配的文本（与索引内容一致，去除CJK分隔符）
+fn searchable_text(fields: &[&str], content_type: &ContentType, html: Option<&str>) -> String {
+    let content_type = content_type.to_string();
+    let mut parts: Vec<String> = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        // 最后一个字段为内容，其余为标题等普通文本
+        if index + 1 == fields.len() {
+            parts.push(index_text_for_content(field, &content_type));
+        } else {
+            parts.push(field.to_string());
+        }
+    }
+    if let Some(html) = html.filter(|h| !h.trim().is_empty()) {
+        parts.push(crate::utils::content_utils::html_to_plain_text(html));
+    }
+    parts.join("\n").replace(SEGMENT_MARK, "")
+}
+
+// 来源筛选条件（空字符串视为不筛选）
+fn source_filters(params: &SearchParams) -> (Option<String>, Option<String>) {
+    let non_empty = |value: &Option<String>| {
+        value
+            .as_ref()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    (non_empty(&params.source_app), non_empty(&params.source_url))
+}
+
+fn paginate<T>(hits: Vec<T>, offset: usize, limit: usize) -> SearchPage<T> {
+    let total = hits.len() as i64;
+    SearchPage {
+        items: hits.into_iter().skip(offset).take(limit).collect(),
+        total,
+        offset,
+        limit,
+    }
+}
+
+fn page_bounds(params: &SearchParams) -> (usize, usize) {
+    let offset = params.offset.unwrap_or(0);
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    (offset, limit)
+}
+
+fn empty_page<T>(offset: usize, limit: usize) -> SearchPage<T> {
+    SearchPage {
+        items: Vec::new(),
+        total: 0,
+        offset,
+        limit,
+    }
+}
+
+// =================== 分词与查询构建 ===================
+
+// 判断是否为CJK字符（中日韩统一表意文字、假名、谚文）
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF      // 平假名、片假名
+        | 0x3400..=0x4DBF    // 扩展A
+        | 0x4E00..=0x9FFF    // 基本区
+        | 0xAC00..=0xD7AF    // 谚文音节
+        | 0xF900..=0xFAFF    // 兼容表意文字
+        | 0x20000..=0x2FA1F  // 扩展B及以后
+    )
+}
+
+// 在每个CJK字符两侧插入分隔符，使其按单字建立索引
+fn segment_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + text.len() / 2);
+    for c in text.chars() {
+        if is_cjk(c) {
+            result.push(SEGMENT_MARK);
+            result.push(c);
+            result.push(SEGMENT_MARK);
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+// 根据内容类型生成用于索引的文本
+fn index_text_for_content(content: &str, content_type: &str) -> String {
+    match content_type {
+        "image" => String::new(),
+        "file" => {
+            // 文件类型只索引文件名和路径
+            if let Some(files_json) = content.strip_prefix("files:") {
+                if let Ok(data) = serde_json::from_str::<serde_json::Value>(files_json) {
+                    let names: Vec<String> = data["files"]
+                        .as_array()
+                        .map(|files| {
+                            files
+                                .iter()
+                                .flat_map(|f| [f["name"].as_str(), f["path"].as_str()])
+                                .flatten()
+                                .map(|s| s.to_string())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    return segment_text(&names.join("\n"));
+                }
+            }
+            segment_text(content)
+        }
+        _ => segment_text(content),
+    }
+}
+
+// 将一段文本拆分为与 unicode61 分词器一致的词元
+fn split_tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            tokens.push(c.to_string());
+        } else if c.is_alphanumeric() {
+            current.push(c);
+        } else if !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+// 将词元组合为FTS5短语
+fn phrase(tokens: &[String]) -> String {
+    format!("\"{}\"", tokens.join(" ").replace('"', "\"\""))
+}
+
+// 查询中的一个词或短语
+#[derive(Debug, Clone)]
+struct QueryTerm {
+    tokens: Vec<String>,
+    // 非引号短语的最后一个词元按前缀匹配
+    prefix: bool,
+}
+
+impl QueryTerm {
+    fn to_fts(&self) -> String {
+        if self.prefix {
+            format!("{}*", phrase(&self.tokens))
+        } else {
+            phrase(&self.tokens)
+        }
+    }
+
+    // 在词元序列中查找匹配位置（忽略大小写）
+    fn find_in(&self, text_tokens: &[String]) -> Option<usize> {
+        let n = self.tokens.len();
+        if n == 0 || text_tokens.len() < n {
+            return None;
+        }
+        (0..=text_tokens.len() - n).find(|&start| {
+            self.tokens.iter().enumerate().all(|(i, token)| {
+                let token = token.to_lowercase();
+                let candidate = &text_tokens[start + i];
+                if self.prefix && i + 1 == n {
+                    candidate.starts_with(&token)
+                } else {
+                    *candidate == token
+                }
+            })
+        })
+    }
+}
+
+// 解析后的查询
+#[derive(Debug, Clone)]
+struct ParsedQuery {
+    includes: Vec<QueryTerm>,
+    excludes: Vec<QueryTerm>,
+}
+
+impl ParsedQuery {
+    // 判断文本是否满足查询（与FTS5查询语义一致）
+    fn matches(&self, text: &str) -> bool {
+        let tokens: Vec<String> = split_tokens(text).iter().map(|t| t.to_lowercase()).collect();
+        self.includes.iter().all(|t| t.find_in(&tokens).is_some())
+            && !self.excludes.iter().any(|t| t.find_in(&tokens).is_some())
+    }
+
+    // 生成首个命中位置附近的摘要
+    fn snippet(&self, text: &str) -> String {
+        const CONTEXT_CHARS: usize = 24;
+
+        let first = match self.includes.first().and_then(|t| t.tokens.first()) {
+            Some(first) => first.to_lowercase(),
+            None => return String::new(),
+        };
+        let chars: Vec<char> = text.chars().collect();
+        let lower: Vec<char> = text.to_lowercase().chars().collect();
+        let needle: Vec<char> = first.chars().collect();
+        let position = if lower.len() == chars.len() {
+            lower.windows(needle.len()).position(|w| w == needle.as_slice())
+        } else {
+            None
+        };
+
+        let mut raw = String::new();
+        match position {
+            Some(pos) => {
+                let start = pos.saturating_sub(CONTEXT_CHARS);
+                let end = (pos + needle.len() + CONTEXT_CHARS).min(chars.len());
+                if start > 0 {
+                    raw.push('…');
+                }
+                raw.extend(&chars[start..pos]);
+                raw.push(HIGHLIGHT_START);
+                raw.extend(&chars[pos..pos + needle.len()]);
+                raw.push(HIGHLIGHT_END);
+                raw.extend(&chars[pos + needle.len()..end]);
+                if end < chars.len() {
+                    raw.push('…');
+                }
+            }
+            None => raw.extend(chars.iter().take(CONTEXT_CHARS * 2)),
+        }
+        render_snippet(&raw.replace(['\n', '\r'], " "))
+    }
+}
+
+// 解析用户输入
+//
+// 支持：普通词（前缀匹配）、"引号短语"（精确短语）、-排除词；CJK文本按相邻单字组成短语
+fn parse_query(input: &str) -> Option<ParsedQuery> {
+    let mut includes: Vec<QueryTerm> = Vec::new();
+    let mut excludes: Vec<QueryTerm> = Vec::new();
+
+    let mut chars = input.trim().chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let negated = c == '-';
+        if negated {
+            chars.next();
+        }
+
+        let quoted = chars.peek() == Some(&'"');
+        let mut term = String::new();
+        if quoted {
+            chars.next();
+            for c in chars.by_ref() {
+                if c == '"' {
+                    break;
+                }
+                term.push(c);
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                term.push(c);
+                chars.next();
+            }
+        }
+
+        let tokens = split_tokens(&term);
+        if tokens.is_empty() {
+            continue;
+        }
+
+        let term = QueryTerm {
+            tokens,
+            prefix: !quoted,
+        };
+        if negated {
+            excludes.push(term);
+        } else {
+            includes.push(term);
+        }
+    }
+
+    if includes.is_empty() {
+        return None;
+    }
+
+    Some(ParsedQuery { includes, excludes })
+}
+
+// 将用户输入转换为FTS5查询表达式
+pub fn build_match_query(input: &str) -> Option<String> {
+    let parsed = parse_query(input)?;
+
+    let mut query = parsed
+        .includes
+        .iter()
+        .map(QueryTerm::to_fts)
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    for term in &parsed.excludes {
+        query = format!("({}) NOT {}", query, term.to_fts());
+    }
+    Some(query)
+}
+
+// 将FTS5摘要转换为可展示的HTML（去除分隔符、转义并替换高亮标记）
+fn render_snippet(raw: &str) -> String {
+    let mut result = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            SEGMENT_MARK => {}
+            HIGHLIGHT_START => result.push_str("<mark>"),
+            HIGHLIGHT_END => result.push_str("</mark>"),
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_match_query() {
+        assert_eq!(build_match_query("  "), None);
+        assert_eq!(build_match_query("hello"), Some("\"hello\"*".to_string()));
+        assert_eq!(
+            build_match_query("\"exact phrase\" rust"),
+            Some("\"exact phrase\" AND \"rust\"*".to_string())
+        );
+        assert_eq!(
+            build_match_query("剪贴板"),
+            Some("\"剪 贴 板\"*".to_string())
+        );
+        assert_eq!(
+            build_match_query("api -test"),
+            Some("(\"api\"*) NOT \"test\"*".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parsed_query_matches() {
+        let query = parse_query("剪贴 rus -java").unwrap();
+        assert!(query.matches("快捷剪贴板 Rust 版本"));
+        assert!(!query.matches("快捷剪贴板 Rust and Java"));
+        assert!(!query.matches("贴剪 Rust"));
+
+        let exact = parse_query("\"hello world\"").unwrap();
+        assert!(exact.matches("Hello World!"));
+        assert!(!exact.matches("hello worlds"));
+        assert_eq!(exact.snippet("say hello world"), "say <mark>hello</mark> world");
+    }
+
+    #[test]
+    fn test_segment_and_render_snippet() {
+        let segmented = segment_text("复制abc");
+        assert_eq!(split_tokens(&segmented), vec!["复", "制", "abc"]);
+
+        let raw = format!("{}复{}{}制{}<b>", HIGHLIGHT_START, SEGMENT_MARK, SEGMENT_MARK, HIGHLIGHT_END);
+        assert_eq!(render_snippet(&raw), "<mark>复制</mark>&lt;b&gt;");
+    }
+
+    // 内存数据库：注册索引函数后执行全部迁移
+    fn open_search_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        register_search_functions(&conn).unwrap();
+        crate::database_migration::run_migrations(&mut conn, std::path::Path::new("search-test.db")).unwrap();
+        ensure_search_index(&conn).unwrap();
+        conn
+    }
+
+    fn search(query: &str, offset: usize, limit: usize) -> SearchParams {
+        SearchParams {
+            query: query.to_string(),
+            content_type: None,
+            group_name: None,
+            tag: None,
+            source_app: None,
+            source_url: None,
+            offset: Some(offset),
+            limit: Some(limit),
+        }
+    }
+
+    fn favorite_ids(conn: &Connection, query: &str) -> Vec<String> {
+        query_favorites(conn, &search(query, 0, 10))
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|hit| hit.item.id)
+            .collect()
+    }
+
+    #[test]
+    fn favorites_index_follows_edits_and_deletes() {
+        let conn = open_search_db();
+        conn.execute_batch(
+            "INSERT INTO favorites (id, title, content, created_at, updated_at) VALUES
+                ('a', 'Rust', 'borrow checker', 1, 1),
+                ('b', 'notes', 'a longer note that mentions rust once among many other words', 1, 1),
+                ('c', 'other', '剪贴板工具', 1, 1);",
+        )
+        .unwrap();
+
+        // 标题命中的权重高于内容
+        assert_eq!(favorite_ids(&conn, "rust"), vec!["a", "b"]);
+        assert_eq!(favorite_ids(&conn, "剪贴"), vec!["c"]);
+
+        let page = query_favorites(&conn, &search("rust", 1, 1)).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].item.id, "b");
+        assert_eq!(
+            page.items[0].snippet,
+            "a longer note that mentions <mark>rust</mark> once among many other words"
+        );
+
+        // 修改内容和 id 后索引随之更新
+        conn.execute("UPDATE favorites SET id = 'a2', content = 'ownership' WHERE id = 'a'", [])
+            .unwrap();
+        assert!(favorite_ids(&conn, "borrow").is_empty());
+        assert_eq!(favorite_ids(&conn, "ownership"), vec!["a2"]);
+
+        conn.execute("DELETE FROM favorites WHERE id = 'b'", []).unwrap();
+        assert_eq!(favorite_ids(&conn, "rust"), vec!["a2"]);
+        let keys: i64 = conn
+            .query_row("SELECT COUNT(*) FROM favorites_fts_keys", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(keys, 2);
+    }
+
+    #[test]
+    fn clipboard_index_follows_updates() {
+        let conn = open_search_db();
+        conn.execute_batch(
+            "INSERT INTO clipboard (content, content_type, item_order, created_at, updated_at) VALUES
+                ('复制的文本 hello', 'text', 0, 1, 1),
+                ('hello world', 'text', 1, 1, 1);",
+        )
+        .unwrap();
+
+        let page = query_clipboard(&conn, &search("hello", 0, 10)).unwrap();
+        assert_eq!(page.total, 2);
+        let page = query_clipboard(&conn, &search("复制", 0, 10)).unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].snippet, "<mark>复制</mark>的文本 hello");
+
+        conn.execute("UPDATE clipboard SET content = 'goodbye' WHERE content = 'hello world'", [])
+            .unwrap();
+        let page = query_clipboard(&conn, &search("hello", 0, 10)).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].item.content, "复制的文本 hello");
+    }
+}
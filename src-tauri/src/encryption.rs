@@ -0,0 +1,803 @@
+// 数据加密模块
+//
+// 可选的静态数据加密：使用 Argon2id 从用户口令派生密钥加密密钥（KEK），
+// KEK 加密随机生成的数据密钥后保存在数据目录的 encryption.json 中。
+// 文本字段使用 XChaCha20-Poly1305 确定性加密（nonce 由 HMAC 派生），
+// 相同内容得到相同密文，因此数据库中按内容查重、比较的查询仍然有效；
+// 图片数据和图片文件使用随机 nonce 加密。解密后的图片只保存在内存中，
+// 前端通过 qcimage 协议按需读取（见 image_manager），不会写出明文文件。
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose as b64_engine, Engine as _};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use zeroize::{Zeroize, Zeroizing};
+
+// 密钥文件名（位于数据目录，与数据库一起迁移和导出）
+pub const KEY_FILE_NAME: &str = "encryption.json";
+
+// 加密文本前缀
+const TEXT_PREFIX: &str = "qcenc1:";
+// 加密二进制数据头
+const BLOB_MAGIC: &[u8] = b"QCENC1\0";
+
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+// Argon2id 默认参数（64 MiB 内存，3 次迭代）
+const DEFAULT_MEMORY_KIB: u32 = 64 * 1024;
+const DEFAULT_ITERATIONS: u32 = 3;
+const DEFAULT_PARALLELISM: u32 = 1;
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+// 是否启用了加密（存在密钥文件）
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 解锁后的数据密钥
+static KEYS: Lazy<RwLock<Option<DataKeys>>> = Lazy::new(|| RwLock::new(None));
+
+// 数据密钥：加密密钥 + nonce 派生密钥
+struct DataKeys {
+    enc_key: Zeroizing<[u8; KEY_LEN]>,
+    mac_key: Zeroizing<[u8; KEY_LEN]>,
+    key_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+}
+
+// 密钥文件内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeyFile {
+    version: u32,
+    // 数据密钥标识，用于判断导入的数据是否使用同一密钥
+    key_id: String,
+    kdf: KdfParams,
+    // 被 KEK 加密的数据密钥（nonce || 密文，Base64）
+    wrapped_key: String,
+    created_at: i64,
+}
+
+// 加密状态
+#[derive(Debug, Clone, Serialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+}
+
+// =================== 状态 ===================
+
+// 启动时初始化加密状态（需在数据库初始化之前调用）
+pub fn init() {
+    let enabled = key_file_path().map(|p| p.exists()).unwrap_or(false);
+    ENABLED.store(enabled, Ordering::SeqCst);
+
+    if enabled {
+        println!("数据加密已启用，等待解锁");
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+pub fn is_unlocked() -> bool {
+    KEYS.read().map(|k| k.is_some()).unwrap_or(false)
+}
+
+// 已启用加密但尚未解锁
+pub fn is_locked() -> bool {
+    is_enabled() && !is_unlocked()
+}
+
+pub fn get_status() -> EncryptionStatus {
+    EncryptionStatus {
+        enabled: is_enabled(),
+        unlocked: is_unlocked(),
+    }
+}
+
+fn key_file_path() -> Result<PathBuf, String> {
+    Ok(crate::settings::get_data_directory()?.join(KEY_FILE_NAME))
+}
+
+// 读取密钥文件中的数据密钥标识
+pub fn read_key_id(key_file: &Path) -> Result<String, String> {
+    Ok(read_key_file(key_file)?.key_id)
+}
+
+// 当前数据目录使用的数据密钥标识
+pub fn current_key_id() -> Option<String> {
+    key_file_path().ok().and_then(|p| read_key_id(&p).ok())
+}
+
+fn read_key_file(path: &Path) -> Result<KeyFile, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("读取密钥文件失败: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析密钥文件失败: {}", e))
+}
+
+fn write_key_file(key_file: &KeyFile) -> Result<(), String> {
+    let path = key_file_path()?;
+    let content = serde_json::to_string_pretty(key_file)
+        .map_err(|e| format!("序列化密钥文件失败: {}", e))?;
+
+    // 先写临时文件再替换，避免写入中断导致密钥丢失
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("写入密钥文件失败: {}", e))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("替换密钥文件失败: {}", e))?;
+    Ok(())
+}
+
+// =================== 密钥派生与包装 ===================
+
+fn derive_kek(passphrase: &str, kdf: &KdfParams) -> Result<Zeroizing<[u8; KEY_LEN]>, String> {
+    if kdf.algorithm != "argon2id" {
+        return Err(format!("不支持的密钥派生算法: {}", kdf.algorithm));
+    }
+
+    let salt = b64_engine::STANDARD
+        .decode(&kdf.salt)
+        .map_err(|e| format!("密钥文件盐值无效: {}", e))?;
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(KEY_LEN))
+        .map_err(|e| format!("密钥派生参数无效: {}", e))?;
+
+    let mut kek = Zeroizing::new([0u8; KEY_LEN]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, kek.as_mut())
+        .map_err(|e| format!("密钥派生失败: {}", e))?;
+    Ok(kek)
+}
+
+fn wrap_keys(
+    passphrase: &str,
+    key_material: &[u8; KEY_LEN * 2],
+    key_id: &str,
+) -> Result<(KdfParams, String), String> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+
+    let kdf = KdfParams {
+        algorithm: "argon2id".to_string(),
+        memory_kib: DEFAULT_MEMORY_KIB,
+        iterations: DEFAULT_ITERATIONS,
+        parallelism: DEFAULT_PARALLELISM,
+        salt: b64_engine::STANDARD.encode(salt),
+    };
+    let kek = derive_kek(passphrase, &kdf)?;
+
+    let cipher = XChaCha20Poly1305::new(kek.as_ref().into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: key_material,
+                aad: key_id.as_bytes(),
+            },
+        )
+        .map_err(|_| "加密数据密钥失败".to_string())?;
+
+    let mut wrapped = nonce.to_vec();
+    wrapped.extend_from_slice(&ciphertext);
+    Ok((kdf, b64_engine::STANDARD.encode(wrapped)))
+}
+
+fn unwrap_keys(passphrase: &str, key_file: &KeyFile) -> Result<DataKeys, String> {
+    let kek = derive_kek(passphrase, &key_file.kdf)?;
+    let wrapped = b64_engine::STANDARD
+        .decode(&key_file.wrapped_key)
+        .map_err(|e| format!("密钥文件内容无效: {}", e))?;
+    if wrapped.len() <= NONCE_LEN {
+        return Err("密钥文件内容无效".to_string());
+    }
+
+    let cipher = XChaCha20Poly1305::new(kek.as_ref().into());
+    let (nonce, ciphertext) = wrapped.split_at(NONCE_LEN);
+    let mut material = cipher
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: key_file.key_id.as_bytes(),
+            },
+        )
+        .map_err(|_| "口令错误".to_string())?;
+
+    if material.len() != KEY_LEN * 2 {
+        material.zeroize();
+        return Err("密钥文件内容无效".to_string());
+    }
+
+    let mut enc_key = Zeroizing::new([0u8; KEY_LEN]);
+    let mut mac_key = Zeroizing::new([0u8; KEY_LEN]);
+    enc_key.copy_from_slice(&material[..KEY_LEN]);
+    mac_key.copy_from_slice(&material[KEY_LEN..]);
+    material.zeroize();
+
+    Ok(DataKeys {
+        enc_key,
+        mac_key,
+        key_id: key_file.key_id.clone(),
+    })
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("口令长度不能少于 {} 个字符", MIN_PASSPHRASE_LEN));
+    }
+    Ok(())
+}
+
+// =================== 加密 / 解密 ===================
+
+fn with_keys<R>(f: impl FnOnce(&DataKeys) -> Result<R, String>) -> Result<R, String> {
+    let guard = KEYS.read().map_err(|e| format!("获取密钥失败: {}", e))?;
+    match guard.as_ref() {
+        Some(keys) => f(keys),
+        None => Err("数据已加密，请先解锁".to_string()),
+    }
+}
+
+// 判断文本是否为加密后的格式
+pub fn is_encrypted_text(value: &str) -> bool {
+    value.starts_with(TEXT_PREFIX)
+}
+
+// 判断二进制数据是否为加密后的格式
+pub fn is_encrypted_bytes(data: &[u8]) -> bool {
+    data.starts_with(BLOB_MAGIC)
+}
+
+// 加密文本字段（未启用加密或已加密时原样返回）
+pub fn encrypt_text(plain: &str) -> Result<String, String> {
+    if !is_enabled() || is_encrypted_text(plain) {
+        return Ok(plain.to_string());
+    }
+    with_keys(|keys| encrypt_text_with(keys, plain))
+}
+
+fn encrypt_text_with(keys: &DataKeys, plain: &str) -> Result<String, String> {
+    // 确定性 nonce：HMAC(mac_key, 明文) 的前 24 字节
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(keys.mac_key.as_ref())
+        .map_err(|e| format!("初始化HMAC失败: {}", e))?;
+    mac.update(plain.as_bytes());
+    let digest = mac.finalize().into_bytes();
+    let nonce = XNonce::from_slice(&digest[..NONCE_LEN]);
+
+    let cipher = XChaCha20Poly1305::new(keys.enc_key.as_ref().into());
+    let ciphertext = cipher
+        .encrypt(nonce, plain.as_bytes())
+        .map_err(|_| "加密数据失败".to_string())?;
+
+    let mut data = nonce.to_vec();
+    data.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", TEXT_PREFIX, b64_engine::STANDARD.encode(data)))
+}
+
+// 解密文本字段（非加密格式原样返回）
+pub fn decrypt_text(value: &str) -> Result<String, String> {
+    let encoded = match value.strip_prefix(TEXT_PREFIX) {
+        Some(encoded) => encoded,
+        None => return Ok(value.to_string()),
+    };
+
+    with_keys(|keys| {
+        let data = b64_engine::STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("加密数据格式无效: {}", e))?;
+        let plain = decrypt_payload(keys, &data)?;
+        String::from_utf8(plain).map_err(|e| format!("解密后的文本无效: {}", e))
+    })
+}
+
+// 加密二进制数据（未启用加密或已加密时原样返回）
+pub fn encrypt_bytes(data: &[u8]) -> Result<Vec<u8>, String> {
+    if !is_enabled() || is_encrypted_bytes(data) {
+        return Ok(data.to_vec());
+    }
+
+    with_keys(|keys| {
+        let cipher = XChaCha20Poly1305::new(keys.enc_key.as_ref().into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, data)
+            .map_err(|_| "加密数据失败".to_string())?;
+
+        let mut result = Vec::with_capacity(BLOB_MAGIC.len() + NONCE_LEN + ciphertext.len());
+        result.extend_from_slice(BLOB_MAGIC);
+        result.extend_from_slice(&nonce);
+        result.extend_from_slice(&ciphertext);
+        Ok(result)
+    })
+}
+
+// 解密二进制数据（非加密格式原样返回）
+pub fn decrypt_bytes(data: &[u8]) -> Result<Vec<u8>, String> {
+    match data.strip_prefix(BLOB_MAGIC) {
+        Some(payload) => with_keys(|keys| decrypt_payload(keys, payload)),
+        None => Ok(data.to_vec()),
+    }
+}
+
+fn decrypt_payload(keys: &DataKeys, data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() <= NONCE_LEN {
+        return Err("加密数据格式无效".to_string());
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(keys.enc_key.as_ref().into())
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "解密数据失败，数据可能已损坏或密钥不匹配".to_string())
+}
+
+// 注册加解密SQL函数（每个连接都需要注册）
+// qc_encrypt/qc_decrypt 用于文本列，qc_encrypt_blob/qc_decrypt_blob 用于二进制列，NULL 原样返回
+pub fn register_sql_functions(conn: &Connection) -> rusqlite::Result<()> {
+    let flags = FunctionFlags::SQLITE_UTF8;
+
+    conn.create_scalar_function("qc_encrypt", 1, flags, |ctx| {
+        let value: Option<String> = ctx.get(0)?;
+        value
+            .map(|v| encrypt_text(&v))
+            .transpose()
+            .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+    })?;
+
+    conn.create_scalar_function("qc_decrypt", 1, flags, |ctx| {
+        let value: Option<String> = ctx.get(0)?;
+        value
+            .map(|v| decrypt_text(&v))
+            .transpose()
+            .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+    })?;
+
+    conn.create_scalar_function("qc_encrypt_blob", 1, flags, |ctx| {
+        let value: Option<Vec<u8>> = ctx.get(0)?;
+        value
+            .map(|v| encrypt_bytes(&v))
+            .transpose()
+            .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+    })?;
+
+    conn.create_scalar_function("qc_decrypt_blob", 1, flags, |ctx| {
+        let value: Option<Vec<u8>> = ctx.get(0)?;
+        value
+            .map(|v| decrypt_bytes(&v))
+            .transpose()
+            .map_err(|e| rusqlite::Error::UserFunctionError(e.into()))
+    })?;
+
+    Ok(())
+}
+
+// =================== 启用 / 解锁 / 关闭 ===================
+
+// 启用加密：生成数据密钥并加密现有数据
+pub fn enable(passphrase: &str) -> Result<(), String> {
+    if is_enabled() {
+        return Err("数据加密已启用".to_string());
+    }
+    validate_passphrase(passphrase)?;
+
+    let mut material = Zeroizing::new([0u8; KEY_LEN * 2]);
+    OsRng.fill_bytes(material.as_mut());
+    let key_id = uuid::Uuid::new_v4().to_string();
+
+    let (kdf, wrapped_key) = wrap_keys(passphrase, &material, &key_id)?;
+    let key_file = KeyFile {
+        version: 1,
+        key_id: key_id.clone(),
+        kdf,
+        wrapped_key,
+        created_at: chrono::Local::now().timestamp(),
+    };
+
+    let mut enc_key = Zeroizing::new([0u8; KEY_LEN]);
+    let mut mac_key = Zeroizing::new([0u8; KEY_LEN]);
+    enc_key.copy_from_slice(&material[..KEY_LEN]);
+    mac_key.copy_from_slice(&material[KEY_LEN..]);
+
+    write_key_file(&key_file)?;
+    *KEYS.write().map_err(|e| format!("获取密钥失败: {}", e))? = Some(DataKeys {
+        enc_key,
+        mac_key,
+        key_id,
+    });
+    ENABLED.store(true, Ordering::SeqCst);
+
+    transform_all_data(true)?;
+    scrub_plaintext_remnants()?;
+    println!("数据加密已启用");
+    Ok(())
+}
+
+// 使用口令解锁，并加密解锁前以明文写入的数据（例如导入的未加密数据）
+pub fn unlock(passphrase: &str) -> Result<(), String> {
+    if !is_enabled() {
+        return Err("数据加密未启用".to_string());
+    }
+
+    let key_file = read_key_file(&key_file_path()?)?;
+    let keys = unwrap_keys(passphrase, &key_file)?;
+    *KEYS.write().map_err(|e| format!("获取密钥失败: {}", e))? = Some(keys);
+
+    decrypt_settings_secrets()?;
+    transform_all_data(true)?;
+    println!("数据已解锁");
+    Ok(())
+}
+
+// 锁定：清除内存中的密钥
+pub fn lock() -> Result<(), String> {
+    if !is_enabled() {
+        return Err("数据加密未启用".to_string());
+    }
+
+    // 内存中的设置恢复为加密后的值
+    let mut settings = crate::settings::get_global_settings();
+    if !settings.ai_api_key.is_empty() {
+        settings.ai_api_key = encrypt_text(&settings.ai_api_key)?;
+    }
+    for profile in settings.ai_profiles.iter_mut().filter(|p| !p.api_key.is_empty()) {
+        profile.api_key = encrypt_text(&profile.api_key)?;
+    }
+    if !settings.sync_webdav_password.is_empty() {
+        settings.sync_webdav_password = encrypt_text(&settings.sync_webdav_password)?;
+    }
+
+    *KEYS.write().map_err(|e| format!("获取密钥失败: {}", e))? = None;
+    crate::settings::update_global_settings(settings)?;
+    crate::translation_memory::clear_prefilled();
+    println!("数据已锁定");
+    Ok(())
+}
+
+// 关闭加密：解密所有数据并删除密钥文件
+pub fn disable(passphrase: &str) -> Result<(), String> {
+    if !is_enabled() {
+        return Err("数据加密未启用".to_string());
+    }
+
+    let key_file = read_key_file(&key_file_path()?)?;
+    let keys = unwrap_keys(passphrase, &key_file)?;
+    *KEYS.write().map_err(|e| format!("获取密钥失败: {}", e))? = Some(keys);
+
+    decrypt_settings_secrets()?;
+    transform_all_data(false)?;
+
+    ENABLED.store(false, Ordering::SeqCst);
+    *KEYS.write().map_err(|e| format!("获取密钥失败: {}", e))? = None;
+
+    // 以明文保存设置
+    crate::settings::update_global_settings(crate::settings::get_global_settings())?;
+
+    fs::remove_file(key_file_path()?).map_err(|e| format!("删除密钥文件失败: {}", e))?;
+    println!("数据加密已关闭");
+    Ok(())
+}
+
+// 修改口令（数据密钥不变，只重新包装）
+pub fn change_passphrase(old_passphrase: &str, new_passphrase: &str) -> Result<(), String> {
+    if !is_enabled() {
+        return Err("数据加密未启用".to_string());
+    }
+    validate_passphrase(new_passphrase)?;
+
+    let mut key_file = read_key_file(&key_file_path()?)?;
+    let keys = unwrap_keys(old_passphrase, &key_file)?;
+
+    let mut material = Zeroizing::new([0u8; KEY_LEN * 2]);
+    material[..KEY_LEN].copy_from_slice(keys.enc_key.as_ref());
+    material[KEY_LEN..].copy_from_slice(keys.mac_key.as_ref());
+
+    let (kdf, wrapped_key) = wrap_keys(new_passphrase, &material, &key_file.key_id)?;
+    key_file.kdf = kdf;
+    key_file.wrapped_key = wrapped_key;
+    write_key_file(&key_file)?;
+
+    println!("加密口令已修改");
+    Ok(())
+}
+
+// 导入数据或切换数据目录后重新读取密钥文件
+// 密钥文件被替换为其他密钥时需要重新解锁
+pub fn reload_key_file() -> Result<(), String> {
+    let path = key_file_path()?;
+    let enabled = path.exists();
+    ENABLED.store(enabled, Ordering::SeqCst);
+
+    let current_key_id = KEYS
+        .read()
+        .map_err(|e| format!("获取密钥失败: {}", e))?
+        .as_ref()
+        .map(|k| k.key_id.clone());
+
+    match (enabled, current_key_id) {
+        (true, Some(key_id)) if read_key_id(&path)? == key_id => {
+            // 同一密钥，加密导入的明文数据
+            transform_all_data(true)
+        }
+        (_, Some(_)) => {
+            *KEYS.write().map_err(|e| format!("获取密钥失败: {}", e))? = None;
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+// 解锁后将内存中的设置密钥解密
+fn decrypt_settings_secrets() -> Result<(), String> {
+    let mut settings = crate::settings::get_global_settings();
+    let mut changed = false;
+    if is_encrypted_text(&settings.ai_api_key) {
+        settings.ai_api_key = decrypt_text(&settings.ai_api_key)?;
+        changed = true;
+    }
+    for profile in settings.ai_profiles.iter_mut() {
+        if is_encrypted_text(&profile.api_key) {
+            profile.api_key = decrypt_text(&profile.api_key)?;
+            changed = true;
+        }
+    }
+    if is_encrypted_text(&settings.sync_webdav_password) {
+        settings.sync_webdav_password = decrypt_text(&settings.sync_webdav_password)?;
+        changed = true;
+    }
+    if changed {
+        crate::settings::update_global_settings(settings)?;
+    }
+    Ok(())
+}
+
+// 加密或解密所有已存储的数据（数据库字段、图片数据、图片文件）
+fn transform_all_data(encrypting: bool) -> Result<(), String> {
+    let (text_fn, blob_fn, condition, blob_condition) = if encrypting {
+        ("qc_encrypt", "qc_encrypt_blob", "NOT LIKE", "!=")
+    } else {
+        ("qc_decrypt", "qc_decrypt_blob", "LIKE", "=")
+    };
+    let pattern = format!("{}%", TEXT_PREFIX);
+    // 只处理尚未转换的二进制数据（按数据头判断），避免每次解锁都重写所有图片
+    let magic: String = BLOB_MAGIC.iter().map(|b| format!("{:02X}", b)).collect();
+    let blob_pending = |column: &str| {
+        format!(
+            "({col} IS NOT NULL AND substr({col}, 1, {len}) {op} X'{magic}')",
+            col = column,
+            len = BLOB_MAGIC.len(),
+            op = blob_condition,
+            magic = magic
+        )
+    };
+
+    crate::database::with_connection(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            &format!(
+                "UPDATE clipboard SET content = {f}(content), html_content = {f}(html_content),
+                        source_title = {f}(source_title), source_url = {f}(source_url)
+                 WHERE content {c} ?1 OR html_content {c} ?1 OR source_title {c} ?1 OR source_url {c} ?1",
+                f = text_fn,
+                c = condition
+            ),
+            [&pattern],
+        )?;
+        tx.execute(
+            &format!(
+                "UPDATE favorites SET title = {f}(title), content = {f}(content), html_content = {f}(html_content)
+                 WHERE content {c} ?1 OR title {c} ?1 OR html_content {c} ?1",
+                f = text_fn,
+                c = condition
+            ),
+            [&pattern],
+        )?;
+        tx.execute(
+            &format!(
+                "UPDATE clipboard_representations SET text_data = {f}(text_data), blob_data = {b}(blob_data)
+                 WHERE text_data {c} ?1 OR {blob}",
+                f = text_fn,
+                b = blob_fn,
+                c = condition,
+                blob = blob_pending("blob_data")
+            ),
+            [&pattern],
+        )?;
+        tx.execute(
+            &format!(
+                "UPDATE clipboard_tags SET detail = {f}(detail) WHERE detail {c} ?1",
+                f = text_fn,
+                c = condition
+            ),
+            [&pattern],
+        )?;
+        tx.execute(
+            &format!(
+                "UPDATE translation_memory SET source_text = {f}(source_text), translation = {f}(translation)
+                 WHERE source_text {c} ?1 OR translation {c} ?1",
+                f = text_fn,
+                c = condition
+            ),
+            [&pattern],
+        )?;
+        tx.execute(
+            &format!(
+                "UPDATE image_data SET bgra_data = {f}(bgra_data), png_data = {f}(png_data)
+                 WHERE {bgra} OR {png}",
+                f = blob_fn,
+                bgra = blob_pending("bgra_data"),
+                png = blob_pending("png_data")
+            ),
+            [],
+        )?;
+        tx.commit()
+    })?;
+
+    transform_image_files(encrypting)?;
+
+    // 保存设置时会按当前状态加密或保留明文
+    if encrypting {
+        crate::settings::update_global_settings(crate::settings::get_global_settings())?;
+    }
+
+    Ok(())
+}
+
+// 启用加密后清除残留的明文：数据库空闲页和 WAL、全文索引的旧分段，以及自动备份
+fn scrub_plaintext_remnants() -> Result<(), String> {
+    crate::database::with_connection(|conn| {
+        // 之后释放的页面用零覆盖
+        conn.pragma_update(None, "secure_delete", true)?;
+        // 重建并合并索引，旧分段中的词元随之删除
+        let tx = conn.unchecked_transaction()?;
+        crate::database_search::rebuild_search_index_with(&tx)?;
+        tx.commit()?;
+        conn.execute_batch("VACUUM")?;
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+    })?;
+
+    // 迁移和导入前的自动备份是加密前的明文数据
+    let backups_dir = crate::settings::get_data_directory()?.join("backups");
+    if let Ok(entries) = fs::read_dir(&backups_dir) {
+        for path in entries.filter_map(Result::ok).map(|e| e.path()) {
+            if path.is_file() {
+                fs::remove_file(&path).map_err(|e| format!("删除明文备份失败: {}", e))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn transform_image_files(encrypting: bool) -> Result<(), String> {
+    let images_dir = crate::settings::get_data_directory()?.join("clipboard_images");
+    let entries = match fs::read_dir(&images_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+
+    for path in entries.filter_map(Result::ok).map(|e| e.path()) {
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != "png") {
+            continue;
+        }
+
+        // 先检查文件头，已经是目标格式的文件不读取全部内容
+        if is_encrypted_file(&path) == encrypting {
+            continue;
+        }
+        let data = fs::read(&path).map_err(|e| format!("读取图片文件失败: {}", e))?;
+        let transformed = if encrypting {
+            encrypt_bytes(&data)?
+        } else {
+            decrypt_bytes(&data)?
+        };
+        write_file_atomic(&path, &transformed)?;
+    }
+
+    Ok(())
+}
+
+// =================== 解锁提示与通知 ===================
+
+// 启动时提示输入口令解锁（最多尝试3次，取消后可在设置中解锁）
+pub async fn prompt_unlock(app_handle: tauri::AppHandle) {
+    use crate::plugins::input_dialog::window::{show_dialog, InputDialogOptions, InputType};
+
+    const MAX_ATTEMPTS: usize = 3;
+
+    let mut message = "剪贴板数据已加密，请输入口令解锁".to_string();
+    for _ in 0..MAX_ATTEMPTS {
+        let options = InputDialogOptions {
+            title: "解锁 QuickClipboard".to_string(),
+            message: message.clone(),
+            placeholder: Some("口令".to_string()),
+            default_value: None,
+            input_type: InputType::Password,
+            min_value: None,
+            max_value: None,
+        };
+
+        let passphrase = match show_dialog(app_handle.clone(), options).await {
+            Ok(Some(passphrase)) => passphrase,
+            Ok(None) => break,
+            Err(e) => {
+                println!("显示解锁对话框失败: {}", e);
+                break;
+            }
+        };
+
+        let result = tauri::async_runtime::spawn_blocking(move || unlock(&passphrase))
+            .await
+            .map_err(|e| format!("解锁任务执行失败: {}", e))
+            .and_then(|r| r);
+
+        match result {
+            Ok(()) => {
+                notify_status_changed(&app_handle);
+                return;
+            }
+            Err(e) => message = format!("解锁失败：{}，请重新输入", e),
+        }
+    }
+
+    println!("数据未解锁，剪贴板记录已暂停");
+    notify_status_changed(&app_handle);
+}
+
+// 通知前端加密状态变化并刷新列表
+pub fn notify_status_changed(app_handle: &tauri::AppHandle) {
+    use tauri::{Emitter, Manager};
+
+    let _ = app_handle.emit("encryption-status-changed", get_status());
+    if let Some(main_window) = app_handle.get_webview_window("main") {
+        let _ = main_window.emit("clipboard-changed", ());
+        let _ = main_window.emit("refreshQuickTexts", ());
+    }
+}
+
+// =================== 文件辅助函数 ===================
+
+// 先写入临时文件再替换，避免中断时留下损坏的文件
+pub fn write_file_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, data).map_err(|e| format!("写入文件失败: {}", e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("替换文件失败: {}", e))?;
+    Ok(())
+}
+
+// 写入图片文件（启用加密时加密）
+pub fn write_image_file(path: &Path, png_data: &[u8]) -> Result<(), String> {
+    let data = encrypt_bytes(png_data)?;
+    fs::write(path, data).map_err(|e| format!("写入PNG文件失败: {}", e))
+}
+
+// 读取图片文件（自动解密）
+pub fn read_image_file(path: &Path) -> Result<Vec<u8>, String> {
+    let data = fs::read(path).map_err(|e| format!("读取图片文件失败: {}", e))?;
+    decrypt_bytes(&data)
+}
+
+// 图片文件是否为加密格式（只读取文件头）
+pub fn is_encrypted_file(path: &Path) -> bool {
+    use std::io::Read;
+    let mut header = [0u8; BLOB_MAGIC.len()];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map(|_| is_encrypted_bytes(&header))
+        .unwrap_or(false)
+}
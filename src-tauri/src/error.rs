@@ -0,0 +1,241 @@
+// 统一的后端错误类型
+//
+// Tauri 命令返回的错误会序列化为固定结构：
+// { "code": "not_found", "key": "error.clipboard.item_not_found", "message": "...", "context": { "id": "42" } }
+// 前端和脚本按 code 区分错误类别，按 key 查找本地化文案，message 为按当前语言可直接显示的说明
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+// 错误类别（序列化后的取值属于稳定接口，只能新增不能修改）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    DatabaseNotInitialized, // 数据库尚未打开
+    Database,               // SQL 执行失败
+    NotFound,               // 请求的对象不存在
+    InvalidInput,           // 参数不合法
+    PermissionDenied,       // 没有访问权限
+    Io,                     // 文件读写失败
+    Image,                  // 图片编解码或存储失败
+    Translation,            // 翻译服务失败
+    Settings,               // 设置读写失败
+    Locked,                 // 数据已加密且尚未解锁
+    Internal,               // 其他内部错误
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::DatabaseNotInitialized => "database_not_initialized",
+            ErrorCode::Database => "database",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::PermissionDenied => "permission_denied",
+            ErrorCode::Io => "io",
+            ErrorCode::Image => "image",
+            ErrorCode::Translation => "translation",
+            ErrorCode::Settings => "settings",
+            ErrorCode::Locked => "locked",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AppError {
+    pub code: ErrorCode,
+    // 本地化文案的键，未指定时为 "error.<code>"
+    pub key: String,
+    // 中文说明（源语言），输出时按当前语言本地化
+    pub message: String,
+    pub context: BTreeMap<String, String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            key: format!("error.{}", code.as_str()),
+            message: message.into(),
+            context: BTreeMap::new(),
+        }
+    }
+
+    pub fn database_not_initialized() -> Self {
+        Self::new(ErrorCode::DatabaseNotInitialized, "数据库未初始化")
+    }
+
+    pub fn database(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Database, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn clipboard_item_not_found(id: i64) -> Self {
+        Self::not_found(format!("剪贴板项目不存在: {}", id))
+            .with_key("error.clipboard.item_not_found")
+            .with_context("id", id)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Io, message)
+    }
+
+    pub fn image(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Image, message)
+    }
+
+    pub fn translation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Translation, message)
+    }
+
+    pub fn settings(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Settings, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    // 指定更具体的本地化键
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = key.into();
+        self
+    }
+
+    // 附加上下文（如 ID、路径），便于调用方定位
+    pub fn with_context(mut self, name: &str, value: impl ToString) -> Self {
+        self.context.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    // 在原有说明前加上操作描述，保留类别、键和上下文
+    pub fn prefixed(mut self, action: &str) -> Self {
+        self.message = format!("{}: {}", action, self.message);
+        self
+    }
+
+    // 按当前语言显示的说明
+    pub fn localized_message(&self) -> String {
+        crate::i18n::localize_error(&self.key, self.code.as_str(), &self.message, &self.context)
+    }
+
+    // 由 std::io::Error 生成，按错误种类区分类别
+    pub fn from_io(action: &str, error: &std::io::Error) -> Self {
+        let code = match error.kind() {
+            std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+            std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            _ => ErrorCode::Io,
+        };
+        Self::new(code, format!("{}: {}", action, error))
+    }
+
+    // 由 rusqlite::Error 生成，说明中使用操作描述代替通用的"数据库操作失败"
+    pub fn from_sqlite(action: &str, error: rusqlite::Error) -> Self {
+        let code = match error {
+            rusqlite::Error::QueryReturnedNoRows => ErrorCode::NotFound,
+            _ => ErrorCode::Database,
+        };
+        Self::new(code, format!("{}: {}", action, error))
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localized_message())
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 4)?;
+        state.serialize_field("code", &self.code)?;
+        state.serialize_field("key", &self.key)?;
+        state.serialize_field("message", &self.localized_message())?;
+        state.serialize_field("context", &self.context)?;
+        state.end()
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        match error {
+            rusqlite::Error::QueryReturnedNoRows => Self::not_found("记录不存在"),
+            other => Self::database(format!("数据库操作失败: {}", other)),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        Self::from_io("文件操作失败", &error)
+    }
+}
+
+// 尚未迁移的模块仍返回 String 错误，统一归为内部错误
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+// 让返回 Result<_, String> 的调用方可以继续使用 `?`
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.localized_message()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_to_stable_shape() {
+        let error = AppError::not_found("未找到ID为 42 的剪贴板项")
+            .with_key("error.clipboard.item_not_found")
+            .with_context("id", 42);
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "code": "not_found",
+                "key": "error.clipboard.item_not_found",
+                "message": "未找到ID为 42 的剪贴板项",
+                "context": { "id": "42" }
+            })
+        );
+    }
+
+    #[test]
+    fn maps_sources_to_codes() {
+        let error: AppError = rusqlite::Error::QueryReturnedNoRows.into();
+        assert_eq!(error.code, ErrorCode::NotFound);
+        assert_eq!(error.key, "error.not_found");
+
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(AppError::from(io).code, ErrorCode::PermissionDenied);
+
+        let message: String = AppError::database_not_initialized().into();
+        assert_eq!(message, "数据库未初始化");
+    }
+}
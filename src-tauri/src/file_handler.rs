@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::error::{AppError, AppResult};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileInfo {
     pub path: String,
@@ -22,7 +24,7 @@ pub struct FileClipboardData {
 
 // 将文件路径写入剪贴板
 #[cfg(windows)]
-pub fn set_clipboard_files(file_paths: &[String]) -> Result<(), String> {
+pub fn set_clipboard_files(file_paths: &[String]) -> AppResult<()> {
     use std::ffi::OsStr;
     use std::os::windows::ffi::OsStrExt;
     use windows::Win32::Foundation::{HANDLE, HWND};
@@ -35,13 +37,13 @@ pub fn set_clipboard_files(file_paths: &[String]) -> Result<(), String> {
     unsafe {
         // 打开剪贴板
         if OpenClipboard(HWND(0)).is_err() {
-            return Err("无法打开剪贴板".to_string());
+            return Err(AppError::io("无法打开剪贴板"));
         }
 
         // 清空剪贴板
         if EmptyClipboard().is_err() {
             let _ = CloseClipboard();
-            return Err("无法清空剪贴板".to_string());
+            return Err(AppError::io("无法清空剪贴板"));
         }
 
         // 计算所需内存大小
@@ -60,19 +62,19 @@ pub fn set_clipboard_files(file_paths: &[String]) -> Result<(), String> {
             Ok(h) => h,
             Err(_) => {
                 let _ = CloseClipboard();
-                return Err("无法分配内存".to_string());
+                return Err(AppError::io("无法分配内存"));
             }
         };
 
         if hmem.is_invalid() {
             let _ = CloseClipboard();
-            return Err("无法分配内存".to_string());
+            return Err(AppError::io("无法分配内存"));
         }
 
         let ptr = GlobalLock(hmem);
         if ptr.is_null() {
             let _ = CloseClipboard();
-            return Err("无法锁定内存".to_string());
+            return Err(AppError::io("无法锁定内存"));
         }
 
         // 设置 DROPFILES 结构
@@ -105,7 +107,7 @@ pub fn set_clipboard_files(file_paths: &[String]) -> Result<(), String> {
         // 设置剪贴板数据
         if SetClipboardData(CF_HDROP.0 as u32, HANDLE(hmem.0 as isize)).is_err() {
             let _ = CloseClipboard();
-            return Err("无法设置剪贴板数据".to_string());
+            return Err(AppError::io("无法设置剪贴板数据"));
         }
 
         let _ = CloseClipboard();
@@ -113,14 +115,19 @@ pub fn set_clipboard_files(file_paths: &[String]) -> Result<(), String> {
     }
 }
 
-#[cfg(not(windows))]
-pub fn set_clipboard_files(_file_paths: &[String]) -> Result<(), String> {
-    Err("当前平台不支持文件剪贴板操作".to_string())
+#[cfg(target_os = "linux")]
+pub fn set_clipboard_files(file_paths: &[String]) -> AppResult<()> {
+    crate::linux_clipboard::write_files(file_paths).map_err(AppError::io)
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn set_clipboard_files(_file_paths: &[String]) -> AppResult<()> {
+    Err(AppError::internal("当前平台不支持文件剪贴板操作"))
 }
 
 // 从剪贴板获取文件路径列表
 #[cfg(windows)]
-pub fn get_clipboard_files() -> Result<Vec<String>, String> {
+pub fn get_clipboard_files() -> AppResult<Vec<String>> {
     use std::ffi::OsString;
     use std::os::windows::ffi::OsStringExt;
     use windows::Win32::Foundation::HWND;
@@ -134,7 +141,7 @@ pub fn get_clipboard_files() -> Result<Vec<String>, String> {
     unsafe {
         // 打开剪贴板
         if OpenClipboard(HWND(0)).is_err() {
-            return Err("无法打开剪贴板".to_string());
+            return Err(AppError::io("无法打开剪贴板"));
         }
 
         let mut files = Vec::new();
@@ -194,21 +201,34 @@ pub fn get_clipboard_files() -> Result<Vec<String>, String> {
     }
 }
 
-#[cfg(not(windows))]
-pub fn get_clipboard_files() -> Result<Vec<String>, String> {
-    // 非Windows平台暂不支持
-    Err("当前平台不支持文件剪贴板操作".to_string())
+#[cfg(target_os = "linux")]
+pub fn get_clipboard_files() -> AppResult<Vec<String>> {
+    if !crate::linux_clipboard::is_available() {
+        return Err(AppError::internal("没有可用的 Linux 剪贴板后端"));
+    }
+    Ok(crate::linux_clipboard::read_files(
+        crate::linux_clipboard::Selection::Clipboard,
+    ))
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn get_clipboard_files() -> AppResult<Vec<String>> {
+    // 其他平台暂不支持
+    Err(AppError::internal("当前平台不支持文件剪贴板操作"))
 }
 
 // 获取文件信息
-pub fn get_file_info(path: &str) -> Result<FileInfo, String> {
+pub fn get_file_info(path: &str) -> AppResult<FileInfo> {
     let path_buf = PathBuf::from(path);
 
     if !path_buf.exists() {
-        return Err(format!("文件不存在: {}", path));
+        return Err(AppError::not_found(format!("文件不存在: {}", path))
+            .with_key("error.file.not_found")
+            .with_context("path", path));
     }
 
-    let metadata = fs::metadata(&path_buf).map_err(|e| format!("获取文件元数据失败: {}", e))?;
+    let metadata = fs::metadata(&path_buf)
+        .map_err(|e| AppError::from_io("获取文件元数据失败", &e).with_context("path", path))?;
 
     let name = path_buf
         .file_name()
@@ -245,7 +265,7 @@ pub fn get_file_info(path: &str) -> Result<FileInfo, String> {
 
 // 获取文件图标（Windows系统图标）
 #[cfg(windows)]
-pub fn get_file_icon(path: &str) -> Result<String, String> {
+pub fn get_file_icon(path: &str) -> AppResult<String> {
     use std::ffi::OsStr;
     use std::os::windows::ffi::OsStrExt;
     use std::path::Path;
@@ -464,7 +484,7 @@ fn get_fallback_icon(path: &str) -> String {
 }
 
 #[cfg(not(windows))]
-pub fn get_file_icon(_path: &str) -> Result<String, String> {
+pub fn get_file_icon(_path: &str) -> AppResult<String> {
     // 非Windows平台返回默认图标
     Ok(get_default_file_icon())
 }
@@ -576,15 +596,17 @@ fn is_file_size_suitable_for_direct_access(path: &str) -> bool {
 
 // 复制文件到目标位置
     #[allow(dead_code)]
-    pub fn copy_files_to_target(files: &[String], target_dir: &str) -> Result<Vec<String>, String> {
+    pub fn copy_files_to_target(files: &[String], target_dir: &str) -> AppResult<Vec<String>> {
     let target_path = Path::new(target_dir);
 
     if !target_path.exists() {
-        return Err(format!("目标目录不存在: {}", target_dir));
+        return Err(AppError::not_found(format!("目标目录不存在: {}", target_dir))
+            .with_context("path", target_dir));
     }
 
     if !target_path.is_dir() {
-        return Err(format!("目标路径不是目录: {}", target_dir));
+        return Err(AppError::invalid_input(format!("目标路径不是目录: {}", target_dir))
+            .with_context("path", target_dir));
     }
 
     let mut copied_files = Vec::new();
@@ -597,7 +619,10 @@ fn is_file_size_suitable_for_direct_access(path: &str) -> bool {
 
         let file_name = source_path
             .file_name()
-            .ok_or_else(|| format!("无法获取文件名: {}", file_path))?;
+            .ok_or_else(|| {
+                AppError::invalid_input(format!("无法获取文件名: {}", file_path))
+                    .with_context("path", file_path)
+            })?;
 
         let target_file_path = target_path.join(file_name);
 
@@ -610,7 +635,7 @@ fn is_file_size_suitable_for_direct_access(path: &str) -> bool {
         } else {
             // 复制文件
             fs::copy(source_path, &final_target_path)
-                .map_err(|e| format!("复制文件失败 {}: {}", file_path, e))?;
+                .map_err(|e| AppError::from_io("复制文件失败", &e).with_context("path", file_path))?;
         }
 
         copied_files.push(final_target_path.to_string_lossy().to_string());
@@ -621,12 +646,15 @@ fn is_file_size_suitable_for_direct_access(path: &str) -> bool {
 
 // 递归复制目录
 #[allow(dead_code)]
-fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
-    fs::create_dir_all(dst).map_err(|e| format!("创建目录失败 {}: {}", dst.display(), e))?;
+fn copy_dir_recursive(src: &Path, dst: &Path) -> AppResult<()> {
+    fs::create_dir_all(dst).map_err(|e| {
+        AppError::from_io("创建目录失败", &e).with_context("path", dst.display())
+    })?;
 
-    for entry in fs::read_dir(src).map_err(|e| format!("读取目录失败 {}: {}", src.display(), e))?
-    {
-        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+    for entry in fs::read_dir(src).map_err(|e| {
+        AppError::from_io("读取目录失败", &e).with_context("path", src.display())
+    })? {
+        let entry = entry.map_err(|e| AppError::from_io("读取目录项失败", &e))?;
 
         let src_path = entry.path();
         let dst_path = dst.join(entry.file_name());
@@ -635,7 +663,9 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
             copy_dir_recursive(&src_path, &dst_path)?;
         } else {
             fs::copy(&src_path, &dst_path)
-                .map_err(|e| format!("复制文件失败 {}: {}", src_path.display(), e))?;
+                .map_err(|e| {
+                    AppError::from_io("复制文件失败", &e).with_context("path", src_path.display())
+                })?;
         }
     }
 
@@ -643,7 +673,7 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
 }
 
 // 生成唯一的文件路径（如果文件已存在，添加数字后缀）
-fn generate_unique_path(path: &Path) -> Result<PathBuf, String> {
+fn generate_unique_path(path: &Path) -> AppResult<PathBuf> {
     if !path.exists() {
         return Ok(path.to_path_buf());
     }
@@ -665,5 +695,5 @@ fn generate_unique_path(path: &Path) -> Result<PathBuf, String> {
         }
     }
 
-    Err("无法生成唯一文件名".to_string())
+    Err(AppError::io("无法生成唯一文件名").with_context("path", path.display()))
 }
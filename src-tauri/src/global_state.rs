@@ -243,6 +243,31 @@ pub fn get_number_shortcuts_modifier() -> String {
     }
 }
 
+// 数字快捷键置顶项目的修饰键配置
+static NUMBER_SHORTCUTS_PIN_MODIFIER: OnceCell<RwLock<String>> = OnceCell::new();
+
+// 更新数字快捷键置顶项目的修饰键配置
+#[cfg(windows)]
+pub fn update_number_shortcuts_pin_modifier(modifier: &str) {
+    let modifier_lock =
+        NUMBER_SHORTCUTS_PIN_MODIFIER.get_or_init(|| RwLock::new("Ctrl+Shift".to_string()));
+    if let Ok(mut modifier_config) = modifier_lock.write() {
+        *modifier_config = modifier.to_string();
+    }
+}
+
+// 获取当前数字快捷键置顶项目的修饰键配置
+#[cfg(windows)]
+pub fn get_number_shortcuts_pin_modifier() -> String {
+    let modifier_lock =
+        NUMBER_SHORTCUTS_PIN_MODIFIER.get_or_init(|| RwLock::new("Ctrl+Shift".to_string()));
+    if let Ok(modifier_config) = modifier_lock.read() {
+        modifier_config.clone()
+    } else {
+        "Ctrl+Shift".to_string()
+    }
+}
+
 // 检查数字快捷键是否启用
 #[cfg(windows)]
 pub fn is_number_shortcuts_enabled() -> bool {
@@ -303,6 +328,14 @@ pub fn get_number_shortcuts_modifier() -> String {
     "Ctrl".to_string()
 }
 
+#[cfg(not(windows))]
+pub fn update_number_shortcuts_pin_modifier(_modifier: &str) {}
+
+#[cfg(not(windows))]
+pub fn get_number_shortcuts_pin_modifier() -> String {
+    String::new()
+}
+
 #[cfg(not(windows))]
 pub fn set_ai_translation_cancel_enabled(_enabled: bool) {}
 
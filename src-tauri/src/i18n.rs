@@ -0,0 +1,311 @@
+// 后端文案本地化
+//
+// 托盘、通知等界面文案按键存放在各语言的目录中，由设置中的 language 选择。
+// zh-CN 为源语言：其他语言缺少某个键时回退到 zh-CN，仍缺少时返回键本身。
+// 错误说明在构造时已是中文，只有当前语言的目录中有对应键时才替换（见 AppError）。
+// 文案中的 {name} 占位符按名称替换为调用方提供的值
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    ZhCn,
+    EnUs,
+}
+
+impl Language {
+    // 由设置中的语言代码解析，无法识别时使用源语言
+    pub fn from_code(code: &str) -> Self {
+        let code = code.trim().to_ascii_lowercase();
+        if code.starts_with("en") {
+            Language::EnUs
+        } else {
+            Language::ZhCn
+        }
+    }
+
+    fn catalog(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::ZhCn => ZH_CN,
+            Language::EnUs => EN_US,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Language::EnUs,
+            _ => Language::ZhCn,
+        }
+    }
+
+    fn as_u8(&self) -> u8 {
+        match self {
+            Language::ZhCn => 0,
+            Language::EnUs => 1,
+        }
+    }
+}
+
+// 当前语言（设置加载和更新时同步）
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+pub fn current_language() -> Language {
+    Language::from_u8(CURRENT_LANGUAGE.load(Ordering::Relaxed))
+}
+
+// 切换当前语言，返回语言是否发生变化
+pub fn set_language(code: &str) -> bool {
+    let language = Language::from_code(code);
+    CURRENT_LANGUAGE.swap(language.as_u8(), Ordering::Relaxed) != language.as_u8()
+}
+
+// 按当前语言获取文案
+pub fn t(key: &str) -> String {
+    translate(current_language(), key).to_string()
+}
+
+// 按当前语言获取文案并替换占位符
+pub fn t_with(key: &str, args: &[(&str, &str)]) -> String {
+    interpolate(translate(current_language(), key), args.iter().copied())
+}
+
+// 本地化错误说明：当前语言的目录中有该键时使用目录文案（用上下文填充），
+// 没有时使用错误类别的通用文案（error.<code>）并在后面附上原说明，都没有时保留原说明
+pub fn localize_error(
+    key: &str,
+    code: &str,
+    message: &str,
+    context: &BTreeMap<String, String>,
+) -> String {
+    localize_error_in(current_language(), key, code, message, context)
+}
+
+fn localize_error_in(
+    language: Language,
+    key: &str,
+    code: &str,
+    message: &str,
+    context: &BTreeMap<String, String>,
+) -> String {
+    if language == Language::ZhCn {
+        return message.to_string();
+    }
+    if let Some(template) = lookup(language, key) {
+        return interpolate(
+            template,
+            context.iter().map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+    }
+    // 通用文案不含具体原因，附上原说明以免丢失细节（如文件路径、SQLite 错误）
+    match lookup(language, &format!("error.{}", code)) {
+        Some(generic) if !message.is_empty() => format!("{}: {}", generic, message),
+        Some(generic) => generic.to_string(),
+        None => message.to_string(),
+    }
+}
+
+fn lookup(language: Language, key: &str) -> Option<&'static str> {
+    language
+        .catalog()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| *v)
+}
+
+fn translate(language: Language, key: &str) -> &str {
+    lookup(language, key)
+        .or_else(|| lookup(Language::ZhCn, key))
+        .unwrap_or(key)
+}
+
+fn interpolate<'a>(template: &str, args: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let mut text = template.to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+// =================== 文案目录 ===================
+
+const ZH_CN: &[(&str, &str)] = &[
+    ("tray.tooltip", "快速剪贴板"),
+    ("tray.toggle", "显示/隐藏"),
+    ("tray.settings", "设置"),
+    ("tray.screenshot", "截屏"),
+    ("tray.hotkeys.enable", "启用快捷键"),
+    ("tray.hotkeys.disable", "禁用快捷键"),
+    ("tray.monitor.enable", "启用剪贴板监听"),
+    ("tray.monitor.disable", "禁用剪贴板监听"),
+    ("tray.queue.title", "粘贴队列（{count}）"),
+    ("tray.queue.enabled", "启用粘贴队列"),
+    ("tray.queue.fifo", "按复制顺序粘贴"),
+    ("tray.queue.lifo", "按复制倒序粘贴"),
+    ("tray.queue.merge", "合并到剪贴板"),
+    ("tray.queue.clear", "清空队列"),
+    ("tray.restart", "重启程序"),
+    ("tray.quit", "退出"),
+    ("notification.startup.body", "QuickClipboard 已启动{admin}\n按 {shortcut} 打开剪贴板"),
+    ("notification.startup.admin", "（管理员模式）"),
+    ("notification.paste_queue.failed", "粘贴队列内容失败：{error}"),
+];
+
+const EN_US: &[(&str, &str)] = &[
+    ("tray.tooltip", "QuickClipboard"),
+    ("tray.toggle", "Show/Hide"),
+    ("tray.settings", "Settings"),
+    ("tray.screenshot", "Screenshot"),
+    ("tray.hotkeys.enable", "Enable Hotkeys"),
+    ("tray.hotkeys.disable", "Disable Hotkeys"),
+    ("tray.monitor.enable", "Enable Clipboard Monitoring"),
+    ("tray.monitor.disable", "Disable Clipboard Monitoring"),
+    ("tray.queue.title", "Paste Queue ({count})"),
+    ("tray.queue.enabled", "Enable Paste Queue"),
+    ("tray.queue.fifo", "Paste in Copy Order"),
+    ("tray.queue.lifo", "Paste in Reverse Order"),
+    ("tray.queue.merge", "Merge to Clipboard"),
+    ("tray.queue.clear", "Clear Queue"),
+    ("tray.restart", "Restart"),
+    ("tray.quit", "Quit"),
+    ("notification.startup.body", "QuickClipboard is running{admin}\nPress {shortcut} to open the clipboard"),
+    ("notification.startup.admin", " (administrator)"),
+    ("notification.paste_queue.failed", "Failed to paste from the paste queue: {error}"),
+    // 错误说明（键与 AppError 中的 key 一致，占位符对应错误上下文）
+    // 各错误类别的通用说明，具体的键没有英文文案时使用
+    ("error.database", "A database operation failed"),
+    ("error.not_found", "The requested item was not found"),
+    ("error.invalid_input", "Invalid input"),
+    ("error.permission_denied", "Permission denied"),
+    ("error.io", "A file operation failed"),
+    ("error.image", "An image operation failed"),
+    ("error.translation", "Translation failed"),
+    ("error.settings", "Failed to read or save settings"),
+    ("error.internal", "An internal error occurred"),
+    ("error.database_not_initialized", "The database is not initialized"),
+    ("error.locked", "Data is encrypted and has not been unlocked"),
+    ("error.clipboard.item_not_found", "Clipboard item not found: {id}"),
+    ("error.clipboard.format_not_found", "Item {id} has no {mime_type} representation"),
+    ("error.file.not_found", "File not found: {path}"),
+    ("error.file.target_dir_not_found", "Target directory not found: {path}"),
+    ("error.file.copy_failed", "Failed to copy {failed} file(s)"),
+    ("error.file.no_file_manager", "No usable file manager was found"),
+    ("error.file.too_large", "File is too large: {path}"),
+    ("error.image.not_found", "Image file not found: {image_id}"),
+    ("error.image.unsupported_format", "Unsupported image format"),
+    ("error.translation.empty_text", "The text to translate is empty"),
+    ("error.translation.text_too_long", "The text is longer than the 50KB limit"),
+    ("error.translation.config_incomplete", "AI translation is not fully configured; check the API key, model and target language"),
+    ("error.translation.disabled", "AI translation is disabled"),
+    ("error.translation.cancelled", "Translation was cancelled"),
+    ("error.ai_action.not_found", "Unknown AI action: {id}"),
+    ("error.ai_action.config_incomplete", "AI is not fully configured; check the API key and model"),
+    ("error.ai_action.text_only", "AI actions can only run on text content"),
+    ("error.ai_action.empty_result", "The AI returned an empty result"),
+    ("error.ai_action.cancelled", "The AI action was cancelled"),
+    ("error.translation_memory.not_found", "Translation memory entry not found: {id}"),
+    ("error.translation_memory.empty", "The translation cannot be empty"),
+    ("error.paste_queue.empty", "The paste queue is empty"),
+    ("error.paste_queue.invalid_index", "The paste queue has no item {index} (it has {len})"),
+    ("error.paste_queue.invalid_order", "Unknown paste queue order: {order}"),
+    ("error.paste_queue.no_text", "The paste queue has no text to merge"),
+    ("error.capture_rule.invalid_pattern", "Capture rule {rule} has an invalid {field} pattern: {detail}"),
+    ("error.offline.no_stardict", "No StarDict dictionary was found in {path}"),
+    ("error.offline.invalid_dictionary", "The offline dictionary is damaged or in an unsupported format"),
+    ("error.offline.empty_command", "The local model command is empty"),
+    ("error.offline.model_failed", "The local model failed ({status}): {detail}"),
+    ("error.group.already_exists", "A group named '{name}' already exists"),
+    ("error.settings.file_not_found", "Settings file not found"),
+    ("error.settings.invalid_storage_path", "Not a valid directory: {path}"),
+    ("error.settings.portable_storage", "The storage location cannot be changed in portable mode"),
+    ("error.import.missing_metadata", "The archive has no metadata file"),
+    ("error.import.invalid_metadata", "Failed to parse the archive metadata: {detail}"),
+    ("error.import.key_mismatch", "The imported data uses a different encryption key and cannot be merged; import it in replace mode"),
+    ("error.import.unsupported_version", "The imported data has schema version {version}, newer than the supported {supported}; update QuickClipboard first"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_to_source_language_then_key() {
+        assert_eq!(translate(Language::EnUs, "tray.quit"), "Quit");
+        assert_eq!(translate(Language::ZhCn, "tray.quit"), "退出");
+        assert_eq!(
+            translate(Language::EnUs, "error.clipboard.item_not_found"),
+            "Clipboard item not found: {id}"
+        );
+        assert_eq!(translate(Language::EnUs, "tray.missing"), "tray.missing");
+    }
+
+    #[test]
+    fn falls_back_to_error_code_message() {
+        let context = BTreeMap::from([("id".to_string(), "42".to_string())]);
+        let localize = |language, key, code| localize_error_in(language, key, code, "原说明", &context);
+
+        assert_eq!(
+            localize(Language::EnUs, "error.clipboard.item_not_found", "not_found"),
+            "Clipboard item not found: 42"
+        );
+        assert_eq!(
+            localize(Language::EnUs, "error.database.missing_key", "database"),
+            "A database operation failed: 原说明"
+        );
+        assert_eq!(
+            localize_error_in(Language::EnUs, "error.database.missing_key", "database", "", &context),
+            "A database operation failed"
+        );
+        assert_eq!(localize(Language::EnUs, "error.unknown", "unknown"), "原说明");
+        assert_eq!(localize(Language::ZhCn, "error.database.missing_key", "database"), "原说明");
+    }
+
+    #[test]
+    fn every_error_code_has_a_generic_message() {
+        use crate::error::ErrorCode;
+        for code in [
+            ErrorCode::DatabaseNotInitialized,
+            ErrorCode::Database,
+            ErrorCode::NotFound,
+            ErrorCode::InvalidInput,
+            ErrorCode::PermissionDenied,
+            ErrorCode::Io,
+            ErrorCode::Image,
+            ErrorCode::Translation,
+            ErrorCode::Settings,
+            ErrorCode::Locked,
+            ErrorCode::Internal,
+        ] {
+            let key = format!("error.{}", code.as_str());
+            assert!(lookup(Language::EnUs, &key).is_some(), "{} 缺少英文文案", key);
+        }
+    }
+
+    #[test]
+    fn parses_language_codes() {
+        assert_eq!(Language::from_code("en-US"), Language::EnUs);
+        assert_eq!(Language::from_code("en"), Language::EnUs);
+        assert_eq!(Language::from_code("zh-CN"), Language::ZhCn);
+        assert_eq!(Language::from_code(""), Language::ZhCn);
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        let text = interpolate(
+            translate(Language::EnUs, "notification.startup.body"),
+            [("admin", ""), ("shortcut", "Win+V")].into_iter(),
+        );
+        assert_eq!(text, "QuickClipboard is running\nPress Win+V to open the clipboard");
+    }
+
+    #[test]
+    fn every_translation_key_exists_in_source_or_is_an_error() {
+        for (key, _) in EN_US {
+            assert!(
+                key.starts_with("error.") || lookup(Language::ZhCn, key).is_some(),
+                "{} 缺少中文文案",
+                key
+            );
+        }
+    }
+}
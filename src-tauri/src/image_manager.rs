@@ -4,6 +4,8 @@ use std::fs;
 use std::path::PathBuf;
 use image::{ImageEncoder, codecs::png::PngEncoder};
 
+use crate::error::{AppError, AppResult};
+
 // 图片存储配置
 const IMAGES_DIR: &str = "clipboard_images";
 const MAX_IMAGE_DATA_COUNT: i64 = 50;
@@ -13,12 +15,12 @@ pub struct ImageManager {
 }
 
 impl ImageManager {
-    pub fn new() -> Result<Self, String> {
+    pub fn new() -> AppResult<Self> {
         let app_data_dir = get_app_data_dir()?;
         let images_dir = app_data_dir.join(IMAGES_DIR);
 
         fs::create_dir_all(&images_dir)
-            .map_err(|e| format!("创建图片目录失败: {}", e))?;
+            .map_err(|e| AppError::from_io("创建图片目录失败", &e))?;
 
         // 清理废弃的缩略图目录
         let thumbnails_dir = images_dir.join("thumbnails");
@@ -36,7 +38,7 @@ impl ImageManager {
     }
 
     // 从Windows剪贴板原始数据保存图片
-    pub fn save_image_from_raw_data(&self, width: u32, height: u32, dib_data: Vec<u8>, png_data: Vec<u8>) -> Result<String, String> {
+    pub fn save_image_from_raw_data(&self, width: u32, height: u32, dib_data: Vec<u8>, png_data: Vec<u8>) -> AppResult<String> {
         let image_id = self.calculate_image_id(&png_data);
         let png_path = self.images_dir.join(format!("{}.png", image_id));
 
@@ -44,8 +46,7 @@ impl ImageManager {
             return Ok(image_id);
         }
 
-        fs::write(&png_path, &png_data)
-            .map_err(|e| format!("写入PNG文件失败: {}", e))?;
+        crate::encryption::write_image_file(&png_path, &png_data)?;
 
         save_image_data(image_id.clone(), width, height, dib_data, png_data);
 
@@ -53,7 +54,7 @@ impl ImageManager {
     }
 
     // 从RGBA数据保存图片
-    pub fn save_image_from_rgba_sync(&self, width: usize, height: usize, rgba_data: &[u8]) -> Result<String, String> {
+    pub fn save_image_from_rgba_sync(&self, width: usize, height: usize, rgba_data: &[u8]) -> AppResult<String> {
         let image_id = self.calculate_image_id(rgba_data);
         let png_path = self.images_dir.join(format!("{}.png", image_id));
 
@@ -74,11 +75,10 @@ impl ImageManager {
                 width as u32,
                 height as u32,
                 image::ExtendedColorType::Rgba8,
-            ).map_err(|e| format!("编码PNG数据失败: {}", e))?;
+            ).map_err(|e| AppError::image(format!("编码PNG数据失败: {}", e)))?;
         }
 
-        fs::write(&png_path, &png_bytes)
-            .map_err(|e| format!("写入PNG文件失败: {}", e))?;
+        crate::encryption::write_image_file(&png_path, &png_bytes)?;
 
         let image_id_clone = image_id.clone();
         let rgba_data_clone = rgba_data.to_vec();
@@ -95,7 +95,7 @@ impl ImageManager {
     }
 
     // 从data URL保存图片
-    pub fn save_image(&self, data_url: &str) -> Result<String, String> {
+    pub fn save_image(&self, data_url: &str) -> AppResult<String> {
         let image_data = self.parse_data_url(data_url)?;
         let image_id = self.calculate_image_id(&image_data);
         let file_path = self.images_dir.join(format!("{}.png", image_id));
@@ -105,10 +105,12 @@ impl ImageManager {
         }
 
         let img = image::load_from_memory(&image_data)
-            .map_err(|e| format!("解析图片失败: {}", e))?;
+            .map_err(|e| AppError::image(format!("解析图片失败: {}", e)))?;
         
-        img.save_with_format(&file_path, image::ImageFormat::Png)
-            .map_err(|e| format!("保存图片失败: {}", e))?;
+        let mut png_bytes: Vec<u8> = Vec::new();
+        img.write_to(&mut std::io::Cursor::new(&mut png_bytes), image::ImageFormat::Png)
+            .map_err(|e| AppError::image(format!("保存图片失败: {}", e)))?;
+        crate::encryption::write_image_file(&file_path, &png_bytes)?;
 
         let rgba_img = img.to_rgba8();
         let (width, height) = rgba_img.dimensions();
@@ -131,33 +133,97 @@ impl ImageManager {
         hash[..16].to_string()
     }
 
-    // 获取图片文件路径
-    pub fn get_image_file_path(&self, image_id: &str) -> Result<String, String> {
+    // 获取图片文件路径（启用加密时文件内容为密文，读取内容请使用 read_image_png）
+    pub fn get_image_file_path(&self, image_id: &str) -> AppResult<String> {
         let file_path = self.images_dir.join(format!("{}.png", image_id));
         if !file_path.exists() {
-            return Err(format!("图片文件不存在: {}", image_id));
+            return Err(image_not_found(image_id));
         }
         Ok(file_path.to_string_lossy().to_string())
     }
 
+    // 获取可以交给其他程序读取的图片文件路径，文件已加密时返回 None
+    pub fn get_plain_image_file_path(&self, image_id: &str) -> AppResult<Option<String>> {
+        let file_path = self.get_image_file_path(image_id)?;
+        if crate::encryption::is_encrypted_file(std::path::Path::new(&file_path)) {
+            Ok(None)
+        } else {
+            Ok(Some(file_path))
+        }
+    }
+
+    // 图片存储目录中的文件对应的图片ID
+    pub fn image_id_for_path(&self, path: &str) -> Option<String> {
+        let path = std::path::Path::new(path);
+        if path.parent()? != self.images_dir.as_path() {
+            return None;
+        }
+        let image_id = path.file_stem()?.to_str()?;
+        is_valid_image_id(image_id).then(|| image_id.to_string())
+    }
+
+    // 获取图片文件大小（字节），文件不存在时返回0
+    pub fn get_image_file_size(&self, image_id: &str) -> u64 {
+        let file_path = self.images_dir.join(format!("{}.png", image_id));
+        fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0)
+    }
+
+    // 获取图片目录中所有PNG文件的总大小（字节）
+    pub fn get_total_images_size(&self) -> u64 {
+        let entries = match fs::read_dir(&self.images_dir) {
+            Ok(entries) => entries,
+            Err(_) => return 0,
+        };
+
+        entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().map_or(false, |ext| ext == "png"))
+            .filter_map(|path| fs::metadata(&path).ok())
+            .map(|m| m.len())
+            .sum()
+    }
+
     // 获取图片data URL（用于粘贴）
-    pub fn get_image_data_url(&self, image_id: &str) -> Result<String, String> {
+    pub fn get_image_data_url(&self, image_id: &str) -> AppResult<String> {
         let file_path = self.images_dir.join(format!("{}.png", image_id));
         if !file_path.exists() {
-            return Err(format!("图片文件不存在: {}", image_id));
+            return Err(image_not_found(image_id));
         }
 
-        let image_data = fs::read(&file_path)
-            .map_err(|e| format!("读取图片文件失败: {}", e))?;
+        let image_data = crate::encryption::read_image_file(&file_path)?;
         let base64_string = b64_engine::STANDARD.encode(&image_data);
         Ok(format!("data:image/png;base64,{}", base64_string))
     }
 
+    // 读取图片PNG字节（自动解密）
+    pub fn read_image_png(&self, image_id: &str) -> AppResult<Vec<u8>> {
+        let file_path = self.images_dir.join(format!("{}.png", image_id));
+        if !file_path.exists() {
+            return Err(image_not_found(image_id));
+        }
+        Ok(crate::encryption::read_image_file(&file_path)?)
+    }
+
+    // 按已知ID导入图片（用于同步其他设备的图片，已存在时跳过）
+    pub fn import_image_png(&self, image_id: &str, png_data: &[u8]) -> AppResult<()> {
+        let file_path = self.images_dir.join(format!("{}.png", image_id));
+        if file_path.exists() {
+            return Ok(());
+        }
+        crate::encryption::write_image_file(&file_path, png_data)?;
+        Ok(())
+    }
+
+    pub fn has_image(&self, image_id: &str) -> bool {
+        self.images_dir.join(format!("{}.png", image_id)).exists()
+    }
+
     // 获取BGRA数据和PNG字节（优先从数据库读取）
-    pub fn get_image_bgra_and_png(&self, image_id: &str) -> Result<(Vec<u8>, Vec<u8>, u32, u32), String> {
+    pub fn get_image_bgra_and_png(&self, image_id: &str) -> AppResult<(Vec<u8>, Vec<u8>, u32, u32)> {
         let db_result = crate::database::with_connection(|conn| {
             conn.query_row(
-                "SELECT bgra_data, png_data, width, height FROM image_data WHERE image_id = ?1",
+                "SELECT qc_decrypt_blob(bgra_data), qc_decrypt_blob(png_data), width, height FROM image_data WHERE image_id = ?1",
                 rusqlite::params![image_id],
                 |row| {
                     Ok((
@@ -180,14 +246,13 @@ impl ImageManager {
 
         let file_path = self.images_dir.join(format!("{}.png", image_id));
         if !file_path.exists() {
-            return Err(format!("图片文件不存在: {}", image_id));
+            return Err(image_not_found(image_id));
         }
 
-        let png_bytes = fs::read(&file_path)
-            .map_err(|e| format!("读取图片文件失败: {}", e))?;
+        let png_bytes = crate::encryption::read_image_file(&file_path)?;
 
         let img = image::load_from_memory(&png_bytes)
-            .map_err(|e| format!("解析PNG失败: {}", e))?
+            .map_err(|e| AppError::image(format!("解析PNG失败: {}", e)))?
             .to_rgba8();
         
         let (width, height) = img.dimensions();
@@ -198,7 +263,7 @@ impl ImageManager {
         Ok((bgra, png_bytes, width, height))
     }
 
-    pub fn delete_image(&self, image_id: &str) -> Result<(), String> {
+    pub fn delete_image(&self, image_id: &str) -> AppResult<()> {
         let _ = crate::database::with_connection(|conn| {
             conn.execute(
                 "DELETE FROM image_data WHERE image_id = ?1",
@@ -209,17 +274,17 @@ impl ImageManager {
         let file_path = self.images_dir.join(format!("{}.png", image_id));
         if file_path.exists() {
             fs::remove_file(&file_path)
-                .map_err(|e| format!("删除图片失败: {}", e))?;
+                .map_err(|e| AppError::from_io("删除图片失败", &e).with_context("image_id", image_id))?;
         }
         Ok(())
     }
 
-    pub fn cleanup_unused_images(&self, used_image_ids: &[String]) -> Result<(), String> {
+    pub fn cleanup_unused_images(&self, used_image_ids: &[String]) -> AppResult<()> {
         let entries = fs::read_dir(&self.images_dir)
-            .map_err(|e| format!("读取图片目录失败: {}", e))?;
+            .map_err(|e| AppError::from_io("读取图片目录失败", &e))?;
 
         for entry in entries {
-            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+            let entry = entry.map_err(|e| AppError::from_io("读取目录项失败", &e))?;
             let path = entry.path();
 
             if path.is_file() && path.extension().map_or(false, |ext| ext == "png") {
@@ -254,36 +319,75 @@ impl ImageManager {
         Ok(())
     }
 
-    fn parse_data_url(&self, data_url: &str) -> Result<Vec<u8>, String> {
+    fn parse_data_url(&self, data_url: &str) -> AppResult<Vec<u8>> {
         if !data_url.starts_with("data:image/") {
-            return Err("不是有效的图片data URL".to_string());
+            return Err(AppError::invalid_input("不是有效的图片data URL"));
         }
         let comma_pos = data_url.find(',')
-            .ok_or_else(|| "无效的data URL格式".to_string())?;
+            .ok_or_else(|| AppError::invalid_input("无效的data URL格式"))?;
         b64_engine::STANDARD.decode(&data_url[(comma_pos + 1)..])
-            .map_err(|e| format!("Base64解码失败: {}", e))
+            .map_err(|e| AppError::invalid_input(format!("Base64解码失败: {}", e)))
     }
 }
 
-fn get_app_data_dir() -> Result<PathBuf, String> {
+fn get_app_data_dir() -> AppResult<PathBuf> {
     crate::settings::get_data_directory()
 }
 
+fn image_not_found(image_id: &str) -> AppError {
+    AppError::not_found(format!("图片文件不存在: {}", image_id))
+        .with_key("error.image.not_found")
+        .with_context("image_id", image_id)
+}
+
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
 
-static IMAGE_MANAGER: Lazy<Result<Mutex<ImageManager>, String>> =
+static IMAGE_MANAGER: Lazy<AppResult<Mutex<ImageManager>>> =
     Lazy::new(|| ImageManager::new().map(Mutex::new));
 
-pub fn get_image_manager() -> Result<&'static Mutex<ImageManager>, String> {
+pub fn get_image_manager() -> AppResult<&'static Mutex<ImageManager>> {
     IMAGE_MANAGER.as_ref().map_err(|e| e.clone())
 }
 
+// 图片ID由内容哈希生成，只包含字母和数字
+fn is_valid_image_id(image_id: &str) -> bool {
+    !image_id.is_empty() && image_id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+// qcimage 协议：前端通过 convertFileSrc(imageId, 'qcimage') 加载图片
+// 图片在内存中解密后直接返回，加密的图片不会以明文写入磁盘
+pub fn handle_image_protocol(
+    request: &tauri::http::Request<Vec<u8>>,
+) -> tauri::http::Response<Vec<u8>> {
+    let image_id = request.uri().path().trim_start_matches('/');
+    let png = if is_valid_image_id(image_id) {
+        get_image_manager().and_then(|manager| {
+            manager
+                .lock()
+                .map_err(|e| AppError::image(format!("获取图片管理器锁失败: {}", e)))?
+                .read_image_png(image_id)
+        })
+    } else {
+        Err(image_not_found(image_id))
+    };
+
+    let builder = tauri::http::Response::builder().header("Cache-Control", "no-store");
+    let response = match png {
+        Ok(png) => builder.header("Content-Type", "image/png").body(png),
+        Err(e) => {
+            println!("加载图片失败: {}", e);
+            builder.status(404).body(Vec::new())
+        }
+    };
+    response.unwrap_or_else(|_| tauri::http::Response::new(Vec::new()))
+}
+
 fn save_image_data(image_id: String, width: u32, height: u32, bgra_data: Vec<u8>, png_data: Vec<u8>) {
     std::thread::spawn(move || {
         let result = crate::database::with_connection(|conn| {
             conn.execute(
-                "INSERT OR REPLACE INTO image_data (image_id, width, height, bgra_data, png_data, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                "INSERT OR REPLACE INTO image_data (image_id, width, height, bgra_data, png_data, created_at) VALUES (?1, ?2, ?3, qc_encrypt_blob(?4), qc_encrypt_blob(?5), ?6)",
                 rusqlite::params![
                     &image_id,
                     width as i64,
@@ -378,3 +482,17 @@ fn rgba_to_bgra(rgba: &[u8]) -> Vec<u8> {
     
     bgra
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_only_hash_like_image_ids() {
+        assert!(is_valid_image_id("3f2a9c0d11be4e7a"));
+        assert!(!is_valid_image_id(""));
+        assert!(!is_valid_image_id("../encryption"));
+        assert!(!is_valid_image_id("a/b"));
+        assert!(!is_valid_image_id("a.png"));
+    }
+}
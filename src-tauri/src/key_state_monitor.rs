@@ -446,21 +446,14 @@ fn handle_number_shortcuts_change(last_state: &KeyState, current_state: &KeyStat
         return;
     }
 
-    // 获取当前配置的修饰键
-    let modifier = get_number_shortcuts_modifier();
-    
-    // 检查修饰键是否匹配
-    let modifier_matches = match modifier.as_str() {
-        "Ctrl" => current_state.ctrl && !current_state.shift && !current_state.alt && !current_state.win,
-        "Alt" => !current_state.ctrl && !current_state.shift && current_state.alt && !current_state.win,
-        "Shift" => !current_state.ctrl && current_state.shift && !current_state.alt && !current_state.win,
-        "Ctrl+Shift" => current_state.ctrl && current_state.shift && !current_state.alt && !current_state.win,
-        "Ctrl+Alt" => current_state.ctrl && !current_state.shift && current_state.alt && !current_state.win,
-        "Alt+Shift" => !current_state.ctrl && current_state.shift && current_state.alt && !current_state.win,
-        _ => current_state.ctrl && !current_state.shift && !current_state.alt && !current_state.win, // 默认为Ctrl
-    };
-
-    if !modifier_matches {
+    // 获取当前配置的修饰键，置顶修饰键与粘贴修饰键相同时只粘贴
+    let paste_matches = modifier_matches(&get_number_shortcuts_modifier(), current_state);
+    let pin_modifier = get_number_shortcuts_pin_modifier();
+    let pin_matches = !paste_matches
+        && !pin_modifier.is_empty()
+        && modifier_matches(&pin_modifier, current_state);
+
+    if !paste_matches && !pin_matches {
         return;
     }
 
@@ -486,11 +479,51 @@ fn handle_number_shortcuts_change(last_state: &KeyState, current_state: &KeyStat
 
     for (last_pressed, current_pressed, index) in numbers {
         if !last_pressed && current_pressed {
-            handle_number_shortcut_paste(index);
+            if pin_matches {
+                handle_number_shortcut_pin(index);
+            } else {
+                handle_number_shortcut_paste(index);
+            }
         }
     }
 }
 
+// 检查当前按下的修饰键是否与配置完全一致
+fn modifier_matches(modifier: &str, state: &KeyState) -> bool {
+    match modifier {
+        "Ctrl" => state.ctrl && !state.shift && !state.alt && !state.win,
+        "Alt" => !state.ctrl && !state.shift && state.alt && !state.win,
+        "Shift" => !state.ctrl && state.shift && !state.alt && !state.win,
+        "Ctrl+Shift" => state.ctrl && state.shift && !state.alt && !state.win,
+        "Ctrl+Alt" => state.ctrl && !state.shift && state.alt && !state.win,
+        "Alt+Shift" => !state.ctrl && state.shift && state.alt && !state.win,
+        _ => state.ctrl && !state.shift && !state.alt && !state.win, // 默认为Ctrl
+    }
+}
+
+// 处理数字快捷键置顶：切换对应序号历史项目的置顶状态
+// 主窗口句柄只在 Windows 上保存
+#[cfg_attr(not(windows), allow(unused_variables))]
+fn handle_number_shortcut_pin(index: usize) {
+    #[cfg(windows)]
+    if let Some(window) = crate::mouse_hook::MAIN_WINDOW_HANDLE.get().cloned() {
+        std::thread::spawn(move || {
+            let clipboard_id = crate::database::get_clipboard_history(None)
+                .ok()
+                .and_then(|items| items.get(index).map(|item| item.id));
+
+            if let Some(id) = clipboard_id {
+                let app_handle = window.app_handle().clone();
+                if let Err(e) =
+                    crate::services::clipboard_service::ClipboardService::toggle_pinned(&app_handle, id)
+                {
+                    println!("切换剪贴板项目置顶状态失败: {}", e);
+                }
+            }
+        });
+    }
+}
+
 // 处理数字快捷键粘贴
 fn handle_number_shortcut_paste(index: usize) {
     use crate::mouse_hook::MAIN_WINDOW_HANDLE;
@@ -535,6 +568,7 @@ fn handle_number_shortcut_paste(index: usize) {
                     let params = crate::services::paste_service::PasteContentParams {
                         clipboard_id: Some(id),
                         quick_text_id: None,
+                        mime_type: None,
                     };
                     let _ = crate::commands::paste_content(params, window_clone).await;
                 });
@@ -574,6 +608,7 @@ fn handle_ai_translation_cancel_change(last_state: &KeyState, current_state: &Ke
             std::thread::spawn(move || {
                 let _ = tauri::async_runtime::block_on(async {
                     let _ = crate::commands::cancel_translation();
+                    let _ = crate::commands::cancel_ai_action(None);
                     let _ = window_clone.emit("ai-translation-cancelled", ());
                 });
             });
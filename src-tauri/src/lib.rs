@@ -1,30 +1,53 @@
-#![recursion_limit = "256"]
+#![recursion_limit = "512"]
 // =================== 模块引入 ===================
 mod admin_privileges;
+mod ai_actions;
 mod ai_config;
+mod ai_provider;
+mod ai_request;
 mod ai_translator;
+mod api_server;
 mod app_filter;
 mod audio_scanner;
+mod capture_rules;
+pub mod cli;
 mod clipboard_content;
 mod clipboard_history;
+mod clipboard_representations;
 mod clipboard_monitor;
 mod commands;
+mod content_classifier;
 mod data_migration;
 mod data_manager;
 mod database;
 mod database_image_utils;
+mod database_migration;
+mod database_search;
+mod encryption;
+mod error;
 mod file_handler;
 mod global_state;
 mod groups;
+mod i18n;
 mod image_manager;
 mod key_state_monitor;
+#[cfg(target_os = "linux")]
+mod linux_clipboard;
+#[cfg(target_os = "linux")]
+mod linux_window;
 mod mouse_hook;
 mod mouse_utils;
+mod offline_translator;
+mod paste_queue;
 mod paste_utils;
 mod preview_window;
 mod pin_image_window;
 mod plugins;
 mod quick_texts;
+mod retention;
+mod sensitive_detector;
+mod snippet_template;
+mod sync;
 
 // 截屏功能模块
 mod screenshot;
@@ -35,6 +58,8 @@ mod settings;
 mod shortcut_interceptor;
 mod sound_manager;
 mod text_input_simulator;
+mod transforms;
+mod translation_memory;
 mod tray;
 mod updater;
 mod utils;
@@ -83,9 +108,9 @@ fn send_startup_notification_internal(app_handle: &tauri::AppHandle) -> Result<(
 
     let admin_status = admin_privileges::get_admin_status();
     let status_text = if admin_status.is_admin {
-        "（管理员模式）"
+        crate::i18n::t("notification.startup.admin")
     } else {
-        ""
+        String::new()
     };
 
     // 获取当前设置的快捷键
@@ -96,9 +121,9 @@ fn send_startup_notification_internal(app_handle: &tauri::AppHandle) -> Result<(
         app_settings.toggle_shortcut.clone()
     };
 
-    let notification_body = format!(
-        "QuickClipboard 已启动{}\n按 {} 打开剪贴板",
-        status_text, shortcut_key
+    let notification_body = crate::i18n::t_with(
+        "notification.startup.body",
+        &[("admin", &status_text), ("shortcut", &shortcut_key)],
     );
 
     match app_handle
@@ -132,6 +157,10 @@ pub fn run() {
                 crate::window_management::show_webview_window(window);
             }
         }))
+        // 按图片ID返回解密后的图片（加密的图片不写出明文文件）
+        .register_uri_scheme_protocol("qcimage", |_ctx, request| {
+            image_manager::handle_image_protocol(&request)
+        })
         .on_menu_event(|app, event| match event.id().as_ref() {
             "toggle" => {
                 let _ = commands::toggle_window_visibility(app.app_handle().clone());
@@ -161,13 +190,13 @@ pub fn run() {
                         crate::shortcut_interceptor::disable_shortcut_interception();
                         crate::key_state_monitor::stop_keyboard_polling_system();
                         if let Some(item) = crate::tray::TOGGLE_HOTKEYS_ITEM.get() {
-                            let _ = item.set_text("启用快捷键");
+                            let _ = item.set_text(crate::tray::hotkeys_label(false));
                         }
                     } else {
                         crate::shortcut_interceptor::enable_shortcut_interception();
                         crate::key_state_monitor::start_keyboard_polling_system();
                         if let Some(item) = crate::tray::TOGGLE_HOTKEYS_ITEM.get() {
-                            let _ = item.set_text("禁用快捷键");
+                            let _ = item.set_text(crate::tray::hotkeys_label(true));
                         }
                     }
                 }
@@ -195,9 +224,26 @@ pub fn run() {
                     );
                 }
                 if let Some(item) = crate::tray::TOGGLE_MONITOR_ITEM.get() {
-                    let _ = item.set_text(if new_enabled { "禁用剪贴板监听" } else { "启用剪贴板监听" });
+                    let _ = item.set_text(crate::tray::monitor_label(new_enabled));
+                }
+            }
+            "paste-queue-toggle" => {
+                crate::paste_queue::set_enabled(app, !crate::paste_queue::is_enabled());
+            }
+            "paste-queue-fifo" => {
+                let _ = crate::paste_queue::set_order(app, crate::paste_queue::QueueOrder::Fifo);
+            }
+            "paste-queue-lifo" => {
+                let _ = crate::paste_queue::set_order(app, crate::paste_queue::QueueOrder::Lifo);
+            }
+            "paste-queue-merge" => {
+                if let Err(e) = crate::paste_queue::merge(app, None) {
+                    eprintln!("合并粘贴队列失败: {}", e);
                 }
             }
+            "paste-queue-clear" => {
+                crate::paste_queue::clear(app);
+            }
             "restart" => {
                 let app_handle = app.app_handle().clone();
                 tauri::async_runtime::spawn(async move {
@@ -205,18 +251,44 @@ pub fn run() {
                 });
             }
             "quit" => {
+                // 清理命令行实例信息
+                cli::protocol::remove_instance_info();
                 app.exit(0);
             }
             _ => {}
         })
         .setup(|app| {
+            // 读取数据加密状态（需在初始化数据库之前）
+            encryption::init();
+
             // 初始化数据库
             if let Err(e) = database::initialize_database() {
                 println!("数据库初始化失败: {}", e);
+
+                // 数据库由更新版本的程序写入时，提示用户并退出，避免破坏数据
+                if let Some(version) = database_migration::newer_schema_version() {
+                    use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+                    let app_handle = app.handle().clone();
+                    app.dialog()
+                        .message(format!(
+                            "数据库结构版本 ({}) 高于当前程序支持的版本 ({})。\n请升级 QuickClipboard 到最新版本后再启动。",
+                            version,
+                            database_migration::current_schema_version()
+                        ))
+                        .title("QuickClipboard")
+                        .kind(MessageDialogKind::Error)
+                        .show(move |_| {
+                            app_handle.exit(1);
+                        });
+                    return Ok(());
+                }
             }
 
             // 首先尝试加载历史记录
             clipboard_history::load_history();
+            // 为尚未分类的历史记录分类
+            content_classifier::classify_pending_in_background();
             // 加载常用文本
             quick_texts::load_quick_texts();
             // 初始化分组系统
@@ -312,6 +384,8 @@ pub fn run() {
             global_state::set_number_shortcuts_enabled(app_settings.number_shortcuts);
             #[cfg(windows)]
             global_state::update_number_shortcuts_modifier(&app_settings.number_shortcuts_modifier);
+            #[cfg(windows)]
+            global_state::update_number_shortcuts_pin_modifier(&app_settings.number_shortcuts_pin_modifier);
 
             // 应用预览窗口快捷键设置
             #[cfg(windows)]
@@ -351,6 +425,26 @@ pub fn run() {
             // 启动剪贴板监听器
             clipboard_monitor::start_clipboard_monitor(app.handle().clone());
 
+            // 启动历史记录保留策略后台清理
+            retention::start_retention_sweeper(app.handle().clone());
+
+            // 启动命令行服务
+            cli::server::start_cli_server(app.handle().clone());
+
+            // 按设置启动本地 HTTP 接口
+            api_server::apply_settings(app.handle(), &settings::get_global_settings());
+
+            // 按设置启动后台同步
+            sync::apply_settings(app.handle(), &settings::get_global_settings());
+
+            // 启用数据加密时提示输入口令解锁
+            if encryption::is_enabled() {
+                let app_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    encryption::prompt_unlock(app_handle).await;
+                });
+            }
+
             // 注册托盘图标和事件
             tray::setup_tray(&app.app_handle())?;
 
@@ -432,6 +526,7 @@ pub fn run() {
             disable_mouse_monitoring_command,
             set_startup_launch,
             set_history_limit,
+            run_retention_sweep,
             get_groups,
             add_group,
             update_group,
@@ -459,7 +554,33 @@ pub fn run() {
             set_preview_index,
             cancel_preview,
             delete_clipboard_item,
+            pin_clipboard_item,
+            unpin_clipboard_item,
+            toggle_clipboard_item_pin,
+            toggle_preview_item_pin,
             update_clipboard_item,
+            get_clipboard_item_formats,
+            get_clipboard_item_tags,
+            get_clipboard_tag_counts,
+            get_clipboard_history_by_tag,
+            get_clipboard_item_actions,
+            search_clipboard,
+            search_favorites,
+            rebuild_search_index,
+            get_builtin_sensitive_detectors,
+            test_sensitive_detection,
+            test_capture_rules,
+            preview_snippet_template,
+            get_encryption_status,
+            unlock_encryption,
+            lock_encryption,
+            enable_encryption,
+            disable_encryption,
+            change_encryption_passphrase,
+            get_api_server_status,
+            regenerate_api_server_token,
+            sync_now,
+            get_sync_status,
             emit_clipboard_updated,
             emit_quick_texts_updated,
             clear_clipboard_history,
@@ -483,8 +604,16 @@ pub fn run() {
             commands::is_currently_pasting,
             commands::check_ai_translation_config,
             commands::get_available_ai_models,
+            commands::get_ai_token_usage,
             commands::test_ai_config,
             commands::cancel_translation,
+            commands::run_ai_action,
+            commands::cancel_ai_action,
+            commands::list_translation_memory,
+            commands::lookup_translation_memory,
+            commands::update_translation_memory_entry,
+            commands::delete_translation_memory_entry,
+            commands::clear_translation_memory,
             commands::enable_ai_translation_cancel_shortcut,
             commands::disable_ai_translation_cancel_shortcut,
             commands::copy_files_to_directory,
@@ -494,6 +623,17 @@ pub fn run() {
             commands::move_clipboard_item_to_front,
             commands::move_clipboard_item,
             commands::paste_content,
+            commands::get_paste_queue,
+            commands::set_paste_queue_enabled,
+            commands::set_paste_queue_order,
+            commands::paste_next_from_queue,
+            commands::remove_paste_queue_item,
+            commands::clear_paste_queue,
+            commands::merge_paste_queue,
+            commands::get_available_transforms,
+            commands::transform_content,
+            commands::paste_transformed_content,
+            commands::save_transformed_content,
             commands::open_file_location,
             commands::open_file_with_default_program,
             
@@ -0,0 +1,172 @@
+// Linux 剪贴板数据格式的解析与生成
+
+// HTML 片段
+pub const MIME_HTML: &str = "text/html";
+// 文件列表（RFC 2483，每行一个 URI）
+pub const MIME_URI_LIST: &str = "text/uri-list";
+pub const MIME_PNG: &str = "image/png";
+// GNOME/Nautilus 等文件管理器使用的文件列表，首行为 copy 或 cut
+pub const MIME_GNOME_COPIED_FILES: &str = "x-special/gnome-copied-files";
+
+// RTF 富文本（LibreOffice 等使用 text/rtf，部分程序使用 application/rtf）
+pub const RTF_MIME_TYPES: &[&str] = &["text/rtf", "application/rtf"];
+
+// 浏览器附带的来源网页地址（Chromium 只有地址，Firefox 的 x-moz-url-priv 首行为地址）
+pub const SOURCE_URL_MIME_TYPES: &[&str] = &["chromium/x-source-url", "text/x-moz-url-priv"];
+
+// 纯文本目标，按优先级排列（X11 旧程序只认 UTF8_STRING/STRING/TEXT）
+pub const TEXT_MIME_TYPES: &[&str] = &[
+    "text/plain;charset=utf-8",
+    "UTF8_STRING",
+    "text/plain",
+    "STRING",
+    "TEXT",
+];
+
+// 解码文本数据：部分浏览器以带 BOM 的 UTF-16 提供 text/html
+pub fn decode_text(data: &[u8]) -> String {
+    let text = if let Some(rest) = data.strip_prefix(&[0xFF, 0xFE]) {
+        decode_utf16(rest, u16::from_le_bytes)
+    } else if let Some(rest) = data.strip_prefix(&[0xFE, 0xFF]) {
+        decode_utf16(rest, u16::from_be_bytes)
+    } else {
+        let data = data.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(data);
+        String::from_utf8_lossy(data).into_owned()
+    };
+    text.trim_end_matches('\0').to_string()
+}
+
+fn decode_utf16(data: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+// 解析来源网页地址，只接受带协议的地址
+pub fn parse_source_url(data: &[u8]) -> Option<String> {
+    decode_text(data)
+        .lines()
+        .next()
+        .map(str::trim)
+        .filter(|url| url.contains("://"))
+        .map(str::to_string)
+}
+
+// 解析 text/uri-list，只保留本地文件路径
+pub fn parse_uri_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(file_uri_to_path)
+        .collect()
+}
+
+// 解析 x-special/gnome-copied-files，返回 (操作, 文件路径)
+pub fn parse_gnome_copied_files(text: &str) -> (String, Vec<String>) {
+    let mut lines = text.lines();
+    let operation = match lines.next().map(|line| line.trim()) {
+        Some("cut") => "cut",
+        _ => "copy",
+    };
+    let paths = lines
+        .map(|line| line.trim())
+        .filter_map(file_uri_to_path)
+        .collect();
+    (operation.to_string(), paths)
+}
+
+pub fn build_uri_list(paths: &[String]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("{}\r\n", path_to_file_uri(path)))
+        .collect()
+}
+
+pub fn build_gnome_copied_files(operation: &str, paths: &[String]) -> String {
+    let mut lines = vec![operation.to_string()];
+    lines.extend(paths.iter().map(|path| path_to_file_uri(path)));
+    lines.join("\n")
+}
+
+// file:///path 或 file://localhost/path 转为本地路径
+fn file_uri_to_path(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = if rest.starts_with('/') {
+        rest
+    } else {
+        let slash = rest.find('/')?;
+        let host = &rest[..slash];
+        if !host.eq_ignore_ascii_case("localhost") {
+            return None;
+        }
+        &rest[slash..]
+    };
+    urlencoding::decode(path).ok().map(|p| p.into_owned())
+}
+
+fn path_to_file_uri(path: &str) -> String {
+    let encoded: Vec<String> = path
+        .split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect();
+    format!("file://{}", encoded.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_list_round_trip() {
+        let paths = vec!["/home/user/My Files/报告.pdf".to_string(), "/tmp/a#b".to_string()];
+        let list = build_uri_list(&paths);
+        assert_eq!(
+            list,
+            "file:///home/user/My%20Files/%E6%8A%A5%E5%91%8A.pdf\r\nfile:///tmp/a%23b\r\n"
+        );
+        assert_eq!(parse_uri_list(&list), paths);
+    }
+
+    #[test]
+    fn uri_list_skips_comments_and_remote_uris() {
+        let list = "# comment\r\nfile://localhost/etc/hosts\r\nhttps://example.com/x\r\nsftp://host/file\r\nfile://otherhost/x\r\n";
+        assert_eq!(parse_uri_list(list), vec!["/etc/hosts".to_string()]);
+    }
+
+    #[test]
+    fn gnome_copied_files() {
+        let paths = vec!["/a b/c".to_string(), "/d".to_string()];
+        let text = build_gnome_copied_files("cut", &paths);
+        assert_eq!(text, "cut\nfile:///a%20b/c\nfile:///d");
+        assert_eq!(parse_gnome_copied_files(&text), ("cut".to_string(), paths));
+        assert_eq!(parse_gnome_copied_files("copy\n").0, "copy");
+    }
+
+    #[test]
+    fn decodes_utf16_html() {
+        let mut data = vec![0xFF, 0xFE];
+        for unit in "<b>你好</b>".encode_utf16() {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+        data.extend_from_slice(&[0, 0]);
+        assert_eq!(decode_text(&data), "<b>你好</b>");
+        assert_eq!(decode_text(b"plain\0"), "plain");
+    }
+
+    #[test]
+    fn parses_source_url() {
+        let mut moz = vec![0xFF, 0xFE];
+        for unit in "https://example.com/页面\nExample".encode_utf16() {
+            moz.extend_from_slice(&unit.to_le_bytes());
+        }
+        assert_eq!(parse_source_url(&moz).as_deref(), Some("https://example.com/页面"));
+        assert_eq!(
+            parse_source_url(b"https://example.com/a?b=1").as_deref(),
+            Some("https://example.com/a?b=1")
+        );
+        assert_eq!(parse_source_url(b"about"), None);
+        assert_eq!(parse_source_url(b""), None);
+    }
+}
@@ -0,0 +1,241 @@
+// Linux 剪贴板后端
+//
+// 直接读写 X11 选区或 Wayland 剪贴板（wlr-data-control 协议），支持 text/html、text/uri-list、
+// image/png 和 x-special/gnome-copied-files，并在选区变化时收到通知（X11 使用 XFixes，
+// Wayland 使用 data-control 的 selection 事件），监听器无需轮询。
+// 可选跟踪 PRIMARY 选区（选中即复制），作为独立的来源记录。
+//
+// X11 部分可在 Xvfb 下测试：xvfb-run cargo test linux_clipboard -- --ignored
+
+pub mod formats;
+mod wayland;
+mod x11;
+
+use once_cell::sync::Lazy;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use formats::{
+    MIME_GNOME_COPIED_FILES, MIME_HTML, MIME_PNG, MIME_URI_LIST, TEXT_MIME_TYPES,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Selection {
+    // 常规剪贴板（Ctrl+C / Ctrl+V）
+    Clipboard,
+    // 主选区（鼠标选中文本，中键粘贴）
+    Primary,
+}
+
+impl Selection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Selection::Clipboard => "clipboard",
+            Selection::Primary => "primary",
+        }
+    }
+}
+
+// 一次写入的多种格式（MIME 类型, 数据）
+pub type MimeContents = Vec<(String, Vec<u8>)>;
+
+trait Backend: Send + Sync {
+    fn name(&self) -> &'static str;
+    // 当前选区提供的格式
+    fn mime_types(&self, selection: Selection) -> Result<Vec<String>, String>;
+    // 读取指定格式（选区为空或不提供该格式时返回 None）
+    fn read(&self, selection: Selection, mime_type: &str) -> Result<Option<Vec<u8>>, String>;
+    // 接管选区并提供给定的格式
+    fn write(&self, selection: Selection, contents: MimeContents) -> Result<(), String>;
+    // 阻塞等待选区变化，返回发生变化的选区（超时返回空列表）
+    fn wait_for_change(&self, timeout: Duration) -> Vec<Selection>;
+}
+
+// 合并短时间内的多次通知：每个选区只报告一次，剪贴板在前
+fn collect_changes(receiver: &Receiver<Selection>, timeout: Duration) -> Vec<Selection> {
+    let Ok(first) = receiver.recv_timeout(timeout) else {
+        return Vec::new();
+    };
+    let mut changes = vec![first];
+    while let Ok(next) = receiver.try_recv() {
+        if !changes.contains(&next) {
+            changes.push(next);
+        }
+    }
+    changes.sort_by_key(|selection| *selection != Selection::Clipboard);
+    changes
+}
+
+static BACKEND: Lazy<Option<Box<dyn Backend>>> = Lazy::new(create_backend);
+
+// 优先使用 Wayland，合成器不支持 data-control 时退回 X11（XWayland）
+fn create_backend() -> Option<Box<dyn Backend>> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        match wayland::WaylandBackend::new() {
+            Ok(backend) => return Some(log_backend(Box::new(backend))),
+            Err(e) => println!("Wayland 剪贴板后端不可用: {}", e),
+        }
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        match x11::X11Backend::new() {
+            Ok(backend) => return Some(log_backend(Box::new(backend))),
+            Err(e) => println!("X11 剪贴板后端不可用: {}", e),
+        }
+    }
+    None
+}
+
+fn log_backend(backend: Box<dyn Backend>) -> Box<dyn Backend> {
+    println!("使用 {} 剪贴板后端", backend.name());
+    backend
+}
+
+// 原生后端是否可用（不可用时监听器退回 arboard 轮询）
+pub fn is_available() -> bool {
+    BACKEND.is_some()
+}
+
+// 等待剪贴板或主选区变化，返回发生变化的选区
+pub fn wait_for_change(timeout: Duration) -> Vec<Selection> {
+    match BACKEND.as_ref() {
+        Some(backend) => backend.wait_for_change(timeout),
+        None => {
+            std::thread::sleep(timeout);
+            Vec::new()
+        }
+    }
+}
+
+// 读取第一个可用的格式
+pub fn read_bytes(selection: Selection, mime_types: &[&str]) -> Option<Vec<u8>> {
+    read_first(selection, mime_types).filter(|data| !data.is_empty())
+}
+
+fn read_first(selection: Selection, mime_types: &[&str]) -> Option<Vec<u8>> {
+    let backend = BACKEND.as_ref()?;
+    let available = backend.mime_types(selection).ok()?;
+    let mime_type = mime_types
+        .iter()
+        .find(|mime| available.iter().any(|a| a == *mime))?;
+    match backend.read(selection, mime_type) {
+        Ok(data) => data,
+        Err(e) => {
+            println!("读取剪贴板 {} 失败: {}", mime_type, e);
+            None
+        }
+    }
+}
+
+pub fn read_text(selection: Selection) -> Option<String> {
+    read_first(selection, TEXT_MIME_TYPES)
+        .map(|data| formats::decode_text(&data))
+        .filter(|text| !text.trim().is_empty())
+}
+
+pub fn read_html(selection: Selection) -> Option<String> {
+    read_first(selection, &[MIME_HTML])
+        .map(|data| formats::decode_text(&data))
+        .filter(|html| !html.trim().is_empty())
+}
+
+pub fn read_png(selection: Selection) -> Option<Vec<u8>> {
+    read_first(selection, &[MIME_PNG]).filter(|data| !data.is_empty())
+}
+
+// 读取复制的文件，优先使用文件管理器的格式
+pub fn read_files(selection: Selection) -> Vec<String> {
+    if let Some(data) = read_first(selection, &[MIME_GNOME_COPIED_FILES]) {
+        let (_, paths) = formats::parse_gnome_copied_files(&formats::decode_text(&data));
+        if !paths.is_empty() {
+            return paths;
+        }
+    }
+    read_first(selection, &[MIME_URI_LIST])
+        .map(|data| formats::parse_uri_list(&formats::decode_text(&data)))
+        .unwrap_or_default()
+}
+
+// 一次性写入多种格式
+pub fn write_contents(contents: MimeContents) -> Result<(), String> {
+    if contents.is_empty() {
+        return Err("没有可写入剪贴板的格式".to_string());
+    }
+    write(contents)
+}
+
+fn write(contents: MimeContents) -> Result<(), String> {
+    let backend = BACKEND
+        .as_ref()
+        .ok_or_else(|| "没有可用的 Linux 剪贴板后端".to_string())?;
+    backend.write(Selection::Clipboard, contents)
+}
+
+fn text_contents(text: &str) -> MimeContents {
+    TEXT_MIME_TYPES
+        .iter()
+        .map(|mime| (mime.to_string(), text.as_bytes().to_vec()))
+        .collect()
+}
+
+// 写入纯文本，可同时提供 HTML
+pub fn write_text(text: &str, html: Option<&str>) -> Result<(), String> {
+    let mut contents = Vec::new();
+    if let Some(html) = html {
+        contents.push((MIME_HTML.to_string(), html.as_bytes().to_vec()));
+    }
+    contents.extend(text_contents(text));
+    write(contents)
+}
+
+// 写入文件列表，同时提供路径文本供编辑器粘贴
+pub fn write_files(paths: &[String]) -> Result<(), String> {
+    if paths.is_empty() {
+        return Err("文件列表为空".to_string());
+    }
+    let mut contents = vec![
+        (
+            MIME_GNOME_COPIED_FILES.to_string(),
+            formats::build_gnome_copied_files("copy", paths).into_bytes(),
+        ),
+        (
+            MIME_URI_LIST.to_string(),
+            formats::build_uri_list(paths).into_bytes(),
+        ),
+    ];
+    contents.extend(text_contents(&paths.join("\n")));
+    write(contents)
+}
+
+pub fn write_png(png: &[u8]) -> Result<(), String> {
+    write(vec![(MIME_PNG.to_string(), png.to_vec())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn collects_each_changed_selection_once() {
+        let (sender, receiver) = mpsc::channel();
+        for selection in [
+            Selection::Primary,
+            Selection::Clipboard,
+            Selection::Primary,
+            Selection::Clipboard,
+        ] {
+            sender.send(selection).unwrap();
+        }
+        assert_eq!(
+            collect_changes(&receiver, Duration::from_millis(10)),
+            vec![Selection::Clipboard, Selection::Primary]
+        );
+
+        sender.send(Selection::Primary).unwrap();
+        assert_eq!(
+            collect_changes(&receiver, Duration::from_millis(10)),
+            vec![Selection::Primary]
+        );
+        assert!(collect_changes(&receiver, Duration::from_millis(10)).is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+// Wayland 剪贴板后端（wlr-data-control）
+//
+// 普通 Wayland 客户端只有在获得焦点时才能访问剪贴板，data-control 协议允许剪贴板管理器在后台读写。
+// 读写通过 wl-clipboard-rs 完成；变化通知由后台线程监听 data-control 设备的 selection /
+// primary_selection 事件（主选区需要协议版本2）。
+
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry, wl_seat::WlSeat};
+use wayland_client::{event_created_child, Connection, Dispatch, QueueHandle};
+use wayland_protocols_wlr::data_control::v1::client::{
+    zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+    zwlr_data_control_manager_v1::ZwlrDataControlManagerV1,
+    zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+};
+use wl_clipboard_rs::{copy, paste};
+
+use super::{Backend, MimeContents, Selection};
+
+pub struct WaylandBackend {
+    changes: Mutex<Receiver<Selection>>,
+}
+
+impl WaylandBackend {
+    pub fn new() -> Result<Self, String> {
+        let (sender, receiver) = mpsc::channel();
+        let (init_sender, init_receiver) = mpsc::sync_channel(1);
+
+        // 事件队列不能跨线程移动，在后台线程中初始化并回传结果
+        std::thread::spawn(move || match WatchState::connect(sender) {
+            Ok((state, queue)) => {
+                let _ = init_sender.send(Ok(()));
+                state.run(queue);
+            }
+            Err(e) => {
+                let _ = init_sender.send(Err(e));
+            }
+        });
+
+        init_receiver
+            .recv()
+            .map_err(|_| "Wayland 剪贴板监听线程异常退出".to_string())??;
+        Ok(Self {
+            changes: Mutex::new(receiver),
+        })
+    }
+}
+
+fn clipboard_type(selection: Selection) -> paste::ClipboardType {
+    match selection {
+        Selection::Clipboard => paste::ClipboardType::Regular,
+        Selection::Primary => paste::ClipboardType::Primary,
+    }
+}
+
+impl Backend for WaylandBackend {
+    fn name(&self) -> &'static str {
+        "Wayland"
+    }
+
+    fn mime_types(&self, selection: Selection) -> Result<Vec<String>, String> {
+        match paste::get_mime_types(clipboard_type(selection), paste::Seat::Unspecified) {
+            Ok(types) => Ok(types.into_iter().collect()),
+            Err(paste::Error::ClipboardEmpty) | Err(paste::Error::NoSeats) => Ok(Vec::new()),
+            Err(e) => Err(format!("读取剪贴板格式失败: {}", e)),
+        }
+    }
+
+    fn read(&self, selection: Selection, mime_type: &str) -> Result<Option<Vec<u8>>, String> {
+        let result = paste::get_contents(
+            clipboard_type(selection),
+            paste::Seat::Unspecified,
+            paste::MimeType::Specific(mime_type),
+        );
+        let (mut pipe, _) = match result {
+            Ok(contents) => contents,
+            Err(paste::Error::ClipboardEmpty)
+            | Err(paste::Error::NoMimeType)
+            | Err(paste::Error::NoSeats) => return Ok(None),
+            Err(e) => return Err(format!("读取剪贴板失败: {}", e)),
+        };
+        let mut data = Vec::new();
+        pipe.read_to_end(&mut data)
+            .map_err(|e| format!("读取剪贴板数据失败: {}", e))?;
+        Ok(Some(data))
+    }
+
+    fn write(&self, selection: Selection, contents: MimeContents) -> Result<(), String> {
+        let sources = contents
+            .into_iter()
+            .map(|(mime_type, data)| copy::MimeSource {
+                source: copy::Source::Bytes(data.into_boxed_slice()),
+                mime_type: copy::MimeType::Specific(mime_type),
+            })
+            .collect();
+
+        let mut options = copy::Options::new();
+        options
+            .clipboard(match selection {
+                Selection::Clipboard => copy::ClipboardType::Regular,
+                Selection::Primary => copy::ClipboardType::Primary,
+            })
+            .omit_additional_text_mime_types(true);
+        // 非前台模式下由 wl-clipboard-rs 的后台线程持续提供数据，直到其他程序复制新内容
+        options
+            .copy_multi(sources)
+            .map_err(|e| format!("写入剪贴板失败: {}", e))
+    }
+
+    fn wait_for_change(&self, timeout: Duration) -> Vec<Selection> {
+        match self.changes.lock() {
+            Ok(receiver) => super::collect_changes(&receiver, timeout),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+// =================== 变化监听 ===================
+
+struct WatchState {
+    sender: Sender<Selection>,
+    finished: bool,
+}
+
+impl WatchState {
+    fn connect(
+        sender: Sender<Selection>,
+    ) -> Result<(Self, wayland_client::EventQueue<Self>), String> {
+        let conn = Connection::connect_to_env()
+            .map_err(|e| format!("连接 Wayland 合成器失败: {}", e))?;
+        let (globals, queue) = registry_queue_init::<Self>(&conn)
+            .map_err(|e| format!("读取 Wayland 全局对象失败: {}", e))?;
+        let qh = queue.handle();
+
+        let manager: ZwlrDataControlManagerV1 = globals
+            .bind(&qh, 1..=2, ())
+            .map_err(|_| "合成器不支持 wlr-data-control 协议".to_string())?;
+        let seat: WlSeat = globals
+            .bind(&qh, 1..=8, ())
+            .map_err(|_| "没有可用的输入座位".to_string())?;
+        manager.get_data_device(&seat, &qh, ());
+
+        Ok((
+            Self {
+                sender,
+                finished: false,
+            },
+            queue,
+        ))
+    }
+
+    fn run(mut self, mut queue: wayland_client::EventQueue<Self>) {
+        while !self.finished {
+            if let Err(e) = queue.blocking_dispatch(&mut self) {
+                println!("Wayland 剪贴板连接已断开: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for WatchState {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlSeat, ()> for WatchState {
+    fn event(
+        _: &mut Self,
+        _: &WlSeat,
+        _: <WlSeat as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlManagerV1, ()> for WatchState {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrDataControlManagerV1,
+        _: <ZwlrDataControlManagerV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlOfferV1, ()> for WatchState {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrDataControlOfferV1,
+        _: <ZwlrDataControlOfferV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlDeviceV1, ()> for WatchState {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrDataControlDeviceV1,
+        event: zwlr_data_control_device_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // 只关心选区变化，数据由读取时重新请求；旧的 offer 不再需要
+        let selection = match event {
+            zwlr_data_control_device_v1::Event::Selection { id } => {
+                if let Some(offer) = id {
+                    offer.destroy();
+                }
+                Selection::Clipboard
+            }
+            zwlr_data_control_device_v1::Event::PrimarySelection { id } => {
+                if let Some(offer) = id {
+                    offer.destroy();
+                }
+                Selection::Primary
+            }
+            zwlr_data_control_device_v1::Event::Finished => {
+                state.finished = true;
+                return;
+            }
+            _ => return,
+        };
+        let _ = state.sender.send(selection);
+    }
+
+    event_created_child!(WatchState, ZwlrDataControlDeviceV1, [
+        zwlr_data_control_device_v1::EVT_DATA_OFFER_OPCODE => (ZwlrDataControlOfferV1, ()),
+    ]);
+}
@@ -0,0 +1,528 @@
+// X11 剪贴板后端
+//
+// 后台线程持有一个隐藏窗口：通过 XFixes 接收 CLIPBOARD/PRIMARY 所有者变化通知，
+// 并在本程序拥有选区时响应其他程序的 SelectionRequest（大数据使用 INCR 分段传输）。
+// 读取选区时使用独立的连接和窗口，避免与后台线程争抢事件。
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xfixes::{ConnectionExt as _, SelectionEventMask};
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ChangeWindowAttributesAux, ConnectionExt as _, CreateWindowAux, EventMask,
+    PropMode, Property, PropertyNotifyEvent, SelectionNotifyEvent, SelectionRequestEvent, Window,
+    WindowClass, SELECTION_NOTIFY_EVENT,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::{CURRENT_TIME, NONE};
+
+use super::{Backend, MimeContents, Selection};
+
+// 等待其他程序响应选区请求的超时时间
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+// 单次写入属性的最大字节数，超过时使用 INCR 分段传输
+const MAX_CHUNK_BYTES: usize = 256 * 1024;
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        CLIPBOARD,
+        PRIMARY,
+        TARGETS,
+        INCR,
+        QC_SELECTION_DATA,
+    }
+}
+
+type ErrorString = String;
+
+fn x11_error(e: impl std::fmt::Display) -> ErrorString {
+    format!("X11 请求失败: {}", e)
+}
+
+// 请求方超过该时间未取走下一段时放弃 INCR 传输
+const INCR_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 进行中的 INCR 分段发送
+struct IncrTransfer {
+    requestor: Window,
+    property: Atom,
+    target: Atom,
+    data: Arc<Vec<u8>>,
+    offset: usize,
+    // 上次写入的时间
+    last_activity: Instant,
+}
+
+// 选区提供的各目标及数据
+type OwnedTargets = Vec<(Atom, Arc<Vec<u8>>)>;
+
+struct Shared {
+    conn: RustConnection,
+    window: Window,
+    atoms: Atoms,
+    // 本程序拥有的选区内容：选区 -> [(目标, 数据)]
+    owned: Mutex<HashMap<Atom, OwnedTargets>>,
+    transfers: Mutex<Vec<IncrTransfer>>,
+}
+
+pub struct X11Backend {
+    shared: Arc<Shared>,
+    changes: Mutex<Receiver<Selection>>,
+}
+
+impl X11Backend {
+    pub fn new() -> Result<Self, String> {
+        let (conn, screen_num) =
+            RustConnection::connect(None).map_err(|e| format!("连接 X 服务器失败: {}", e))?;
+        conn.xfixes_query_version(5, 0)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(|e| format!("X 服务器不支持 XFixes 扩展: {}", e))?;
+
+        let atoms = Atoms::new(&conn).map_err(x11_error)?.reply().map_err(x11_error)?;
+        let window = create_window(&conn, screen_num)?;
+
+        let mask = SelectionEventMask::SET_SELECTION_OWNER
+            | SelectionEventMask::SELECTION_WINDOW_DESTROY
+            | SelectionEventMask::SELECTION_CLIENT_CLOSE;
+        for selection in [atoms.CLIPBOARD, atoms.PRIMARY] {
+            conn.xfixes_select_selection_input(window, selection, mask)
+                .map_err(x11_error)?;
+        }
+        conn.flush().map_err(x11_error)?;
+
+        let shared = Arc::new(Shared {
+            conn,
+            window,
+            atoms,
+            owned: Mutex::new(HashMap::new()),
+            transfers: Mutex::new(Vec::new()),
+        });
+
+        let (sender, receiver) = mpsc::channel();
+        let thread_shared = shared.clone();
+        std::thread::spawn(move || run_event_loop(thread_shared, sender));
+
+        Ok(Self {
+            shared,
+            changes: Mutex::new(receiver),
+        })
+    }
+
+    fn selection_atom(&self, selection: Selection) -> Atom {
+        match selection {
+            Selection::Clipboard => self.shared.atoms.CLIPBOARD,
+            Selection::Primary => self.shared.atoms.PRIMARY,
+        }
+    }
+}
+
+impl Backend for X11Backend {
+    fn name(&self) -> &'static str {
+        "X11"
+    }
+
+    fn mime_types(&self, selection: Selection) -> Result<Vec<String>, String> {
+        let reader = Reader::new()?;
+        reader.targets(self.selection_atom(selection))
+    }
+
+    fn read(&self, selection: Selection, mime_type: &str) -> Result<Option<Vec<u8>>, String> {
+        let reader = Reader::new()?;
+        let target = intern(&reader.conn, mime_type)?;
+        reader.read(self.selection_atom(selection), target)
+    }
+
+    fn write(&self, selection: Selection, contents: MimeContents) -> Result<(), String> {
+        let shared = &self.shared;
+        let selection = self.selection_atom(selection);
+
+        let mut targets = Vec::with_capacity(contents.len());
+        for (mime_type, data) in contents {
+            targets.push((intern(&shared.conn, &mime_type)?, Arc::new(data)));
+        }
+        shared
+            .owned
+            .lock()
+            .map_err(|e| format!("获取剪贴板状态失败: {}", e))?
+            .insert(selection, targets);
+
+        shared
+            .conn
+            .set_selection_owner(shared.window, selection, CURRENT_TIME)
+            .map_err(x11_error)?;
+        let owner = shared
+            .conn
+            .get_selection_owner(selection)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .owner;
+        if owner != shared.window {
+            return Err("获取剪贴板所有权失败".to_string());
+        }
+        Ok(())
+    }
+
+    fn wait_for_change(&self, timeout: Duration) -> Vec<Selection> {
+        match self.changes.lock() {
+            Ok(receiver) => super::collect_changes(&receiver, timeout),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+fn create_window(conn: &RustConnection, screen_num: usize) -> Result<Window, String> {
+    let screen = &conn.setup().roots[screen_num];
+    let window = conn.generate_id().map_err(x11_error)?;
+    conn.create_window(
+        x11rb::COPY_DEPTH_FROM_PARENT,
+        window,
+        screen.root,
+        0,
+        0,
+        1,
+        1,
+        0,
+        WindowClass::INPUT_OUTPUT,
+        screen.root_visual,
+        &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+    )
+    .map_err(x11_error)?;
+    Ok(window)
+}
+
+fn intern(conn: &RustConnection, name: &str) -> Result<Atom, String> {
+    Ok(conn
+        .intern_atom(false, name.as_bytes())
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?
+        .atom)
+}
+
+// =================== 后台事件线程 ===================
+
+fn run_event_loop(shared: Arc<Shared>, sender: Sender<Selection>) {
+    loop {
+        let event = match shared.conn.wait_for_event() {
+            Ok(event) => event,
+            Err(e) => {
+                println!("X11 剪贴板连接已断开: {}", e);
+                return;
+            }
+        };
+
+        let result = match event {
+            Event::XfixesSelectionNotify(e) => {
+                let selection = if e.selection == shared.atoms.CLIPBOARD {
+                    Selection::Clipboard
+                } else {
+                    Selection::Primary
+                };
+                let _ = sender.send(selection);
+                Ok(())
+            }
+            Event::SelectionRequest(e) => handle_selection_request(&shared, &e),
+            Event::SelectionClear(e) => {
+                if let Ok(mut owned) = shared.owned.lock() {
+                    owned.remove(&e.selection);
+                }
+                Ok(())
+            }
+            Event::PropertyNotify(e) => continue_incr_transfer(&shared, &e),
+            // 请求方窗口已销毁，不再需要继续发送
+            Event::DestroyNotify(e) => {
+                if let Ok(mut transfers) = shared.transfers.lock() {
+                    transfers.retain(|t| t.requestor != e.window);
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        };
+
+        // 清理请求方长时间没有响应的传输
+        if let Ok(mut transfers) = shared.transfers.lock() {
+            transfers.retain(|t| t.last_activity.elapsed() < INCR_TIMEOUT);
+        }
+
+        if let Err(e) = result.and_then(|_| shared.conn.flush().map_err(x11_error)) {
+            println!("处理 X11 剪贴板事件失败: {}", e);
+        }
+    }
+}
+
+fn handle_selection_request(shared: &Shared, e: &SelectionRequestEvent) -> Result<(), String> {
+    // 旧客户端可能不指定属性，此时使用目标作为属性
+    let property = if e.property == NONE { e.target } else { e.property };
+    let conn = &shared.conn;
+
+    let contents = shared
+        .owned
+        .lock()
+        .map_err(|e| format!("获取剪贴板状态失败: {}", e))?
+        .get(&e.selection)
+        .cloned();
+
+    let mut replied_property = NONE;
+    if let Some(contents) = contents {
+        if e.target == shared.atoms.TARGETS {
+            let mut targets = vec![shared.atoms.TARGETS];
+            targets.extend(contents.iter().map(|(target, _)| *target));
+            conn.change_property32(PropMode::REPLACE, e.requestor, property, AtomEnum::ATOM, &targets)
+                .map_err(x11_error)?;
+            replied_property = property;
+        } else if let Some((_, data)) = contents.iter().find(|(target, _)| *target == e.target) {
+            if data.len() > MAX_CHUNK_BYTES {
+                // 数据过大：先告知总长度，请求方删除属性后逐段写入
+                // 同时关注窗口销毁，请求方中途退出时及时清理
+                conn.change_window_attributes(
+                    e.requestor,
+                    &ChangeWindowAttributesAux::new()
+                        .event_mask(EventMask::PROPERTY_CHANGE | EventMask::STRUCTURE_NOTIFY),
+                )
+                .map_err(x11_error)?;
+                conn.change_property32(
+                    PropMode::REPLACE,
+                    e.requestor,
+                    property,
+                    shared.atoms.INCR,
+                    &[data.len() as u32],
+                )
+                .map_err(x11_error)?;
+                if let Ok(mut transfers) = shared.transfers.lock() {
+                    transfers.push(IncrTransfer {
+                        requestor: e.requestor,
+                        property,
+                        target: e.target,
+                        data: data.clone(),
+                        offset: 0,
+                        last_activity: Instant::now(),
+                    });
+                }
+            } else {
+                conn.change_property8(PropMode::REPLACE, e.requestor, property, e.target, data)
+                    .map_err(x11_error)?;
+            }
+            replied_property = property;
+        }
+    }
+
+    let notify = SelectionNotifyEvent {
+        response_type: SELECTION_NOTIFY_EVENT,
+        sequence: 0,
+        time: e.time,
+        requestor: e.requestor,
+        selection: e.selection,
+        target: e.target,
+        property: replied_property,
+    };
+    conn.send_event(false, e.requestor, EventMask::NO_EVENT, notify)
+        .map_err(x11_error)?;
+    Ok(())
+}
+
+fn continue_incr_transfer(shared: &Shared, e: &PropertyNotifyEvent) -> Result<(), String> {
+    if e.state != Property::DELETE {
+        return Ok(());
+    }
+    let mut transfers = shared
+        .transfers
+        .lock()
+        .map_err(|e| format!("获取剪贴板状态失败: {}", e))?;
+    let Some(index) = transfers
+        .iter()
+        .position(|t| t.requestor == e.window && t.property == e.atom)
+    else {
+        return Ok(());
+    };
+
+    let transfer = &mut transfers[index];
+    let end = (transfer.offset + MAX_CHUNK_BYTES).min(transfer.data.len());
+    let chunk = &transfer.data[transfer.offset..end];
+    shared
+        .conn
+        .change_property8(
+            PropMode::REPLACE,
+            transfer.requestor,
+            transfer.property,
+            transfer.target,
+            chunk,
+        )
+        .map_err(x11_error)?;
+
+    // 写入长度为0的数据表示传输结束
+    if chunk.is_empty() {
+        transfers.remove(index);
+    } else {
+        transfer.offset = end;
+        transfer.last_activity = Instant::now();
+    }
+    Ok(())
+}
+
+// =================== 读取 ===================
+
+struct Reader {
+    conn: RustConnection,
+    window: Window,
+    atoms: Atoms,
+}
+
+impl Reader {
+    fn new() -> Result<Self, String> {
+        let (conn, screen_num) =
+            RustConnection::connect(None).map_err(|e| format!("连接 X 服务器失败: {}", e))?;
+        let atoms = Atoms::new(&conn).map_err(x11_error)?.reply().map_err(x11_error)?;
+        let window = create_window(&conn, screen_num)?;
+        Ok(Self {
+            conn,
+            window,
+            atoms,
+        })
+    }
+
+    fn targets(&self, selection: Atom) -> Result<Vec<String>, String> {
+        let Some(data) = self.read(selection, self.atoms.TARGETS)? else {
+            return Ok(Vec::new());
+        };
+        let atoms: Vec<Atom> = data
+            .chunks_exact(4)
+            .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+
+        let cookies = atoms
+            .iter()
+            .map(|atom| self.conn.get_atom_name(*atom))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(x11_error)?;
+        Ok(cookies
+            .into_iter()
+            .filter_map(|cookie| cookie.reply().ok())
+            .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+            .collect())
+    }
+
+    fn read(&self, selection: Atom, target: Atom) -> Result<Option<Vec<u8>>, String> {
+        let property = self.atoms.QC_SELECTION_DATA;
+        self.conn
+            .convert_selection(self.window, selection, target, property, CURRENT_TIME)
+            .map_err(x11_error)?;
+        self.conn.flush().map_err(x11_error)?;
+
+        let notify = self.wait_for(|event| match event {
+            Event::SelectionNotify(e) if e.requestor == self.window => Some(e.property),
+            _ => None,
+        })?;
+        if notify == NONE {
+            return Ok(None);
+        }
+
+        let reply = self
+            .conn
+            .get_property(true, self.window, property, AtomEnum::ANY, 0, u32::MAX / 4)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+        if reply.type_ != self.atoms.INCR {
+            return Ok(Some(reply.value));
+        }
+
+        // INCR：删除属性后对方逐段写入，长度为0的一段表示结束
+        self.conn.flush().map_err(x11_error)?;
+        let mut data = Vec::new();
+        loop {
+            self.wait_for(|event| match event {
+                Event::PropertyNotify(e)
+                    if e.window == self.window
+                        && e.atom == property
+                        && e.state == Property::NEW_VALUE =>
+                {
+                    Some(())
+                }
+                _ => None,
+            })?;
+            let chunk = self
+                .conn
+                .get_property(true, self.window, property, AtomEnum::ANY, 0, u32::MAX / 4)
+                .map_err(x11_error)?
+                .reply()
+                .map_err(x11_error)?;
+            self.conn.flush().map_err(x11_error)?;
+            if chunk.value.is_empty() {
+                return Ok(Some(data));
+            }
+            data.extend_from_slice(&chunk.value);
+        }
+    }
+
+    fn wait_for<T>(&self, mut matcher: impl FnMut(Event) -> Option<T>) -> Result<T, String> {
+        let deadline = Instant::now() + READ_TIMEOUT;
+        loop {
+            match self.conn.poll_for_event().map_err(x11_error)? {
+                Some(event) => {
+                    if let Some(value) = matcher(event) {
+                        return Ok(value);
+                    }
+                }
+                None if Instant::now() >= deadline => {
+                    return Err("等待剪贴板数据超时".to_string());
+                }
+                None => std::thread::sleep(Duration::from_millis(5)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore = "需要 X 服务器，例如：xvfb-run cargo test linux_clipboard -- --ignored"]
+    fn round_trip_under_x_server() {
+        let backend = X11Backend::new().unwrap();
+        // 清空后台线程启动前后产生的通知
+        while !backend.wait_for_change(Duration::from_millis(100)).is_empty() {}
+
+        let html = "<b>粗体</b>".to_string();
+        backend
+            .write(
+                Selection::Clipboard,
+                vec![
+                    ("text/html".to_string(), html.clone().into_bytes()),
+                    ("UTF8_STRING".to_string(), "粗体".as_bytes().to_vec()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            backend.wait_for_change(Duration::from_secs(2)),
+            vec![Selection::Clipboard]
+        );
+
+        let targets = backend.mime_types(Selection::Clipboard).unwrap();
+        assert!(targets.contains(&"text/html".to_string()));
+        assert_eq!(
+            backend.read(Selection::Clipboard, "text/html").unwrap(),
+            Some(html.into_bytes())
+        );
+        assert_eq!(backend.read(Selection::Clipboard, "image/png").unwrap(), None);
+
+        // 超过单次写入上限的数据走 INCR 分段传输
+        let large: Vec<u8> = (0..MAX_CHUNK_BYTES * 3 + 17).map(|i| i as u8).collect();
+        backend
+            .write(Selection::Primary, vec![("image/png".to_string(), large.clone())])
+            .unwrap();
+        assert_eq!(
+            backend.wait_for_change(Duration::from_secs(2)),
+            vec![Selection::Primary]
+        );
+        assert_eq!(backend.read(Selection::Primary, "image/png").unwrap(), Some(large));
+    }
+}
@@ -0,0 +1,249 @@
+// Wayland 合成器 IPC
+//
+// Wayland 不允许普通客户端查询其他窗口，只能借助合成器自己的接口：
+// - Sway/i3：SWAYSOCK/I3SOCK 指向的 IPC 套接字，GET_TREE 返回包含 focused 标记的窗口树
+// - Hyprland：$XDG_RUNTIME_DIR/hypr/<实例>/.socket.sock，j/activewindow 和 j/clients 返回 JSON
+// 都不可用时返回 None，由调用方退回 X11
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::WindowInfo;
+
+const IPC_TIMEOUT: Duration = Duration::from_secs(1);
+
+const I3_IPC_MAGIC: &[u8] = b"i3-ipc";
+const I3_IPC_GET_TREE: u32 = 4;
+
+pub fn active_window() -> Option<WindowInfo> {
+    if let Some(tree) = sway_tree() {
+        let mut windows = Vec::new();
+        collect_sway_windows(&tree, true, &mut windows);
+        return windows.into_iter().next();
+    }
+    if let Some(window) = hyprland_request("j/activewindow") {
+        return parse_hyprland_window(&window);
+    }
+    None
+}
+
+pub fn list_windows() -> Option<Vec<WindowInfo>> {
+    if let Some(tree) = sway_tree() {
+        let mut windows = Vec::new();
+        collect_sway_windows(&tree, false, &mut windows);
+        return Some(windows);
+    }
+    if let Some(clients) = hyprland_request("j/clients") {
+        return Some(
+            clients
+                .as_array()
+                .map(|clients| clients.iter().filter_map(parse_hyprland_window).collect())
+                .unwrap_or_default(),
+        );
+    }
+    None
+}
+
+fn connect(path: PathBuf) -> Option<UnixStream> {
+    let stream = UnixStream::connect(path).ok()?;
+    stream.set_read_timeout(Some(IPC_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(IPC_TIMEOUT)).ok()?;
+    Some(stream)
+}
+
+// ---------- Sway/i3 ----------
+
+fn sway_tree() -> Option<Value> {
+    let path = std::env::var_os("SWAYSOCK").or_else(|| std::env::var_os("I3SOCK"))?;
+    let mut stream = connect(PathBuf::from(path))?;
+    match i3_ipc_request(&mut stream, I3_IPC_GET_TREE, b"") {
+        Ok(tree) => Some(tree),
+        Err(e) => {
+            eprintln!("Sway IPC 请求失败: {}", e);
+            None
+        }
+    }
+}
+
+// 消息格式："i3-ipc" + 负载长度(u32) + 消息类型(u32) + 负载，整数为本机字节序
+fn i3_ipc_request(
+    stream: &mut (impl Read + Write),
+    message_type: u32,
+    payload: &[u8],
+) -> Result<Value, String> {
+    let mut request = Vec::with_capacity(14 + payload.len());
+    request.extend_from_slice(I3_IPC_MAGIC);
+    request.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+    request.extend_from_slice(&message_type.to_ne_bytes());
+    request.extend_from_slice(payload);
+    stream.write_all(&request).map_err(|e| e.to_string())?;
+
+    let mut header = [0u8; 14];
+    stream.read_exact(&mut header).map_err(|e| e.to_string())?;
+    if &header[..6] != I3_IPC_MAGIC {
+        return Err("响应格式无效".to_string());
+    }
+    let length = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]) as usize;
+    let mut body = vec![0u8; length];
+    stream.read_exact(&mut body).map_err(|e| e.to_string())?;
+    serde_json::from_slice(&body).map_err(|e| e.to_string())
+}
+
+// 遍历窗口树，只收集有进程的叶子窗口；focused_only 时只收集获得焦点的窗口
+fn collect_sway_windows(node: &Value, focused_only: bool, windows: &mut Vec<WindowInfo>) {
+    let pid = node["pid"].as_u64().filter(|&pid| pid > 0);
+    if let Some(pid) = pid {
+        if !focused_only || node["focused"].as_bool() == Some(true) {
+            // 原生 Wayland 窗口有 app_id，XWayland 窗口只有 window_properties.class
+            let app_id = node["app_id"]
+                .as_str()
+                .or_else(|| node["window_properties"]["class"].as_str())
+                .unwrap_or_default();
+            windows.push(WindowInfo {
+                pid: Some(pid as u32),
+                title: node["name"].as_str().unwrap_or_default().to_string(),
+                app_id: app_id.to_string(),
+            });
+        }
+    }
+
+    for key in ["nodes", "floating_nodes"] {
+        if let Some(children) = node[key].as_array() {
+            for child in children {
+                collect_sway_windows(child, focused_only, windows);
+            }
+        }
+    }
+}
+
+// ---------- Hyprland ----------
+
+fn hyprland_socket() -> Option<PathBuf> {
+    let signature = std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE")?;
+    // 0.40 起套接字位于 XDG_RUNTIME_DIR，旧版本在 /tmp/hypr
+    let runtime = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(|dir| PathBuf::from(dir).join("hypr").join(&signature).join(".socket.sock"))
+        .filter(|path| path.exists());
+    runtime.or_else(|| {
+        Some(PathBuf::from("/tmp/hypr").join(&signature).join(".socket.sock"))
+            .filter(|path| path.exists())
+    })
+}
+
+fn hyprland_request(command: &str) -> Option<Value> {
+    let mut stream = connect(hyprland_socket()?)?;
+    stream.write_all(command.as_bytes()).ok()?;
+    // 合成器写完响应后关闭连接
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).ok()?;
+    serde_json::from_slice(&response).ok()
+}
+
+fn parse_hyprland_window(window: &Value) -> Option<WindowInfo> {
+    // 没有焦点窗口时返回 {}
+    let pid = window["pid"].as_i64().filter(|&pid| pid > 0)?;
+    Some(WindowInfo {
+        pid: Some(pid as u32),
+        title: window["title"].as_str().unwrap_or_default().to_string(),
+        app_id: window["class"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    // 将写入记录下来，读取时返回预设的响应
+    struct MockStream {
+        written: Vec<u8>,
+        response: Cursor<Vec<u8>>,
+    }
+
+    impl Read for MockStream {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.response.read(buf)
+        }
+    }
+
+    impl Write for MockStream {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parses_sway_tree() {
+        let tree = json!({
+            "type": "root",
+            "nodes": [{
+                "type": "workspace",
+                "focused": false,
+                "nodes": [
+                    { "type": "con", "pid": 100, "name": "终端", "app_id": "foot", "focused": false },
+                    { "type": "con", "pid": 200, "name": "文档 - Firefox", "app_id": null,
+                      "window_properties": { "class": "firefox" }, "focused": true }
+                ],
+                "floating_nodes": [
+                    { "type": "floating_con", "pid": 300, "name": "计算器", "app_id": "gnome-calculator" }
+                ]
+            }]
+        });
+
+        let body = serde_json::to_vec(&tree).unwrap();
+        let mut response = Vec::new();
+        response.extend_from_slice(I3_IPC_MAGIC);
+        response.extend_from_slice(&(body.len() as u32).to_ne_bytes());
+        response.extend_from_slice(&I3_IPC_GET_TREE.to_ne_bytes());
+        response.extend_from_slice(&body);
+        let mut stream = MockStream {
+            written: Vec::new(),
+            response: Cursor::new(response),
+        };
+
+        let parsed = i3_ipc_request(&mut stream, I3_IPC_GET_TREE, b"").unwrap();
+        assert_eq!(&stream.written[..6], I3_IPC_MAGIC);
+        assert_eq!(stream.written.len(), 14);
+
+        let mut focused = Vec::new();
+        collect_sway_windows(&parsed, true, &mut focused);
+        assert_eq!(
+            focused,
+            vec![WindowInfo {
+                pid: Some(200),
+                title: "文档 - Firefox".to_string(),
+                app_id: "firefox".to_string(),
+            }]
+        );
+
+        let mut all = Vec::new();
+        collect_sway_windows(&parsed, false, &mut all);
+        let app_ids: Vec<&str> = all.iter().map(|w| w.app_id.as_str()).collect();
+        assert_eq!(app_ids, vec!["foot", "firefox", "gnome-calculator"]);
+    }
+
+    #[test]
+    fn parses_hyprland_windows() {
+        assert_eq!(parse_hyprland_window(&json!({})), None);
+
+        let window = parse_hyprland_window(&json!({
+            "class": "kitty",
+            "title": "~/projects",
+            "pid": 4242
+        }))
+        .unwrap();
+        assert_eq!(window.pid, Some(4242));
+        assert_eq!(window.app_id, "kitty");
+        assert_eq!(window.title, "~/projects");
+    }
+}
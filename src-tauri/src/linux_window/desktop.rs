@@ -0,0 +1,286 @@
+// .desktop 文件中的应用图标
+//
+// 按 XDG 规范在 $XDG_DATA_HOME 和 $XDG_DATA_DIRS 的 applications 目录下查找 .desktop 文件，
+// 通过文件名、StartupWMClass 或 Exec 的程序名匹配窗口，再从 hicolor 主题和 pixmaps 中解析 Icon。
+// 图标以 data URL 返回，与 Windows 下的图标格式一致。
+
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose, Engine as _};
+
+// 超过此大小的图标文件不读取
+const MAX_ICON_BYTES: u64 = 1024 * 1024;
+
+// 从大到小尝试的 hicolor 图标尺寸
+const ICON_SIZES: &[&str] = &[
+    "256x256", "128x128", "96x96", "64x64", "48x48", "32x32", "scalable",
+];
+
+const ICON_EXTENSIONS: &[&str] = &["png", "svg"];
+
+#[derive(Debug, Clone, Default)]
+struct DesktopEntry {
+    // 文件名（不含 .desktop），通常与 Wayland app_id 一致
+    id: String,
+    icon: String,
+    startup_wm_class: String,
+    // Exec 中的程序名
+    program: String,
+}
+
+// 一次加载所有 .desktop 文件，列出多个窗口时复用
+pub struct IconResolver {
+    data_dirs: Vec<PathBuf>,
+    entries: Vec<DesktopEntry>,
+}
+
+impl Default for IconResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IconResolver {
+    pub fn new() -> Self {
+        Self::with_data_dirs(data_dirs())
+    }
+
+    fn with_data_dirs(data_dirs: Vec<PathBuf>) -> Self {
+        let entries = load_entries(&data_dirs);
+        Self { data_dirs, entries }
+    }
+
+    // 根据窗口的 app_id 和进程名查找图标
+    pub fn icon_for(&self, app_id: &str, process: &str) -> Option<String> {
+        let entry = self.find_entry(app_id, process)?;
+        let path = self.resolve_icon(&entry.icon)?;
+        icon_data_url(&path)
+    }
+
+    fn find_entry(&self, app_id: &str, process: &str) -> Option<&DesktopEntry> {
+        let app_id = app_id.to_lowercase();
+        let process = process.to_lowercase();
+
+        if !app_id.is_empty() {
+            // 反向域名形式的 id（如 org.gnome.Nautilus）也按最后一段匹配 WM_CLASS
+            let by_id = self.entries.iter().find(|entry| {
+                let id = entry.id.to_lowercase();
+                id == app_id || id.rsplit('.').next() == Some(app_id.as_str())
+            });
+            let by_class = || {
+                self.entries
+                    .iter()
+                    .find(|entry| entry.startup_wm_class.to_lowercase() == app_id)
+            };
+            if let Some(entry) = by_id.or_else(by_class) {
+                return Some(entry);
+            }
+        }
+
+        if process.is_empty() {
+            return None;
+        }
+        self.entries
+            .iter()
+            .find(|entry| entry.program.to_lowercase() == process)
+    }
+
+    fn resolve_icon(&self, icon: &str) -> Option<PathBuf> {
+        if icon.is_empty() {
+            return None;
+        }
+        if icon.starts_with('/') {
+            return Some(PathBuf::from(icon)).filter(|path| path.is_file());
+        }
+
+        for dir in &self.data_dirs {
+            for size in ICON_SIZES {
+                for extension in ICON_EXTENSIONS {
+                    let path = dir
+                        .join("icons/hicolor")
+                        .join(size)
+                        .join("apps")
+                        .join(format!("{}.{}", icon, extension));
+                    if path.is_file() {
+                        return Some(path);
+                    }
+                }
+            }
+        }
+
+        self.data_dirs.iter().find_map(|dir| {
+            ICON_EXTENSIONS
+                .iter()
+                .map(|extension| dir.join("pixmaps").join(format!("{}.{}", icon, extension)))
+                .find(|path| path.is_file())
+        })
+    }
+}
+
+// XDG 数据目录，用户目录优先，另外包括 Flatpak 和 Snap 导出的应用
+fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    match std::env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => dirs.push(PathBuf::from(dir)),
+        None => {
+            if let Some(home) = dirs::home_dir() {
+                dirs.push(home.join(".local/share"));
+            }
+        }
+    }
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".local/share/flatpak/exports/share"));
+    }
+
+    let system = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(system.split(':').filter(|dir| !dir.is_empty()).map(PathBuf::from));
+    dirs.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+    dirs.push(PathBuf::from("/var/lib/snapd/desktop"));
+
+    let mut unique = Vec::new();
+    for dir in dirs {
+        if !unique.contains(&dir) {
+            unique.push(dir);
+        }
+    }
+    unique
+}
+
+fn load_entries(data_dirs: &[PathBuf]) -> Vec<DesktopEntry> {
+    let mut entries = Vec::new();
+    for dir in data_dirs {
+        collect_entries(&dir.join("applications"), &mut entries);
+    }
+    entries
+}
+
+fn collect_entries(dir: &Path, entries: &mut Vec<DesktopEntry>) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for item in read_dir.flatten() {
+        let path = item.path();
+        if path.is_dir() {
+            collect_entries(&path, entries);
+        } else if path.extension().is_some_and(|ext| ext == "desktop") {
+            if let Some(entry) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| parse_entry(&path, &content))
+            {
+                entries.push(entry);
+            }
+        }
+    }
+}
+
+fn parse_entry(path: &Path, content: &str) -> Option<DesktopEntry> {
+    let mut entry = DesktopEntry {
+        id: path.file_stem()?.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let mut in_main_section = false;
+    let mut hidden = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_main_section = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main_section {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Icon" => entry.icon = value.to_string(),
+            "StartupWMClass" => entry.startup_wm_class = value.to_string(),
+            "Exec" => entry.program = exec_program(value),
+            "Hidden" => hidden = value == "true",
+            _ => {}
+        }
+    }
+
+    (!hidden && !entry.icon.is_empty()).then_some(entry)
+}
+
+// Exec 的程序名：跳过 env 和环境变量赋值，取路径的文件名
+fn exec_program(exec: &str) -> String {
+    exec.split_whitespace()
+        .map(|token| token.trim_matches('"'))
+        .find(|token| *token != "env" && !token.contains('='))
+        .and_then(|program| Path::new(program).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn icon_data_url(path: &Path) -> Option<String> {
+    let mime = match path.extension()?.to_str()? {
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        _ => return None,
+    };
+    if std::fs::metadata(path).ok()?.len() > MAX_ICON_BYTES {
+        return None;
+    }
+    let data = std::fs::read(path).ok()?;
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        general_purpose::STANDARD.encode(data)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: PathBuf, content: &[u8]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn resolves_icons_from_desktop_files() {
+        let dir = std::env::temp_dir().join(format!("qc-desktop-{}", uuid::Uuid::new_v4()));
+        write(
+            dir.join("applications/org.example.Editor.desktop"),
+            b"[Desktop Entry]\nName=Editor\nExec=env LANG=C /opt/editor/bin/editor %F\nIcon=example-editor\n\n[Desktop Action new]\nIcon=other\n",
+        );
+        write(
+            dir.join("applications/term.desktop"),
+            b"[Desktop Entry]\nExec=\"/usr/bin/term\"\nIcon=term\nStartupWMClass=TermWindow\n",
+        );
+        write(
+            dir.join("applications/hidden.desktop"),
+            b"[Desktop Entry]\nExec=hidden\nIcon=term\nHidden=true\n",
+        );
+        write(dir.join("icons/hicolor/48x48/apps/example-editor.png"), b"png");
+        write(dir.join("icons/hicolor/scalable/apps/term.svg"), b"<svg/>");
+
+        let resolver = IconResolver::with_data_dirs(vec![dir.clone()]);
+        let png = format!("data:image/png;base64,{}", general_purpose::STANDARD.encode("png"));
+        let svg = format!(
+            "data:image/svg+xml;base64,{}",
+            general_purpose::STANDARD.encode("<svg/>")
+        );
+
+        // 完整 app_id、反向域名的最后一段、Exec 程序名
+        assert_eq!(resolver.icon_for("org.example.Editor", ""), Some(png.clone()));
+        assert_eq!(resolver.icon_for("Editor", ""), Some(png.clone()));
+        assert_eq!(resolver.icon_for("", "editor"), Some(png));
+        // StartupWMClass
+        assert_eq!(resolver.icon_for("TermWindow", "unknown"), Some(svg));
+        // Hidden 的条目被忽略
+        assert_eq!(resolver.icon_for("hidden", "hidden"), None);
+        assert_eq!(resolver.icon_for("missing", "missing"), None);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
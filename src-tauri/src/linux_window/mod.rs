@@ -0,0 +1,75 @@
+// Linux 前台窗口检测
+//
+// 为应用过滤和来源记录提供前台窗口和窗口列表：
+// - X11（包括 XWayland 中的窗口）：读取根窗口的 _NET_ACTIVE_WINDOW / _NET_CLIENT_LIST，
+//   以及窗口的 _NET_WM_PID、_NET_WM_NAME 和 WM_CLASS，进程路径来自 /proc/<pid>/exe
+// - Wayland：没有通用的协议，尽力通过合成器 IPC 获取（Sway/i3 的 IPC 套接字、Hyprland 的请求套接字），
+//   其他合成器退回 X11，只能识别 XWayland 窗口
+// 应用图标从 .desktop 文件的 Icon 字段解析
+//
+// X11 部分可在 Xvfb 下测试：xvfb-run cargo test linux_window
+
+mod compositor;
+mod desktop;
+mod x11;
+
+pub use desktop::IconResolver;
+
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowInfo {
+    pub pid: Option<u32>,
+    pub title: String,
+    // WM_CLASS 的类名或 Wayland 的 app_id
+    pub app_id: String,
+}
+
+impl WindowInfo {
+    // 进程可执行文件路径（程序被更新或删除时去掉 " (deleted)" 后缀）
+    pub fn exe_path(&self) -> Option<PathBuf> {
+        let path = std::fs::read_link(format!("/proc/{}/exe", self.pid?)).ok()?;
+        let path = path.to_string_lossy();
+        Some(PathBuf::from(path.strip_suffix(" (deleted)").unwrap_or(&path)))
+    }
+
+    // 进程名：可执行文件名，无权读取时使用 /proc/<pid>/comm，都没有时使用 app_id
+    pub fn process_name(&self) -> String {
+        if let Some(name) = self
+            .exe_path()
+            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+        {
+            return name;
+        }
+
+        self.pid
+            .and_then(|pid| std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok())
+            .map(|comm| comm.trim().to_string())
+            .filter(|comm| !comm.is_empty())
+            .unwrap_or_else(|| self.app_id.clone())
+    }
+}
+
+fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+// 当前获得焦点的窗口
+pub fn foreground_window() -> Option<WindowInfo> {
+    if is_wayland() {
+        if let Some(window) = compositor::active_window() {
+            return Some(window);
+        }
+    }
+    x11::active_window()
+}
+
+// 所有顶层窗口
+pub fn list_windows() -> Vec<WindowInfo> {
+    if is_wayland() {
+        if let Some(windows) = compositor::list_windows() {
+            return windows;
+        }
+    }
+    x11::list_windows().unwrap_or_default()
+}
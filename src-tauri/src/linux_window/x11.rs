@@ -0,0 +1,253 @@
+// X11 窗口信息
+//
+// 读取窗口管理器在根窗口上维护的 EWMH 属性：_NET_ACTIVE_WINDOW 为当前焦点窗口，
+// _NET_CLIENT_LIST 为所有顶层窗口。连接在首次使用时建立并复用，请求失败后丢弃并在下次重连。
+
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt as _, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::WindowInfo;
+
+// 标题属性读取的最大长度（以 4 字节为单位）
+const MAX_TITLE_LENGTH: u32 = 1024;
+
+// 客户端列表读取的最大窗口数
+const MAX_CLIENTS: u32 = 4096;
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_ACTIVE_WINDOW,
+        _NET_CLIENT_LIST,
+        _NET_WM_PID,
+        _NET_WM_NAME,
+        UTF8_STRING,
+    }
+}
+
+fn x11_error(e: impl std::fmt::Display) -> String {
+    format!("X11 请求失败: {}", e)
+}
+
+struct Session {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+static SESSION: Lazy<Mutex<Option<Session>>> = Lazy::new(|| Mutex::new(None));
+
+impl Session {
+    fn connect() -> Result<Self, String> {
+        let (conn, screen_num) =
+            RustConnection::connect(None).map_err(|e| format!("连接 X 服务器失败: {}", e))?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = Atoms::new(&conn)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?;
+        Ok(Self { conn, root, atoms })
+    }
+
+    fn property(
+        &self,
+        window: Window,
+        property: Atom,
+        type_: impl Into<Atom>,
+        long_length: u32,
+    ) -> Result<x11rb::protocol::xproto::GetPropertyReply, String> {
+        self.conn
+            .get_property(false, window, property, type_, 0, long_length)
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)
+    }
+
+    fn active_window(&self) -> Result<Option<Window>, String> {
+        let reply = self.property(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW, 1)?;
+        Ok(reply
+            .value32()
+            .and_then(|mut values| values.next())
+            .filter(|&window| window != x11rb::NONE))
+    }
+
+    fn client_list(&self) -> Result<Vec<Window>, String> {
+        let reply = self.property(
+            self.root,
+            self.atoms._NET_CLIENT_LIST,
+            AtomEnum::WINDOW,
+            MAX_CLIENTS,
+        )?;
+        Ok(reply
+            .value32()
+            .map(|values| values.collect())
+            .unwrap_or_default())
+    }
+
+    fn window_info(&self, window: Window) -> Result<WindowInfo, String> {
+        let pid = self
+            .property(window, self.atoms._NET_WM_PID, AtomEnum::CARDINAL, 1)?
+            .value32()
+            .and_then(|mut values| values.next())
+            .filter(|&pid| pid != 0);
+
+        // 优先使用 UTF-8 的 _NET_WM_NAME，旧程序只设置 WM_NAME
+        let mut title = self
+            .property(window, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING, MAX_TITLE_LENGTH)?
+            .value;
+        if title.is_empty() {
+            title = self
+                .property(window, AtomEnum::WM_NAME.into(), AtomEnum::ANY, MAX_TITLE_LENGTH)?
+                .value;
+        }
+
+        // WM_CLASS 为 "实例名\0类名\0"，取类名
+        let class = self
+            .property(window, AtomEnum::WM_CLASS.into(), AtomEnum::STRING, MAX_TITLE_LENGTH)?
+            .value;
+        let app_id = class
+            .split(|&byte| byte == 0)
+            .rfind(|part| !part.is_empty())
+            .map(|part| String::from_utf8_lossy(part).to_string())
+            .unwrap_or_default();
+
+        Ok(WindowInfo {
+            pid,
+            title: String::from_utf8_lossy(&title).to_string(),
+            app_id,
+        })
+    }
+}
+
+// 使用共享连接执行请求，失败时丢弃连接
+fn with_session<T>(f: impl FnOnce(&Session) -> Result<T, String>) -> Option<T> {
+    std::env::var_os("DISPLAY")?;
+
+    let mut guard = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    if guard.is_none() {
+        match Session::connect() {
+            Ok(session) => *guard = Some(session),
+            Err(e) => {
+                eprintln!("{}", e);
+                return None;
+            }
+        }
+    }
+
+    match f(guard.as_ref()?) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("获取 X11 窗口信息失败: {}", e);
+            *guard = None;
+            None
+        }
+    }
+}
+
+pub fn active_window() -> Option<WindowInfo> {
+    with_session(|session| match session.active_window()? {
+        Some(window) => session.window_info(window).map(Some),
+        None => Ok(None),
+    })
+    .flatten()
+}
+
+pub fn list_windows() -> Option<Vec<WindowInfo>> {
+    with_session(|session| {
+        // 窗口可能在读取过程中关闭，跳过单个窗口的错误
+        Ok(session
+            .client_list()?
+            .into_iter()
+            .filter_map(|window| session.window_info(window).ok())
+            .collect())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x11rb::protocol::xproto::{CreateWindowAux, PropMode, WindowClass};
+    use x11rb::wrapper::ConnectionExt as _;
+
+    #[test]
+    #[ignore = "需要 X 服务器，例如：xvfb-run cargo test linux_window -- --ignored"]
+    fn reads_active_window_properties() {
+        // Xvfb 下没有窗口管理器，由测试自己设置 EWMH 属性
+        let session = Session::connect().unwrap();
+        let conn = &session.conn;
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            x11rb::COPY_DEPTH_FROM_PARENT,
+            window,
+            session.root,
+            0,
+            0,
+            100,
+            100,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            x11rb::COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )
+        .unwrap();
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            session.atoms._NET_WM_NAME,
+            session.atoms.UTF8_STRING,
+            "测试窗口".as_bytes(),
+        )
+        .unwrap();
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            AtomEnum::WM_CLASS,
+            AtomEnum::STRING,
+            b"qc-test\0QcTest\0",
+        )
+        .unwrap();
+        conn.change_property32(
+            PropMode::REPLACE,
+            window,
+            session.atoms._NET_WM_PID,
+            AtomEnum::CARDINAL,
+            &[std::process::id()],
+        )
+        .unwrap();
+        conn.change_property32(
+            PropMode::REPLACE,
+            session.root,
+            session.atoms._NET_ACTIVE_WINDOW,
+            AtomEnum::WINDOW,
+            &[window],
+        )
+        .unwrap();
+        conn.change_property32(
+            PropMode::APPEND,
+            session.root,
+            session.atoms._NET_CLIENT_LIST,
+            AtomEnum::WINDOW,
+            &[window],
+        )
+        .unwrap();
+        conn.sync().unwrap();
+
+        let active = active_window().unwrap();
+        assert_eq!(active.title, "测试窗口");
+        assert_eq!(active.app_id, "QcTest");
+        assert_eq!(active.pid, Some(std::process::id()));
+        let exe = std::env::current_exe().unwrap();
+        assert_eq!(
+            active.process_name(),
+            exe.file_name().unwrap().to_string_lossy()
+        );
+
+        assert!(list_windows().unwrap().contains(&active));
+
+        conn.destroy_window(window).unwrap();
+        conn.sync().unwrap();
+    }
+}
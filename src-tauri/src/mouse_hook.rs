@@ -81,6 +81,22 @@ unsafe extern "system" fn mouse_hook_proc(
         // 处理鼠标事件
         match wparam.0 as u32 {
             WM_MBUTTONDOWN => {
+                // 预览窗口显示时，中键切换当前预览项的置顶状态
+                if mouse_monitoring_enabled && preview_visible {
+                    if let Some(window) = MAIN_WINDOW_HANDLE.get() {
+                        use tauri::Manager;
+                        let app_handle = window.app_handle().clone();
+                        std::thread::spawn(move || {
+                            if let Err(e) =
+                                crate::preview_window::toggle_current_preview_item_pin(&app_handle)
+                            {
+                                println!("切换预览项置顶状态失败: {}", e);
+                            }
+                        });
+                    }
+                    return windows::Win32::Foundation::LRESULT(1);
+                }
+
                 // 鼠标中键点击事件：全局监听
                 let settings = crate::settings::get_global_settings();
                 if settings.mouse_middle_button_enabled {
@@ -0,0 +1,363 @@
+// 离线词典
+//
+// 支持两种格式：
+// - StarDict：.ifo/.idx/.dict 三个文件，索引和词典数据可为 gzip 压缩（.idx.gz、.dict.dz）
+// - 文本词典：每行 "词条<Tab>释义"，# 开头的行为注释，释义中的 \n 表示换行
+// 查询不区分大小写，大小写不同的重复词条保留第一条
+
+use flate2::read::GzDecoder;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, AppResult};
+
+pub trait Dictionary: Send + Sync {
+    // 查询词条，返回释义
+    fn lookup(&self, word: &str) -> Option<String>;
+}
+
+// 打开词典：目录中查找第一个 .ifo 文件，.ifo 按 StarDict 读取，其他文件按文本词典读取
+pub fn open_dictionary(path: &Path) -> AppResult<Box<dyn Dictionary>> {
+    if !path.exists() {
+        return Err(missing_file(path));
+    }
+
+    let path = if path.is_dir() {
+        find_ifo(path)?
+    } else {
+        path.to_path_buf()
+    };
+
+    if path.extension().and_then(|ext| ext.to_str()) == Some("ifo") {
+        Ok(Box::new(StarDict::open(&path)?))
+    } else {
+        Ok(Box::new(TextDictionary::open(&path)?))
+    }
+}
+
+fn find_ifo(dir: &Path) -> AppResult<PathBuf> {
+    let entries = std::fs::read_dir(dir).map_err(|e| AppError::from_io("读取词典目录失败", &e))?;
+    let mut candidates: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("ifo"))
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            AppError::not_found(format!("目录中没有 StarDict 词典: {}", dir.display()))
+                .with_key("error.offline.no_stardict")
+                .with_context("path", dir.display())
+        })
+}
+
+fn missing_file(path: &Path) -> AppError {
+    AppError::not_found(format!("词典文件不存在: {}", path.display()))
+        .with_key("error.file.not_found")
+        .with_context("path", path.display())
+}
+
+fn invalid_dictionary(message: &str) -> AppError {
+    AppError::invalid_input(message).with_key("error.offline.invalid_dictionary")
+}
+
+fn normalize_key(word: &str) -> String {
+    word.trim().to_lowercase()
+}
+
+// =================== StarDict ===================
+
+pub struct StarDict {
+    index: HashMap<String, (u64, u32)>,
+    data: Vec<u8>,
+    same_type_sequence: Option<String>,
+}
+
+impl StarDict {
+    pub fn open(ifo_path: &Path) -> AppResult<Self> {
+        let ifo = std::fs::read_to_string(ifo_path).map_err(|e| AppError::from_io("读取词典信息失败", &e))?;
+        if !ifo.starts_with("StarDict's dict ifo file") {
+            return Err(invalid_dictionary("不是有效的 StarDict 词典信息文件"));
+        }
+
+        let info: HashMap<&str, &str> = ifo
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .collect();
+
+        let offset_bits = match info.get("idxoffsetbits") {
+            Some(&"64") => 64,
+            _ => 32,
+        };
+        let same_type_sequence = info
+            .get("sametypesequence")
+            .filter(|seq| !seq.is_empty())
+            .map(|seq| seq.to_string());
+
+        let idx = read_maybe_compressed(ifo_path, &["idx", "idx.gz"])?;
+        let data = read_maybe_compressed(ifo_path, &["dict", "dict.dz"])?;
+
+        Ok(Self {
+            index: parse_index(&idx, offset_bits)?,
+            data,
+            same_type_sequence,
+        })
+    }
+}
+
+impl Dictionary for StarDict {
+    fn lookup(&self, word: &str) -> Option<String> {
+        let (offset, size) = *self.index.get(&normalize_key(word))?;
+        let start = offset as usize;
+        let end = start.checked_add(size as usize)?;
+        let entry = self.data.get(start..end)?;
+        let text = parse_entry(entry, self.same_type_sequence.as_deref());
+        if text.is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    }
+}
+
+// 按扩展名依次查找同名文件，.gz/.dz 结尾的按 gzip 解压
+fn read_maybe_compressed(ifo_path: &Path, extensions: &[&str]) -> AppResult<Vec<u8>> {
+    for extension in extensions {
+        let path = ifo_path.with_extension(extension);
+        if !path.exists() {
+            continue;
+        }
+
+        let bytes = std::fs::read(&path).map_err(|e| AppError::from_io("读取词典文件失败", &e))?;
+        if extension.ends_with("gz") || extension.ends_with("dz") {
+            let mut decoded = Vec::new();
+            GzDecoder::new(bytes.as_slice())
+                .read_to_end(&mut decoded)
+                .map_err(|e| invalid_dictionary(&format!("解压词典文件失败: {}", e)))?;
+            return Ok(decoded);
+        }
+        return Ok(bytes);
+    }
+
+    Err(missing_file(&ifo_path.with_extension(extensions[0])))
+}
+
+// 索引项：以 \0 结尾的词条，之后是大端序的偏移（32 或 64 位）和 32 位长度
+fn parse_index(idx: &[u8], offset_bits: u32) -> AppResult<HashMap<String, (u64, u32)>> {
+    let offset_len = (offset_bits / 8) as usize;
+    let mut index = HashMap::new();
+    let mut pos = 0;
+
+    while pos < idx.len() {
+        let word_end = idx[pos..]
+            .iter()
+            .position(|&b| b == 0)
+            .map(|i| pos + i)
+            .ok_or_else(|| invalid_dictionary("词典索引格式错误"))?;
+        let word = String::from_utf8_lossy(&idx[pos..word_end]);
+
+        let fields = idx
+            .get(word_end + 1..word_end + 1 + offset_len + 4)
+            .ok_or_else(|| invalid_dictionary("词典索引格式错误"))?;
+        let offset = fields[..offset_len]
+            .iter()
+            .fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        let size = u32::from_be_bytes([
+            fields[offset_len],
+            fields[offset_len + 1],
+            fields[offset_len + 2],
+            fields[offset_len + 3],
+        ]);
+
+        index.entry(normalize_key(&word)).or_insert((offset, size));
+        pos = word_end + 1 + offset_len + 4;
+    }
+
+    Ok(index)
+}
+
+// 解析词条数据，只保留文本字段。小写类型为文本（最后一个字段外以 \0 结尾），
+// 大写类型为二进制（最后一个字段外带 32 位长度），未指定 sametypesequence 时每个字段前有类型字节
+fn parse_entry(entry: &[u8], same_type_sequence: Option<&str>) -> String {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+
+    let types: Vec<u8> = match same_type_sequence {
+        Some(seq) => seq.bytes().collect(),
+        None => Vec::new(),
+    };
+    let mut type_index = 0;
+
+    while pos < entry.len() {
+        let (field_type, is_last) = if types.is_empty() {
+            let t = entry[pos];
+            pos += 1;
+            (t, false)
+        } else if type_index < types.len() {
+            type_index += 1;
+            (types[type_index - 1], type_index == types.len())
+        } else {
+            break;
+        };
+
+        if field_type.is_ascii_lowercase() {
+            let end = if is_last {
+                entry.len()
+            } else {
+                entry[pos..].iter().position(|&b| b == 0).map(|i| pos + i).unwrap_or(entry.len())
+            };
+            let text = String::from_utf8_lossy(&entry[pos..end]).to_string();
+            if let Some(text) = field_text(field_type, &text) {
+                fields.push(text);
+            }
+            pos = end + 1;
+        } else {
+            let size = if is_last {
+                entry.len() - pos
+            } else {
+                let Some(bytes) = entry.get(pos..pos + 4) else {
+                    break;
+                };
+                pos += 4;
+                u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+            };
+            pos += size;
+        }
+    }
+
+    fields.join("\n").trim().to_string()
+}
+
+static BREAK_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)<br\s*/?>|</p>|</div>").unwrap());
+static MARKUP_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+fn field_text(field_type: u8, text: &str) -> Option<String> {
+    let text = match field_type {
+        // 纯文本、音标、拼音等
+        b'm' | b'l' | b'y' | b'k' | b'w' => text.to_string(),
+        b't' => format!("[{}]", text),
+        // HTML、Pango、XDXF 标记去掉标签
+        b'h' | b'g' | b'x' => {
+            let text = BREAK_TAG.replace_all(text, "\n");
+            MARKUP_TAG
+                .replace_all(&text, "")
+                .replace("&nbsp;", " ")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&")
+        }
+        _ => return None,
+    };
+
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+// =================== 文本词典 ===================
+
+pub struct TextDictionary {
+    entries: HashMap<String, String>,
+}
+
+impl TextDictionary {
+    pub fn open(path: &Path) -> AppResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| AppError::from_io("读取词典文件失败", &e))?;
+        Ok(Self::parse(&content))
+    }
+
+    fn parse(content: &str) -> Self {
+        let mut entries = HashMap::new();
+        for line in content.lines() {
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            if let Some((word, definition)) = line.split_once('\t') {
+                let definition = definition.trim().replace("\\n", "\n");
+                if !word.trim().is_empty() && !definition.is_empty() {
+                    entries.entry(normalize_key(word)).or_insert(definition);
+                }
+            }
+        }
+        Self { entries }
+    }
+}
+
+impl Dictionary for TextDictionary {
+    fn lookup(&self, word: &str) -> Option<String> {
+        self.entries.get(&normalize_key(word)).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_entry(word: &str, offset: u32, size: u32) -> Vec<u8> {
+        let mut bytes = word.as_bytes().to_vec();
+        bytes.push(0);
+        bytes.extend_from_slice(&offset.to_be_bytes());
+        bytes.extend_from_slice(&size.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn reads_stardict_files() {
+        let dir = std::env::temp_dir().join(format!("qc-stardict-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let apple = "n. 苹果";
+        let book = "<b>n.</b> 书<br>v. 预订";
+        let dict = format!("{}{}", apple, book);
+        let mut idx = index_entry("Apple", 0, apple.len() as u32);
+        idx.extend(index_entry("book", apple.len() as u32, book.len() as u32));
+
+        std::fs::write(
+            dir.join("test.ifo"),
+            "StarDict's dict ifo file\nversion=2.4.2\nwordcount=2\nbookname=test\nsametypesequence=h\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("test.idx"), idx).unwrap();
+        std::fs::write(dir.join("test.dict"), dict).unwrap();
+
+        let dictionary = open_dictionary(&dir).unwrap();
+        assert_eq!(dictionary.lookup("apple").as_deref(), Some("n. 苹果"));
+        assert_eq!(dictionary.lookup("BOOK").as_deref(), Some("n. 书\nv. 预订"));
+        assert_eq!(dictionary.lookup("pear"), None);
+
+        std::fs::remove_file(dir.join("test.dict")).unwrap();
+        let error = open_dictionary(&dir).err().unwrap();
+        assert_eq!(error.code, crate::error::ErrorCode::NotFound);
+        assert_eq!(error.key, "error.file.not_found");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parses_typed_entry_fields() {
+        // 未指定 sametypesequence：音标、二进制资源、释义
+        let mut entry = b"thello\0".to_vec();
+        entry.push(b'W');
+        entry.extend_from_slice(&3u32.to_be_bytes());
+        entry.extend_from_slice(&[1, 2, 3]);
+        entry.extend_from_slice(b"m\xe4\xbd\xa0\xe5\xa5\xbd\0");
+        assert_eq!(parse_entry(&entry, None), "[hello]\n你好");
+
+        assert_eq!(parse_entry(b"he'lo\0int. hi", Some("tm")), "[he'lo]\nint. hi");
+    }
+
+    #[test]
+    fn parses_text_dictionary() {
+        let dictionary = TextDictionary::parse("# 注释\nHello\t你好\\n喂\nhello\t重复\nbad line\n");
+        assert_eq!(dictionary.lookup("hello").as_deref(), Some("你好\n喂"));
+        assert_eq!(dictionary.entries.len(), 1);
+    }
+}
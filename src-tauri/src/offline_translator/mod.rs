@@ -0,0 +1,248 @@
+// 离线翻译
+//
+// 不依赖网络的翻译后端：单词和短语优先查询本地词典（StarDict 或制表符分隔的文本词典），
+// 其余文本交给本地模型运行器。结果通过与 AI 翻译相同的 TranslationResult 通道返回，
+// 翻译服务无需区分后端。
+
+mod dictionary;
+mod runner;
+
+pub use dictionary::{open_dictionary, Dictionary};
+pub use runner::{CommandRunner, LocalModelRunner, RunRequest};
+
+use crate::ai_translator::{TranslationError, TranslationResult};
+use crate::error::AppResult;
+use once_cell::sync::Lazy;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+// 超过该长度的文本不查词典，直接交给本地模型
+const MAX_DICTIONARY_QUERY_CHARS: usize = 64;
+
+// 已加载的词典（按路径缓存，避免每次翻译都重新读取索引）
+type CachedDictionary = (String, Arc<dyn Dictionary>);
+static DICTIONARY_CACHE: Lazy<Mutex<Option<CachedDictionary>>> = Lazy::new(|| Mutex::new(None));
+
+pub struct OfflineTranslator {
+    dictionary: Option<Arc<dyn Dictionary>>,
+    runner: Option<Arc<dyn LocalModelRunner>>,
+    target_language: String,
+}
+
+impl OfflineTranslator {
+    pub fn new(
+        dictionary: Option<Arc<dyn Dictionary>>,
+        runner: Option<Arc<dyn LocalModelRunner>>,
+        target_language: String,
+    ) -> Result<Self, TranslationError> {
+        if dictionary.is_none() && runner.is_none() {
+            return Err(TranslationError::ConfigError(
+                "未配置离线词典或本地模型".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            dictionary,
+            runner,
+            target_language,
+        })
+    }
+
+    // 从应用设置创建离线翻译器
+    pub fn from_settings(settings: &crate::settings::AppSettings) -> Result<Self, TranslationError> {
+        let dictionary = match settings.offline_dictionary_path.trim() {
+            "" => None,
+            path => Some(load_dictionary(path).map_err(|e| TranslationError::ConfigError(e.to_string()))?),
+        };
+
+        let runner: Option<Arc<dyn LocalModelRunner>> = match settings.offline_model_command.trim() {
+            "" => None,
+            command => Some(Arc::new(
+                CommandRunner::new(command).map_err(|e| TranslationError::ConfigError(e.to_string()))?,
+            )),
+        };
+
+        Self::new(dictionary, runner, settings.ai_target_language.clone())
+    }
+
+    // 翻译文本（流式）
+    pub async fn translate_stream(
+        &self,
+        text: &str,
+    ) -> Result<mpsc::Receiver<TranslationResult>, TranslationError> {
+        let (tx, rx) = mpsc::channel(100);
+
+        let source_language = detect_language(text);
+        let target_language = resolve_target_language(&self.target_language, source_language);
+
+        if let Some(definition) = self.lookup(text) {
+            let _ = tx.send(TranslationResult::Chunk(definition)).await;
+            let _ = tx.send(TranslationResult::Complete).await;
+            return Ok(rx);
+        }
+
+        let runner = self.runner.clone().ok_or_else(|| {
+            TranslationError::ConfigError("离线词典中没有该词条，且未配置本地模型".to_string())
+        })?;
+
+        let request = RunRequest {
+            text: text.to_string(),
+            source_language: source_language.to_string(),
+            target_language,
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let result = runner.run(&request, &mut |chunk| {
+                tx.blocking_send(TranslationResult::Chunk(chunk)).is_ok()
+            });
+
+            let _ = match result {
+                Ok(()) => tx.blocking_send(TranslationResult::Complete),
+                Err(e) => tx.blocking_send(TranslationResult::Error(TranslationError::UnknownError(e.to_string()))),
+            };
+        });
+
+        Ok(rx)
+    }
+
+    // 单行的短文本查询词典，去掉首尾标点后再试一次
+    fn lookup(&self, text: &str) -> Option<String> {
+        let dictionary = self.dictionary.as_ref()?;
+        let query = text.trim();
+        if query.is_empty() || query.contains('\n') || query.chars().count() > MAX_DICTIONARY_QUERY_CHARS {
+            return None;
+        }
+
+        dictionary.lookup(query).or_else(|| {
+            let stripped = query.trim_matches(|c: char| c.is_ascii_punctuation() || "，。！？；：“”‘’".contains(c));
+            if stripped.is_empty() || stripped == query {
+                None
+            } else {
+                dictionary.lookup(stripped)
+            }
+        })
+    }
+}
+
+// 检查离线翻译设置是否可用（至少配置了词典或本地模型）
+pub fn is_configured(settings: &crate::settings::AppSettings) -> bool {
+    !settings.offline_dictionary_path.trim().is_empty()
+        || !settings.offline_model_command.trim().is_empty()
+}
+
+fn load_dictionary(path: &str) -> AppResult<Arc<dyn Dictionary>> {
+    let mut cache = DICTIONARY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some((cached_path, dictionary)) = cache.as_ref() {
+        if cached_path == path {
+            return Ok(dictionary.clone());
+        }
+    }
+
+    let dictionary: Arc<dyn Dictionary> = Arc::from(open_dictionary(std::path::Path::new(path))?);
+    *cache = Some((path.to_string(), dictionary.clone()));
+    Ok(dictionary)
+}
+
+// 按文字系统检测源语言：出现假名即为日语，否则取字符数最多的文字
+pub fn detect_language(text: &str) -> &'static str {
+    let (mut han, mut hangul, mut cyrillic, mut latin) = (0usize, 0usize, 0usize, 0usize);
+
+    for c in text.chars() {
+        match c as u32 {
+            0x3040..=0x30FF => return "ja",
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => han += 1,
+            0xAC00..=0xD7AF | 0x1100..=0x11FF | 0x3130..=0x318F => hangul += 1,
+            0x0400..=0x04FF => cyrillic += 1,
+            _ if c.is_ascii_alphabetic() => latin += 1,
+            _ => {}
+        }
+    }
+
+    [(han, "zh"), (hangul, "ko"), (cyrillic, "ru"), (latin, "en")]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .max_by_key(|(count, _)| *count)
+        .map(|(_, language)| language)
+        .unwrap_or("unknown")
+}
+
+// 目标语言为 auto 时中文译为英文，其他语言译为中文
+fn resolve_target_language(target: &str, source: &str) -> String {
+    match target {
+        "auto" | "" if source == "zh" => "en".to_string(),
+        "auto" | "" => "zh-CN".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct MapDictionary(HashMap<&'static str, &'static str>);
+
+    impl Dictionary for MapDictionary {
+        fn lookup(&self, word: &str) -> Option<String> {
+            self.0.get(word).map(|s| s.to_string())
+        }
+    }
+
+    struct EchoRunner;
+
+    impl LocalModelRunner for EchoRunner {
+        fn run(&self, request: &RunRequest, on_chunk: &mut dyn FnMut(String) -> bool) -> AppResult<()> {
+            for part in [request.source_language.as_str(), "->", request.target_language.as_str()] {
+                if !on_chunk(part.to_string()) {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    async fn collect(translator: &OfflineTranslator, text: &str) -> String {
+        let mut receiver = translator.translate_stream(text).await.unwrap();
+        let mut result = String::new();
+        while let Some(item) = receiver.recv().await {
+            match item {
+                TranslationResult::Chunk(chunk) => result.push_str(&chunk),
+                TranslationResult::Complete => break,
+                TranslationResult::Error(e) => panic!("{}", e),
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn detects_script() {
+        assert_eq!(detect_language("hello world"), "en");
+        assert_eq!(detect_language("你好，世界！hi"), "zh");
+        assert_eq!(detect_language("日本語のテキスト"), "ja");
+        assert_eq!(detect_language("안녕하세요"), "ko");
+        assert_eq!(detect_language("привет"), "ru");
+        assert_eq!(detect_language("123 !?"), "unknown");
+        assert_eq!(resolve_target_language("auto", "zh"), "en");
+        assert_eq!(resolve_target_language("auto", "en"), "zh-CN");
+        assert_eq!(resolve_target_language("ja", "en"), "ja");
+    }
+
+    #[tokio::test]
+    async fn prefers_dictionary_then_runner() {
+        let dictionary: Arc<dyn Dictionary> = Arc::new(MapDictionary(HashMap::from([("apple", "n. 苹果")])));
+        let translator = OfflineTranslator::new(
+            Some(dictionary.clone()),
+            Some(Arc::new(EchoRunner)),
+            "auto".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(collect(&translator, " apple. ").await, "n. 苹果");
+        assert_eq!(collect(&translator, "an apple a day").await, "en->zh-CN");
+
+        let dictionary_only = OfflineTranslator::new(Some(dictionary), None, "auto".to_string()).unwrap();
+        assert!(dictionary_only.translate_stream("pear").await.is_err());
+        assert!(OfflineTranslator::new(None, None, "auto".to_string()).is_err());
+    }
+}
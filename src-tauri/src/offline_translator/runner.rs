@@ -0,0 +1,206 @@
+// 本地模型运行器
+//
+// 运行器接收原文和语言，按输出顺序逐段回调译文。内置的 CommandRunner 启动一个本地命令
+// （如 llama.cpp、argos-translate 的命令行），原文写入标准输入，标准输出即译文。
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+use crate::error::{AppError, AppResult};
+
+pub struct RunRequest {
+    pub text: String,
+    // 检测到的源语言代码（无法识别时为 unknown）
+    pub source_language: String,
+    pub target_language: String,
+}
+
+pub trait LocalModelRunner: Send + Sync {
+    // 执行翻译，每得到一段输出调用一次 on_chunk；on_chunk 返回 false 时应停止
+    fn run(&self, request: &RunRequest, on_chunk: &mut dyn FnMut(String) -> bool) -> AppResult<()>;
+}
+
+// 命令行运行器，参数中的 {source} 和 {target} 替换为语言代码
+pub struct CommandRunner {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandRunner {
+    pub fn new(command: &str) -> AppResult<Self> {
+        let mut parts = split_command(command).into_iter();
+        let program = parts.next().ok_or_else(|| {
+            AppError::invalid_input("本地模型命令为空").with_key("error.offline.empty_command")
+        })?;
+        Ok(Self {
+            program,
+            args: parts.collect(),
+        })
+    }
+}
+
+impl LocalModelRunner for CommandRunner {
+    fn run(&self, request: &RunRequest, on_chunk: &mut dyn FnMut(String) -> bool) -> AppResult<()> {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| {
+                arg.replace("{source}", &request.source_language)
+                    .replace("{target}", &request.target_language)
+            })
+            .collect();
+
+        let mut command = Command::new(&self.program);
+        command
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| AppError::from_io("启动本地模型失败", &e).with_context("program", &self.program))?;
+
+        // 在单独的线程写入原文和读取错误输出，避免管道写满后互相等待
+        let mut stdin = child.stdin.take().ok_or_else(|| AppError::internal("无法写入本地模型输入"))?;
+        let text = request.text.clone();
+        let writer = std::thread::spawn(move || {
+            let _ = stdin.write_all(text.as_bytes());
+        });
+
+        let mut stderr = child.stderr.take().ok_or_else(|| AppError::internal("无法读取本地模型错误输出"))?;
+        let error_reader = std::thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            output
+        });
+
+        let mut stdout = child.stdout.take().ok_or_else(|| AppError::internal("无法读取本地模型输出"))?;
+        let mut buffer = [0u8; 1024];
+        let mut pending = Vec::new();
+
+        loop {
+            let read = stdout
+                .read(&mut buffer)
+                .map_err(|e| AppError::from_io("读取本地模型输出失败", &e))?;
+            if read == 0 {
+                break;
+            }
+            pending.extend_from_slice(&buffer[..read]);
+
+            // 只发送完整的 UTF-8 字符，被截断的多字节字符留到下一次
+            let valid = match std::str::from_utf8(&pending) {
+                Ok(text) => text.len(),
+                Err(e) => e.valid_up_to(),
+            };
+            if valid == 0 {
+                continue;
+            }
+
+            let chunk = String::from_utf8_lossy(&pending[..valid]).to_string();
+            pending.drain(..valid);
+            if !on_chunk(chunk) {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(());
+            }
+        }
+
+        if !pending.is_empty() {
+            on_chunk(String::from_utf8_lossy(&pending).to_string());
+        }
+
+        let _ = writer.join();
+        let status = child.wait().map_err(|e| AppError::from_io("等待本地模型结束失败", &e))?;
+        let error_output = error_reader.join().unwrap_or_default();
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(
+                AppError::translation(format!("本地模型运行失败（{}）: {}", status, error_output.trim()))
+                    .with_key("error.offline.model_failed")
+                    .with_context("status", status)
+                    .with_context("detail", error_output.trim()),
+            )
+        }
+    }
+}
+
+// 按空白拆分命令行，双引号内的空白不拆分
+fn split_command(command: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_part = false;
+
+    for c in command.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_part = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_part {
+                    parts.push(std::mem::take(&mut current));
+                    has_part = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_part = true;
+            }
+        }
+    }
+
+    if has_part {
+        parts.push(current);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_quoted_arguments() {
+        assert_eq!(
+            split_command(r#""C:\Program Files\llm\run.exe" --from {source} --prompt "" -t  {target}"#),
+            vec![r"C:\Program Files\llm\run.exe", "--from", "{source}", "--prompt", "", "-t", "{target}"]
+        );
+        let error = CommandRunner::new("   ").err().unwrap();
+        assert_eq!(error.code, crate::error::ErrorCode::InvalidInput);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn streams_command_output() {
+        let runner = CommandRunner::new("sed s/{source}/{target}/").unwrap();
+        let request = RunRequest {
+            text: "en 你好".to_string(),
+            source_language: "en".to_string(),
+            target_language: "zh-CN".to_string(),
+        };
+
+        let mut output = String::new();
+        runner
+            .run(&request, &mut |chunk| {
+                output.push_str(&chunk);
+                true
+            })
+            .unwrap();
+        assert_eq!(output, "zh-CN 你好");
+
+        let failing = CommandRunner::new("sh -c \"exit 3\"").unwrap();
+        let error = failing.run(&request, &mut |_| true).unwrap_err();
+        assert_eq!(error.key, "error.offline.model_failed");
+    }
+}
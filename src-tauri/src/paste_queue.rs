@@ -0,0 +1,325 @@
+// 粘贴队列：开启后新复制的内容依次进入队列，每次粘贴取出下一项放到剪贴板
+//
+// 队列只保存剪贴板记录的 ID，内容在取出时从数据库读取，已删除的记录直接跳过。
+// 取出顺序由设置中的 paste_queue_order 决定；队列不持久化，关闭或重启后清空。
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::database::ClipboardItem;
+use crate::error::{AppError, AppResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueOrder {
+    // 先复制先粘贴
+    Fifo,
+    // 后复制先粘贴
+    Lifo,
+}
+
+impl QueueOrder {
+    // 由设置中的顺序代码解析，无法识别时按复制顺序
+    pub fn from_code(code: &str) -> Self {
+        if code.trim().eq_ignore_ascii_case("lifo") {
+            QueueOrder::Lifo
+        } else {
+            QueueOrder::Fifo
+        }
+    }
+
+    // 解析前端传入的顺序代码，无法识别时返回错误
+    pub fn parse(code: &str) -> AppResult<Self> {
+        match code.trim().to_ascii_lowercase().as_str() {
+            "fifo" => Ok(QueueOrder::Fifo),
+            "lifo" => Ok(QueueOrder::Lifo),
+            _ => Err(AppError::invalid_input(format!("无效的粘贴队列顺序: {}", code))
+                .with_key("error.paste_queue.invalid_order")
+                .with_context("order", code)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueueOrder::Fifo => "fifo",
+            QueueOrder::Lifo => "lifo",
+        }
+    }
+
+    pub fn current() -> Self {
+        Self::from_code(&crate::settings::get_global_settings().paste_queue_order)
+    }
+}
+
+#[derive(Debug, Default)]
+struct PasteQueue {
+    enabled: bool,
+    // 按复制顺序排列
+    entries: VecDeque<i64>,
+}
+
+impl PasteQueue {
+    fn push(&mut self, id: i64) {
+        self.entries.push_back(id);
+    }
+
+    fn pop(&mut self, order: QueueOrder) -> Option<i64> {
+        match order {
+            QueueOrder::Fifo => self.entries.pop_front(),
+            QueueOrder::Lifo => self.entries.pop_back(),
+        }
+    }
+
+    // index 为按取出顺序的位置
+    fn remove(&mut self, index: usize, order: QueueOrder) -> Option<i64> {
+        let position = match order {
+            QueueOrder::Fifo => index,
+            QueueOrder::Lifo => self.entries.len().checked_sub(index + 1)?,
+        };
+        self.entries.remove(position)
+    }
+
+    // 按取出顺序列出
+    fn ordered(&self, order: QueueOrder) -> Vec<i64> {
+        match order {
+            QueueOrder::Fifo => self.entries.iter().copied().collect(),
+            QueueOrder::Lifo => self.entries.iter().rev().copied().collect(),
+        }
+    }
+}
+
+static QUEUE: Lazy<Mutex<PasteQueue>> = Lazy::new(|| Mutex::new(PasteQueue::default()));
+
+// 队列状态（paste-queue-changed 事件和 get_paste_queue 命令的内容）
+#[derive(Debug, Clone, Serialize)]
+pub struct PasteQueueState {
+    pub enabled: bool,
+    pub order: &'static str,
+    // 按取出顺序排列
+    pub items: Vec<ClipboardItem>,
+}
+
+pub fn is_enabled() -> bool {
+    QUEUE.lock().unwrap().enabled
+}
+
+// 队列已开启且有待粘贴的内容（系统粘贴钩子据此决定是否接管 Ctrl+V）
+#[cfg(windows)]
+pub fn has_pending() -> bool {
+    let queue = QUEUE.lock().unwrap();
+    queue.enabled && !queue.entries.is_empty()
+}
+
+pub fn len() -> usize {
+    QUEUE.lock().unwrap().entries.len()
+}
+
+// 开启或关闭队列模式，关闭时清空队列
+pub fn set_enabled(app: &AppHandle, enabled: bool) {
+    {
+        let mut queue = QUEUE.lock().unwrap();
+        queue.enabled = enabled;
+        if !enabled {
+            queue.entries.clear();
+        }
+    }
+    emit_changed(app);
+}
+
+// 修改取出顺序并保存到设置
+pub fn set_order(app: &AppHandle, order: QueueOrder) -> AppResult<()> {
+    let mut settings = crate::settings::get_global_settings();
+    settings.paste_queue_order = order.as_str().to_string();
+    crate::settings::update_global_settings(settings)?;
+
+    let settings_json = crate::settings::get_global_settings().to_json();
+    for label in ["main", "settings"] {
+        if let Some(window) = app.get_webview_window(label) {
+            let _ = window.emit("settings-changed", settings_json.clone());
+        }
+    }
+    emit_changed(app);
+    Ok(())
+}
+
+// 新复制的内容加入队列（队列未开启时忽略）
+pub fn push(app: &AppHandle, id: i64) {
+    {
+        let mut queue = QUEUE.lock().unwrap();
+        if !queue.enabled {
+            return;
+        }
+        queue.push(id);
+    }
+    emit_changed(app);
+}
+
+// 取出下一项，跳过已被删除的记录
+pub fn pop_next(app: &AppHandle) -> Option<i64> {
+    let order = QueueOrder::current();
+    let next = loop {
+        let id = QUEUE.lock().unwrap().pop(order);
+        match id {
+            Some(id) => {
+                if matches!(crate::database::get_clipboard_item_by_id(id), Ok(Some(_))) {
+                    break Some(id);
+                }
+            }
+            None => break None,
+        }
+    };
+    emit_changed(app);
+    next
+}
+
+// 移除按取出顺序位于 index 的项目
+pub fn remove(app: &AppHandle, index: usize) -> AppResult<()> {
+    let order = QueueOrder::current();
+    {
+        let mut queue = QUEUE.lock().unwrap();
+        let len = queue.entries.len();
+        if len == 0 {
+            return Err(empty_queue());
+        }
+        queue.remove(index, order).ok_or_else(|| {
+            AppError::invalid_input(format!("粘贴队列中没有第 {} 项（共 {} 项）", index + 1, len))
+                .with_key("error.paste_queue.invalid_index")
+                .with_context("index", index + 1)
+                .with_context("len", len)
+        })?;
+    }
+    emit_changed(app);
+    Ok(())
+}
+
+pub fn clear(app: &AppHandle) {
+    QUEUE.lock().unwrap().entries.clear();
+    emit_changed(app);
+}
+
+pub fn state() -> PasteQueueState {
+    let order = QueueOrder::current();
+    let (enabled, ids) = {
+        let queue = QUEUE.lock().unwrap();
+        (queue.enabled, queue.ordered(order))
+    };
+    let items = ids
+        .into_iter()
+        .filter_map(|id| crate::database::get_clipboard_item_by_id(id).ok().flatten())
+        .collect();
+    PasteQueueState {
+        enabled,
+        order: order.as_str(),
+        items,
+    }
+}
+
+// 按取出顺序把队列中的文本用分隔符合并后放到剪贴板，并清空队列
+// separator 为空时使用设置中的分隔符；图片和文件不参与合并
+pub fn merge(app: &AppHandle, separator: Option<String>) -> AppResult<String> {
+    let ids = QUEUE.lock().unwrap().ordered(QueueOrder::current());
+    if ids.is_empty() {
+        return Err(empty_queue());
+    }
+
+    let texts: Vec<String> = ids
+        .into_iter()
+        .filter_map(|id| {
+            crate::services::paste_service::load_content(
+                &crate::services::paste_service::PasteContentParams {
+                    clipboard_id: Some(id),
+                    quick_text_id: None,
+                    mime_type: None,
+                },
+            )
+            .ok()
+        })
+        .map(|(content, _)| content)
+        .filter(|content| is_text(content))
+        .collect();
+    if texts.is_empty() {
+        return Err(AppError::invalid_input("粘贴队列中没有可合并的文本")
+            .with_key("error.paste_queue.no_text"));
+    }
+
+    let separator =
+        separator.unwrap_or_else(|| crate::settings::get_global_settings().paste_queue_separator);
+    let merged = texts.join(&separator);
+
+    // 合并结果会被记录到历史，按内部粘贴处理以免重新进入队列
+    crate::clipboard_monitor::start_pasting_operation();
+    let result = crate::clipboard_content::set_clipboard_content(merged.clone());
+    std::thread::spawn(|| {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        crate::clipboard_monitor::end_pasting_operation();
+    });
+    result?;
+
+    clear(app);
+    Ok(merged)
+}
+
+fn empty_queue() -> AppError {
+    AppError::not_found("粘贴队列为空").with_key("error.paste_queue.empty")
+}
+
+fn is_text(content: &str) -> bool {
+    !content.starts_with("files:")
+        && !content.starts_with("image:")
+        && !content.starts_with("data:image/")
+}
+
+fn emit_changed(app: &AppHandle) {
+    let _ = app.emit("paste-queue-changed", state());
+    crate::tray::refresh_paste_queue_menu();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue_of(ids: &[i64]) -> PasteQueue {
+        let mut queue = PasteQueue::default();
+        for id in ids {
+            queue.push(*id);
+        }
+        queue
+    }
+
+    #[test]
+    fn pops_in_configured_order() {
+        let mut queue = queue_of(&[1, 2, 3]);
+        assert_eq!(queue.pop(QueueOrder::Fifo), Some(1));
+        assert_eq!(queue.pop(QueueOrder::Lifo), Some(3));
+        assert_eq!(queue.pop(QueueOrder::Fifo), Some(2));
+        assert_eq!(queue.pop(QueueOrder::Fifo), None);
+    }
+
+    #[test]
+    fn lists_and_removes_by_pop_position() {
+        let mut queue = queue_of(&[1, 2, 3, 2]);
+        assert_eq!(queue.ordered(QueueOrder::Fifo), vec![1, 2, 3, 2]);
+        assert_eq!(queue.ordered(QueueOrder::Lifo), vec![2, 3, 2, 1]);
+
+        assert_eq!(queue.remove(1, QueueOrder::Lifo), Some(3));
+        assert_eq!(queue.ordered(QueueOrder::Fifo), vec![1, 2, 2]);
+        assert_eq!(queue.remove(0, QueueOrder::Fifo), Some(1));
+        assert_eq!(queue.remove(5, QueueOrder::Lifo), None);
+        assert_eq!(queue.ordered(QueueOrder::Fifo), vec![2, 2]);
+    }
+
+    #[test]
+    fn parses_order_codes() {
+        assert_eq!(QueueOrder::from_code("lifo"), QueueOrder::Lifo);
+        assert_eq!(QueueOrder::from_code(" LIFO "), QueueOrder::Lifo);
+        assert_eq!(QueueOrder::from_code("fifo"), QueueOrder::Fifo);
+        assert_eq!(QueueOrder::from_code(""), QueueOrder::Fifo);
+
+        assert_eq!(QueueOrder::parse(" LIFO ").unwrap(), QueueOrder::Lifo);
+        let error = QueueOrder::parse("random").unwrap_err();
+        assert_eq!(error.code, crate::error::ErrorCode::InvalidInput);
+        assert_eq!(error.key, "error.paste_queue.invalid_order");
+    }
+}
@@ -106,3 +106,41 @@ pub fn windows_paste() -> bool {
 pub fn windows_paste() -> bool {
     false
 }
+
+// 将光标向左移动指定字符数（用于片段模板的 {{cursor}}）
+#[cfg(windows)]
+pub fn move_caret_left(count: usize) -> bool {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        VK_LEFT,
+    };
+
+    if count == 0 {
+        return true;
+    }
+
+    let mut inputs: Vec<INPUT> = Vec::with_capacity(count * 2);
+    for flags in std::iter::repeat([KEYBD_EVENT_FLAGS(0), KEYEVENTF_KEYUP])
+        .take(count)
+        .flatten()
+    {
+        let mut input = INPUT::default();
+        input.r#type = INPUT_KEYBOARD;
+        input.Anonymous.ki = KEYBDINPUT {
+            wVk: VK_LEFT,
+            wScan: 0,
+            dwFlags: flags,
+            time: 0,
+            dwExtraInfo: 0,
+        };
+        inputs.push(input);
+    }
+
+    unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) != 0 }
+}
+
+// 非Windows平台的空实现
+#[cfg(not(windows))]
+pub fn move_caret_left(_count: usize) -> bool {
+    false
+}
@@ -14,6 +14,8 @@ static PIN_IMAGE_DATA_MAP: OnceCell<Mutex<HashMap<String, PinImageData>>> = Once
 #[derive(Clone, Debug)]
 struct PinImageData {
     file_path: String,
+    // 来自历史图片时的图片ID（加密的图片由前端通过 qcimage 协议读取）
+    image_id: Option<String>,
     width: u32,
     height: u32,
 }
@@ -48,6 +50,7 @@ pub async fn show_pin_image_window(
         window_label.clone(),
         PinImageData {
             file_path,
+            image_id: None,
             width,
             height,
         },
@@ -75,9 +78,12 @@ pub async fn show_pin_image_from_file(
     app: AppHandle,
     file_path: String,
 ) -> Result<(), String> {
-    // 读取图片以获取尺寸
-    let image_data = std::fs::read(&file_path)
+    // 读取图片以获取尺寸（加密的图片在内存中解密）
+    let image_data = crate::encryption::read_image_file(std::path::Path::new(&file_path))
         .map_err(|e| format!("读取图片文件失败: {}", e))?;
+    let image_id = crate::image_manager::get_image_manager()
+        .ok()
+        .and_then(|manager| manager.lock().ok()?.image_id_for_path(&file_path));
     
     let img = image::load_from_memory(&image_data)
         .map_err(|e| format!("解析图片失败: {}", e))?;
@@ -95,6 +101,7 @@ pub async fn show_pin_image_from_file(
             window_label.clone(),
             PinImageData {
                 file_path,
+                image_id,
                 width,
                 height,
             },
@@ -199,6 +206,7 @@ pub fn get_pin_image_data(window: WebviewWindow) -> Result<serde_json::Value, St
         if let Some(data) = map.get(window.label()) {
             return Ok(json!({
                 "file_path": data.file_path,
+                "image_id": data.image_id,
                 "width": data.width,
                 "height": data.height
             }));
@@ -224,7 +232,7 @@ pub fn copy_pin_image_to_clipboard(window: WebviewWindow) -> Result<(), String>
                 use crate::clipboard_content::{data_url_to_bgra_and_png, set_windows_clipboard_image_with_file};
                 
                 // 从文件读取图片数据
-                let image_data = std::fs::read(&file_path)
+                let image_data = crate::encryption::read_image_file(std::path::Path::new(&file_path))
                     .map_err(|e| format!("读取图片文件失败: {}", e))?;
                 
                 // 转换为 base64 data URL
@@ -235,8 +243,13 @@ pub fn copy_pin_image_to_clipboard(window: WebviewWindow) -> Result<(), String>
                 // 转换为 BGRA 和 PNG 格式
                 let (bgra, png_bytes, width, height) = data_url_to_bgra_and_png(&data_url)?;
                 
-                // 同时设置图片数据和文件路径
-                set_windows_clipboard_image_with_file(&bgra, &png_bytes, width, height, Some(&file_path))?;
+                // 同时设置图片数据和文件路径（加密的图片文件不提供路径）
+                let file_path_opt = if crate::encryption::is_encrypted_file(std::path::Path::new(&file_path)) {
+                    None
+                } else {
+                    Some(file_path.as_str())
+                };
+                set_windows_clipboard_image_with_file(&bgra, &png_bytes, width, height, file_path_opt)?;
             }
             
             #[cfg(not(windows))]
@@ -284,8 +297,10 @@ pub async fn save_pin_image_as(app: AppHandle, window: WebviewWindow) -> Result<
         .blocking_save_file();
     
     if let Some(file_path) = file_path {
-        // 复制文件到目标位置
-        std::fs::copy(&source_path, file_path.as_path().unwrap())
+        // 写出图片到目标位置（加密的图片解密后写出）
+        let image_data = crate::encryption::read_image_file(std::path::Path::new(&source_path))
+            .map_err(|e| format!("读取图片文件失败: {}", e))?;
+        std::fs::write(file_path.as_path().unwrap(), image_data)
             .map_err(|e| format!("保存图片失败: {}", e))?;
         
         Ok(())
@@ -30,6 +30,7 @@ pub async fn show_input(
     // 解析输入框类型
     let input_type_enum = match input_type.as_deref() {
         Some("number") => InputType::Number,
+        Some("password") => InputType::Password,
         _ => InputType::Text,
     };
     
@@ -8,6 +8,7 @@ use tauri::{AppHandle, Manager, WebviewWindowBuilder};
 pub enum InputType {
     Text,
     Number,
+    Password,
 }
 
 impl Default for InputType {
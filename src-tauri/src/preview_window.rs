@@ -339,6 +339,44 @@ pub fn update_preview_source(tab: String, group_id: String) -> Result<(), String
     Ok(())
 }
 
+// 切换当前预览项的置顶状态（仅剪贴板历史），预览继续停留在该项目上
+pub fn toggle_current_preview_item_pin(app: &AppHandle) -> Result<(), String> {
+    if !PREVIEW_WINDOW_VISIBLE.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let state = get_main_window_state()?;
+    if state["tab"] != "clipboard" {
+        return Ok(());
+    }
+
+    let index = get_preview_index();
+    let items = crate::commands::get_clipboard_history();
+    let id = match items.get(index) {
+        Some(item) => item.id,
+        None => return Err(format!("索引 {} 超出范围", index)),
+    };
+
+    crate::services::clipboard_service::ClipboardService::toggle_pinned(app, id)?;
+
+    // 置顶状态改变后项目位置随之改变
+    let new_index = crate::commands::get_clipboard_history()
+        .iter()
+        .position(|item| item.id == id)
+        .unwrap_or(0);
+    PREVIEW_CURRENT_INDEX.store(new_index, Ordering::SeqCst);
+
+    if let Some(window) = PREVIEW_WINDOW_HANDLE
+        .get()
+        .and_then(|handle| handle.lock().ok())
+        .and_then(|guard| guard.clone())
+    {
+        let _ = window.emit("preview-index-changed", json!({ "index": new_index }));
+    }
+
+    Ok(())
+}
+
 // 获取当前数据源的长度
 fn get_current_data_source_length() -> usize {
     let state_handle = MAIN_WINDOW_STATE.get_or_init(|| {
@@ -437,6 +475,7 @@ pub async fn paste_current_preview_item() -> Result<(), String> {
                     let params = crate::services::paste_service::PasteContentParams {
                         clipboard_id: Some(items[index].id),
                         quick_text_id: None,
+                        mime_type: None,
                     };
                     crate::commands::paste_content(params, main_window.clone()).await?;
                 } else {
@@ -458,6 +497,7 @@ pub async fn paste_current_preview_item() -> Result<(), String> {
                     let params = crate::services::paste_service::PasteContentParams {
                         clipboard_id: None,
                         quick_text_id: Some(quick_text.id.clone()),
+                        mime_type: None,
                     };
                     crate::commands::paste_content(params, main_window.clone()).await?;
                 }
@@ -129,7 +129,8 @@ pub fn move_quick_text_within_group(
     group_texts.insert(new_index, item);
 
     // 更新排序
-    database::reorder_favorite_items(&group_texts)
+    database::reorder_favorite_items(&group_texts)?;
+    Ok(())
 }
 
 // 移动常用文本到指定分组
@@ -0,0 +1,461 @@
+// 历史记录保留策略模块
+//
+// 按时间和图片占用空间清理剪贴板历史，置顶的项目和收藏中存在的内容不会被清理；
+// 同时负责删除到达自动过期时间的记录
+
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::AppResult;
+use crate::settings::AppSettings;
+
+// 启动后首次清理的延迟
+const STARTUP_DELAY_SECS: u64 = 30;
+// 后台清理间隔
+const SWEEP_INTERVAL_SECS: u64 = 10 * 60;
+// 自动过期记录的检查间隔
+const EXPIRY_CHECK_INTERVAL_SECS: u64 = 30;
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+// 豁免条件：置顶的项目和内容已被收藏的剪贴板项目不参与清理
+const EXEMPT_CONDITION: &str =
+    "c.pinned = 0 AND NOT EXISTS (SELECT 1 FROM favorites f WHERE f.content = c.content)";
+
+// 按时间清理的条件（以最近一次复制时间 updated_at 为准）
+const TEXT_AGE_CONDITION: &str = "c.content_type != 'image' AND c.updated_at < ?1";
+const IMAGE_AGE_CONDITION: &str = "c.content_type = 'image' AND c.updated_at < ?1";
+// 参与图片占用空间统计的项目
+const IMAGE_CONDITION: &str = "c.content_type = 'image' AND c.image_id IS NOT NULL";
+
+static SWEEPER_STARTED: AtomicBool = AtomicBool::new(false);
+
+// 防止后台清理与手动清理同时执行
+static SWEEP_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// 保留策略（0 表示不限制）
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    pub text_max_age_days: u32,
+    pub image_max_age_days: u32,
+    pub image_storage_limit_mb: u64,
+}
+
+impl RetentionPolicy {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            text_max_age_days: settings.retention_text_days,
+            image_max_age_days: settings.retention_image_days,
+            image_storage_limit_mb: settings.retention_image_storage_mb,
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.text_max_age_days == 0 && self.image_max_age_days == 0 && self.image_storage_limit_mb == 0
+    }
+}
+
+// 清理结果（通过 clipboard-retention-swept 事件发送给前端）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetentionReport {
+    pub deleted_item_ids: Vec<i64>,
+    pub expired_text_count: usize,
+    pub expired_image_count: usize,
+    pub storage_limit_image_count: usize,
+    pub auto_expired_count: usize,
+    pub deleted_image_files: usize,
+    pub freed_bytes: u64,
+    pub swept_at: i64,
+}
+
+impl RetentionReport {
+    pub fn is_empty(&self) -> bool {
+        self.deleted_item_ids.is_empty() && self.deleted_image_files == 0
+    }
+}
+
+// 待删除的剪贴板项目
+struct SweepCandidate {
+    id: i64,
+    image_id: Option<String>,
+    html_content: Option<String>,
+}
+
+// 启动后台清理线程
+pub fn start_retention_sweeper(app_handle: AppHandle) {
+    if SWEEPER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(STARTUP_DELAY_SECS));
+
+        let mut last_policy_sweep: Option<std::time::Instant> = None;
+        loop {
+            let settings = crate::settings::get_global_settings();
+            let policy_due = last_policy_sweep
+                .is_none_or(|t| t.elapsed() >= Duration::from_secs(SWEEP_INTERVAL_SECS));
+
+            // 自动过期的记录每次都检查，保留策略按较长间隔执行
+            let policy = if settings.retention_enabled && policy_due {
+                last_policy_sweep = Some(std::time::Instant::now());
+                RetentionPolicy::from_settings(&settings)
+            } else {
+                RetentionPolicy::default()
+            };
+
+            if let Err(e) = run_sweep_and_notify(&app_handle, &policy) {
+                println!("历史记录保留策略清理失败: {}", e);
+            }
+
+            std::thread::sleep(Duration::from_secs(EXPIRY_CHECK_INTERVAL_SECS));
+        }
+    });
+}
+
+// 执行清理并通知前端
+pub fn run_sweep_and_notify(
+    app_handle: &AppHandle,
+    policy: &RetentionPolicy,
+) -> Result<RetentionReport, String> {
+    let report = sweep(policy)?;
+
+    if !report.is_empty() {
+        println!(
+            "保留策略清理完成: 删除 {} 条记录, {} 个图片文件, 释放 {} 字节",
+            report.deleted_item_ids.len(),
+            report.deleted_image_files,
+            report.freed_bytes
+        );
+
+        let _ = app_handle.emit("clipboard-retention-swept", &report);
+        if let Some(main_window) = app_handle.get_webview_window("main") {
+            let _ = main_window.emit("refresh-clipboard-history", ());
+        }
+    }
+
+    Ok(report)
+}
+
+// 按策略清理剪贴板历史
+pub fn sweep(policy: &RetentionPolicy) -> Result<RetentionReport, String> {
+    let _guard = SWEEP_LOCK.lock().map_err(|e| format!("获取清理锁失败: {}", e))?;
+
+    let now = chrono::Local::now().timestamp();
+    let mut report = RetentionReport {
+        swept_at: now,
+        ..Default::default()
+    };
+
+    // 数据未解锁时无法判断图片引用，等待解锁后再清理
+    if crate::encryption::is_locked() {
+        return Ok(report);
+    }
+
+    // 删除已到期的记录（敏感内容自动过期，不受置顶和收藏豁免）
+    let auto_expired = query_auto_expired(now)?;
+    report.auto_expired_count = auto_expired.len();
+    delete_candidates(&auto_expired, &mut report)?;
+
+    if policy.is_unlimited() {
+        return Ok(report);
+    }
+
+    // 按时间清理
+    let mut expired = Vec::new();
+    if policy.text_max_age_days > 0 {
+        let cutoff = now - policy.text_max_age_days as i64 * SECONDS_PER_DAY;
+        let items = query_candidates(TEXT_AGE_CONDITION, params![cutoff])?;
+        report.expired_text_count = items.len();
+        expired.extend(items);
+    }
+    if policy.image_max_age_days > 0 {
+        let cutoff = now - policy.image_max_age_days as i64 * SECONDS_PER_DAY;
+        let items = query_candidates(IMAGE_AGE_CONDITION, params![cutoff])?;
+        report.expired_image_count = items.len();
+        expired.extend(items);
+    }
+    delete_candidates(&expired, &mut report)?;
+
+    // 按图片占用空间清理（从最旧的图片开始删除）
+    if policy.image_storage_limit_mb > 0 {
+        let over_limit = select_over_storage_limit(policy.image_storage_limit_mb * 1024 * 1024)?;
+        report.storage_limit_image_count = over_limit.len();
+        delete_candidates(&over_limit, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+// 查询符合条件且未被豁免的剪贴板项目
+fn query_candidates(condition: &str, params: impl rusqlite::Params) -> AppResult<Vec<SweepCandidate>> {
+    crate::database::with_connection(|conn| candidates_in(conn, condition, params))
+}
+
+// 按最近一次复制时间从旧到新列出符合条件且未被豁免的项目
+fn candidates_in(
+    conn: &Connection,
+    condition: &str,
+    params: impl rusqlite::Params,
+) -> rusqlite::Result<Vec<SweepCandidate>> {
+    let sql = format!(
+        "SELECT c.id, c.image_id, qc_decrypt(c.html_content) FROM clipboard c
+         WHERE {} AND {} ORDER BY c.updated_at ASC",
+        condition, EXEMPT_CONDITION
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params, |row| {
+        Ok(SweepCandidate {
+            id: row.get(0)?,
+            image_id: row.get(1)?,
+            html_content: row.get(2)?,
+        })
+    })?;
+    rows.collect()
+}
+
+// 查询已到期的记录
+fn query_auto_expired(now: i64) -> AppResult<Vec<SweepCandidate>> {
+    crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, image_id, qc_decrypt(html_content) FROM clipboard WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+        )?;
+        let rows = stmt.query_map(params![now], |row| {
+            Ok(SweepCandidate {
+                id: row.get(0)?,
+                image_id: row.get(1)?,
+                html_content: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+// 选出需要删除的图片项目，使图片总占用不超过限制
+// 只统计可以删除的图片，置顶和收藏中的图片既不计入占用也不会被删除
+fn select_over_storage_limit(limit_bytes: u64) -> Result<Vec<SweepCandidate>, String> {
+    let images = query_candidates(IMAGE_CONDITION, [])?;
+    if images.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let image_manager = crate::image_manager::get_image_manager()?;
+    let manager = image_manager
+        .lock()
+        .map_err(|e| format!("获取图片管理器锁失败: {}", e))?;
+    let sized = images
+        .into_iter()
+        .map(|item| {
+            let size = item
+                .image_id
+                .as_deref()
+                .map(|image_id| manager.get_image_file_size(image_id))
+                .unwrap_or(0);
+            (item, size)
+        })
+        .collect();
+
+    Ok(pick_over_limit(sized, limit_bytes))
+}
+
+// 从最旧的项目开始选出，直到剩余总大小不超过限制
+fn pick_over_limit(items: Vec<(SweepCandidate, u64)>, limit_bytes: u64) -> Vec<SweepCandidate> {
+    let mut total: u64 = items.iter().map(|(_, size)| size).sum();
+    let mut selected = Vec::new();
+    for (item, size) in items {
+        if total <= limit_bytes {
+            break;
+        }
+        total = total.saturating_sub(size);
+        selected.push(item);
+    }
+    selected
+}
+
+// 删除剪贴板记录，并删除不再被引用的图片文件和 image_data 记录
+fn delete_candidates(
+    candidates: &[SweepCandidate],
+    report: &mut RetentionReport,
+) -> Result<(), String> {
+    if candidates.is_empty() {
+        return Ok(());
+    }
+
+    crate::database::with_connection(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        for item in candidates {
+            tx.execute("DELETE FROM clipboard WHERE id = ?1", params![item.id])?;
+        }
+        tx.commit()
+    })?;
+
+    report
+        .deleted_item_ids
+        .extend(candidates.iter().map(|item| item.id));
+
+    // 收集被删除记录引用的图片
+    let mut image_ids = Vec::new();
+    for item in candidates {
+        if let Some(image_id) = &item.image_id {
+            if !image_ids.contains(image_id) {
+                image_ids.push(image_id.clone());
+            }
+        }
+        if let Some(html) = &item.html_content {
+            crate::clipboard_history::extract_image_ids_from_html(html, &mut image_ids);
+        }
+    }
+
+    if image_ids.is_empty() {
+        return Ok(());
+    }
+
+    let used_image_ids = crate::clipboard_history::collect_used_image_ids();
+    let image_manager = crate::image_manager::get_image_manager()?;
+    let manager = image_manager
+        .lock()
+        .map_err(|e| format!("获取图片管理器锁失败: {}", e))?;
+
+    for image_id in image_ids {
+        if used_image_ids.contains(&image_id) {
+            continue;
+        }
+
+        let size = manager.get_image_file_size(&image_id);
+        match manager.delete_image(&image_id) {
+            Ok(()) => {
+                report.deleted_image_files += 1;
+                report.freed_bytes += size;
+            }
+            Err(e) => println!("删除图片 {} 失败: {}", image_id, e),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 100 * SECONDS_PER_DAY;
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.create_scalar_function(
+            "qc_decrypt",
+            1,
+            rusqlite::functions::FunctionFlags::SQLITE_UTF8,
+            |ctx| ctx.get::<Option<String>>(0),
+        )
+        .unwrap();
+        conn.execute_batch(
+            "CREATE TABLE clipboard (
+                id INTEGER PRIMARY KEY,
+                content TEXT NOT NULL,
+                html_content TEXT,
+                content_type TEXT NOT NULL,
+                image_id TEXT,
+                item_order INTEGER NOT NULL DEFAULT 0,
+                updated_at INTEGER NOT NULL,
+                pinned INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE favorites (id TEXT PRIMARY KEY, content TEXT NOT NULL);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn insert(conn: &Connection, id: i64, content_type: &str, age_days: i64, pinned: bool) {
+        let image_id = (content_type == "image").then(|| format!("img{}", id));
+        conn.execute(
+            "INSERT INTO clipboard (id, content, content_type, image_id, item_order, updated_at, pinned)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                id,
+                format!("item {}", id),
+                content_type,
+                image_id,
+                age_days,
+                NOW - age_days * SECONDS_PER_DAY,
+                pinned
+            ],
+        )
+        .unwrap();
+    }
+
+    fn ids(items: &[SweepCandidate]) -> Vec<i64> {
+        items.iter().map(|item| item.id).collect()
+    }
+
+    #[test]
+    fn age_rules_skip_pinned_and_favorited_items() {
+        let conn = test_db();
+        insert(&conn, 1, "text", 40, false);
+        insert(&conn, 2, "text", 10, false);
+        insert(&conn, 3, "text", 50, true);
+        insert(&conn, 4, "text", 60, false);
+        insert(&conn, 5, "image", 40, false);
+        conn.execute("INSERT INTO favorites VALUES ('f', 'item 4')", [])
+            .unwrap();
+
+        let cutoff = NOW - 30 * SECONDS_PER_DAY;
+        let texts = candidates_in(&conn, TEXT_AGE_CONDITION, params![cutoff]).unwrap();
+        assert_eq!(ids(&texts), vec![1]);
+        let images = candidates_in(&conn, IMAGE_AGE_CONDITION, params![cutoff]).unwrap();
+        assert_eq!(ids(&images), vec![5]);
+    }
+
+    #[test]
+    fn count_rule_keeps_newest_and_pinned_items() {
+        let conn = test_db();
+        for id in 1..=5 {
+            insert(&conn, id, "text", id, id == 5);
+        }
+
+        assert_eq!(crate::database::delete_over_history_limit(&conn, 2).unwrap(), 2);
+
+        let mut stmt = conn.prepare("SELECT id FROM clipboard ORDER BY id").unwrap();
+        let remaining: Vec<i64> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(remaining, vec![1, 2, 5]);
+    }
+
+    #[test]
+    fn size_rule_counts_only_deletable_images() {
+        let conn = test_db();
+        insert(&conn, 1, "image", 3, false);
+        insert(&conn, 2, "image", 2, true);
+        insert(&conn, 3, "image", 1, false);
+        insert(&conn, 4, "text", 5, false);
+
+        let images = candidates_in(&conn, IMAGE_CONDITION, []).unwrap();
+        assert_eq!(ids(&images), vec![1, 3]);
+
+        // 置顶的 2 号图片不计入占用：可删除的共 150，限制 100 时只需删除最旧的 1 号
+        let sized = images.into_iter().map(|item| (item, 75)).collect();
+        assert_eq!(ids(&pick_over_limit(sized, 100)), vec![1]);
+    }
+
+    #[test]
+    fn size_rule_deletes_oldest_until_under_limit() {
+        let item = |id| SweepCandidate {
+            id,
+            image_id: None,
+            html_content: None,
+        };
+        let sized = || vec![(item(1), 40), (item(2), 30), (item(3), 50)];
+
+        assert!(pick_over_limit(sized(), 120).is_empty());
+        assert_eq!(ids(&pick_over_limit(sized(), 100)), vec![1]);
+        assert_eq!(ids(&pick_over_limit(sized(), 50)), vec![1, 2]);
+        assert_eq!(ids(&pick_over_limit(sized(), 0)), vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,349 @@
+// AI操作服务
+//
+// 对剪贴板历史项或常用文本执行自定义AI操作（见 ai_actions），
+// 流式进度通过 ai-action-* 事件通知前端（带 requestId），可通过 cancel_ai_action 按请求取消
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, WebviewWindow};
+use tokio::sync::Notify;
+
+use super::paste_service::{self, PasteContentParams};
+use super::translation_service::TranslationGuard;
+use crate::ai_actions::{self, OutputMode};
+use crate::ai_translator::{AITranslator, TranslationConfig, TranslationResult};
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::settings::{self, AIAction};
+
+// 单个AI操作的取消标记
+#[derive(Default)]
+struct CancelToken {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    // 等待取消（先注册等待再检查标记，避免错过通知）
+    async fn cancelled(&self) {
+        loop {
+            let notified = self.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+// 正在进行的AI操作，按请求ID保存各自的取消标记
+static ACTIVE_ACTIONS: Lazy<Mutex<HashMap<u64, Arc<CancelToken>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 登记一次AI操作，结束（包括出错返回）时自动移除
+struct ActiveAction {
+    id: u64,
+    token: Arc<CancelToken>,
+}
+
+impl ActiveAction {
+    fn register() -> Self {
+        let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        let token = Arc::new(CancelToken::default());
+        ACTIVE_ACTIONS.lock().unwrap().insert(id, token.clone());
+        Self { id, token }
+    }
+}
+
+impl Drop for ActiveAction {
+    fn drop(&mut self) {
+        ACTIVE_ACTIONS.lock().unwrap().remove(&self.id);
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AIActionParams {
+    // 剪贴板历史项ID
+    pub clipboard_id: Option<i64>,
+    // 常用文本ID
+    pub quick_text_id: Option<String>,
+    // 要执行的AI操作 id
+    pub action_id: String,
+}
+
+// 执行AI操作并按操作的输出方式处理结果，返回完整结果文本
+pub async fn run_ai_action(params: AIActionParams, window: WebviewWindow) -> AppResult<String> {
+    let active = ActiveAction::register();
+
+    let settings = settings::get_global_settings();
+    let action = ai_actions::find_action(&settings, &params.action_id)?;
+
+    let (content, _) = paste_service::load_content(&PasteContentParams {
+        clipboard_id: params.clipboard_id,
+        quick_text_id: params.quick_text_id.clone(),
+        mime_type: None,
+    })?;
+
+    if content.starts_with("files:")
+        || content.starts_with("data:image/")
+        || content.starts_with("image:")
+    {
+        return Err(AppError::invalid_input("只能对文本内容执行AI操作").with_key("error.ai_action.text_only"));
+    }
+
+    let ai_config = ai_actions::ai_config_for_action(&settings, &action);
+    if !ai_config.is_valid() {
+        return Err(AppError::new(ErrorCode::Settings, "AI配置不完整，请检查API密钥和模型设置")
+            .with_key("error.ai_action.config_incomplete"));
+    }
+
+    let translator = AITranslator::new(TranslationConfig {
+        ai_config,
+        ..Default::default()
+    })
+    .map_err(|e| AppError::translation(format!("创建AI请求失败: {}", e)))?;
+
+    let output_mode = OutputMode::from_setting(&action.output_mode);
+
+    // 模拟输入时启用取消快捷键并禁用导航按键，与流式翻译相同
+    let _guard = if output_mode == OutputMode::Type {
+        #[cfg(windows)]
+        {
+            crate::global_state::enable_ai_translation_cancel();
+            crate::shortcut_interceptor::set_translation_in_progress(true);
+        }
+        crate::text_input_simulator::update_global_input_simulator_config(
+            crate::text_input_simulator::config_from_settings(&settings),
+        );
+        Some(TranslationGuard)
+    } else {
+        None
+    };
+
+    emit_event(
+        &window,
+        "ai-action-start",
+        serde_json::json!({
+            "requestId": active.id,
+            "actionId": action.id,
+            "name": action.name,
+            "outputMode": action.output_mode,
+            "textLength": content.len()
+        }),
+    );
+
+    let result = match stream_action(&translator, &action, &content, output_mode, &window, &active).await {
+        Ok(text) => output_result(&text, output_mode, &window)
+            .await
+            .map(|item_id| (text, item_id)),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok((text, item_id)) => {
+            emit_event(
+                &window,
+                "ai-action-complete",
+                serde_json::json!({
+                    "requestId": active.id,
+                    "actionId": action.id,
+                    "outputMode": action.output_mode,
+                    "text": text,
+                    "itemId": item_id
+                }),
+            );
+            Ok(text)
+        }
+        Err(e) => {
+            emit_event(
+                &window,
+                "ai-action-error",
+                serde_json::json!({
+                    "requestId": active.id,
+                    "actionId": action.id,
+                    "error": e
+                }),
+            );
+            Err(e)
+        }
+    }
+}
+
+// 取消指定的AI操作（request_id 为 ai-action-start 事件中的 requestId），未指定时取消全部
+pub fn cancel_ai_action(request_id: Option<u64>) -> AppResult<()> {
+    let actions = ACTIVE_ACTIONS.lock().unwrap();
+    match request_id {
+        Some(id) => {
+            if let Some(token) = actions.get(&id) {
+                token.cancel();
+                println!("AI操作 {} 已被用户取消", id);
+            }
+        }
+        None => {
+            for token in actions.values() {
+                token.cancel();
+            }
+            println!("AI操作已被用户取消");
+        }
+    }
+    Ok(())
+}
+
+// 接收流式结果，发送进度事件；输入模式下同时模拟键盘输入
+async fn stream_action(
+    translator: &AITranslator,
+    action: &AIAction,
+    content: &str,
+    output_mode: OutputMode,
+    window: &WebviewWindow,
+    active: &ActiveAction,
+) -> AppResult<String> {
+    let mut receiver = translator.chat_stream(ai_actions::build_messages(action, content));
+    let mut text = String::new();
+
+    loop {
+        // 连接和等待首个片段期间同样可以取消；丢弃接收端后后台请求随之结束
+        let result = tokio::select! {
+            result = receiver.recv() => result,
+            _ = active.token.cancelled() => return Err(action_cancelled()),
+        };
+        let Some(result) = result else { break };
+        if active.token.is_cancelled() {
+            return Err(action_cancelled());
+        }
+
+        match result {
+            TranslationResult::Chunk(chunk) => {
+                text.push_str(&chunk);
+                emit_event(
+                    window,
+                    "ai-action-progress",
+                    serde_json::json!({
+                        "requestId": active.id,
+                        "actionId": action.id,
+                        "chunk": chunk,
+                        "length": text.len()
+                    }),
+                );
+
+                if output_mode == OutputMode::Type {
+                    if let Err(e) = crate::text_input_simulator::simulate_text_chunk_input_smart(&chunk).await {
+                        println!("输入失败: {}", e);
+                        // 降级输入失败时继续处理下一个片段
+                        if let Err(fallback_error) =
+                            crate::text_input_simulator::simulate_text_chunk_input_precise(&chunk).await
+                        {
+                            println!("降级输入也失败: {}", fallback_error);
+                        }
+                    }
+                }
+            }
+            TranslationResult::Complete => break,
+            TranslationResult::Error(e) => {
+                return Err(AppError::translation(format!("AI操作失败: {}", e)));
+            }
+        }
+    }
+
+    if active.token.is_cancelled() {
+        return Err(action_cancelled());
+    }
+
+    if text.trim().is_empty() {
+        return Err(AppError::translation("AI返回结果为空").with_key("error.ai_action.empty_result"));
+    }
+
+    Ok(text)
+}
+
+// 按输出方式处理结果，保存为历史记录时返回记录ID
+async fn output_result(
+    text: &str,
+    output_mode: OutputMode,
+    window: &WebviewWindow,
+) -> AppResult<Option<i64>> {
+    match output_mode {
+        // 已在流式接收时输入
+        OutputMode::Type => Ok(None),
+        OutputMode::ReplaceClipboard => {
+            crate::clipboard_monitor::start_pasting_operation();
+            let result = crate::clipboard_content::set_clipboard_content_no_history(text.to_string());
+
+            // 延迟结束，避免剪贴板监听把结果记录为新的历史
+            std::thread::spawn(|| {
+                std::thread::sleep(std::time::Duration::from_millis(500));
+                crate::clipboard_monitor::end_pasting_operation();
+            });
+
+            result?;
+            Ok(None)
+        }
+        OutputMode::Paste => {
+            paste_service::paste_text_without_translation(text.to_string(), window).await?;
+            Ok(None)
+        }
+        OutputMode::Save => {
+            let (id, _) = crate::clipboard_history::add_item_with_filter(text.to_string(), None)?;
+            emit_event(window, "clipboard-changed", ());
+            Ok(Some(id))
+        }
+    }
+}
+
+fn emit_event<S: serde::Serialize + Clone>(window: &WebviewWindow, event: &str, payload: S) {
+    if let Err(e) = window.emit(event, payload) {
+        eprintln!("发送 {} 事件失败: {}", event, e);
+    }
+}
+
+fn action_cancelled() -> AppError {
+    AppError::translation("AI操作已被取消").with_key("error.ai_action.cancelled")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cancels_only_the_requested_action() {
+        let first = ActiveAction::register();
+        let second = ActiveAction::register();
+
+        cancel_ai_action(Some(first.id)).unwrap();
+        assert!(first.token.is_cancelled());
+        assert!(!second.token.is_cancelled());
+
+        // 已取消的标记立即返回，等待中的标记在取消后被唤醒
+        first.token.cancelled().await;
+        let token = second.token.clone();
+        let waiter = tokio::spawn(async move { token.cancelled().await });
+        cancel_ai_action(Some(second.id)).unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn finished_actions_are_unregistered() {
+        let id = {
+            let active = ActiveAction::register();
+            assert!(ACTIVE_ACTIONS.lock().unwrap().contains_key(&active.id));
+            active.id
+        };
+        assert!(!ACTIVE_ACTIONS.lock().unwrap().contains_key(&id));
+    }
+}
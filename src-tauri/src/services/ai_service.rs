@@ -19,4 +19,9 @@ impl AIService {
             .await
             .map_err(|e| format!("获取模型列表失败: {}", e))
     }
+
+    // 获取各服务提供方最近一分钟的token用量
+    pub fn get_ai_token_usage() -> Vec<crate::ai_request::TokenUsage> {
+        crate::ai_request::token_usage()
+    }
 }
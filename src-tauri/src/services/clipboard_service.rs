@@ -42,6 +42,28 @@ impl ClipboardService {
         }
     }
 
+    // 设置剪贴板项目的置顶状态并通知各窗口
+    pub fn set_pinned(app: &tauri::AppHandle, id: i64, pinned: bool) -> Result<(), String> {
+        clipboard_history::set_pinned(id, pinned)?;
+        Self::emit_pinned(app, id, pinned);
+        Ok(())
+    }
+
+    // 切换剪贴板项目的置顶状态并通知各窗口，返回新的状态
+    pub fn toggle_pinned(app: &tauri::AppHandle, id: i64) -> Result<bool, String> {
+        let pinned = clipboard_history::toggle_pinned(id)?;
+        Self::emit_pinned(app, id, pinned);
+        Ok(pinned)
+    }
+
+    fn emit_pinned(app: &tauri::AppHandle, id: i64, pinned: bool) {
+        use tauri::Emitter;
+        let _ = app.emit(
+            "clipboard-item-pinned",
+            serde_json::json!({ "id": id, "pinned": pinned }),
+        );
+    }
+
     // 移动剪贴板项目到第一位
     pub fn move_to_front(text: String) -> Result<(), String> {
         clipboard_history::move_to_front_if_exists(text);
@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use crate::error::{AppError, AppResult};
+
 // 文件操作服务 - 处理文件复制、移动等操作
 pub struct FileOperationService;
 
@@ -8,12 +10,14 @@ impl FileOperationService {
     pub async fn copy_files_to_directory(
         files: Vec<String>,
         target_dir: String,
-    ) -> Result<Vec<String>, String> {
+    ) -> AppResult<Vec<String>> {
         use tokio::fs;
 
         // 验证目标目录是否存在
         if !Path::new(&target_dir).exists() {
-            return Err(format!("目标目录不存在: {}", target_dir));
+            return Err(AppError::not_found(format!("目标目录不存在: {}", target_dir))
+                .with_key("error.file.target_dir_not_found")
+                .with_context("path", &target_dir));
         }
 
         let mut results = Vec::new();
@@ -57,7 +61,9 @@ impl FileOperationService {
         }
 
         if !errors.is_empty() {
-            return Err(format!("部分文件复制失败: {}", errors.join("; ")));
+            return Err(AppError::io(format!("部分文件复制失败: {}", errors.join("; ")))
+                .with_key("error.file.copy_failed")
+                .with_context("failed", errors.len()));
         }
 
         Ok(results)
@@ -97,22 +103,23 @@ impl FileOperationService {
     }
 
     // 获取文件信息
-    pub async fn get_file_info(path: String) -> Result<crate::file_handler::FileInfo, String> {
+    pub async fn get_file_info(path: String) -> AppResult<crate::file_handler::FileInfo> {
         crate::file_handler::get_file_info(&path)
     }
 
     // 获取剪贴板中的文件
-    pub async fn get_clipboard_files() -> Result<Vec<String>, String> {
-        crate::file_handler::get_clipboard_files()
+    pub async fn get_clipboard_files() -> AppResult<Vec<String>> {
+        Ok(crate::file_handler::get_clipboard_files()?)
     }
 
     // 设置剪贴板中的文件
-    pub async fn set_clipboard_files(files: Vec<String>) -> Result<(), String> {
-        crate::file_handler::set_clipboard_files(&files)
+    pub async fn set_clipboard_files(files: Vec<String>) -> AppResult<()> {
+        crate::file_handler::set_clipboard_files(&files)?;
+        Ok(())
     }
 
     // 在文件管理器中打开文件位置
-    pub async fn open_file_location(file_path: String) -> Result<(), String> {
+    pub async fn open_file_location(file_path: String) -> AppResult<()> {
         use std::process::Command;
 
         #[cfg(windows)]
@@ -121,7 +128,7 @@ impl FileOperationService {
             let output = Command::new("explorer")
                 .args(&["/select,", &file_path])
                 .output()
-                .map_err(|e| format!("执行命令失败: {}", e))?;
+                .map_err(|e| AppError::from_io("执行命令失败", &e))?;
 
             if !output.status.success() {
                 let error_msg = String::from_utf8_lossy(&output.stderr);
@@ -138,11 +145,11 @@ impl FileOperationService {
             let output = Command::new("open")
                 .args(&["-R", &file_path])
                 .output()
-                .map_err(|e| format!("执行命令失败: {}", e))?;
+                .map_err(|e| AppError::from_io("执行命令失败", &e))?;
 
             if !output.status.success() {
                 let error_msg = String::from_utf8_lossy(&output.stderr);
-                return Err(format!("打开文件位置失败: {}", error_msg));
+                return Err(AppError::io(format!("打开文件位置失败: {}", error_msg)));
             }
         }
 
@@ -178,7 +185,8 @@ impl FileOperationService {
             }
 
             if !success {
-                return Err("找不到可用的文件管理器".to_string());
+                return Err(AppError::not_found("找不到可用的文件管理器")
+                    .with_key("error.file.no_file_manager"));
             }
         }
 
@@ -186,7 +194,7 @@ impl FileOperationService {
     }
 
     // 使用默认程序打开文件
-    pub async fn open_file_with_default_program(file_path: String) -> Result<(), String> {
+    pub async fn open_file_with_default_program(file_path: String) -> AppResult<()> {
         use std::process::Command;
 
         #[cfg(windows)]
@@ -202,7 +210,7 @@ impl FileOperationService {
 
             match result {
                 Ok(_) => Ok(()),
-                Err(e) => Err(format!("打开文件失败: {}", e)),
+                Err(e) => Err(AppError::from_io("打开文件失败", &e).with_context("path", &file_path)),
             }
         }
 
@@ -213,7 +221,7 @@ impl FileOperationService {
 
             match result {
                 Ok(_) => Ok(()),
-                Err(e) => Err(format!("打开文件失败: {}", e)),
+                Err(e) => Err(AppError::from_io("打开文件失败", &e).with_context("path", &file_path)),
             }
         }
 
@@ -224,13 +232,13 @@ impl FileOperationService {
 
             match result {
                 Ok(_) => Ok(()),
-                Err(e) => Err(format!("打开文件失败: {}", e)),
+                Err(e) => Err(AppError::from_io("打开文件失败", &e).with_context("path", &file_path)),
             }
         }
     }
 
     // 读取图片文件并转换为数据URL
-    pub fn read_image_file(file_path: String) -> Result<String, String> {
+    pub fn read_image_file(file_path: String) -> AppResult<String> {
         use std::fs;
         use std::path::Path;
         use base64::{engine::general_purpose, Engine as _};
@@ -239,14 +247,19 @@ impl FileOperationService {
 
         // 检查文件是否存在
         if !path.exists() {
-            return Err("文件不存在".to_string());
+            return Err(AppError::not_found("文件不存在")
+                .with_key("error.file.not_found")
+                .with_context("path", &file_path));
         }
 
         // 检查文件大小（限制为10MB）
         if let Ok(metadata) = fs::metadata(&path) {
             const MAX_SIZE: u64 = 10 * 1024 * 1024; // 10MB
             if metadata.len() > MAX_SIZE {
-                return Err("文件太大".to_string());
+                return Err(AppError::invalid_input("文件太大")
+                    .with_key("error.file.too_large")
+                    .with_context("path", &file_path)
+                    .with_context("size", metadata.len()));
             }
         }
 
@@ -265,7 +278,7 @@ impl FileOperationService {
 
         // 读取文件内容
         let image_data = fs::read(&file_path)
-            .map_err(|e| format!("读取文件失败: {}", e))?;
+            .map_err(|e| AppError::from_io("读取文件失败", &e).with_context("path", &file_path))?;
 
         // 转换为base64
         let base64_data = general_purpose::STANDARD.encode(&image_data);
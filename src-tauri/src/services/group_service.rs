@@ -100,6 +100,7 @@ impl GroupService {
             item_order: 0,
         };
 
-        crate::database::add_favorite_item(&favorite_item).map(|_| favorite_item)
+        crate::database::add_favorite_item(&favorite_item)?;
+        Ok(favorite_item)
     }
 }
@@ -1,37 +1,39 @@
+use crate::error::{AppError, AppResult};
+
 // 图片处理服务
 pub struct ImageService;
 
 impl ImageService {
     // 获取图片文件路径
-    pub fn get_image_file_path(content: String) -> Result<String, String> {
+    pub fn get_image_file_path(content: String) -> AppResult<String> {
         if content.starts_with("image:") {
             // 格式：image:{image_id}
             let image_id = content.strip_prefix("image:").unwrap_or("");
             let image_manager = crate::image_manager::get_image_manager()?;
             let manager = image_manager
                 .lock()
-                .map_err(|e| format!("获取图片管理器锁失败: {}", e))?;
+                .map_err(|e| AppError::image(format!("获取图片管理器锁失败: {}", e)))?;
             manager.get_image_file_path(image_id)
         } else {
-            Err("不支持的图片格式".to_string())
+            Err(unsupported_format())
         }
     }
 
     // 保存图片到文件（用于"另存为"功能）
-    pub fn save_image_to_file(content: String, file_path: String) -> Result<(), String> {
+    pub fn save_image_to_file(content: String, file_path: String) -> AppResult<()> {
         use std::fs;
 
         if content.starts_with("image:") {
-            // 从图片管理器复制文件
+            // 从图片管理器读取（加密的图片解密后写出）
             let image_id = content.strip_prefix("image:").unwrap_or("");
             let image_manager = crate::image_manager::get_image_manager()?;
             let manager = image_manager
                 .lock()
-                .map_err(|e| format!("获取图片管理器锁失败: {}", e))?;
+                .map_err(|e| AppError::image(format!("获取图片管理器锁失败: {}", e)))?;
             
-            let source_path = manager.get_image_file_path(image_id)?;
-            fs::copy(&source_path, &file_path)
-                .map_err(|e| format!("复制文件失败: {}", e))?;
+            let png_data = manager.read_image_png(image_id)?;
+            fs::write(&file_path, png_data)
+                .map_err(|e| AppError::from_io("保存图片失败", &e).with_context("path", &file_path))?;
         } else if content.starts_with("data:image/") {
             // 从data URL保存
             use base64::{engine::general_purpose, Engine as _};
@@ -39,18 +41,22 @@ impl ImageService {
             let base64_data = content
                 .split_once(',')
                 .map(|(_, data)| data)
-                .ok_or("无效的data URL格式")?;
+                .ok_or_else(|| AppError::invalid_input("无效的data URL格式"))?;
 
             let image_data = general_purpose::STANDARD
                 .decode(base64_data)
-                .map_err(|e| format!("Base64解码失败: {}", e))?;
+                .map_err(|e| AppError::invalid_input(format!("Base64解码失败: {}", e)))?;
 
             fs::write(&file_path, image_data)
-                .map_err(|e| format!("写入文件失败: {}", e))?;
+                .map_err(|e| AppError::from_io("写入文件失败", &e).with_context("path", &file_path))?;
         } else {
-            return Err("不支持的图片格式".to_string());
+            return Err(unsupported_format());
         }
 
         Ok(())
     }
 }
+
+fn unsupported_format() -> AppError {
+    AppError::invalid_input("不支持的图片格式").with_key("error.image.unsupported_format")
+}
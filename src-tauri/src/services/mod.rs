@@ -1,5 +1,6 @@
 pub mod translation_service;
 pub mod paste_service;
+pub mod transform_service;
 pub mod window_service;
 pub mod edge_snap_service;
 pub mod clipboard_service;
@@ -12,4 +13,5 @@ pub mod system_service;
 pub mod file_operation_service;
 pub mod image_service;
 pub mod ai_service;
+pub mod ai_action_service;
 pub mod preview_service;
@@ -1,11 +1,15 @@
 use serde::Deserialize;
 use tauri::WebviewWindow;
+use crate::error::ErrorCode;
 #[derive(Deserialize)]
 pub struct PasteContentParams {
     // 剪贴板历史项ID
     pub clipboard_id: Option<i64>,
     // 常用文本ID
     pub quick_text_id: Option<String>,
+    // 只粘贴剪贴板记录的指定格式（MIME 类型）
+    #[serde(default)]
+    pub mime_type: Option<String>,
 }
 
 // 统一粘贴入口
@@ -13,15 +17,60 @@ pub async fn paste_content(
     params: PasteContentParams,
     window: WebviewWindow,
 ) -> Result<(), String> {
-    // 从数据库获取内容
-    let (content, html_content) = if let Some(id) = params.clipboard_id {
-        get_clipboard_item_by_id(id)?
-    } else if let Some(ref id) = params.quick_text_id {
-        get_quick_text_by_id(id)?
+    // 未指定内容且粘贴队列已开启时，粘贴队列中的下一项
+    let params = if params.clipboard_id.is_none()
+        && params.quick_text_id.is_none()
+        && crate::paste_queue::is_enabled()
+    {
+        use tauri::Manager;
+        let id = crate::paste_queue::pop_next(window.app_handle()).ok_or("粘贴队列为空")?;
+        PasteContentParams {
+            clipboard_id: Some(id),
+            ..params
+        }
     } else {
-        return Err("必须提供 clipboard_id 或 quick_text_id".to_string());
+        params
     };
 
+    // 从数据库获取内容
+    let (content, html_content) = load_content(&params)?;
+
+    // 剪贴板记录：指定格式时只粘贴该格式，否则在保留格式粘贴时恢复复制时的全部格式
+    if let Some(id) = params.clipboard_id {
+        if let Some(mime_type) = &params.mime_type {
+            let representation = crate::clipboard_representations::load_one(id, mime_type)?;
+            return paste_representations(vec![representation], &window).await;
+        }
+        if should_restore_all_formats(&content) {
+            let representations = crate::clipboard_representations::load(id)?;
+            if representations.len() > 1 {
+                return paste_representations(representations, &window).await;
+            }
+        }
+    }
+
+    // 收藏中的片段模板：展开占位符后按纯文本粘贴
+    if params.quick_text_id.is_some()
+        && !content.starts_with("files:")
+        && !content.starts_with("data:image/")
+        && !content.starts_with("image:")
+    {
+        use tauri::Manager;
+        if let Some(rendered) =
+            crate::snippet_template::expand(window.app_handle(), &content).await?
+        {
+            paste_text_with_html(rendered.text.clone(), None, &window).await?;
+
+            // 将光标移动到 {{cursor}} 所在位置
+            let offset = rendered.cursor_offset_from_end();
+            if offset > 0 {
+                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                crate::paste_utils::move_caret_left(offset);
+            }
+            return Ok(());
+        }
+    }
+
     // 根据内容类型执行相应的粘贴操作
     if content.starts_with("files:") {
         paste_files(content, &window).await
@@ -35,11 +84,22 @@ pub async fn paste_content(
     Ok(())
 }
 
+// 从数据库获取要粘贴的内容和HTML格式
+pub fn load_content(params: &PasteContentParams) -> Result<(String, Option<String>), String> {
+    if let Some(id) = params.clipboard_id {
+        get_clipboard_item_by_id(id)
+    } else if let Some(ref id) = params.quick_text_id {
+        get_quick_text_by_id(id)
+    } else {
+        Err("必须提供 clipboard_id 或 quick_text_id".to_string())
+    }
+}
+
 // 根据ID从数据库获取剪贴板项目
 fn get_clipboard_item_by_id(id: i64) -> Result<(String, Option<String>), String> {
     let result = crate::database::with_connection(|conn| {
         conn.query_row(
-            "SELECT content, html_content FROM clipboard WHERE id = ?",
+            "SELECT qc_decrypt(content), qc_decrypt(html_content) FROM clipboard WHERE id = ?",
             [id],
             |row| {
                 Ok((
@@ -52,10 +112,10 @@ fn get_clipboard_item_by_id(id: i64) -> Result<(String, Option<String>), String>
     
     // 转换错误信息
     result.map_err(|e| {
-        if e.contains("Query returned no rows") {
+        if e.code == ErrorCode::NotFound {
             format!("未找到ID为 {} 的剪贴板项，可能已被删除或数据不同步", id)
         } else {
-            e
+            e.into()
         }
     })
 }
@@ -64,7 +124,7 @@ fn get_clipboard_item_by_id(id: i64) -> Result<(String, Option<String>), String>
 fn get_quick_text_by_id(id: &str) -> Result<(String, Option<String>), String> {
     let result = crate::database::with_connection(|conn| {
         conn.query_row(
-            "SELECT content, html_content FROM favorites WHERE id = ?",
+            "SELECT qc_decrypt(content), qc_decrypt(html_content) FROM favorites WHERE id = ?",
             [id],
             |row| {
                 Ok((
@@ -76,10 +136,10 @@ fn get_quick_text_by_id(id: &str) -> Result<(String, Option<String>), String> {
     });
     
     result.map_err(|e| {
-        if e.contains("Query returned no rows") {
+        if e.code == ErrorCode::NotFound {
             format!("未找到ID为 {} 的常用文本", id)
         } else {
-            e
+            e.into()
         }
     })
 }
@@ -108,7 +168,7 @@ pub async fn paste_text_with_html(
                 return Ok(());
             }
             Err(e) => {
-                send_translation_error_events(window, &e, "文本粘贴").await;
+                send_translation_error_events(window, &e.localized_message(), "文本粘贴").await;
                 // 翻译失败，继续执行普通粘贴
             }
         }
@@ -118,6 +178,14 @@ pub async fn paste_text_with_html(
     paste_text_without_translation_internal_with_html(text_content, html_content, window).await
 }
 
+// 粘贴纯文本（不触发粘贴时翻译）
+pub async fn paste_text_without_translation(
+    text_content: String,
+    window: &WebviewWindow,
+) -> Result<(), String> {
+    paste_text_without_translation_internal_with_html(text_content, None, window).await
+}
+
 // 粘贴文本内容
 async fn paste_text_without_translation_internal_with_html(
     text_content: String,
@@ -193,7 +261,8 @@ pub async fn paste_image(image_content: String, window: &WebviewWindow) -> Resul
                 format!("获取图片数据失败: {}", e)
             })?;
 
-            let file_path = manager.get_image_file_path(image_id).map_err(|e| {
+            // 加密的图片文件无法被其他程序读取，只粘贴图片数据
+            let file_path = manager.get_plain_image_file_path(image_id).map_err(|e| {
                 crate::clipboard_monitor::end_pasting_operation();
                 format!("获取图片文件路径失败: {}", e)
             })?;
@@ -221,7 +290,7 @@ pub async fn paste_image(image_content: String, window: &WebviewWindow) -> Resul
             let file_path_opt = if prefers_image_data {
                 None
             } else {
-                Some(file_path.as_str())
+                file_path.as_deref()
             };
             if let Err(e) =
                 set_windows_clipboard_image_with_file(&bgra, &png_bytes, width, height, file_path_opt)
@@ -276,6 +345,55 @@ pub async fn paste_image(image_content: String, window: &WebviewWindow) -> Resul
     Ok(())
 }
 
+// 是否恢复全部格式：需要保留格式，且文本不需要在粘贴时翻译
+fn should_restore_all_formats(content: &str) -> bool {
+    let settings = crate::settings::get_global_settings();
+    if !settings.paste_with_format {
+        return false;
+    }
+    let is_text = !content.starts_with("files:") && !content.starts_with("image:");
+    let translates = crate::ai_translator::is_translation_config_valid(&settings)
+        && settings.ai_translate_on_paste;
+    !(is_text && translates)
+}
+
+// 一次性写入多种格式后粘贴
+pub async fn paste_representations(
+    representations: Vec<crate::clipboard_representations::Representation>,
+    window: &WebviewWindow,
+) -> Result<(), String> {
+    // 开始粘贴操作，增加粘贴计数器
+    crate::clipboard_monitor::start_pasting_operation();
+
+    if let Err(e) = crate::clipboard_content::set_clipboard_representations(&representations) {
+        crate::clipboard_monitor::end_pasting_operation();
+        return Err(e);
+    }
+
+    // 短暂延迟确保剪贴板内容完全设置
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    // 执行粘贴操作
+    if !crate::paste_utils::windows_paste() {
+        crate::clipboard_monitor::end_pasting_operation();
+        return Err("粘贴操作失败".to_string());
+    }
+
+    // 播放粘贴音效
+    crate::sound_manager::play_paste_sound();
+
+    // 处理窗口显示/隐藏
+    handle_window_after_paste(window)?;
+
+    // 延迟结束粘贴操作，确保剪贴板监听器能正确识别
+    std::thread::spawn(|| {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        crate::clipboard_monitor::end_pasting_operation();
+    });
+
+    Ok(())
+}
+
 // 粘贴文件内容
 pub async fn paste_files(files_data: String, window: &WebviewWindow) -> Result<(), String> {
     // 解析文件数据
@@ -310,7 +428,7 @@ pub async fn paste_files(files_data: String, window: &WebviewWindow) -> Result<(
     // 设置剪贴板文件
     if let Err(e) = crate::file_handler::set_clipboard_files(&valid_file_paths) {
         crate::clipboard_monitor::end_pasting_operation();
-        return Err(e);
+        return Err(e.into());
     }
 
     // 执行粘贴操作
@@ -21,6 +21,11 @@ impl PreviewService {
         Ok(())
     }
 
+    // 切换当前预览项的置顶状态
+    pub fn toggle_current_item_pin(app: &tauri::AppHandle) -> Result<(), String> {
+        crate::preview_window::toggle_current_preview_item_pin(app)
+    }
+
     // 获取主窗口状态
     pub fn get_main_window_state() -> Result<serde_json::Value, String> {
         crate::preview_window::get_main_window_state()
@@ -36,7 +36,7 @@ impl QuickTextService {
         // 从数据库查询指定ID的剪贴板项
         let (content, html_content) = crate::database::with_connection(|conn| {
             conn.query_row(
-                "SELECT content, html_content FROM clipboard WHERE id = ?",
+                "SELECT qc_decrypt(content), qc_decrypt(html_content) FROM clipboard WHERE id = ?",
                 [id],
                 |row| {
                     Ok((
@@ -57,6 +57,17 @@ impl QuickTextService {
         quick_texts::add_quick_text_with_group_and_html(title, final_content, html_content, "全部".to_string())
     }
 
+    // 将内容添加到指定分组（记录规则自动收藏时使用）
+    pub fn add_content(
+        content: String,
+        html_content: Option<String>,
+        group_name: String,
+    ) -> Result<FavoriteItem, String> {
+        let final_content = Self::process_image_content(content)?;
+        let title = Self::generate_title(&final_content);
+        quick_texts::add_quick_text_with_group_and_html(title, final_content, html_content, group_name)
+    }
+
     // 处理图片内容，使用图片ID
     fn process_image_content(content: String) -> Result<String, String> {
         if content.starts_with("image:") {
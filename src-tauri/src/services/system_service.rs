@@ -10,7 +10,7 @@ impl SystemService {
     }
 
     // 获取应用数据目录
-    pub fn get_app_data_dir() -> Result<String, String> {
+    pub fn get_app_data_dir() -> crate::error::AppResult<String> {
         crate::data_manager::get_app_data_dir().map(|path| path.to_string_lossy().to_string())
     }
 
@@ -0,0 +1,48 @@
+use serde::Deserialize;
+use tauri::WebviewWindow;
+
+use super::paste_service::{self, PasteContentParams};
+
+#[derive(Deserialize)]
+pub struct TransformParams {
+    // 剪贴板历史项ID
+    pub clipboard_id: Option<i64>,
+    // 常用文本ID
+    pub quick_text_id: Option<String>,
+    // 按顺序执行的转换 id
+    pub transforms: Vec<String>,
+}
+
+// 对剪贴板历史项或常用文本执行转换，返回转换结果
+pub fn transform_content(params: &TransformParams) -> Result<String, String> {
+    let (content, html_content) = paste_service::load_content(&PasteContentParams {
+        clipboard_id: params.clipboard_id,
+        quick_text_id: params.quick_text_id.clone(),
+        mime_type: None,
+    })?;
+
+    if content.starts_with("files:")
+        || content.starts_with("data:image/")
+        || content.starts_with("image:")
+    {
+        return Err("只能转换文本内容".to_string());
+    }
+
+    crate::transforms::apply_chain(&params.transforms, &content, html_content.as_deref())
+}
+
+// 转换后粘贴（按纯文本粘贴，不再经过粘贴时翻译）
+pub async fn paste_transformed_content(
+    params: TransformParams,
+    window: WebviewWindow,
+) -> Result<(), String> {
+    let text = transform_content(&params)?;
+    paste_service::paste_text_without_translation(text, &window).await
+}
+
+// 转换后保存为新的历史记录，返回记录ID
+pub fn save_transformed_content(params: &TransformParams) -> Result<i64, String> {
+    let text = transform_content(params)?;
+    let (id, _) = crate::clipboard_history::add_item_with_filter(text, None)?;
+    Ok(id)
+}
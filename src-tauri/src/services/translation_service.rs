@@ -2,7 +2,8 @@
 //
 // 整合 ai_translator 和 text_input_simulator 模块，提供高级的翻译服务
 
-use crate::ai_translator::{config_from_settings, AITranslator, TranslationResult};
+use crate::ai_translator::{create_translator, TranslationResult};
+use crate::error::{AppError, AppResult, ErrorCode};
 use crate::settings;
 use std::sync::atomic::{AtomicBool, Ordering};
 
@@ -24,9 +25,19 @@ impl Drop for TranslationGuard {
 }
 
 // 智能翻译文本（根据设置选择流式输入或直接粘贴）
-pub async fn translate_text_smart(text: String) -> Result<(), String> {
+pub async fn translate_text_smart(text: String) -> AppResult<()> {
     let settings = settings::get_global_settings();
 
+    // 预载模式下已确认过的译文直接整段粘贴
+    if settings.translation_memory_prefill {
+        if let Some(key) = crate::ai_translator::memory_key_from_settings(&settings, &text) {
+            if crate::translation_memory::has_confirmed(&key).unwrap_or(false) {
+                println!("使用已确认的翻译记忆直接粘贴");
+                return translate_and_paste_text(text).await;
+            }
+        }
+    }
+
     // 根据输出模式设置选择翻译方式
     match settings.ai_output_mode.as_str() {
         "paste" => {
@@ -41,7 +52,7 @@ pub async fn translate_text_smart(text: String) -> Result<(), String> {
 }
 
 // 翻译文本并直接粘贴（非流式）
-pub async fn translate_and_paste_text(text: String) -> Result<(), String> {
+pub async fn translate_and_paste_text(text: String) -> AppResult<()> {
     // 重置取消状态
     TRANSLATION_CANCELLED.store(false, Ordering::SeqCst);
 
@@ -60,23 +71,20 @@ pub async fn translate_and_paste_text(text: String) -> Result<(), String> {
 
     // 检查翻译是否启用
     if !settings.ai_translation_enabled {
-        return Err("AI翻译功能未启用".to_string());
+        return Err(translation_disabled());
     }
 
     // 检查配置是否有效
     if !crate::ai_translator::is_translation_config_valid(&settings) {
-        return Err("AI翻译配置不完整".to_string());
+        return Err(config_incomplete());
     }
 
     // 预处理输入文本
     let processed_text = preprocess_translation_text(&text)?;
 
-    // 创建翻译配置
-    let translation_config = config_from_settings(&settings);
-
     // 创建翻译器
     let translator =
-        AITranslator::new(translation_config).map_err(|e| format!("创建翻译器失败: {}", e))?;
+        create_translator(&settings).map_err(|e| AppError::translation(format!("创建翻译器失败: {}", e)))?;
 
     // 开始翻译（非流式）
     match translator.translate(&processed_text).await {
@@ -84,7 +92,7 @@ pub async fn translate_and_paste_text(text: String) -> Result<(), String> {
             // 检查是否被取消
             if TRANSLATION_CANCELLED.load(Ordering::SeqCst) {
                 println!("翻译在粘贴前被用户取消");
-                return Err("翻译已被取消".to_string());
+                return Err(translation_cancelled());
             }
 
             println!("翻译完成，结果长度: {} 字符", translated_text.len());
@@ -111,12 +119,12 @@ pub async fn translate_and_paste_text(text: String) -> Result<(), String> {
 
             Ok(())
         }
-        Err(e) => Err(format!("翻译失败: {}", e)),
+        Err(e) => Err(AppError::translation(format!("翻译失败: {}", e))),
     }
 }
 
 // 翻译文本并流式输入
-pub async fn translate_and_input_text(text: String) -> Result<(), String> {
+pub async fn translate_and_input_text(text: String) -> AppResult<()> {
     // 重置取消状态
     TRANSLATION_CANCELLED.store(false, Ordering::SeqCst);
 
@@ -135,24 +143,23 @@ pub async fn translate_and_input_text(text: String) -> Result<(), String> {
 
     // 检查翻译是否启用
     if !settings.ai_translation_enabled {
-        return Err("AI翻译功能未启用".to_string());
+        return Err(translation_disabled());
     }
 
     // 检查配置是否有效
     if !crate::ai_translator::is_translation_config_valid(&settings) {
-        return Err("AI翻译配置不完整".to_string());
+        return Err(config_incomplete());
     }
 
     // 预处理输入文本
     let processed_text = preprocess_translation_text(&text)?;
 
-    // 创建翻译配置
-    let translation_config = config_from_settings(&settings);
+    // 创建输入配置
     let input_config = crate::text_input_simulator::config_from_settings(&settings);
 
     // 创建翻译器
     let translator =
-        AITranslator::new(translation_config).map_err(|e| format!("创建翻译器失败: {}", e))?;
+        create_translator(&settings).map_err(|e| AppError::translation(format!("创建翻译器失败: {}", e)))?;
 
     // 更新输入模拟器配置
     crate::text_input_simulator::update_global_input_simulator_config(input_config);
@@ -168,7 +175,7 @@ pub async fn translate_and_input_text(text: String) -> Result<(), String> {
                 // 检查是否被取消
                 if TRANSLATION_CANCELLED.load(Ordering::SeqCst) {
                     println!("翻译被用户取消");
-                    return Err("翻译已被取消".to_string());
+                    return Err(translation_cancelled());
                 }
 
                 match translation_result {
@@ -176,7 +183,7 @@ pub async fn translate_and_input_text(text: String) -> Result<(), String> {
                         // 再次检查是否被取消（在输入前）
                         if TRANSLATION_CANCELLED.load(Ordering::SeqCst) {
                             println!("翻译在输入前被用户取消");
-                            return Err("翻译已被取消".to_string());
+                            return Err(translation_cancelled());
                         }
 
                         // 累积文本用于错误恢复
@@ -215,26 +222,26 @@ pub async fn translate_and_input_text(text: String) -> Result<(), String> {
                         break;
                     }
                     TranslationResult::Error(e) => {
-                        return Err(format!("翻译失败: {}", e));
+                        return Err(AppError::translation(format!("翻译失败: {}", e)));
                     }
                 }
             }
 
             Ok(())
         }
-        Err(e) => Err(format!("启动翻译失败: {}", e)),
+        Err(e) => Err(AppError::translation(format!("启动翻译失败: {}", e))),
     }
 }
 
 // 取消正在进行的翻译
-pub fn cancel_translation() -> Result<(), String> {
+pub fn cancel_translation() -> AppResult<()> {
     TRANSLATION_CANCELLED.store(true, Ordering::SeqCst);
     println!("翻译已被用户取消");
     Ok(())
 }
 
 // 复制时翻译并直接输入到目标位置
-pub async fn translate_and_input_on_copy(text: String) -> Result<(), String> {
+pub async fn translate_and_input_on_copy(text: String) -> AppResult<()> {
     // 重置取消状态
     TRANSLATION_CANCELLED.store(false, Ordering::SeqCst);
 
@@ -253,24 +260,23 @@ pub async fn translate_and_input_on_copy(text: String) -> Result<(), String> {
 
     // 检查翻译是否启用
     if !settings.ai_translation_enabled {
-        return Err("AI翻译功能未启用".to_string());
+        return Err(translation_disabled());
     }
 
     // 检查配置是否有效
     if !crate::ai_translator::is_translation_config_valid(&settings) {
-        return Err("AI翻译配置不完整".to_string());
+        return Err(config_incomplete());
     }
 
     // 预处理输入文本
     let processed_text = preprocess_translation_text(&text)?;
 
-    // 创建翻译配置和输入配置
-    let translation_config = config_from_settings(&settings);
+    // 创建输入配置
     let input_config = crate::text_input_simulator::config_from_settings(&settings);
 
     // 创建翻译器
     let translator =
-        AITranslator::new(translation_config).map_err(|e| format!("创建翻译器失败: {}", e))?;
+        create_translator(&settings).map_err(|e| AppError::translation(format!("创建翻译器失败: {}", e)))?;
 
     // 更新输入模拟器配置
     crate::text_input_simulator::update_global_input_simulator_config(input_config);
@@ -287,7 +293,7 @@ pub async fn translate_and_input_on_copy(text: String) -> Result<(), String> {
                     // 检查是否被取消
                     if TRANSLATION_CANCELLED.load(Ordering::SeqCst) {
                         println!("翻译在粘贴前被用户取消");
-                        return Err("翻译已被取消".to_string());
+                        return Err(translation_cancelled());
                     }
 
                     println!("复制时翻译完成，结果长度: {} 字符", translated_text.len());
@@ -314,7 +320,7 @@ pub async fn translate_and_input_on_copy(text: String) -> Result<(), String> {
 
                     Ok(())
                 }
-                Err(e) => Err(format!("复制时翻译失败: {}", e)),
+                Err(e) => Err(AppError::translation(format!("复制时翻译失败: {}", e))),
             }
         }
         "stream" | _ => {
@@ -330,7 +336,7 @@ pub async fn translate_and_input_on_copy(text: String) -> Result<(), String> {
                         // 检查是否被取消
                         if TRANSLATION_CANCELLED.load(Ordering::SeqCst) {
                             println!("复制时翻译被用户取消");
-                            return Err("翻译已被取消".to_string());
+                            return Err(translation_cancelled());
                         }
 
                         match receiver.recv().await {
@@ -370,7 +376,7 @@ pub async fn translate_and_input_on_copy(text: String) -> Result<(), String> {
                                     break;
                                 }
                                 TranslationResult::Error(e) => {
-                                    return Err(format!("复制时翻译失败: {}", e));
+                                    return Err(AppError::translation(format!("复制时翻译失败: {}", e)));
                                 }
                             },
                             None => {
@@ -382,21 +388,23 @@ pub async fn translate_and_input_on_copy(text: String) -> Result<(), String> {
 
                     Ok(())
                 }
-                Err(e) => Err(format!("启动复制时翻译失败: {}", e)),
+                Err(e) => Err(AppError::translation(format!("启动复制时翻译失败: {}", e))),
             }
         }
     }
 }
 
 // 预处理翻译文本
-fn preprocess_translation_text(text: &str) -> Result<String, String> {
+fn preprocess_translation_text(text: &str) -> AppResult<String> {
     // 检查文本长度
     if text.is_empty() {
-        return Err("输入文本为空".to_string());
+        return Err(AppError::invalid_input("输入文本为空").with_key("error.translation.empty_text"));
     }
 
     if text.len() > 50_000 {
-        return Err("输入文本过长，超过50KB限制".to_string());
+        return Err(AppError::invalid_input("输入文本过长，超过50KB限制")
+            .with_key("error.translation.text_too_long")
+            .with_context("length", text.len()));
     }
 
     // 规范化文本格式
@@ -414,21 +422,22 @@ fn preprocess_translation_text(text: &str) -> Result<String, String> {
 }
 
 // 测试AI翻译配置
-pub async fn test_ai_translation() -> Result<String, String> {
+pub async fn test_ai_translation() -> AppResult<String> {
     let settings = crate::settings::get_global_settings();
 
     // 检查配置是否有效
     if !crate::ai_translator::is_translation_config_valid(&settings) {
-        return Err("AI翻译配置不完整，请检查API密钥、模型和目标语言设置".to_string());
+        return Err(AppError::new(
+            ErrorCode::Settings,
+            "AI翻译配置不完整，请检查API密钥、模型和目标语言设置",
+        )
+        .with_key("error.translation.config_incomplete"));
     }
 
-    // 创建翻译配置
-    let config = crate::ai_translator::config_from_settings(&settings);
-
     // 创建翻译器
-    let translator = match crate::ai_translator::AITranslator::new(config) {
+    let translator = match crate::ai_translator::create_translator(&settings) {
         Ok(t) => t,
-        Err(e) => return Err(format!("创建翻译器失败: {}", e)),
+        Err(e) => return Err(AppError::translation(format!("创建翻译器失败: {}", e))),
     };
 
     // 测试翻译
@@ -448,37 +457,49 @@ pub async fn test_ai_translation() -> Result<String, String> {
                         break;
                     }
                     crate::ai_translator::TranslationResult::Error(e) => {
-                        return Err(format!("翻译失败: {}", e));
+                        return Err(AppError::translation(format!("翻译失败: {}", e)));
                     }
                 }
             }
 
             if result.is_empty() {
-                Err("翻译结果为空".to_string())
+                Err(AppError::translation("翻译结果为空"))
             } else {
                 Ok(format!("测试成功！翻译结果：{}", result))
             }
         }
-        Err(e) => Err(format!("启动翻译失败: {}", e)),
+        Err(e) => Err(AppError::translation(format!("启动翻译失败: {}", e))),
     }
 }
 
+fn translation_disabled() -> AppError {
+    AppError::new(ErrorCode::Settings, "AI翻译功能未启用").with_key("error.translation.disabled")
+}
+
+fn config_incomplete() -> AppError {
+    AppError::new(ErrorCode::Settings, "AI翻译配置不完整").with_key("error.translation.config_incomplete")
+}
+
+fn translation_cancelled() -> AppError {
+    AppError::translation("翻译已被取消").with_key("error.translation.cancelled")
+}
+
 // 启用AI翻译取消快捷键
-pub fn enable_ai_translation_cancel_shortcut() -> Result<(), String> {
+pub fn enable_ai_translation_cancel_shortcut() -> AppResult<()> {
     #[cfg(windows)]
     crate::global_state::enable_ai_translation_cancel();
     Ok(())
 }
 
 // 禁用AI翻译取消快捷键
-pub fn disable_ai_translation_cancel_shortcut() -> Result<(), String> {
+pub fn disable_ai_translation_cancel_shortcut() -> AppResult<()> {
     #[cfg(windows)]
     crate::global_state::disable_ai_translation_cancel();
     Ok(())
 }
 
 // 检查AI翻译配置是否有效
-pub fn check_ai_translation_config() -> Result<bool, String> {
+pub fn check_ai_translation_config() -> AppResult<bool> {
     let settings = crate::settings::get_global_settings();
     Ok(crate::ai_translator::is_translation_config_valid(&settings))
 }
@@ -14,15 +14,38 @@ impl SettingsConverter {
             "showStartupNotification": settings.show_startup_notification,
             "historyLimit": settings.history_limit,
             "theme": settings.theme,
+            "language": settings.language,
             "opacity": settings.opacity,
             "backgroundImagePath": settings.background_image_path,
             "toggleShortcut": settings.toggle_shortcut,
             "numberShortcuts": settings.number_shortcuts,
             "numberShortcutsModifier": settings.number_shortcuts_modifier,
+            "numberShortcutsPinModifier": settings.number_shortcuts_pin_modifier,
             "clipboardMonitor": settings.clipboard_monitor,
             "ignoreDuplicates": settings.ignore_duplicates,
             "saveImages": settings.save_images,
             "showImagePreview": settings.show_image_preview,
+            "linuxPrimarySelectionEnabled": settings.linux_primary_selection_enabled,
+            "retentionEnabled": settings.retention_enabled,
+            "retentionTextDays": settings.retention_text_days,
+            "retentionImageDays": settings.retention_image_days,
+            "retentionImageStorageMb": settings.retention_image_storage_mb,
+            "sensitiveDetectionEnabled": settings.sensitive_detection_enabled,
+            "sensitiveAction": settings.sensitive_action,
+            "sensitiveExpireMinutes": settings.sensitive_expire_minutes,
+            "sensitiveBuiltinDetectors": settings.sensitive_builtin_detectors,
+            "sensitiveCustomRules": settings.sensitive_custom_rules,
+            "apiServerEnabled": settings.api_server_enabled,
+            "apiServerPort": settings.api_server_port,
+            "apiServerToken": settings.api_server_token,
+            "syncEnabled": settings.sync_enabled,
+            "syncMode": settings.sync_mode,
+            "syncFolderPath": settings.sync_folder_path,
+            "syncWebdavUrl": settings.sync_webdav_url,
+            "syncWebdavUsername": settings.sync_webdav_username,
+            "syncWebdavPassword": settings.sync_webdav_password,
+            "syncIntervalMinutes": settings.sync_interval_minutes,
+            "syncDeviceId": settings.sync_device_id,
             "soundEnabled": settings.sound_enabled,
             "soundVolume": settings.sound_volume,
             "copySoundPath": settings.copy_sound_path,
@@ -43,6 +66,7 @@ impl SettingsConverter {
             "previewScrollSound": settings.preview_scroll_sound,
             "previewScrollSoundPath": settings.preview_scroll_sound_path,
             "aiTranslationEnabled": settings.ai_translation_enabled,
+            "aiProvider": settings.ai_provider,
             "aiApiKey": settings.ai_api_key,
             "aiModel": settings.ai_model,
             "aiBaseUrl": settings.ai_base_url,
@@ -53,6 +77,17 @@ impl SettingsConverter {
             "aiInputSpeed": settings.ai_input_speed,
             "aiNewlineMode": settings.ai_newline_mode,
             "aiOutputMode": settings.ai_output_mode,
+            "aiMaxRetries": settings.ai_max_retries,
+            "aiMaxConcurrentRequests": settings.ai_max_concurrent_requests,
+            "aiTokenBudgetPerMinute": settings.ai_token_budget_per_minute,
+            "translationBackend": settings.translation_backend,
+            "offlineDictionaryPath": settings.offline_dictionary_path,
+            "offlineModelCommand": settings.offline_model_command,
+            "translationMemoryEnabled": settings.translation_memory_enabled,
+            "translationMemoryPrefill": settings.translation_memory_prefill,
+            "aiProfiles": settings.ai_profiles,
+            "aiActiveProfile": settings.ai_active_profile,
+            "aiActions": settings.ai_actions,
             "mouseMiddleButtonEnabled": settings.mouse_middle_button_enabled,
             "mouseMiddleButtonModifier": settings.mouse_middle_button_modifier,
             "clipboardAnimationEnabled": settings.clipboard_animation_enabled,
@@ -64,12 +99,15 @@ impl SettingsConverter {
             "appFilterEnabled": settings.app_filter_enabled,
             "appFilterMode": settings.app_filter_mode,
             "appFilterList": settings.app_filter_list,
+            "captureRules": settings.capture_rules,
             "titleBarPosition": settings.title_bar_position,
             "edgeHideEnabled": settings.edge_hide_enabled,
             "edgeHideOffset": settings.edge_hide_offset,
             "autoFocusSearch": settings.auto_focus_search,
             "sidebarHoverDelay": settings.sidebar_hover_delay,
             "pasteWithFormat": settings.paste_with_format,
+            "pasteQueueOrder": settings.paste_queue_order,
+            "pasteQueueSeparator": settings.paste_queue_separator,
             "imageDataPriorityApps": settings.image_data_priority_apps,
             "navigateUpShortcut": settings.navigate_up_shortcut,
             "navigateDownShortcut": settings.navigate_down_shortcut,
@@ -142,15 +180,65 @@ impl SettingsConverter {
         update_bool!(show_startup_notification, "showStartupNotification");
         update_u64!(history_limit, "historyLimit");
         update_string!(theme, "theme");
+        update_string!(language, "language");
         update_f64!(opacity, "opacity");
         update_string!(background_image_path, "backgroundImagePath");
         update_string!(toggle_shortcut, "toggleShortcut");
         update_bool!(number_shortcuts, "numberShortcuts");
         update_string!(number_shortcuts_modifier, "numberShortcutsModifier");
+        update_string!(number_shortcuts_pin_modifier, "numberShortcutsPinModifier");
         update_bool!(clipboard_monitor, "clipboardMonitor");
         update_bool!(ignore_duplicates, "ignoreDuplicates");
         update_bool!(save_images, "saveImages");
         update_bool!(show_image_preview, "showImagePreview");
+        update_bool!(linux_primary_selection_enabled, "linuxPrimarySelectionEnabled");
+
+        // 保留策略设置
+        update_bool!(retention_enabled, "retentionEnabled");
+        update_u32!(retention_text_days, "retentionTextDays");
+        update_u32!(retention_image_days, "retentionImageDays");
+        update_u64!(retention_image_storage_mb, "retentionImageStorageMb");
+
+        // 敏感内容检测设置
+        update_bool!(sensitive_detection_enabled, "sensitiveDetectionEnabled");
+        update_string!(sensitive_action, "sensitiveAction");
+        update_u32!(sensitive_expire_minutes, "sensitiveExpireMinutes");
+
+        if let Some(v) = json.get("sensitiveBuiltinDetectors").and_then(|v| v.as_array()) {
+            settings.sensitive_builtin_detectors = v
+                .iter()
+                .filter_map(|item| item.as_str().map(|s| s.to_string()))
+                .collect();
+        }
+
+        if let Some(v) = json.get("sensitiveCustomRules") {
+            if let Ok(rules) = serde_json::from_value(v.clone()) {
+                settings.sensitive_custom_rules = rules;
+            }
+        }
+
+        // 本地 HTTP 接口设置（令牌只能通过重新生成修改）
+        update_bool!(api_server_enabled, "apiServerEnabled");
+        if let Some(v) = json.get("apiServerPort").and_then(|v| v.as_u64()) {
+            if (1024..=65535).contains(&v) {
+                settings.api_server_port = v as u16;
+            }
+        }
+
+        // 多设备同步设置（设备ID由程序生成，不接受修改）
+        update_bool!(sync_enabled, "syncEnabled");
+        if let Some(v) = json.get("syncMode").and_then(|v| v.as_str()) {
+            if v == "folder" || v == "webdav" {
+                settings.sync_mode = v.to_string();
+            }
+        }
+        update_string!(sync_folder_path, "syncFolderPath");
+        update_string!(sync_webdav_url, "syncWebdavUrl");
+        update_string!(sync_webdav_username, "syncWebdavUsername");
+        update_string!(sync_webdav_password, "syncWebdavPassword");
+        if let Some(v) = json.get("syncIntervalMinutes").and_then(|v| v.as_u64()) {
+            settings.sync_interval_minutes = v.clamp(1, 1440) as u32;
+        }
 
         // 音效设置
         update_bool!(sound_enabled, "soundEnabled");
@@ -179,6 +267,7 @@ impl SettingsConverter {
 
         // AI翻译设置
         update_bool!(ai_translation_enabled, "aiTranslationEnabled");
+        update_string!(ai_provider, "aiProvider");
         update_string!(ai_api_key, "aiApiKey");
         update_string!(ai_model, "aiModel");
         update_string!(ai_base_url, "aiBaseUrl");
@@ -189,6 +278,29 @@ impl SettingsConverter {
         update_u32!(ai_input_speed, "aiInputSpeed");
         update_string!(ai_newline_mode, "aiNewlineMode");
         update_string!(ai_output_mode, "aiOutputMode");
+        if let Some(v) = json.get("aiMaxRetries").and_then(|v| v.as_u64()) {
+            settings.ai_max_retries = v.min(10) as u32;
+        }
+        if let Some(v) = json.get("aiMaxConcurrentRequests").and_then(|v| v.as_u64()) {
+            settings.ai_max_concurrent_requests = v.clamp(1, 16) as u32;
+        }
+        update_u32!(ai_token_budget_per_minute, "aiTokenBudgetPerMinute");
+        update_string!(translation_backend, "translationBackend");
+        update_string!(offline_dictionary_path, "offlineDictionaryPath");
+        update_string!(offline_model_command, "offlineModelCommand");
+        update_bool!(translation_memory_enabled, "translationMemoryEnabled");
+        update_bool!(translation_memory_prefill, "translationMemoryPrefill");
+        if let Some(v) = json.get("aiProfiles") {
+            if let Ok(profiles) = serde_json::from_value(v.clone()) {
+                settings.ai_profiles = profiles;
+            }
+        }
+        update_string!(ai_active_profile, "aiActiveProfile");
+        if let Some(v) = json.get("aiActions") {
+            if let Ok(actions) = serde_json::from_value(v.clone()) {
+                settings.ai_actions = actions;
+            }
+        }
 
         // 鼠标设置
         update_bool!(mouse_middle_button_enabled, "mouseMiddleButtonEnabled");
@@ -231,6 +343,12 @@ impl SettingsConverter {
                 .collect();
         }
 
+        if let Some(v) = json.get("captureRules") {
+            if let Ok(rules) = serde_json::from_value(v.clone()) {
+                settings.capture_rules = rules;
+            }
+        }
+
         // 标题栏设置
         update_string!(title_bar_position, "titleBarPosition");
 
@@ -251,6 +369,10 @@ impl SettingsConverter {
         // 格式设置
         update_bool!(paste_with_format, "pasteWithFormat");
 
+        // 粘贴队列设置
+        update_string!(paste_queue_order, "pasteQueueOrder");
+        update_string!(paste_queue_separator, "pasteQueueSeparator");
+
         if let Some(v) = json.get("imageDataPriorityApps").and_then(|v| v.as_array()) {
             settings.image_data_priority_apps = v
                 .iter()
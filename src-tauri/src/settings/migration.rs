@@ -3,6 +3,8 @@ use super::storage::SettingsStorage;
 use std::path::PathBuf;
 use std::fs;
 
+use crate::error::{AppError, AppResult};
+
 // 数据迁移管理
 pub struct SettingsMigration;
 
@@ -12,17 +14,19 @@ impl SettingsMigration {
         settings: &mut AppSettings,
         new_path: String,
         app: Option<tauri::AppHandle>,
-    ) -> Result<(), String> {
+    ) -> AppResult<()> {
         let new_dir = PathBuf::from(&new_path);
 
         // 验证新路径
         if !new_dir.exists() {
             fs::create_dir_all(&new_dir)
-                .map_err(|e| format!("创建新存储目录失败: {}", e))?;
+                .map_err(|e| AppError::from_io("创建新存储目录失败", &e).with_context("path", &new_path))?;
         }
 
         if !new_dir.is_dir() {
-            return Err("指定的路径不是有效的目录".to_string());
+            return Err(AppError::invalid_input("指定的路径不是有效的目录")
+                .with_key("error.settings.invalid_storage_path")
+                .with_context("path", &new_path));
         }
 
         // 获取当前存储目录
@@ -45,8 +49,7 @@ impl SettingsMigration {
         settings.use_custom_storage = true;
 
         // 重新初始化数据库
-        crate::database::reinitialize_database()
-            .map_err(|e| format!("重新初始化数据库失败: {}", e))?;
+        crate::database::reinitialize_database()?;
 
         // 刷新窗口
         if let Some(app_handle) = app {
@@ -62,7 +65,7 @@ impl SettingsMigration {
     pub async fn reset_to_default_storage(
         settings: &mut AppSettings,
         app: Option<tauri::AppHandle>,
-    ) -> Result<(), String> {
+    ) -> AppResult<()> {
         let default_dir = SettingsStorage::get_default_data_directory()?;
         let current_dir = SettingsStorage::get_data_directory(settings)?;
 
@@ -83,8 +86,7 @@ impl SettingsMigration {
         settings.use_custom_storage = false;
 
         // 重新初始化数据库
-        crate::database::reinitialize_database()
-            .map_err(|e| format!("重新初始化数据库失败: {}", e))?;
+        crate::database::reinitialize_database()?;
 
         // 刷新窗口
         if let Some(app_handle) = app {
@@ -97,7 +99,7 @@ impl SettingsMigration {
     }
 
     // 获取存储信息
-    pub fn get_storage_info(settings: &AppSettings) -> Result<StorageInfo, String> {
+    pub fn get_storage_info(settings: &AppSettings) -> AppResult<StorageInfo> {
         let current_dir = SettingsStorage::get_data_directory(settings)?;
         let default_dir = SettingsStorage::get_default_data_directory()?;
         let is_portable = SettingsStorage::is_portable_mode();
@@ -10,7 +10,7 @@ mod settings_service;   // 业务逻辑服务
 mod window;             // 设置窗口管理
 
 // 公共导出 - 供全局 commands.rs 直接调用
-pub use model::{AppSettings, StorageInfo};
+pub use model::{AIAction, AppSettings, CaptureRule, SensitiveRule, StorageInfo};
 pub use state::{
     get_global_settings, 
     update_global_settings, 
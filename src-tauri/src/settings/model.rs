@@ -11,15 +11,49 @@ pub struct AppSettings {
     pub show_startup_notification: bool,
     pub history_limit: u64,
     pub theme: String,
+    // 后端文案（托盘、通知、错误说明）使用的语言：zh-CN / en-US
+    pub language: String,
     pub opacity: f64,
     pub background_image_path: String,
     pub toggle_shortcut: String,
     pub number_shortcuts: bool,
     pub number_shortcuts_modifier: String,
+    // 数字快捷键置顶/取消置顶对应项目的修饰键（空字符串表示关闭）
+    pub number_shortcuts_pin_modifier: String,
     pub clipboard_monitor: bool,
     pub ignore_duplicates: bool,
     pub save_images: bool,
     pub show_image_preview: bool,
+    // 同时记录 Linux 主选区（选中即复制）
+    pub linux_primary_selection_enabled: bool,
+
+    // === 保留策略设置（0 表示不限制） ===
+    pub retention_enabled: bool,
+    pub retention_text_days: u32,
+    pub retention_image_days: u32,
+    pub retention_image_storage_mb: u64,
+
+    // === 敏感内容检测设置 ===
+    pub sensitive_detection_enabled: bool,
+    pub sensitive_action: String,
+    pub sensitive_expire_minutes: u32,
+    pub sensitive_builtin_detectors: Vec<String>,
+    pub sensitive_custom_rules: Vec<SensitiveRule>,
+
+    // === 本地 HTTP 接口设置 ===
+    pub api_server_enabled: bool,
+    pub api_server_port: u16,
+    pub api_server_token: String,
+
+    // === 多设备同步设置 ===
+    pub sync_enabled: bool,
+    pub sync_mode: String,
+    pub sync_folder_path: String,
+    pub sync_webdav_url: String,
+    pub sync_webdav_username: String,
+    pub sync_webdav_password: String,
+    pub sync_interval_minutes: u32,
+    pub sync_device_id: String,
 
     // === 音效设置 ===
     pub sound_enabled: bool,
@@ -48,6 +82,8 @@ pub struct AppSettings {
 
     // === AI翻译设置 ===
     pub ai_translation_enabled: bool,
+    // 服务提供方：openai / anthropic / ollama / gemini
+    pub ai_provider: String,
     pub ai_api_key: String,
     pub ai_model: String,
     pub ai_base_url: String,
@@ -58,6 +94,27 @@ pub struct AppSettings {
     pub ai_input_speed: u32,
     pub ai_newline_mode: String,
     pub ai_output_mode: String,
+    // AI请求失败后的最大重试次数（网络错误、限流和服务端错误）
+    pub ai_max_retries: u32,
+    // 同时进行的AI请求数上限
+    pub ai_max_concurrent_requests: u32,
+    // 每个服务提供方每分钟的token预算，0 表示不限制
+    pub ai_token_budget_per_minute: u32,
+    // 翻译后端：ai（在线模型）/ offline（离线词典和本地模型）
+    pub translation_backend: String,
+    // 离线词典路径（StarDict 的 .ifo 文件或所在目录，也可以是制表符分隔的文本词典）
+    pub offline_dictionary_path: String,
+    // 本地模型命令，{source}/{target} 替换为语言代码，原文从标准输入传入
+    pub offline_model_command: String,
+    // AI翻译记忆：相同内容再次翻译时直接使用保存的译文
+    pub translation_memory_enabled: bool,
+    // 预载已确认的译文，粘贴时直接整段粘贴
+    pub translation_memory_prefill: bool,
+    // 额外的AI配置档案，ai_active_profile 为空或找不到时使用上面的基础配置
+    pub ai_profiles: Vec<AIProfile>,
+    pub ai_active_profile: String,
+    // 自定义AI操作（总结、润色、解释代码等），可对历史记录或常用文本执行
+    pub ai_actions: Vec<AIAction>,
 
     // === 鼠标设置 ===
     pub mouse_middle_button_enabled: bool,
@@ -73,6 +130,8 @@ pub struct AppSettings {
     pub app_filter_enabled: bool,
     pub app_filter_mode: String,
     pub app_filter_list: Vec<String>,
+    // 剪贴板记录规则，按顺序匹配，第一条命中的规则决定如何记录
+    pub capture_rules: Vec<CaptureRule>,
 
     #[serde(default)]
     pub image_data_priority_apps: Vec<String>,
@@ -98,6 +157,12 @@ pub struct AppSettings {
     // === 格式设置 ===
     pub paste_with_format: bool,
 
+    // === 粘贴队列设置 ===
+    // 队列弹出顺序：fifo（先复制先粘贴）/ lifo（后复制先粘贴）
+    pub paste_queue_order: String,
+    // 合并队列内容时使用的分隔符
+    pub paste_queue_separator: String,
+
     // === 快捷键设置 ===
     pub navigate_up_shortcut: String,
     pub navigate_down_shortcut: String,
@@ -125,15 +190,53 @@ impl Default for AppSettings {
             show_startup_notification: true,
             history_limit: 100,
             theme: "light".to_string(),
+            language: "zh-CN".to_string(),
             opacity: 0.9,
             background_image_path: String::new(),
             toggle_shortcut: "Win+V".to_string(),
             number_shortcuts: true,
             number_shortcuts_modifier: "Ctrl".to_string(),
+            number_shortcuts_pin_modifier: "Ctrl+Shift".to_string(),
             clipboard_monitor: true,
             ignore_duplicates: true,
             save_images: true,
             show_image_preview: false,
+            linux_primary_selection_enabled: false,
+
+            // 保留策略设置
+            retention_enabled: false,
+            retention_text_days: 0,
+            retention_image_days: 0,
+            retention_image_storage_mb: 0,
+
+            // 敏感内容检测设置
+            sensitive_detection_enabled: true,
+            sensitive_action: "skip".to_string(),
+            sensitive_expire_minutes: 2,
+            sensitive_builtin_detectors: vec![
+                "credit_card".to_string(),
+                "aws_key".to_string(),
+                "github_token".to_string(),
+                "openai_key".to_string(),
+                "jwt".to_string(),
+                "private_key".to_string(),
+            ],
+            sensitive_custom_rules: vec![],
+
+            // 本地 HTTP 接口设置（令牌在首次启用时生成）
+            api_server_enabled: false,
+            api_server_port: 27631,
+            api_server_token: String::new(),
+
+            // 多设备同步设置（设备ID在首次启用时生成）
+            sync_enabled: false,
+            sync_mode: "folder".to_string(),
+            sync_folder_path: String::new(),
+            sync_webdav_url: String::new(),
+            sync_webdav_username: String::new(),
+            sync_webdav_password: String::new(),
+            sync_interval_minutes: 5,
+            sync_device_id: String::new(),
 
             // 音效设置
             sound_enabled: true,
@@ -162,6 +265,7 @@ impl Default for AppSettings {
 
             // AI翻译设置
             ai_translation_enabled: false,
+            ai_provider: "openai".to_string(),
             ai_api_key: String::new(),
             ai_model: "Qwen/Qwen2-7B-Instruct".to_string(),
             ai_base_url: "https://api.siliconflow.cn/v1".to_string(),
@@ -172,6 +276,17 @@ impl Default for AppSettings {
             ai_input_speed: 50,
             ai_newline_mode: "auto".to_string(),
             ai_output_mode: "stream".to_string(),
+            ai_max_retries: 3,
+            ai_max_concurrent_requests: 2,
+            ai_token_budget_per_minute: 0,
+            translation_backend: "ai".to_string(),
+            offline_dictionary_path: String::new(),
+            offline_model_command: String::new(),
+            translation_memory_enabled: true,
+            translation_memory_prefill: false,
+            ai_profiles: vec![],
+            ai_active_profile: String::new(),
+            ai_actions: default_ai_actions(),
 
             // 鼠标设置
             mouse_middle_button_enabled: true,
@@ -187,6 +302,7 @@ impl Default for AppSettings {
             app_filter_enabled: false,
             app_filter_mode: "blacklist".to_string(),
             app_filter_list: vec![],
+            capture_rules: vec![],
             image_data_priority_apps: vec![],
 
             // 窗口设置
@@ -210,6 +326,10 @@ impl Default for AppSettings {
             // 格式设置
             paste_with_format: true,
 
+            // 粘贴队列设置
+            paste_queue_order: "fifo".to_string(),
+            paste_queue_separator: "\n".to_string(),
+
             // 快捷键设置
             navigate_up_shortcut: "ArrowUp".to_string(),
             navigate_down_shortcut: "ArrowDown".to_string(),
@@ -229,6 +349,156 @@ impl Default for AppSettings {
     }
 }
 
+// 剪贴板记录规则（匹配和处理见 capture_rules）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureRule {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+
+    // 匹配条件，为空的条件不参与匹配
+    // 进程名，完全匹配或 * / ? 通配符，不区分大小写
+    pub process: String,
+    // 窗口标题正则
+    pub title_pattern: String,
+    // 来源网页地址正则（浏览器复制时才有地址）
+    pub url_pattern: String,
+    // 内容类型：text / rich_text / image / file / link
+    pub content_types: Vec<String>,
+    // 内容正则
+    pub content_pattern: String,
+
+    // 处理方式
+    // 不记录
+    pub skip: bool,
+    // 记录但不播放复制音效
+    pub mute: bool,
+    // 记录后自动过期的分钟数，0 表示不过期
+    pub expire_minutes: u32,
+    // 只保留纯文本，丢弃HTML和其他格式
+    pub plain_text: bool,
+    // 同时保存到该收藏分组，为空表示不保存
+    pub favorite_group: String,
+    // 触发复制时翻译
+    pub translate: bool,
+}
+
+impl Default for CaptureRule {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            enabled: true,
+            process: String::new(),
+            title_pattern: String::new(),
+            url_pattern: String::new(),
+            content_types: vec![],
+            content_pattern: String::new(),
+            skip: false,
+            mute: false,
+            expire_minutes: 0,
+            plain_text: false,
+            favorite_group: String::new(),
+            translate: false,
+        }
+    }
+}
+
+// 用户自定义敏感内容规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SensitiveRule {
+    pub name: String,
+    pub pattern: String,
+    // skip / mask / expire，为空时使用全局处理方式
+    pub action: String,
+    pub enabled: bool,
+}
+
+impl Default for SensitiveRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            pattern: String::new(),
+            action: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+// AI配置档案（每个档案可使用不同的服务提供方）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AIProfile {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+    pub base_url: String,
+    pub api_key: String,
+    pub model: String,
+}
+
+impl Default for AIProfile {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            provider: "openai".to_string(),
+            base_url: String::new(),
+            api_key: String::new(),
+            model: String::new(),
+        }
+    }
+}
+
+// 自定义AI操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AIAction {
+    pub id: String,
+    pub name: String,
+    // 提示词模板，{text} 替换为内容；没有该占位符时内容附在提示词之后
+    pub prompt_template: String,
+    // 为空时使用当前AI配置中的模型
+    pub model: String,
+    pub temperature: f32,
+    // 结果输出方式：replace_clipboard / paste / type / save
+    pub output_mode: String,
+}
+
+impl Default for AIAction {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            prompt_template: String::new(),
+            model: String::new(),
+            temperature: 0.3,
+            output_mode: "replace_clipboard".to_string(),
+        }
+    }
+}
+
+fn default_ai_actions() -> Vec<AIAction> {
+    let action = |id: &str, name: &str, prompt: &str, temperature: f32, output_mode: &str| AIAction {
+        id: id.to_string(),
+        name: name.to_string(),
+        prompt_template: prompt.to_string(),
+        model: String::new(),
+        temperature,
+        output_mode: output_mode.to_string(),
+    };
+
+    vec![
+        action("summarize", "总结", "请用简洁的语言总结以下内容，只返回总结：\n\n{text}", 0.3, "save"),
+        action("fix_grammar", "修正语法", "请修正以下文本中的拼写和语法错误，保持原意和格式，只返回修正后的文本：\n\n{text}", 0.2, "replace_clipboard"),
+        action("explain_code", "解释代码", "请解释以下代码的作用和关键逻辑：\n\n{text}", 0.3, "save"),
+        action("convert_to_sql", "转换为 SQL", "请把以下描述或数据转换为 SQL 语句，只返回 SQL：\n\n{text}", 0.2, "replace_clipboard"),
+        action("rewrite_formal", "正式改写", "请把以下文本改写为正式、礼貌的书面语，保持原意，只返回改写结果：\n\n{text}", 0.5, "paste"),
+    ]
+}
+
 // 存储信息
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct StorageInfo {
@@ -242,12 +512,12 @@ pub struct StorageInfo {
 
 impl AppSettings {
     // 获取数据存储目录
-    pub fn get_data_directory(&self) -> Result<std::path::PathBuf, String> {
+    pub fn get_data_directory(&self) -> crate::error::AppResult<std::path::PathBuf> {
         super::storage::SettingsStorage::get_data_directory(self)
     }
 
     // 获取默认数据目录
-    pub fn get_default_data_directory() -> Result<std::path::PathBuf, String> {
+    pub fn get_default_data_directory() -> crate::error::AppResult<std::path::PathBuf> {
         super::storage::SettingsStorage::get_default_data_directory()
     }
 
@@ -257,7 +527,7 @@ impl AppSettings {
     }
 
     // 获取存储信息
-    pub fn get_storage_info(&self) -> Result<StorageInfo, String> {
+    pub fn get_storage_info(&self) -> crate::error::AppResult<StorageInfo> {
         super::migration::SettingsMigration::get_storage_info(self)
     }
 
@@ -266,7 +536,7 @@ impl AppSettings {
         &mut self,
         new_path: String,
         app: Option<tauri::AppHandle>,
-    ) -> Result<(), String> {
+    ) -> crate::error::AppResult<()> {
         super::migration::SettingsMigration::set_custom_storage_path(self, new_path, app).await
     }
 
@@ -274,7 +544,7 @@ impl AppSettings {
     pub async fn reset_to_default_storage(
         &mut self,
         app: Option<tauri::AppHandle>,
-    ) -> Result<(), String> {
+    ) -> crate::error::AppResult<()> {
         super::migration::SettingsMigration::reset_to_default_storage(self, app).await
     }
 }
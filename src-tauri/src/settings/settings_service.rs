@@ -8,12 +8,16 @@ use super::model::AppSettings;
 use super::state;
 use super::storage::SettingsStorage;
 
+use crate::error::AppResult;
+#[cfg(not(debug_assertions))]
+use crate::error::AppError;
+
 // 设置服务 - 专注于复杂的业务逻辑
 pub struct SettingsService;
 
 impl SettingsService {
     // 保存设置
-    pub fn save_settings(app_handle: AppHandle, settings: serde_json::Value) -> Result<(), String> {
+    pub fn save_settings(app_handle: AppHandle, settings: serde_json::Value) -> AppResult<()> {
         let mut settings_filtered = settings.clone();
         if let Some(obj) = settings_filtered.as_object_mut() {
             obj.remove("savedWindowSize");
@@ -36,7 +40,7 @@ impl SettingsService {
     }
 
     // 设置开机自启动
-    pub fn set_startup_launch(enabled: bool) -> Result<(), String> {
+    pub fn set_startup_launch(enabled: bool) -> AppResult<()> {
         #[cfg(debug_assertions)]
         {
             println!("开发模式下跳过开机自启动设置: enabled = {}", enabled);
@@ -46,18 +50,18 @@ impl SettingsService {
         #[cfg(not(debug_assertions))]
         {
             let app_name = "QuickClipboard";
-            let app_path = std::env::current_exe().map_err(|e| format!("获取程序路径失败: {}", e))?;
+            let app_path = std::env::current_exe().map_err(|e| AppError::from_io("获取程序路径失败", &e))?;
 
             let auto_launch = AutoLaunch::new(app_name, &app_path.to_string_lossy(), &[] as &[&str]);
 
             if enabled {
                 auto_launch
                     .enable()
-                    .map_err(|e| format!("启用开机自启动失败: {}", e))?;
+                    .map_err(|e| AppError::settings(format!("启用开机自启动失败: {}", e)))?;
             } else {
                 auto_launch
                     .disable()
-                    .map_err(|e| format!("禁用开机自启动失败: {}", e))?;
+                    .map_err(|e| AppError::settings(format!("禁用开机自启动失败: {}", e)))?;
             }
 
             Ok(())
@@ -65,7 +69,7 @@ impl SettingsService {
     }
 
     // 应用所有设置
-    fn apply_settings(app_settings: &AppSettings) -> Result<(), String> {
+    fn apply_settings(app_settings: &AppSettings) -> AppResult<()> {
         crate::clipboard_history::set_history_limit(app_settings.history_limit as usize);
 
         if let Err(e) = Self::set_startup_launch(app_settings.auto_start) {
@@ -82,6 +86,8 @@ impl SettingsService {
         crate::global_state::set_number_shortcuts_enabled(app_settings.number_shortcuts);
         #[cfg(windows)]
         crate::global_state::update_number_shortcuts_modifier(&app_settings.number_shortcuts_modifier);
+        #[cfg(windows)]
+        crate::global_state::update_number_shortcuts_pin_modifier(&app_settings.number_shortcuts_pin_modifier);
 
         #[cfg(windows)]
         crate::global_state::update_preview_shortcut_config(&app_settings.preview_shortcut);
@@ -111,7 +117,7 @@ impl SettingsService {
         app_handle: &AppHandle,
         _settings_filtered: &serde_json::Value,
         app_settings: &AppSettings,
-    ) -> Result<(), String> {
+    ) -> AppResult<()> {
         #[cfg(windows)]
         {
             // 更新主窗口快捷键
@@ -131,6 +137,12 @@ impl SettingsService {
             crate::shortcut_interceptor::update_preview_shortcut_to_intercept(&preview_shortcut);
         }
 
+        // 启动、重启或停止本地 HTTP 接口
+        crate::api_server::apply_settings(app_handle, app_settings);
+
+        // 启动、重启或停止后台同步
+        crate::sync::apply_settings(app_handle, app_settings);
+
         use tauri::Emitter;
         if let Some(main_window) = app_handle.get_webview_window("main") {
             let _ = main_window.emit("settings-changed", SettingsConverter::to_json(app_settings));
@@ -138,10 +150,8 @@ impl SettingsService {
         if let Some(settings_window) = app_handle.get_webview_window("settings") {
             let _ = settings_window.emit("settings-changed", SettingsConverter::to_json(app_settings));
         }
-        // 同步托盘"剪贴板监听"菜单文案
-        if let Some(item) = crate::tray::TOGGLE_MONITOR_ITEM.get() {
-            let _ = item.set_text(if app_settings.clipboard_monitor { "禁用剪贴板监听" } else { "启用剪贴板监听" });
-        }
+        // 按当前语言和状态刷新托盘菜单文案（包括"剪贴板监听"切换项）
+        crate::tray::refresh_labels(app_handle);
 
         Ok(())
     }
@@ -3,9 +3,16 @@ use super::storage::SettingsStorage;
 use once_cell::sync::Lazy;
 use std::sync::{Arc, Mutex};
 
+use crate::error::AppResult;
+
 // 全局设置状态管理
-static GLOBAL_SETTINGS: Lazy<Arc<Mutex<AppSettings>>> =
-    Lazy::new(|| Arc::new(Mutex::new(SettingsStorage::load_or_default())));
+static GLOBAL_SETTINGS: Lazy<Arc<Mutex<AppSettings>>> = Lazy::new(|| {
+    let settings = SettingsStorage::load_or_default();
+    crate::i18n::set_language(&settings.language);
+    crate::capture_rules::load(&settings.capture_rules);
+    crate::sensitive_detector::load(&settings);
+    Arc::new(Mutex::new(settings))
+});
 
 // 获取全局设置
 pub fn get_global_settings() -> AppSettings {
@@ -13,11 +20,14 @@ pub fn get_global_settings() -> AppSettings {
 }
 
 // 更新全局设置
-pub fn update_global_settings(settings: AppSettings) -> Result<(), String> {
+pub fn update_global_settings(settings: AppSettings) -> AppResult<()> {
     {
         let mut global_settings = GLOBAL_SETTINGS.lock().unwrap();
         *global_settings = settings.clone();
     }
+    crate::i18n::set_language(&settings.language);
+    crate::capture_rules::load(&settings.capture_rules);
+    crate::sensitive_detector::load(&settings);
 
     SettingsStorage::save(&settings)?;
 
@@ -25,27 +35,27 @@ pub fn update_global_settings(settings: AppSettings) -> Result<(), String> {
 }
 
 // 从JSON更新全局设置
-pub(super) fn update_global_settings_from_json(json: &serde_json::Value) -> Result<(), String> {
+pub(super) fn update_global_settings_from_json(json: &serde_json::Value) -> AppResult<()> {
     let mut settings = get_global_settings();
     super::converter::SettingsConverter::update_from_json(&mut settings, json);
     update_global_settings(settings)
 }
 
 // 获取当前数据存储目录（全局函数）
-pub fn get_data_directory() -> Result<std::path::PathBuf, String> {
+pub fn get_data_directory() -> AppResult<std::path::PathBuf> {
     let settings = get_global_settings();
     SettingsStorage::get_data_directory(&settings)
 }
 
 // 保存窗口位置
-pub fn save_window_position(x: i32, y: i32) -> Result<(), String> {
+pub fn save_window_position(x: i32, y: i32) -> AppResult<()> {
     let mut settings = get_global_settings();
     settings.saved_window_position = Some((x, y));
     update_global_settings(settings)
 }
 
 // 保存窗口大小
-pub fn save_window_size(width: u32, height: u32) -> Result<(), String> {
+pub fn save_window_size(width: u32, height: u32) -> AppResult<()> {
     let mut settings = get_global_settings();
     settings.saved_window_size = Some((width, height));
     update_global_settings(settings)
@@ -4,6 +4,8 @@ use std::fs;
 use std::path::PathBuf;
 use std::env;
 
+use crate::error::{AppError, AppResult};
+
 // 设置文件存储管理
 pub struct SettingsStorage;
 
@@ -20,23 +22,23 @@ impl SettingsStorage {
     }
 
     // 获取便携版数据目录
-    fn get_portable_data_directory() -> Result<PathBuf, String> {
+    fn get_portable_data_directory() -> AppResult<PathBuf> {
         let exe_path = env::current_exe()
-            .map_err(|e| format!("无法获取可执行文件路径: {}", e))?;
+            .map_err(|e| AppError::from_io("无法获取可执行文件路径", &e))?;
         
         let exe_dir = exe_path.parent()
-            .ok_or_else(|| "无法获取可执行文件所在目录".to_string())?;
+            .ok_or_else(|| AppError::settings("无法获取可执行文件所在目录"))?;
         
         let portable_data_dir = exe_dir.join("data");
         
         fs::create_dir_all(&portable_data_dir)
-            .map_err(|e| format!("创建便携版数据目录失败: {}", e))?;
+            .map_err(|e| AppError::from_io("创建便携版数据目录失败", &e))?;
         
         Ok(portable_data_dir)
     }
 
     // 获取默认的应用数据目录
-    pub fn get_default_data_directory() -> Result<PathBuf, String> {
+    pub fn get_default_data_directory() -> AppResult<PathBuf> {
         // 检测便携版模式
         if Self::is_portable_mode() {
             return Self::get_portable_data_directory();
@@ -44,28 +46,30 @@ impl SettingsStorage {
 
         // 正常模式：使用系统数据目录
         let app_data_dir = dirs::data_local_dir()
-            .ok_or_else(|| "无法获取本地数据目录".to_string())?
+            .ok_or_else(|| AppError::settings("无法获取本地数据目录"))?
             .join("quickclipboard");
 
         fs::create_dir_all(&app_data_dir)
-            .map_err(|e| format!("创建应用数据目录失败: {}", e))?;
+            .map_err(|e| AppError::from_io("创建应用数据目录失败", &e))?;
         
         Ok(app_data_dir)
     }
 
     // 获取设置文件路径（总是在默认位置）
-    fn get_settings_file_path() -> Result<PathBuf, String> {
+    fn get_settings_file_path() -> AppResult<PathBuf> {
         let config_dir = Self::get_default_data_directory()?;
         Ok(config_dir.join("settings.json"))
     }
 
     // 根据设置获取数据存储目录
-    pub fn get_data_directory(settings: &AppSettings) -> Result<PathBuf, String> {
+    pub fn get_data_directory(settings: &AppSettings) -> AppResult<PathBuf> {
         if settings.use_custom_storage {
             if let Some(custom_path) = &settings.custom_storage_path {
                 let path = PathBuf::from(custom_path);
                 fs::create_dir_all(&path)
-                    .map_err(|e| format!("创建自定义存储目录失败: {}", e))?;
+                    .map_err(|e| {
+                        AppError::from_io("创建自定义存储目录失败", &e).with_context("path", custom_path)
+                    })?;
                 return Ok(path);
             }
         }
@@ -73,31 +77,43 @@ impl SettingsStorage {
     }
 
     // 从文件加载设置
-    pub fn load() -> Result<AppSettings, String> {
+    pub fn load() -> AppResult<AppSettings> {
         let settings_path = Self::get_settings_file_path()?;
 
         if !settings_path.exists() {
-            return Err("设置文件不存在".to_string());
+            return Err(AppError::not_found("设置文件不存在").with_key("error.settings.file_not_found"));
         }
 
         let content = fs::read_to_string(&settings_path)
-            .map_err(|e| format!("读取设置文件失败: {}", e))?;
+            .map_err(|e| AppError::from_io("读取设置文件失败", &e))?;
 
         let settings: AppSettings = serde_json::from_str(&content)
-            .map_err(|e| format!("解析设置文件失败: {}", e))?;
+            .map_err(|e| AppError::settings(format!("解析设置文件失败: {}", e)))?;
 
         Ok(settings)
     }
 
     // 保存设置到文件
-    pub fn save(settings: &AppSettings) -> Result<(), String> {
+    pub fn save(settings: &AppSettings) -> AppResult<()> {
         let settings_path = Self::get_settings_file_path()?;
 
-        let content = serde_json::to_string_pretty(settings)
-            .map_err(|e| format!("序列化设置失败: {}", e))?;
+        // 启用数据加密时API密钥和WebDAV密码以密文保存
+        let mut settings = settings.clone();
+        if !settings.ai_api_key.is_empty() {
+            settings.ai_api_key = crate::encryption::encrypt_text(&settings.ai_api_key)?;
+        }
+        for profile in settings.ai_profiles.iter_mut().filter(|p| !p.api_key.is_empty()) {
+            profile.api_key = crate::encryption::encrypt_text(&profile.api_key)?;
+        }
+        if !settings.sync_webdav_password.is_empty() {
+            settings.sync_webdav_password = crate::encryption::encrypt_text(&settings.sync_webdav_password)?;
+        }
+
+        let content = serde_json::to_string_pretty(&settings)
+            .map_err(|e| AppError::settings(format!("序列化设置失败: {}", e)))?;
 
         fs::write(&settings_path, content)
-            .map_err(|e| format!("写入设置文件失败: {}", e))?;
+            .map_err(|e| AppError::from_io("写入设置文件失败", &e))?;
 
         Ok(())
     }
@@ -115,7 +131,7 @@ impl SettingsStorage {
     }
 
     // 打开存储文件夹
-    pub fn open_folder(settings: &AppSettings) -> Result<(), String> {
+    pub fn open_folder(settings: &AppSettings) -> AppResult<()> {
         let storage_path = Self::get_data_directory(settings)?;
         
         #[cfg(target_os = "windows")]
@@ -123,7 +139,7 @@ impl SettingsStorage {
             std::process::Command::new("explorer")
                 .arg(storage_path)
                 .spawn()
-                .map_err(|e| format!("打开文件夹失败: {}", e))?;
+                .map_err(|e| AppError::from_io("打开文件夹失败", &e))?;
         }
         
         #[cfg(target_os = "macos")]
@@ -131,7 +147,7 @@ impl SettingsStorage {
             std::process::Command::new("open")
                 .arg(storage_path)
                 .spawn()
-                .map_err(|e| format!("打开文件夹失败: {}", e))?;
+                .map_err(|e| AppError::from_io("打开文件夹失败", &e))?;
         }
         
         #[cfg(target_os = "linux")]
@@ -139,7 +155,7 @@ impl SettingsStorage {
             std::process::Command::new("xdg-open")
                 .arg(storage_path)
                 .spawn()
-                .map_err(|e| format!("打开文件夹失败: {}", e))?;
+                .map_err(|e| AppError::from_io("打开文件夹失败", &e))?;
         }
         
         Ok(())
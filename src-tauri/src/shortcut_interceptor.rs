@@ -88,8 +88,8 @@ unsafe extern "system" fn shortcut_hook_proc(
     use windows::Win32::Foundation::LRESULT;
     use windows::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
     use windows::Win32::UI::WindowsAndMessaging::{
-        CallNextHookEx, HC_ACTION, KBDLLHOOKSTRUCT, WM_KEYDOWN, WM_KEYUP, WM_SYSKEYDOWN,
-        WM_SYSKEYUP,
+        CallNextHookEx, HC_ACTION, KBDLLHOOKSTRUCT, LLKHF_INJECTED, WM_KEYDOWN, WM_KEYUP,
+        WM_SYSKEYDOWN, WM_SYSKEYUP,
     };
 
     if code == HC_ACTION as i32 && SHORTCUT_INTERCEPTION_ENABLED.load(Ordering::Relaxed) {
@@ -327,6 +327,43 @@ unsafe extern "system" fn shortcut_hook_proc(
             }
         }
 
+        // 粘贴队列有内容时接管 Ctrl+V：拦截按键，改为粘贴队列中的下一项
+        // 自身模拟的粘贴按键带有 LLKHF_INJECTED 标记，不再拦截
+        if wparam.0 as u32 == WM_KEYDOWN
+            && vk_code == 0x56
+            && ctrl_pressed
+            && !shift_pressed
+            && !alt_pressed
+            && !win_pressed
+            && !is_own_window
+            && (kbd_data.flags.0 & LLKHF_INJECTED.0) == 0
+            && !crate::clipboard_monitor::is_currently_pasting()
+            && crate::paste_queue::has_pending()
+        {
+            if let Some(window) = MAIN_WINDOW_HANDLE.get() {
+                let window = window.clone();
+                std::thread::spawn(move || {
+                    // 队列中的记录可能都已被删除，此时按原样粘贴当前剪贴板
+                    let Some(id) = crate::paste_queue::pop_next(window.app_handle()) else {
+                        crate::paste_utils::windows_paste();
+                        return;
+                    };
+                    let params = crate::services::paste_service::PasteContentParams {
+                        clipboard_id: Some(id),
+                        quick_text_id: None,
+                        mime_type: None,
+                    };
+                    let app = window.app_handle().clone();
+                    if let Err(e) = tauri::async_runtime::block_on(
+                        crate::services::paste_service::paste_content(params, window),
+                    ) {
+                        notify_paste_queue_failed(&app, &e);
+                    }
+                });
+                return LRESULT(1);
+            }
+        }
+
         // 监听 Ctrl+V 触发粘贴音效（不拦截事件）
         if wparam.0 as u32 == WM_KEYDOWN {
             if vk_code == 0x56 && ctrl_pressed && !shift_pressed && !alt_pressed && !win_pressed {
@@ -355,6 +392,20 @@ fn emit_navigation_action(action: &str) {
     }
 }
 
+// 粘贴队列内容失败时发送系统通知（此时焦点在其他程序，主窗口通常不可见）
+#[cfg(windows)]
+fn notify_paste_queue_failed(app: &tauri::AppHandle, error: &str) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let body = crate::i18n::t_with("notification.paste_queue.failed", &[("error", error)]);
+    let _ = app
+        .notification()
+        .builder()
+        .title("QuickClipboard")
+        .body(&body)
+        .show();
+}
+
 
 // 发送Win键松开事件
 #[cfg(windows)]
@@ -0,0 +1,433 @@
+// 收藏片段模板
+//
+// 收藏内容中包含以下占位符时，粘贴时展开为实际内容：
+//   {{date}} / {{date:%Y-%m-%d}}   当前日期（可指定 strftime 格式）
+//   {{time}} / {{time:%H:%M}}      当前时间（可指定 strftime 格式）
+//   {{clipboard}}                  当前剪贴板文本
+//   {{uuid}}                       随机 UUID（每处独立生成）
+//   {{cursor}}                     粘贴后光标停留的位置（最多一处）
+//   {{input:名称}}                  粘贴前弹出输入框，同名占位符只询问一次
+//
+// 只有上述写法会被展开，其余花括号（如代码、JSON、单花括号的 {date}）原样保留；
+// 不包含任何占位符的收藏按普通文本粘贴。
+//
+// 占位符前加反斜杠表示原样输出：\{{date}} 粘贴为 {{date}}；紧邻占位符的连续反斜杠两两合并为一个，
+// 如 \\{{date}} 粘贴为反斜杠加日期。
+//
+// 需求中的占位符写法是单花括号（{date:%Y-%m-%d}、{time}），但现有收藏中大量代码和 JSON 片段
+// 含有单花括号，按该写法展开会改变这些收藏的粘贴结果，因此改为双花括号。
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S";
+
+// 占位符：{{名称}} 或 {{名称:参数}}，前面紧邻的反斜杠用于转义
+static PLACEHOLDER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\\*)\{\{(date|time|clipboard|uuid|cursor|input)(?::([^{}]*))?\}\}").unwrap()
+});
+
+// 模板片段
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Text(String),
+    Date(String),
+    Time(String),
+    Clipboard,
+    Uuid,
+    Cursor,
+    Input(String),
+}
+
+// 模板解析错误（position 为出错位置的字符序号，从 1 开始）
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "模板第 {} 个字符处: {}", self.position, self.message)
+    }
+}
+
+impl From<TemplateError> for String {
+    fn from(e: TemplateError) -> Self {
+        e.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+// 渲染所需的外部数据
+pub struct RenderContext {
+    pub now: DateTime<Local>,
+    pub clipboard: String,
+    pub inputs: HashMap<String, String>,
+}
+
+// 渲染结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedTemplate {
+    pub text: String,
+    // 光标位置（字符序号），模板中没有 {{cursor}} 时为 None
+    pub cursor_position: Option<usize>,
+}
+
+impl RenderedTemplate {
+    // 粘贴后光标需要向左移动的字符数
+    pub fn cursor_offset_from_end(&self) -> usize {
+        self.cursor_position
+            .map(|pos| self.text.chars().count() - pos)
+            .unwrap_or(0)
+    }
+}
+
+// 预览结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplatePreview {
+    pub is_template: bool,
+    pub text: String,
+    pub cursor_position: Option<usize>,
+    // 需要用户输入的字段名称
+    pub inputs: Vec<String>,
+}
+
+// 内容是否应作为模板处理
+pub fn is_template(content: &str) -> bool {
+    PLACEHOLDER_REGEX.is_match(content)
+}
+
+pub fn parse(source: &str) -> Result<Template, TemplateError> {
+    let mut segments = Vec::new();
+    let mut has_cursor = false;
+    let mut last = 0;
+
+    for captures in PLACEHOLDER_REGEX.captures_iter(source) {
+        let whole = captures.get(0).unwrap();
+        let backslashes = captures[1].len();
+        let start = whole.start() + backslashes;
+
+        let mut text = source[last..whole.start()].to_string();
+        text.push_str(&"\\".repeat(backslashes / 2));
+
+        // 奇数个反斜杠：占位符原样输出
+        let segment = if backslashes % 2 == 1 {
+            text.push_str(&source[start..whole.end()]);
+            None
+        } else {
+            let position = source[..start].chars().count() + 1;
+            let error = |message: String| TemplateError { position, message };
+
+            let segment = parse_placeholder(&captures[2], captures.get(3).map(|m| m.as_str()))
+                .map_err(error)?;
+            if segment == Segment::Cursor {
+                if has_cursor {
+                    return Err(error("{{cursor}} 只能出现一次".to_string()));
+                }
+                has_cursor = true;
+            }
+            Some(segment)
+        };
+
+        push_text(&mut segments, text);
+        segments.extend(segment);
+        last = whole.end();
+    }
+
+    push_text(&mut segments, source[last..].to_string());
+
+    Ok(Template { segments })
+}
+
+// 追加文本片段，与前一个文本片段合并
+fn push_text(segments: &mut Vec<Segment>, text: String) {
+    if text.is_empty() {
+        return;
+    }
+    match segments.last_mut() {
+        Some(Segment::Text(previous)) => previous.push_str(&text),
+        _ => segments.push(Segment::Text(text)),
+    }
+}
+
+fn parse_placeholder(name: &str, arg: Option<&str>) -> Result<Segment, String> {
+    match (name, arg) {
+        ("date", format) => Ok(Segment::Date(validate_format(
+            format.unwrap_or(DEFAULT_DATE_FORMAT),
+        )?)),
+        ("time", format) => Ok(Segment::Time(validate_format(
+            format.unwrap_or(DEFAULT_TIME_FORMAT),
+        )?)),
+        ("clipboard", None) => Ok(Segment::Clipboard),
+        ("uuid", None) => Ok(Segment::Uuid),
+        ("cursor", None) => Ok(Segment::Cursor),
+        ("input", Some(label)) if !label.trim().is_empty() => {
+            Ok(Segment::Input(label.trim().to_string()))
+        }
+        ("input", _) => Err("{{input:名称}} 缺少名称".to_string()),
+        _ => Err(format!("{{{{{}}}}} 不接受参数", name)),
+    }
+}
+
+// 校验 strftime 格式，避免渲染时出错
+fn validate_format(format: &str) -> Result<String, String> {
+    if format.is_empty() {
+        return Err("日期时间格式不能为空".to_string());
+    }
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("无效的日期时间格式: {}", format));
+    }
+    Ok(format.to_string())
+}
+
+impl Template {
+    // 需要用户输入的字段（按首次出现顺序去重）
+    pub fn input_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for segment in &self.segments {
+            if let Segment::Input(name) = segment {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        names
+    }
+
+    pub fn uses_clipboard(&self) -> bool {
+        self.segments.contains(&Segment::Clipboard)
+    }
+
+    pub fn render(&self, context: &RenderContext) -> RenderedTemplate {
+        let mut text = String::new();
+        let mut cursor_position = None;
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(value) => text.push_str(value),
+                Segment::Date(format) | Segment::Time(format) => {
+                    text.push_str(&context.now.format(format).to_string())
+                }
+                Segment::Clipboard => text.push_str(&context.clipboard),
+                Segment::Uuid => text.push_str(&uuid::Uuid::new_v4().to_string()),
+                Segment::Cursor => cursor_position = Some(text.chars().count()),
+                Segment::Input(name) => {
+                    if let Some(value) = context.inputs.get(name) {
+                        text.push_str(value);
+                    }
+                }
+            }
+        }
+
+        RenderedTemplate {
+            text,
+            cursor_position,
+        }
+    }
+}
+
+// 预览模板（不弹出输入框，未提供的输入显示为 [名称]）
+pub fn preview(content: &str, inputs: HashMap<String, String>) -> Result<TemplatePreview, String> {
+    if !is_template(content) {
+        return Ok(TemplatePreview {
+            is_template: false,
+            text: content.to_string(),
+            cursor_position: None,
+            inputs: vec![],
+        });
+    }
+
+    let template = parse(content)?;
+    let names = template.input_names();
+    let mut inputs = inputs;
+    for name in &names {
+        inputs
+            .entry(name.clone())
+            .or_insert_with(|| format!("[{}]", name));
+    }
+
+    let clipboard = if template.uses_clipboard() {
+        read_clipboard_text()
+    } else {
+        String::new()
+    };
+    let rendered = template.render(&RenderContext {
+        now: Local::now(),
+        clipboard,
+        inputs,
+    });
+
+    Ok(TemplatePreview {
+        is_template: true,
+        text: rendered.text,
+        cursor_position: rendered.cursor_position,
+        inputs: names,
+    })
+}
+
+// 展开模板（粘贴时使用），内容不是模板时返回 None；用户取消输入时返回错误
+pub async fn expand(
+    app_handle: &tauri::AppHandle,
+    content: &str,
+) -> Result<Option<RenderedTemplate>, String> {
+    if !is_template(content) {
+        return Ok(None);
+    }
+
+    let template = parse(content)?;
+
+    // 先读取剪贴板，避免输入过程中剪贴板发生变化
+    let clipboard = if template.uses_clipboard() {
+        read_clipboard_text()
+    } else {
+        String::new()
+    };
+
+    let inputs = prompt_inputs(app_handle, &template.input_names()).await?;
+
+    Ok(Some(template.render(&RenderContext {
+        now: Local::now(),
+        clipboard,
+        inputs,
+    })))
+}
+
+async fn prompt_inputs(
+    app_handle: &tauri::AppHandle,
+    names: &[String],
+) -> Result<HashMap<String, String>, String> {
+    use crate::plugins::input_dialog::window::{show_dialog, InputDialogOptions, InputType};
+
+    let mut inputs = HashMap::new();
+    if names.is_empty() {
+        return Ok(inputs);
+    }
+
+    // 输入框会抢占焦点，记录粘贴目标窗口以便输入完成后恢复
+    #[cfg(windows)]
+    let target_hwnd = unsafe { windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow() };
+
+    for name in names {
+        let options = InputDialogOptions {
+            title: "填写片段".to_string(),
+            message: format!("请输入「{}」", name),
+            placeholder: Some(name.clone()),
+            default_value: None,
+            input_type: InputType::Text,
+            min_value: None,
+            max_value: None,
+        };
+
+        match show_dialog(app_handle.clone(), options).await? {
+            Some(value) => {
+                inputs.insert(name.clone(), value);
+            }
+            None => return Err("已取消填写片段".to_string()),
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        crate::window_management::set_last_focus_hwnd(target_hwnd.0);
+        crate::window_management::restore_last_focus()?;
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+
+    Ok(inputs)
+}
+
+fn read_clipboard_text() -> String {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn render(source: &str, inputs: &[(&str, &str)]) -> RenderedTemplate {
+        parse(source).unwrap().render(&RenderContext {
+            now: Local.with_ymd_and_hms(2024, 3, 9, 8, 5, 7).unwrap(),
+            clipboard: "剪贴板".to_string(),
+            inputs: inputs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let rendered = render(
+            "{{date}} {{time}} {{date:%Y/%m}} {{clipboard}} {{input:Name}}-{{input:Name}}",
+            &[("Name", "张三")],
+        );
+        assert_eq!(
+            rendered.text,
+            "2024-03-09 08:05:07 2024/03 剪贴板 张三-张三"
+        );
+        assert_eq!(rendered.cursor_position, None);
+        assert_eq!(render("{{uuid}}", &[]).text.len(), 36);
+    }
+
+    #[test]
+    fn keeps_other_braces_and_places_cursor() {
+        let rendered = render("fn {{ {{cursor}} }} {date}", &[]);
+        assert_eq!(rendered.text, "fn {{  }} {date}");
+        assert_eq!(rendered.cursor_position, Some(6));
+        assert_eq!(rendered.cursor_offset_from_end(), 10);
+    }
+
+    #[test]
+    fn reports_malformed_templates() {
+        let position = |source: &str| parse(source).unwrap_err().position;
+        assert_eq!(position("ab{{date:%Q}}"), 3);
+        assert_eq!(position("{{cursor}}{{cursor}}"), 11);
+        assert!(parse("{{input:}}").is_err());
+        assert!(parse("{{uuid:x}}").is_err());
+    }
+
+    #[test]
+    fn plain_text_is_not_template() {
+        assert!(!is_template(r#"{"key": "value"} {foo}"#));
+        assert!(!is_template("{{ date }} {{foo}} {{date"));
+        assert!(is_template("日期 {{date}}"));
+        let preview = preview("{{input:城市}}", HashMap::new()).unwrap();
+        assert_eq!(preview.text, "[城市]");
+        assert_eq!(preview.inputs, vec!["城市"]);
+    }
+
+    #[test]
+    fn plain_brace_favorite_pastes_unchanged() {
+        let content = "fn main() {\n    println!(\"{date} {time}\", x);\n}\n{cursor} {input:name} }{";
+        assert!(!is_template(content));
+        let preview = preview(content, HashMap::new()).unwrap();
+        assert!(!preview.is_template);
+        assert_eq!(preview.text, content);
+        assert_eq!(render(content, &[]).text, content);
+    }
+
+    #[test]
+    fn escapes_placeholders_with_backslash() {
+        let rendered = render(r"\{{date}} \\{{date}} \\\{{cursor}} C:\dir {{cursor}}", &[]);
+        assert_eq!(rendered.text, r"{{date}} \2024-03-09 \{{cursor}} C:\dir ");
+        assert_eq!(rendered.cursor_position, Some(40));
+        // 转义的占位符不校验参数
+        assert_eq!(render(r"\{{uuid:x}}", &[]).text, "{{uuid:x}}");
+        assert_eq!(parse(r"\{{date}}").unwrap().segments, vec![Segment::Text("{{date}}".to_string())]);
+        assert!(is_template(r"\{{date}}"));
+    }
+}
@@ -0,0 +1,225 @@
+// 同步变更日志
+//
+// 每个设备只向自己的目录追加日志文件（changes/<设备ID>/<时间戳>-<随机后缀>.jsonl），已写入的文件不再修改。
+// 文件每行一条变更，按时间戳和设备ID比较新旧，同一条记录以最新的变更为准（last-writer-wins）。
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// 日志文件扩展名
+pub const SEGMENT_EXTENSION: &str = ".jsonl";
+
+// 剪贴板记录（以内容摘要作为跨设备的记录键）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipboardRecord {
+    pub content: String,
+    pub html_content: Option<String>,
+    pub content_type: String,
+    pub image_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FavoriteRecord {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub html_content: Option<String>,
+    pub content_type: String,
+    pub image_id: Option<String>,
+    pub group_name: String,
+    pub item_order: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupRecord {
+    pub name: String,
+    pub icon: String,
+    pub order: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ChangeOp {
+    ClipboardUpsert { key: String, record: ClipboardRecord },
+    ClipboardDelete { key: String },
+    // 剪贴板顺序（记录键列表，最前面的在前）
+    ClipboardReorder { keys: Vec<String> },
+    FavoriteUpsert { record: FavoriteRecord },
+    FavoriteDelete { id: String },
+    GroupUpsert { record: GroupRecord },
+    GroupDelete { name: String },
+}
+
+// 记录类型，对应 sync_records.kind
+pub const KIND_CLIPBOARD: &str = "clipboard";
+pub const KIND_CLIPBOARD_ORDER: &str = "clipboard_order";
+pub const KIND_FAVORITE: &str = "favorite";
+pub const KIND_GROUP: &str = "group";
+
+impl ChangeOp {
+    // 变更针对的记录（类型, 键）
+    pub fn target(&self) -> (&'static str, String) {
+        match self {
+            ChangeOp::ClipboardUpsert { key, .. } | ChangeOp::ClipboardDelete { key } => {
+                (KIND_CLIPBOARD, key.clone())
+            }
+            ChangeOp::ClipboardReorder { .. } => (KIND_CLIPBOARD_ORDER, String::new()),
+            ChangeOp::FavoriteUpsert { record } => (KIND_FAVORITE, record.id.clone()),
+            ChangeOp::FavoriteDelete { id } => (KIND_FAVORITE, id.clone()),
+            ChangeOp::GroupUpsert { record } => (KIND_GROUP, record.name.clone()),
+            ChangeOp::GroupDelete { name } => (KIND_GROUP, name.clone()),
+        }
+    }
+
+    pub fn is_delete(&self) -> bool {
+        matches!(
+            self,
+            ChangeOp::ClipboardDelete { .. }
+                | ChangeOp::FavoriteDelete { .. }
+                | ChangeOp::GroupDelete { .. }
+        )
+    }
+
+    // 变更引用的图片
+    pub fn image_id(&self) -> Option<&str> {
+        match self {
+            ChangeOp::ClipboardUpsert { record, .. } => record.image_id.as_deref(),
+            ChangeOp::FavoriteUpsert { record } => record.image_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    // 变更后记录内容的摘要（删除为空）
+    pub fn digest(&self) -> String {
+        match self {
+            ChangeOp::ClipboardUpsert { record, .. } => digest_of(record),
+            ChangeOp::ClipboardReorder { keys } => digest_of(keys),
+            ChangeOp::FavoriteUpsert { record } => digest_of(record),
+            ChangeOp::GroupUpsert { record } => digest_of(record),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEntry {
+    // 变更时间（毫秒）
+    pub ts: i64,
+    pub device: String,
+    #[serde(flatten)]
+    pub op: ChangeOp,
+}
+
+// 判断变更版本 (ts, device) 是否比当前版本新，时间相同时按设备ID决定，保证各设备结果一致
+pub fn is_newer(ts: i64, device: &str, current_ts: i64, current_device: &str) -> bool {
+    (ts, device) > (current_ts, current_device)
+}
+
+// 剪贴板记录键：内容类型和内容的摘要
+pub fn clipboard_key(content_type: &str, content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content_type.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())[..32].to_string()
+}
+
+pub fn digest_of<T: Serialize>(value: &T) -> String {
+    let json = serde_json::to_string(value).unwrap_or_default();
+    format!("{:x}", Sha256::digest(json.as_bytes()))
+}
+
+// 生成新的日志文件名（时间戳补零，按文件名排序即按时间排序）
+pub fn segment_file_name(ts: i64) -> String {
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    format!("{:013}-{}{}", ts, &suffix[..8], SEGMENT_EXTENSION)
+}
+
+pub fn encode_segment(entries: &[ChangeEntry]) -> Result<String, String> {
+    let mut output = String::new();
+    for entry in entries {
+        let line = serde_json::to_string(entry).map_err(|e| format!("序列化同步变更失败: {}", e))?;
+        output.push_str(&line);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+// 解析日志文件，跳过无法识别的行（可能来自更新版本的程序）
+pub fn parse_segment(text: &str) -> Vec<ChangeEntry> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                println!("跳过无法解析的同步变更: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+// 设备ID和图片ID会用作路径，只允许字母、数字、连字符和下划线
+pub fn is_safe_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 128
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_round_trip_and_skips_unknown_lines() {
+        let entries = vec![
+            ChangeEntry {
+                ts: 1_700_000_000_000,
+                device: "a".to_string(),
+                op: ChangeOp::ClipboardUpsert {
+                    key: clipboard_key("text", "hello"),
+                    record: ClipboardRecord {
+                        content: "hello".to_string(),
+                        html_content: None,
+                        content_type: "text".to_string(),
+                        image_id: None,
+                    },
+                },
+            },
+            ChangeEntry {
+                ts: 1_700_000_000_001,
+                device: "a".to_string(),
+                op: ChangeOp::GroupDelete { name: "工作".to_string() },
+            },
+        ];
+
+        let mut text = encode_segment(&entries).unwrap();
+        text.push_str("{\"ts\":1,\"device\":\"a\",\"op\":\"future_op\"}\nnot json\n");
+
+        assert_eq!(parse_segment(&text), entries);
+    }
+
+    #[test]
+    fn newer_version_breaks_ties_by_device() {
+        assert!(is_newer(2, "a", 1, "z"));
+        assert!(!is_newer(1, "z", 2, "a"));
+        assert!(is_newer(5, "b", 5, "a"));
+        assert!(!is_newer(5, "a", 5, "a"));
+    }
+
+    #[test]
+    fn segment_names_sort_by_time() {
+        let earlier = segment_file_name(999);
+        let later = segment_file_name(1_700_000_000_000);
+        assert!(earlier < later);
+        assert!(later.ends_with(SEGMENT_EXTENSION));
+    }
+
+    #[test]
+    fn rejects_path_like_names() {
+        assert!(is_safe_name("3f2a9c0d1e2b4a5c"));
+        assert!(!is_safe_name("../etc"));
+        assert!(!is_safe_name("a/b"));
+        assert!(!is_safe_name(""));
+    }
+}
@@ -0,0 +1,685 @@
+// 同步引擎
+//
+// 一次同步分两步：
+// 1. 将本地数据与 sync_records 中上次同步的摘要对比，生成新增/修改/删除/排序变更并写入本设备的日志文件
+// 2. 读取其他设备新增的日志文件，按记录比较版本，只应用比本地版本新的变更
+// 图片按ID（内容摘要）存放在 images/<图片ID>.png，上传前检查是否已存在，应用变更时按需下载
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use super::changelog::{
+    self, ChangeEntry, ChangeOp, ClipboardRecord, FavoriteRecord, GroupRecord, KIND_CLIPBOARD,
+    KIND_CLIPBOARD_ORDER, KIND_FAVORITE, KIND_GROUP,
+};
+use super::transport::SyncTransport;
+use crate::database::with_connection;
+use crate::error::AppResult;
+
+const CHANGES_DIR: &str = "changes";
+const IMAGES_DIR: &str = "images";
+
+// 单次同步的结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    // 本次上传的本地变更数
+    pub uploaded: usize,
+    // 本次应用的远端变更数
+    pub applied: usize,
+    pub clipboard_changed: bool,
+    pub favorites_changed: bool,
+}
+
+// 记录上次同步时的状态
+#[derive(Debug, Clone)]
+struct RecordState {
+    digest: String,
+    ts: i64,
+    device: String,
+    deleted: bool,
+}
+
+type StateMap = HashMap<(String, String), RecordState>;
+
+// 本地当前数据
+struct LocalSnapshot {
+    // 每条记录及其修改时间（毫秒）
+    ops: Vec<(ChangeOp, i64)>,
+    clipboard_order: Vec<String>,
+}
+
+pub fn run(transport: &dyn SyncTransport, device_id: &str, target: &str) -> Result<SyncReport, String> {
+    reset_state_if_target_changed(target)?;
+
+    let mut report = SyncReport::default();
+    let mut state = load_state()?;
+
+    push_local_changes(transport, device_id, &mut state, &mut report)?;
+    pull_remote_changes(transport, device_id, &mut state, &mut report)?;
+
+    Ok(report)
+}
+
+// 同步目标变化后旧的同步状态不再适用，清空后重新上传全部数据
+fn reset_state_if_target_changed(target: &str) -> AppResult<()> {
+    with_connection(|conn| {
+        let current: Option<String> = conn
+            .query_row("SELECT value FROM sync_meta WHERE key = 'target'", [], |row| row.get(0))
+            .optional()?;
+        if current.as_deref() == Some(target) {
+            return Ok(());
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM sync_records", [])?;
+        tx.execute("DELETE FROM sync_cursors", [])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO sync_meta (key, value) VALUES ('target', ?1)",
+            params![target],
+        )?;
+        tx.commit()
+    })
+}
+
+// =================== 本地变更 ===================
+
+fn push_local_changes(
+    transport: &dyn SyncTransport,
+    device_id: &str,
+    state: &mut StateMap,
+    report: &mut SyncReport,
+) -> Result<(), String> {
+    let snapshot = load_snapshot()?;
+    let snapshot_keys: HashSet<(String, String)> = snapshot
+        .ops
+        .iter()
+        .map(|(op, _)| op.target())
+        .map(|(kind, key)| (kind.to_string(), key))
+        .collect();
+    let now = chrono::Utc::now().timestamp_millis();
+    // 变更的版本不低于上次同步的版本，否则其他设备会忽略这次修改
+    let version = |current: Option<&RecordState>, ts: i64| match current {
+        Some(current) => ts.max(current.ts + 1),
+        None => ts,
+    };
+    let mut ops = Vec::new();
+
+    // 修改和新增以记录自身的修改时间作为版本，冲突时保留最后修改的一方
+    for (op, updated_at) in snapshot.ops {
+        let (kind, key) = op.target();
+        let current = state.get(&(kind.to_string(), key));
+        let changed = match current {
+            Some(current) => current.deleted || current.digest != op.digest(),
+            None => true,
+        };
+        if changed {
+            ops.push((op, version(current, updated_at)));
+        }
+    }
+
+    // 上次同步时存在、现在已不存在的记录视为删除
+    for ((kind, key), current) in state.iter() {
+        if current.deleted || snapshot_keys.contains(&(kind.clone(), key.clone())) {
+            continue;
+        }
+        let op = match kind.as_str() {
+            KIND_CLIPBOARD => ChangeOp::ClipboardDelete { key: key.clone() },
+            KIND_FAVORITE => ChangeOp::FavoriteDelete { id: key.clone() },
+            KIND_GROUP => ChangeOp::GroupDelete { name: key.clone() },
+            _ => continue,
+        };
+        ops.push((op, version(Some(current), now)));
+    }
+
+    let order_op = ChangeOp::ClipboardReorder {
+        keys: snapshot.clipboard_order,
+    };
+    // 首次同步且本地没有剪贴板记录时不上传空顺序，避免覆盖其他设备的排序
+    let current_order = state.get(&(KIND_CLIPBOARD_ORDER.to_string(), String::new()));
+    let order_changed = match current_order {
+        Some(current) => current.digest != order_op.digest(),
+        None => !matches!(&order_op, ChangeOp::ClipboardReorder { keys } if keys.is_empty()),
+    };
+    if order_changed {
+        ops.push((order_op, version(current_order, now)));
+    }
+
+    if ops.is_empty() {
+        return Ok(());
+    }
+
+    // 先上传图片再写日志，其他设备读到日志时图片已可用
+    for (op, _) in &ops {
+        if let Some(image_id) = op.image_id() {
+            upload_image(transport, image_id)?;
+        }
+    }
+
+    let entries: Vec<ChangeEntry> = ops
+        .into_iter()
+        .map(|(op, ts)| ChangeEntry {
+            ts,
+            device: device_id.to_string(),
+            op,
+        })
+        .collect();
+    let segment = changelog::encode_segment(&entries)?;
+    let path = format!(
+        "{}/{}/{}",
+        CHANGES_DIR,
+        device_id,
+        changelog::segment_file_name(now)
+    );
+    transport.write(&path, segment.as_bytes())?;
+
+    for entry in &entries {
+        record_state(state, entry, entry.op.digest())?;
+    }
+    report.uploaded = entries.len();
+    Ok(())
+}
+
+fn upload_image(transport: &dyn SyncTransport, image_id: &str) -> Result<(), String> {
+    if !changelog::is_safe_name(image_id) {
+        return Ok(());
+    }
+    let path = format!("{}/{}.png", IMAGES_DIR, image_id);
+    if transport.exists(&path)? {
+        return Ok(());
+    }
+    let png = {
+        let manager = crate::image_manager::get_image_manager()?;
+        let manager = manager.lock().map_err(|e| format!("获取图片管理器失败: {}", e))?;
+        match manager.read_image_png(image_id) {
+            Ok(png) => png,
+            // 本地图片已被清理时只同步记录
+            Err(_) => return Ok(()),
+        }
+    };
+    transport.write(&path, &png)
+}
+
+// 读取本地需要同步的数据（不同步敏感的自动过期记录和仅在本机有效的文件记录）
+fn load_snapshot() -> AppResult<LocalSnapshot> {
+    with_connection(|conn| {
+        let mut ops = Vec::new();
+        let mut clipboard_order = Vec::new();
+        let mut seen = HashSet::new();
+
+        let mut stmt = conn.prepare(
+            "SELECT qc_decrypt(content), qc_decrypt(html_content), content_type, image_id, updated_at FROM clipboard
+             WHERE expires_at IS NULL AND content_type != 'file'
+             ORDER BY item_order, updated_at DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                ClipboardRecord {
+                    content: row.get(0)?,
+                    html_content: row.get(1)?,
+                    content_type: row.get(2)?,
+                    image_id: row.get(3)?,
+                },
+                row.get::<_, i64>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (record, updated_at) = row?;
+            let key = changelog::clipboard_key(&record.content_type, &record.content);
+            // 重复内容只同步最前面的一条
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            clipboard_order.push(key.clone());
+            ops.push((ChangeOp::ClipboardUpsert { key, record }, updated_at * 1000));
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT id, qc_decrypt(title), qc_decrypt(content), qc_decrypt(html_content), content_type, image_id, group_name, item_order, updated_at FROM favorites",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                FavoriteRecord {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    html_content: row.get(3)?,
+                    content_type: row.get(4)?,
+                    image_id: row.get(5)?,
+                    group_name: row.get(6)?,
+                    item_order: row.get(7)?,
+                },
+                row.get::<_, i64>(8)?,
+            ))
+        })?;
+        for row in rows {
+            let (record, updated_at) = row?;
+            ops.push((ChangeOp::FavoriteUpsert { record }, updated_at * 1000));
+        }
+
+        let mut stmt = conn.prepare("SELECT name, icon, order_index, updated_at FROM groups")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                GroupRecord {
+                    name: row.get(0)?,
+                    icon: row.get(1)?,
+                    order: row.get(2)?,
+                },
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (record, updated_at) = row?;
+            ops.push((ChangeOp::GroupUpsert { record }, updated_at * 1000));
+        }
+
+        Ok(LocalSnapshot {
+            ops,
+            clipboard_order,
+        })
+    })
+}
+
+// =================== 远端变更 ===================
+
+fn pull_remote_changes(
+    transport: &dyn SyncTransport,
+    device_id: &str,
+    state: &mut StateMap,
+    report: &mut SyncReport,
+) -> Result<(), String> {
+    let mut devices = transport.list(CHANGES_DIR)?;
+    devices.retain(|d| d != device_id && changelog::is_safe_name(d));
+    devices.sort();
+
+    let mut clipboard_index = load_clipboard_index()?;
+
+    for device in devices {
+        let cursor = load_cursor(&device)?;
+        let mut segments: Vec<String> = transport
+            .list(&format!("{}/{}", CHANGES_DIR, device))?
+            .into_iter()
+            .filter(|name| name.ends_with(changelog::SEGMENT_EXTENSION))
+            .filter(|name| cursor.as_ref().is_none_or(|c| name > c))
+            .collect();
+        segments.sort();
+
+        for segment in segments {
+            let path = format!("{}/{}/{}", CHANGES_DIR, device, segment);
+            let Some(data) = transport.read(&path)? else {
+                continue;
+            };
+            let text = String::from_utf8_lossy(&data);
+            for entry in changelog::parse_segment(&text) {
+                // 日志中的设备ID必须与所在目录一致，避免冒充其他设备
+                if entry.device != device {
+                    continue;
+                }
+                if apply_entry(transport, &entry, state, &mut clipboard_index, report)? {
+                    report.applied += 1;
+                }
+            }
+            save_cursor(&device, &segment)?;
+        }
+    }
+
+    // 远端变更改变了本地顺序，把当前顺序记为已同步，避免下次把合并结果当作本地排序再上传
+    if report.clipboard_changed {
+        let order = load_snapshot()?.clipboard_order;
+        let key = (KIND_CLIPBOARD_ORDER.to_string(), String::new());
+        let (ts, device) = state
+            .get(&key)
+            .map(|s| (s.ts, s.device.clone()))
+            .unwrap_or((0, String::new()));
+        let entry = ChangeEntry {
+            ts,
+            device,
+            op: ChangeOp::ClipboardReorder { keys: order },
+        };
+        record_state(state, &entry, entry.op.digest())?;
+    }
+
+    Ok(())
+}
+
+// 应用一条远端变更，返回是否实际应用
+fn apply_entry(
+    transport: &dyn SyncTransport,
+    entry: &ChangeEntry,
+    state: &mut StateMap,
+    clipboard_index: &mut HashMap<String, i64>,
+    report: &mut SyncReport,
+) -> Result<bool, String> {
+    let (kind, key) = entry.op.target();
+    if let Some(current) = state.get(&(kind.to_string(), key)) {
+        if !changelog::is_newer(entry.ts, &entry.device, current.ts, &current.device) {
+            return Ok(false);
+        }
+    }
+
+    if let Some(image_id) = entry.op.image_id() {
+        download_image(transport, image_id)?;
+    }
+
+    let updated_at = entry.ts / 1000;
+    match &entry.op {
+        ChangeOp::ClipboardUpsert { key, record } => {
+            let existing = clipboard_index.get(key).copied();
+            let id = with_connection(|conn| upsert_clipboard(conn, existing, record, updated_at))?;
+            clipboard_index.insert(key.clone(), id);
+            report.clipboard_changed = true;
+        }
+        ChangeOp::ClipboardDelete { key } => {
+            if let Some(id) = clipboard_index.remove(key) {
+                crate::database::delete_clipboard_item(id)?;
+                report.clipboard_changed = true;
+            }
+        }
+        ChangeOp::ClipboardReorder { keys } => {
+            let ids: Vec<i64> = keys
+                .iter()
+                .filter_map(|key| clipboard_index.get(key).copied())
+                .collect();
+            with_connection(|conn| {
+                let tx = conn.unchecked_transaction()?;
+                for (index, id) in ids.iter().enumerate() {
+                    tx.execute(
+                        "UPDATE clipboard SET item_order = ?1 WHERE id = ?2",
+                        params![index as i32, id],
+                    )?;
+                }
+                tx.commit()
+            })?;
+            report.clipboard_changed = true;
+        }
+        ChangeOp::FavoriteUpsert { record } => {
+            with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO favorites (id, title, content, html_content, content_type, image_id, group_name, item_order, created_at, updated_at)
+                     VALUES (?1, qc_encrypt(?2), qc_encrypt(?3), qc_encrypt(?4), ?5, ?6, ?7, ?8, ?9, ?9)
+                     ON CONFLICT(id) DO UPDATE SET title = excluded.title, content = excluded.content,
+                        html_content = excluded.html_content, content_type = excluded.content_type,
+                        image_id = excluded.image_id, group_name = excluded.group_name,
+                        item_order = excluded.item_order, updated_at = excluded.updated_at",
+                    params![
+                        record.id,
+                        record.title,
+                        record.content,
+                        record.html_content,
+                        record.content_type,
+                        record.image_id,
+                        record.group_name,
+                        record.item_order,
+                        updated_at
+                    ],
+                )
+            })?;
+            report.favorites_changed = true;
+        }
+        ChangeOp::FavoriteDelete { id } => {
+            crate::database::delete_favorite_item(id)?;
+            report.favorites_changed = true;
+        }
+        ChangeOp::GroupUpsert { record } => {
+            with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO groups (name, icon, order_index, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)
+                     ON CONFLICT(name) DO UPDATE SET icon = excluded.icon, order_index = excluded.order_index, updated_at = excluded.updated_at",
+                    params![record.name, record.icon, record.order, updated_at],
+                )
+            })?;
+            report.favorites_changed = true;
+        }
+        ChangeOp::GroupDelete { name } => {
+            crate::database::delete_group_items(name)?;
+            report.favorites_changed = true;
+        }
+    }
+
+    record_state(state, entry, entry.op.digest())?;
+    Ok(true)
+}
+
+// 新记录插入到最前面，已有记录只更新附带的HTML
+fn upsert_clipboard(
+    conn: &Connection,
+    existing: Option<i64>,
+    record: &ClipboardRecord,
+    updated_at: i64,
+) -> rusqlite::Result<i64> {
+    if let Some(id) = existing {
+        conn.execute(
+            "UPDATE clipboard SET html_content = qc_encrypt(?1) WHERE id = ?2",
+            params![record.html_content, id],
+        )?;
+        return Ok(id);
+    }
+
+    let min_order: i32 = conn
+        .query_row("SELECT COALESCE(MIN(item_order), 0) FROM clipboard", [], |row| row.get(0))
+        .unwrap_or(0);
+    conn.execute(
+        "INSERT INTO clipboard (content, html_content, content_type, image_id, item_order, created_at, updated_at) VALUES (qc_encrypt(?1), qc_encrypt(?2), ?3, ?4, ?5, ?6, ?6)",
+        params![
+            record.content,
+            record.html_content,
+            record.content_type,
+            record.image_id,
+            min_order.min(0) - 1,
+            updated_at
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn download_image(transport: &dyn SyncTransport, image_id: &str) -> Result<(), String> {
+    if !changelog::is_safe_name(image_id) {
+        return Err(format!("无效的图片ID: {}", image_id));
+    }
+    let manager = crate::image_manager::get_image_manager()?;
+    if manager
+        .lock()
+        .map_err(|e| format!("获取图片管理器失败: {}", e))?
+        .has_image(image_id)
+    {
+        return Ok(());
+    }
+
+    let Some(png) = transport.read(&format!("{}/{}.png", IMAGES_DIR, image_id))? else {
+        println!("同步目录中缺少图片: {}", image_id);
+        return Ok(());
+    };
+    manager
+        .lock()
+        .map_err(|e| format!("获取图片管理器失败: {}", e))?
+        .import_image_png(image_id, &png)?;
+    Ok(())
+}
+
+// 剪贴板记录键到本地ID的索引（重复内容取最前面的一条）
+fn load_clipboard_index() -> AppResult<HashMap<String, i64>> {
+    with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, qc_decrypt(content), content_type FROM clipboard ORDER BY item_order DESC, updated_at",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+        let mut index = HashMap::new();
+        for row in rows {
+            let (id, content, content_type) = row?;
+            index.insert(changelog::clipboard_key(&content_type, &content), id);
+        }
+        Ok(index)
+    })
+}
+
+// =================== 同步状态 ===================
+
+fn load_state() -> AppResult<StateMap> {
+    with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT kind, record_key, digest, updated_at, device_id, deleted FROM sync_records",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                (row.get::<_, String>(0)?, row.get::<_, String>(1)?),
+                RecordState {
+                    digest: row.get(2)?,
+                    ts: row.get(3)?,
+                    device: row.get(4)?,
+                    deleted: row.get::<_, i64>(5)? != 0,
+                },
+            ))
+        })?;
+        rows.collect()
+    })
+}
+
+fn record_state(state: &mut StateMap, entry: &ChangeEntry, digest: String) -> Result<(), String> {
+    let (kind, key) = entry.op.target();
+    let deleted = entry.op.is_delete();
+    with_connection(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_records (kind, record_key, digest, updated_at, device_id, deleted) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![kind, key, digest, entry.ts, entry.device, deleted as i64],
+        )
+    })?;
+    state.insert(
+        (kind.to_string(), key),
+        RecordState {
+            digest,
+            ts: entry.ts,
+            device: entry.device.clone(),
+            deleted,
+        },
+    );
+    Ok(())
+}
+
+fn load_cursor(device: &str) -> AppResult<Option<String>> {
+    with_connection(|conn| {
+        conn.query_row(
+            "SELECT last_segment FROM sync_cursors WHERE device_id = ?1",
+            params![device],
+            |row| row.get(0),
+        )
+        .optional()
+    })
+}
+
+fn save_cursor(device: &str, segment: &str) -> Result<(), String> {
+    with_connection(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_cursors (device_id, last_segment) VALUES (?1, ?2)",
+            params![device, segment],
+        )
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::transport::FolderTransport;
+    use super::*;
+    use crate::database::DB_CONNECTION;
+    use std::path::Path;
+
+    // 一台同步设备（独立的数据库文件）
+    struct Replica {
+        device: &'static str,
+        conn: Option<Connection>,
+    }
+
+    impl Replica {
+        fn open(dir: &Path, device: &'static str) -> Self {
+            let path = dir.join(format!("{}.db", device));
+            let mut conn = Connection::open(&path).unwrap();
+            crate::database_search::register_search_functions(&conn).unwrap();
+            crate::encryption::register_sql_functions(&conn).unwrap();
+            crate::database_migration::run_migrations(&mut conn, &path).unwrap();
+            Self {
+                device,
+                conn: Some(conn),
+            }
+        }
+
+        fn conn(&self) -> &Connection {
+            self.conn.as_ref().unwrap()
+        }
+
+        // 同步引擎通过全局连接读写数据库，同步期间换入本设备的连接
+        fn sync(&mut self, transport: &FolderTransport) -> SyncReport {
+            // 日志文件名精确到毫秒，错开两次同步以保证文件顺序
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            *DB_CONNECTION.lock().unwrap() = self.conn.take();
+            let report = run(transport, self.device, "folder:test");
+            self.conn = DB_CONNECTION.lock().unwrap().take();
+            report.unwrap()
+        }
+
+        fn favorite_title(&self, id: &str) -> Option<String> {
+            self.conn()
+                .query_row("SELECT title FROM favorites WHERE id = ?1", params![id], |row| {
+                    row.get(0)
+                })
+                .optional()
+                .unwrap()
+        }
+
+        fn clipboard_contents(&self) -> Vec<String> {
+            let mut stmt = self
+                .conn()
+                .prepare("SELECT content FROM clipboard ORDER BY item_order")
+                .unwrap();
+            let rows = stmt.query_map([], |row| row.get(0)).unwrap();
+            rows.map(Result::unwrap).collect()
+        }
+    }
+
+    #[test]
+    fn replicas_converge_on_latest_edit_and_deletes() {
+        let dir = std::env::temp_dir().join(format!("qc-sync-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let transport = FolderTransport::new(dir.join("remote").to_str().unwrap()).unwrap();
+        let mut a = Replica::open(&dir, "device-a");
+        let mut b = Replica::open(&dir, "device-b");
+
+        a.conn()
+            .execute_batch(
+                "INSERT INTO clipboard (content, content_type, created_at, updated_at) VALUES ('hello', 'text', 1000, 1000);
+                 INSERT INTO favorites (id, title, content, created_at, updated_at) VALUES
+                    ('f1', 'first', 'x', 1000, 1000),
+                    ('f2', 'second', 'y', 1000, 1000);",
+            )
+            .unwrap();
+        assert!(a.sync(&transport).uploaded > 0);
+        assert!(b.sync(&transport).applied > 0);
+        assert_eq!(b.clipboard_contents(), vec!["hello"]);
+        assert_eq!(b.favorite_title("f1").as_deref(), Some("first"));
+
+        // 两边修改同一收藏：B 的修改更晚，但 A 后同步，结果仍应以 B 为准
+        b.conn()
+            .execute("UPDATE favorites SET title = 'edited on b', updated_at = 3000 WHERE id = 'f1'", [])
+            .unwrap();
+        a.conn()
+            .execute("UPDATE favorites SET title = 'edited on a', updated_at = 2000 WHERE id = 'f1'", [])
+            .unwrap();
+        b.sync(&transport);
+        a.sync(&transport);
+        b.sync(&transport);
+        assert_eq!(a.favorite_title("f1").as_deref(), Some("edited on b"));
+        assert_eq!(b.favorite_title("f1").as_deref(), Some("edited on b"));
+
+        a.conn()
+            .execute("DELETE FROM favorites WHERE id = 'f2'", [])
+            .unwrap();
+        a.sync(&transport);
+        b.sync(&transport);
+        assert_eq!(b.favorite_title("f2"), None);
+        assert_eq!(b.favorite_title("f1").as_deref(), Some("edited on b"));
+
+        drop((a, b));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
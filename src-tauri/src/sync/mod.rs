@@ -0,0 +1,231 @@
+// 多设备同步
+//
+// 通过共享文件夹（网盘同步目录、网络共享等）或 WebDAV 在多台设备间同步剪贴板历史、收藏和分组。
+// 各设备只追加写入自己的变更日志，不直接共享数据库文件；合并时同一记录以最新的变更为准。
+// 同步目录中的数据为明文，因此启用数据加密时不进行同步。
+
+mod changelog;
+mod engine;
+mod transport;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::settings::AppSettings;
+pub use engine::SyncReport;
+use transport::{FolderTransport, SyncTransport, WebDavTransport};
+
+// 后台线程检查停止标记的间隔
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+struct RunningWorker {
+    // 同步配置（配置变化时重启后台线程）
+    config: String,
+    stop: Arc<AtomicBool>,
+}
+
+static WORKER: Lazy<Mutex<Option<RunningWorker>>> = Lazy::new(|| Mutex::new(None));
+
+// 防止后台同步和手动同步同时执行
+static SYNC_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+static LAST_RESULT: Lazy<Mutex<LastResult>> = Lazy::new(|| Mutex::new(LastResult::default()));
+
+#[derive(Debug, Clone, Default)]
+struct LastResult {
+    at: Option<i64>,
+    error: Option<String>,
+    report: Option<SyncReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub device_id: String,
+    pub last_sync_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub last_report: Option<SyncReport>,
+}
+
+// 根据设置启动、重启或停止后台同步
+pub fn apply_settings(app_handle: &AppHandle, settings: &AppSettings) {
+    if !settings.sync_enabled {
+        stop_worker();
+        return;
+    }
+
+    if settings.sync_device_id.is_empty() {
+        let mut updated = crate::settings::get_global_settings();
+        updated.sync_device_id = uuid::Uuid::new_v4().simple().to_string();
+        if let Err(e) = crate::settings::update_global_settings(updated) {
+            println!("生成同步设备ID失败: {}", e);
+            return;
+        }
+    }
+
+    let config = worker_config(settings);
+    let Ok(mut worker) = WORKER.lock() else {
+        return;
+    };
+    if let Some(running) = worker.as_ref() {
+        if running.config == config {
+            return;
+        }
+        running.stop.store(true, Ordering::SeqCst);
+        *worker = None;
+    }
+
+    *worker = Some(start_worker(
+        app_handle.clone(),
+        config,
+        settings.sync_interval_minutes.max(1),
+    ));
+}
+
+pub fn stop_worker() {
+    if let Ok(mut worker) = WORKER.lock() {
+        if let Some(running) = worker.take() {
+            running.stop.store(true, Ordering::SeqCst);
+            println!("后台同步已停止");
+        }
+    }
+}
+
+pub fn get_status() -> SyncStatus {
+    let settings = crate::settings::get_global_settings();
+    let running = WORKER.lock().map(|w| w.is_some()).unwrap_or(false);
+    let last = LAST_RESULT.lock().map(|r| r.clone()).unwrap_or_default();
+    SyncStatus {
+        enabled: settings.sync_enabled,
+        running,
+        device_id: settings.sync_device_id,
+        last_sync_at: last.at,
+        last_error: last.error,
+        last_report: last.report,
+    }
+}
+
+// 立即同步一次（阻塞执行，会访问网络）
+pub fn sync_now(app_handle: &AppHandle) -> Result<SyncReport, String> {
+    let result = run_sync();
+
+    if let Ok(mut last) = LAST_RESULT.lock() {
+        last.at = Some(chrono::Local::now().timestamp());
+        match &result {
+            Ok(report) => {
+                last.error = None;
+                last.report = Some(report.clone());
+            }
+            Err(e) => last.error = Some(e.clone()),
+        }
+    }
+
+    if let Ok(report) = &result {
+        notify_changes(app_handle, report);
+    }
+    result
+}
+
+fn run_sync() -> Result<SyncReport, String> {
+    let settings = crate::settings::get_global_settings();
+    if !settings.sync_enabled {
+        return Err("同步未启用".to_string());
+    }
+    if crate::encryption::is_enabled() {
+        return Err("启用数据加密时不支持同步".to_string());
+    }
+    if settings.sync_device_id.is_empty() {
+        return Err("同步设备ID未生成".to_string());
+    }
+
+    let _guard = SYNC_LOCK
+        .lock()
+        .map_err(|e| format!("获取同步锁失败: {}", e))?;
+
+    let transport = create_transport(&settings)?;
+    let report = engine::run(transport.as_ref(), &settings.sync_device_id, &target_id(&settings))?;
+
+    if report.clipboard_changed {
+        crate::database::limit_clipboard_history(settings.history_limit as usize)?;
+        crate::content_classifier::classify_pending_in_background();
+    }
+    if report.uploaded > 0 || report.applied > 0 {
+        println!(
+            "同步完成: 上传 {} 条变更，应用 {} 条变更",
+            report.uploaded, report.applied
+        );
+    }
+    Ok(report)
+}
+
+fn create_transport(settings: &AppSettings) -> Result<Box<dyn SyncTransport>, String> {
+    match settings.sync_mode.as_str() {
+        "webdav" => Ok(Box::new(WebDavTransport::new(
+            &settings.sync_webdav_url,
+            &settings.sync_webdav_username,
+            &settings.sync_webdav_password,
+        )?)),
+        _ => Ok(Box::new(FolderTransport::new(&settings.sync_folder_path)?)),
+    }
+}
+
+// 同步目标标识（更换目标后重新上传全部数据）
+fn target_id(settings: &AppSettings) -> String {
+    match settings.sync_mode.as_str() {
+        "webdav" => format!("webdav:{}", settings.sync_webdav_url.trim().trim_end_matches('/')),
+        _ => format!("folder:{}", settings.sync_folder_path.trim()),
+    }
+}
+
+fn worker_config(settings: &AppSettings) -> String {
+    format!(
+        "{}|{}|{}|{}",
+        target_id(settings),
+        settings.sync_webdav_username,
+        settings.sync_webdav_password,
+        settings.sync_interval_minutes
+    )
+}
+
+fn start_worker(app_handle: AppHandle, config: String, interval_minutes: u32) -> RunningWorker {
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let interval = Duration::from_secs(interval_minutes as u64 * 60);
+
+    std::thread::spawn(move || {
+        while !thread_stop.load(Ordering::SeqCst) {
+            if let Err(e) = sync_now(&app_handle) {
+                println!("后台同步失败: {}", e);
+            }
+
+            let started = Instant::now();
+            while started.elapsed() < interval {
+                if thread_stop.load(Ordering::SeqCst) {
+                    return;
+                }
+                std::thread::sleep(STOP_POLL_INTERVAL);
+            }
+        }
+    });
+
+    println!("后台同步已启动，间隔 {} 分钟", interval_minutes);
+    RunningWorker { config, stop }
+}
+
+// 应用了其他设备的变更后通知前端刷新
+fn notify_changes(app_handle: &AppHandle, report: &SyncReport) {
+    let Some(main_window) = app_handle.get_webview_window("main") else {
+        return;
+    };
+    if report.clipboard_changed {
+        let _ = main_window.emit("clipboard-changed", ());
+    }
+    if report.favorites_changed {
+        let _ = main_window.emit("refreshQuickTexts", ());
+    }
+}
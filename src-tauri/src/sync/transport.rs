@@ -0,0 +1,260 @@
+// 同步存储后端：本地/网络共享文件夹或 WebDAV
+//
+// 路径均为相对同步根目录、以 / 分隔的路径，例如 changes/<设备ID>/<文件名>
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+const WEBDAV_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub trait SyncTransport {
+    // 列出目录下的条目名称（目录不存在时返回空列表）
+    fn list(&self, dir: &str) -> Result<Vec<String>, String>;
+    // 读取文件（不存在时返回 None）
+    fn read(&self, path: &str) -> Result<Option<Vec<u8>>, String>;
+    // 写入文件，必要时创建上级目录
+    fn write(&self, path: &str, data: &[u8]) -> Result<(), String>;
+    fn exists(&self, path: &str) -> Result<bool, String>;
+}
+
+// =================== 文件夹 ===================
+
+pub struct FolderTransport {
+    root: PathBuf,
+}
+
+impl FolderTransport {
+    pub fn new(root: &str) -> Result<Self, String> {
+        if root.trim().is_empty() {
+            return Err("未设置同步文件夹".to_string());
+        }
+        let root = PathBuf::from(root);
+        fs::create_dir_all(&root).map_err(|e| format!("创建同步文件夹失败: {}", e))?;
+        Ok(Self { root })
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        path.split('/')
+            .filter(|s| !s.is_empty())
+            .fold(self.root.clone(), |acc, part| acc.join(part))
+    }
+}
+
+impl SyncTransport for FolderTransport {
+    fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        let entries = match fs::read_dir(self.resolve(dir)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("读取同步目录失败: {}", e)),
+        };
+        Ok(entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| !name.ends_with(".tmp"))
+            .collect())
+    }
+
+    fn read(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        match fs::read(self.resolve(path)) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("读取同步文件失败: {}", e)),
+        }
+    }
+
+    fn write(&self, path: &str, data: &[u8]) -> Result<(), String> {
+        let target = self.resolve(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建同步目录失败: {}", e))?;
+        }
+        // 先写临时文件再改名，其他设备不会读到写了一半的文件
+        crate::encryption::write_file_atomic(&target, data)
+    }
+
+    fn exists(&self, path: &str) -> Result<bool, String> {
+        Ok(self.resolve(path).exists())
+    }
+}
+
+// =================== WebDAV ===================
+
+static HREF_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<(?:[a-z0-9]+:)?href>\s*([^<]*?)\s*</(?:[a-z0-9]+:)?href>").unwrap());
+
+pub struct WebDavTransport {
+    base_url: String,
+    username: String,
+    password: String,
+    client: reqwest::blocking::Client,
+}
+
+impl WebDavTransport {
+    pub fn new(url: &str, username: &str, password: &str) -> Result<Self, String> {
+        let url = url.trim();
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("WebDAV 地址无效".to_string());
+        }
+        let client = reqwest::blocking::Client::builder()
+            .timeout(WEBDAV_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 WebDAV 客户端失败: {}", e))?;
+        Ok(Self {
+            base_url: url.trim_end_matches('/').to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            client,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        let encoded: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|part| urlencoding::encode(part).into_owned())
+            .collect();
+        format!("{}/{}", self.base_url, encoded.join("/"))
+    }
+
+    fn request(&self, method: &str, url: &str) -> Result<reqwest::blocking::RequestBuilder, String> {
+        let method = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|e| format!("无效的请求方法: {}", e))?;
+        let mut builder = self.client.request(method, url);
+        if !self.username.is_empty() {
+            builder = builder.basic_auth(&self.username, Some(&self.password));
+        }
+        Ok(builder)
+    }
+
+    // 逐级创建目录，已存在时服务器返回 405
+    fn ensure_collections(&self, path: &str) -> Result<(), String> {
+        let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        for depth in 1..parts.len() {
+            let url = format!("{}/", self.url(&parts[..depth].join("/")));
+            let response = self
+                .request("MKCOL", &url)?
+                .send()
+                .map_err(|e| format!("WebDAV 请求失败: {}", e))?;
+            let status = response.status().as_u16();
+            if !(response.status().is_success() || status == 405) {
+                return Err(format!("WebDAV 创建目录失败: HTTP {}", status));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SyncTransport for WebDavTransport {
+    fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        let dir_url = format!("{}/", self.url(dir));
+        let response = self
+            .request("PROPFIND", &dir_url)?
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(r#"<?xml version="1.0"?><propfind xmlns="DAV:"><prop><resourcetype/></prop></propfind>"#)
+            .send()
+            .map_err(|e| format!("WebDAV 请求失败: {}", e))?;
+
+        if response.status().as_u16() == 404 {
+            return Ok(Vec::new());
+        }
+        if !response.status().is_success() {
+            return Err(format!("WebDAV 列出目录失败: HTTP {}", response.status().as_u16()));
+        }
+
+        let body = response.text().map_err(|e| format!("读取 WebDAV 响应失败: {}", e))?;
+        Ok(parse_propfind_names(&body, &dir_url))
+    }
+
+    fn read(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        let response = self
+            .request("GET", &self.url(path))?
+            .send()
+            .map_err(|e| format!("WebDAV 请求失败: {}", e))?;
+        if response.status().as_u16() == 404 {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("WebDAV 读取文件失败: HTTP {}", response.status().as_u16()));
+        }
+        response
+            .bytes()
+            .map(|b| Some(b.to_vec()))
+            .map_err(|e| format!("读取 WebDAV 响应失败: {}", e))
+    }
+
+    fn write(&self, path: &str, data: &[u8]) -> Result<(), String> {
+        self.ensure_collections(path)?;
+        let response = self
+            .request("PUT", &self.url(path))?
+            .body(data.to_vec())
+            .send()
+            .map_err(|e| format!("WebDAV 请求失败: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("WebDAV 写入文件失败: HTTP {}", response.status().as_u16()));
+        }
+        Ok(())
+    }
+
+    fn exists(&self, path: &str) -> Result<bool, String> {
+        let response = self
+            .request("HEAD", &self.url(path))?
+            .send()
+            .map_err(|e| format!("WebDAV 请求失败: {}", e))?;
+        match response.status().as_u16() {
+            404 => Ok(false),
+            _ if response.status().is_success() => Ok(true),
+            status => Err(format!("WebDAV 请求失败: HTTP {}", status)),
+        }
+    }
+}
+
+// 从 PROPFIND 响应中提取子条目名称（跳过目录自身）
+fn parse_propfind_names(body: &str, dir_url: &str) -> Vec<String> {
+    let dir_path = url_path(dir_url).trim_end_matches('/').to_string();
+    HREF_REGEX
+        .captures_iter(body)
+        .filter_map(|cap| {
+            let href = cap[1].replace("&amp;", "&");
+            let path = url_path(&href).trim_end_matches('/').to_string();
+            if path == dir_path {
+                return None;
+            }
+            let name = path.rsplit('/').next()?;
+            urlencoding::decode(name).ok().map(|n| n.into_owned())
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+// href 可能是完整URL或绝对路径，统一取路径部分
+fn url_path(href: &str) -> &str {
+    match href.find("://") {
+        Some(scheme_end) => {
+            let rest = &href[scheme_end + 3..];
+            rest.find('/').map(|i| &rest[i..]).unwrap_or("/")
+        }
+        None => href,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_propfind_listing() {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response><d:href>/dav/qc/changes/</d:href></d:response>
+  <d:response><d:href>/dav/qc/changes/device-a/</d:href></d:response>
+  <D:response><D:href>http://example.com/dav/qc/changes/%E8%AE%BE%E5%A4%87/</D:href></D:response>
+</d:multistatus>"#;
+
+        let names = parse_propfind_names(body, "http://example.com/dav/qc/changes/");
+        assert_eq!(names, vec!["device-a".to_string(), "设备".to_string()]);
+    }
+}
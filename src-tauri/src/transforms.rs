@@ -0,0 +1,423 @@
+// 文本转换
+//
+// 内置转换以 id 注册，可单独使用或按顺序组成转换链。
+// 只有链中的第一个转换能读取原始 HTML 内容，之后的转换都作用于纯文本结果。
+
+use base64::{engine::general_purpose as b64_engine, Engine as _};
+use serde::Serialize;
+
+// 转换输入
+pub struct TransformInput<'a> {
+    pub text: &'a str,
+    pub html: Option<&'a str>,
+}
+
+// 转换定义（供前端展示）
+#[derive(Clone, Serialize)]
+pub struct TransformInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+    #[serde(skip)]
+    apply: fn(&TransformInput) -> Result<String, String>,
+}
+
+pub const TRANSFORMS: &[TransformInfo] = &[
+    TransformInfo { id: "trim", name: "去除首尾空白", apply: trim },
+    TransformInfo { id: "trim_lines", name: "去除每行首尾空白", apply: trim_lines },
+    TransformInfo { id: "remove_empty_lines", name: "删除空行", apply: remove_empty_lines },
+    TransformInfo { id: "collapse_whitespace", name: "合并连续空白", apply: collapse_whitespace },
+    TransformInfo { id: "uppercase", name: "转为大写", apply: uppercase },
+    TransformInfo { id: "lowercase", name: "转为小写", apply: lowercase },
+    TransformInfo { id: "title_case", name: "单词首字母大写", apply: title_case },
+    TransformInfo { id: "sort_lines", name: "按行排序", apply: sort_lines },
+    TransformInfo { id: "unique_lines", name: "删除重复行", apply: unique_lines },
+    TransformInfo { id: "url_encode", name: "URL 编码", apply: url_encode },
+    TransformInfo { id: "url_decode", name: "URL 解码", apply: url_decode },
+    TransformInfo { id: "base64_encode", name: "Base64 编码", apply: base64_encode },
+    TransformInfo { id: "base64_decode", name: "Base64 解码", apply: base64_decode },
+    TransformInfo { id: "json_pretty", name: "JSON 格式化", apply: json_pretty },
+    TransformInfo { id: "json_minify", name: "JSON 压缩", apply: json_minify },
+    TransformInfo { id: "html_to_text", name: "HTML 转纯文本", apply: html_to_text },
+    TransformInfo { id: "markdown_table", name: "转为 Markdown 表格", apply: markdown_table },
+    TransformInfo { id: "color_to_hex", name: "颜色转为十六进制", apply: color_to_hex },
+    TransformInfo { id: "color_to_rgb", name: "颜色转为 RGB", apply: color_to_rgb },
+    TransformInfo { id: "evaluate_math", name: "计算表达式", apply: evaluate_math },
+];
+
+fn find(id: &str) -> Result<&'static TransformInfo, String> {
+    TRANSFORMS
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("未知的转换: {}", id))
+}
+
+// 按顺序执行转换链
+pub fn apply_chain(ids: &[String], text: &str, html: Option<&str>) -> Result<String, String> {
+    if ids.is_empty() {
+        return Err("未指定转换".to_string());
+    }
+
+    // 先校验全部 id，避免执行到一半才报错
+    let transforms = ids
+        .iter()
+        .map(|id| find(id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut current = text.to_string();
+    let mut html = html;
+    for transform in transforms {
+        current = (transform.apply)(&TransformInput {
+            text: &current,
+            html: html.take(),
+        })
+        .map_err(|e| format!("{}失败: {}", transform.name, e))?;
+    }
+    Ok(current)
+}
+
+// =================== 空白处理 ===================
+
+fn trim(input: &TransformInput) -> Result<String, String> {
+    Ok(input.text.trim().to_string())
+}
+
+fn trim_lines(input: &TransformInput) -> Result<String, String> {
+    Ok(input.text.lines().map(str::trim).collect::<Vec<_>>().join("\n"))
+}
+
+fn remove_empty_lines(input: &TransformInput) -> Result<String, String> {
+    Ok(input
+        .text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn collapse_whitespace(input: &TransformInput) -> Result<String, String> {
+    Ok(input.text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+// =================== 大小写与行处理 ===================
+
+fn uppercase(input: &TransformInput) -> Result<String, String> {
+    Ok(input.text.to_uppercase())
+}
+
+fn lowercase(input: &TransformInput) -> Result<String, String> {
+    Ok(input.text.to_lowercase())
+}
+
+fn title_case(input: &TransformInput) -> Result<String, String> {
+    let mut result = String::with_capacity(input.text.len());
+    let mut at_word_start = true;
+    for c in input.text.chars() {
+        if c.is_alphanumeric() {
+            if at_word_start {
+                result.extend(c.to_uppercase());
+            } else {
+                result.extend(c.to_lowercase());
+            }
+            at_word_start = false;
+        } else {
+            result.push(c);
+            at_word_start = c != '\'';
+        }
+    }
+    Ok(result)
+}
+
+fn sort_lines(input: &TransformInput) -> Result<String, String> {
+    let mut lines: Vec<&str> = input.text.lines().collect();
+    lines.sort();
+    Ok(lines.join("\n"))
+}
+
+fn unique_lines(input: &TransformInput) -> Result<String, String> {
+    let mut seen = std::collections::HashSet::new();
+    Ok(input
+        .text
+        .lines()
+        .filter(|line| seen.insert(*line))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+// =================== 编码 ===================
+
+fn url_encode(input: &TransformInput) -> Result<String, String> {
+    Ok(urlencoding::encode(input.text).into_owned())
+}
+
+fn url_decode(input: &TransformInput) -> Result<String, String> {
+    urlencoding::decode(&input.text.trim().replace('+', " "))
+        .map(|s| s.into_owned())
+        .map_err(|_| "解码结果不是有效的 UTF-8 文本".to_string())
+}
+
+fn base64_encode(input: &TransformInput) -> Result<String, String> {
+    Ok(b64_engine::STANDARD.encode(input.text))
+}
+
+fn base64_decode(input: &TransformInput) -> Result<String, String> {
+    // 兼容换行分段和 URL 安全字母表，允许省略末尾的 =
+    let cleaned: String = input
+        .text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    let bytes = b64_engine::STANDARD_NO_PAD
+        .decode(cleaned.trim_end_matches('='))
+        .map_err(|e| format!("不是有效的 Base64: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| "解码结果不是有效的 UTF-8 文本".to_string())
+}
+
+// =================== JSON ===================
+
+fn json_pretty(input: &TransformInput) -> Result<String, String> {
+    reformat_json(input.text, Some("  "))
+}
+
+fn json_minify(input: &TransformInput) -> Result<String, String> {
+    reformat_json(input.text, None)
+}
+
+// 校验后按记号重新排版，不经过 Value，保留键的顺序和数字的原始写法
+// indent 为 None 时输出紧凑格式
+fn reformat_json(text: &str, indent: Option<&str>) -> Result<String, String> {
+    let text = text.trim();
+    serde_json::from_str::<serde::de::IgnoredAny>(text)
+        .map_err(|e| format!("不是有效的 JSON: {}", e))?;
+
+    let newline = |out: &mut String, depth: usize| {
+        if let Some(indent) = indent {
+            out.push('\n');
+            out.push_str(&indent.repeat(depth));
+        }
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut depth = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    if c == '\\' {
+                        out.extend(chars.next());
+                    } else if c == '"' {
+                        break;
+                    }
+                }
+            }
+            '{' | '[' => {
+                out.push(c);
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                // 空对象和空数组保持在同一行
+                if let Some(close) = chars.next_if(|&c| c == '}' || c == ']') {
+                    out.push(close);
+                } else {
+                    depth += 1;
+                    newline(&mut out, depth);
+                }
+            }
+            '}' | ']' => {
+                depth -= 1;
+                newline(&mut out, depth);
+                out.push(c);
+            }
+            ',' => {
+                out.push(c);
+                newline(&mut out, depth);
+            }
+            ':' => {
+                out.push(c);
+                if indent.is_some() {
+                    out.push(' ');
+                }
+            }
+            c if c.is_whitespace() => {}
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+// =================== HTML ===================
+
+// 优先使用剪贴板中的 HTML 格式，没有时把文本本身当作 HTML 处理
+fn html_to_text(input: &TransformInput) -> Result<String, String> {
+    Ok(crate::utils::content_utils::html_to_plain_text(
+        input.html.unwrap_or(input.text),
+    ))
+}
+
+// =================== Markdown 表格 ===================
+
+// 将制表符分隔（如从表格软件复制）或逗号分隔的文本转为 Markdown 表格，第一行作为表头
+fn markdown_table(input: &TransformInput) -> Result<String, String> {
+    let lines: Vec<&str> = input
+        .text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.is_empty() {
+        return Err("内容为空".to_string());
+    }
+
+    let rows: Vec<Vec<String>> = if lines.iter().any(|line| line.contains('\t')) {
+        lines
+            .iter()
+            .map(|line| line.split('\t').map(|cell| cell.trim().to_string()).collect())
+            .collect()
+    } else {
+        lines.iter().map(|line| split_csv_line(line)).collect()
+    };
+
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if columns < 2 {
+        return Err("未识别到制表符或逗号分隔的列".to_string());
+    }
+
+    let format_row = |row: &[String]| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| {
+                row.get(i)
+                    .map(|cell| cell.replace('|', "\\|"))
+                    .unwrap_or_default()
+            })
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut output = vec![format_row(&rows[0])];
+    output.push(format!("|{}", " --- |".repeat(columns)));
+    output.extend(rows[1..].iter().map(|row| format_row(row)));
+    Ok(output.join("\n"))
+}
+
+// 解析一行 CSV（支持双引号包裹和 "" 转义）
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+// =================== 颜色与计算 ===================
+
+fn parse_color(text: &str) -> Result<[u8; 4], String> {
+    crate::content_classifier::parse_color(text)
+        .ok_or_else(|| "不是有效的颜色值（支持 #RGB、rgb()、hsl()）".to_string())
+}
+
+fn color_to_hex(input: &TransformInput) -> Result<String, String> {
+    let [r, g, b, a] = parse_color(input.text)?;
+    Ok(if a == 255 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    })
+}
+
+fn color_to_rgb(input: &TransformInput) -> Result<String, String> {
+    let [r, g, b, a] = parse_color(input.text)?;
+    Ok(if a == 255 {
+        format!("rgb({}, {}, {})", r, g, b)
+    } else {
+        let alpha = crate::content_classifier::format_number((a as f64 / 255.0 * 100.0).round() / 100.0);
+        format!("rgba({}, {}, {}, {})", r, g, b, alpha)
+    })
+}
+
+fn evaluate_math(input: &TransformInput) -> Result<String, String> {
+    crate::content_classifier::evaluate_math(input.text.trim())
+        .map(crate::content_classifier::format_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(ids: &[&str], text: &str) -> Result<String, String> {
+        let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        apply_chain(&ids, text, None)
+    }
+
+    #[test]
+    fn applies_chains_in_order() {
+        assert_eq!(run(&["trim", "uppercase"], "  abc \n").unwrap(), "ABC");
+        assert_eq!(run(&["title_case"], "hello wORLD it's").unwrap(), "Hello World It's");
+        assert_eq!(run(&["sort_lines", "unique_lines"], "b\na\nb").unwrap(), "a\nb");
+        assert!(run(&["trim", "nope"], "x").unwrap_err().contains("nope"));
+    }
+
+    #[test]
+    fn encodes_and_decodes() {
+        assert_eq!(run(&["url_encode"], "a b/中").unwrap(), "a%20b%2F%E4%B8%AD");
+        assert_eq!(run(&["url_decode"], "a+b%2F%E4%B8%AD").unwrap(), "a b/中");
+        assert_eq!(run(&["base64_encode", "base64_decode"], "你好").unwrap(), "你好");
+        assert_eq!(run(&["base64_decode"], "aGk").unwrap(), "hi");
+        assert_eq!(run(&["json_minify"], "{ \"a\" : [1, 2] }").unwrap(), "{\"a\":[1,2]}");
+        assert!(run(&["json_pretty"], "{").is_err());
+    }
+
+    #[test]
+    fn reformats_json_without_reordering() {
+        let source = r#" { "zeta": 1.10, "alpha": { "b": [ ], "a": 12345678901234567890123 },
+            "text": "a , b : [\" {}" } "#;
+        assert_eq!(
+            run(&["json_pretty"], source).unwrap(),
+            "{\n  \"zeta\": 1.10,\n  \"alpha\": {\n    \"b\": [],\n    \"a\": 12345678901234567890123\n  },\n  \"text\": \"a , b : [\\\" {}\"\n}"
+        );
+        assert_eq!(
+            run(&["json_minify"], source).unwrap(),
+            r#"{"zeta":1.10,"alpha":{"b":[],"a":12345678901234567890123},"text":"a , b : [\" {}"}"#
+        );
+    }
+
+    #[test]
+    fn converts_html_and_tables() {
+        let html = "<style>p{}</style><p>A &amp; B</p><ul><li>x</li><li>y</li></ul>";
+        assert_eq!(run(&["html_to_text"], html).unwrap(), "A & B\nx\ny");
+        assert_eq!(
+            apply_chain(&["html_to_text".to_string()], "fallback", Some("<b>rich</b>")).unwrap(),
+            "rich"
+        );
+        assert_eq!(
+            run(&["markdown_table"], "name,note\n\"a, b\",x|y").unwrap(),
+            "| name | note |\n| --- | --- |\n| a, b | x\\|y |"
+        );
+        assert!(run(&["markdown_table"], "single column").is_err());
+    }
+
+    #[test]
+    fn converts_colors_and_math() {
+        assert_eq!(run(&["color_to_hex"], "rgb(255, 136, 0)").unwrap(), "#ff8800");
+        assert_eq!(run(&["color_to_rgb"], "#ff880080").unwrap(), "rgba(255, 136, 0, 0.5)");
+        assert_eq!(run(&["color_to_hex"], "hsl(120, 100%, 25%)").unwrap(), "#008000");
+        assert!(run(&["color_to_rgb"], "red").is_err());
+        assert_eq!(run(&["evaluate_math"], " 1.5 * 4 - 1 ").unwrap(), "5");
+    }
+}
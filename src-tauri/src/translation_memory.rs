@@ -0,0 +1,391 @@
+// AI翻译记忆
+//
+// AI翻译的结果保存在 translation_memory 表中，按规范化原文、目标语言、模型和提示词摘要区分，
+// 同一组合再次翻译时直接返回保存的译文，不再请求接口。
+// 用户确认（或手动修改）过的译文不会被新的翻译结果覆盖；开启预载后，确认过的译文常驻内存，
+// 粘贴时可以直接整段粘贴。原文和译文与剪贴板内容一样按需加密。
+
+use once_cell::sync::Lazy;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::error::{AppError, AppResult};
+
+// 模糊匹配默认的最低相似度
+pub const DEFAULT_MIN_SIMILARITY: f64 = 0.8;
+
+// 翻译记忆的查找键
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MemoryKey {
+    pub source: String,
+    pub target_language: String,
+    pub model: String,
+    pub prompt_hash: String,
+}
+
+impl MemoryKey {
+    pub fn new(text: &str, target_language: &str, model: &str, prompt: &str) -> Self {
+        Self {
+            source: normalize_source(text),
+            target_language: target_language.to_string(),
+            model: model.to_string(),
+            prompt_hash: prompt_hash(prompt),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationMemoryEntry {
+    pub id: i64,
+    pub source_text: String,
+    pub translation: String,
+    pub target_language: String,
+    pub model: String,
+    pub confirmed: bool,
+    pub hit_count: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// 模糊匹配结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationMemoryMatch {
+    #[serde(flatten)]
+    pub entry: TranslationMemoryEntry,
+    // 与查询文本的相似度（0-1）
+    pub similarity: f64,
+}
+
+// 预载的已确认译文（None 表示尚未加载或已失效）
+type PrefilledMap = HashMap<MemoryKey, String>;
+static PREFILLED: Lazy<RwLock<Option<PrefilledMap>>> = Lazy::new(|| RwLock::new(None));
+
+// 规范化原文：统一换行符，去掉首尾空白，合并行内连续空白和连续空行
+pub fn normalize_source(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.replace("\r\n", "\n").replace('\r', "\n").lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() && lines.last().is_some_and(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
+
+fn prompt_hash(prompt: &str) -> String {
+    format!("{:x}", Sha256::digest(prompt.as_bytes()))[..16].to_string()
+}
+
+// 按字符计算的编辑距离相似度（0-1）
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    1.0 - previous[b.len()] as f64 / max_len as f64
+}
+
+fn now() -> i64 {
+    chrono::Local::now().timestamp()
+}
+
+const ENTRY_COLUMNS: &str = "id, qc_decrypt(source_text), qc_decrypt(translation), target_language, model, confirmed, hit_count, created_at, updated_at";
+
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<TranslationMemoryEntry> {
+    Ok(TranslationMemoryEntry {
+        id: row.get(0)?,
+        source_text: row.get(1)?,
+        translation: row.get(2)?,
+        target_language: row.get(3)?,
+        model: row.get(4)?,
+        confirmed: row.get::<_, i64>(5)? != 0,
+        hit_count: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+// =================== 查找和保存 ===================
+
+// 精确查找，命中时增加命中次数
+pub fn lookup(key: &MemoryKey) -> AppResult<Option<String>> {
+    if key.source.is_empty() {
+        return Ok(None);
+    }
+
+    if crate::settings::get_global_settings().translation_memory_prefill {
+        if let Some(translation) = prefilled(key)? {
+            return Ok(Some(translation));
+        }
+    }
+
+    crate::database::with_connection(|conn| {
+        let found: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT id, qc_decrypt(translation) FROM translation_memory
+                 WHERE source_text = qc_encrypt(?1) AND target_language = ?2 AND model = ?3 AND prompt_hash = ?4",
+                params![key.source, key.target_language, key.model, key.prompt_hash],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        if let Some((id, _)) = &found {
+            conn.execute(
+                "UPDATE translation_memory SET hit_count = hit_count + 1 WHERE id = ?1",
+                params![id],
+            )?;
+        }
+        Ok(found.map(|(_, translation)| translation))
+    })
+}
+
+// 是否有已确认的译文（预载模式下用于直接粘贴）
+pub fn has_confirmed(key: &MemoryKey) -> AppResult<bool> {
+    Ok(prefilled(key)?.is_some())
+}
+
+// 保存翻译结果；已确认的译文保持不变
+pub fn store(key: &MemoryKey, translation: &str) -> AppResult<()> {
+    if key.source.is_empty() || translation.trim().is_empty() {
+        return Ok(());
+    }
+
+    let timestamp = now();
+    crate::database::with_connection(|conn| {
+        conn.execute(
+            "INSERT INTO translation_memory
+                (source_text, source_length, target_language, model, prompt_hash, translation, created_at, updated_at)
+             VALUES (qc_encrypt(?1), ?2, ?3, ?4, ?5, qc_encrypt(?6), ?7, ?7)
+             ON CONFLICT (source_text, target_language, model, prompt_hash) DO UPDATE
+             SET translation = excluded.translation, updated_at = excluded.updated_at
+             WHERE confirmed = 0",
+            params![
+                key.source,
+                key.source.chars().count() as i64,
+                key.target_language,
+                key.model,
+                key.prompt_hash,
+                translation,
+                timestamp
+            ],
+        )?;
+        Ok(())
+    })
+}
+
+// 模糊查找：在同一目标语言中按相似度排序，长度相差过大的记录不参与比较
+pub fn lookup_fuzzy(
+    text: &str,
+    target_language: &str,
+    min_similarity: f64,
+    limit: usize,
+) -> AppResult<Vec<TranslationMemoryMatch>> {
+    let source = normalize_source(text);
+    if source.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let length = source.chars().count() as f64;
+    let min_length = (length * min_similarity).floor() as i64;
+    let max_length = (length / min_similarity.max(0.01)).ceil() as i64;
+
+    let candidates = crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM translation_memory
+             WHERE target_language = ?1 AND source_length BETWEEN ?2 AND ?3",
+            ENTRY_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![target_language, min_length, max_length], entry_from_row)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+
+    let mut matches: Vec<TranslationMemoryMatch> = candidates
+        .into_iter()
+        .filter_map(|entry| {
+            let similarity = similarity(&source, &entry.source_text);
+            (similarity >= min_similarity).then_some(TranslationMemoryMatch { entry, similarity })
+        })
+        .collect();
+
+    // 相似度相同时确认过的译文优先
+    matches.sort_by(|a, b| {
+        b.similarity
+            .total_cmp(&a.similarity)
+            .then(b.entry.confirmed.cmp(&a.entry.confirmed))
+    });
+    matches.truncate(limit);
+    Ok(matches)
+}
+
+// =================== 管理 ===================
+
+// 列出翻译记忆（原文或译文包含关键词，按更新时间倒序）
+pub fn list(query: Option<&str>, limit: usize, offset: usize) -> AppResult<Vec<TranslationMemoryEntry>> {
+    let entries = crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM translation_memory ORDER BY updated_at DESC, id DESC",
+            ENTRY_COLUMNS
+        ))?;
+        let rows = stmt.query_map([], entry_from_row)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+
+    // 数据可能已加密，关键词只能在解密后匹配
+    let query = query.map(str::trim).filter(|q| !q.is_empty()).map(str::to_lowercase);
+    Ok(entries
+        .into_iter()
+        .filter(|entry| match &query {
+            Some(q) => {
+                entry.source_text.to_lowercase().contains(q) || entry.translation.to_lowercase().contains(q)
+            }
+            None => true,
+        })
+        .skip(offset)
+        .take(limit)
+        .collect())
+}
+
+// 修改译文或确认状态（修改译文的同时视为确认）
+pub fn update_entry(id: i64, translation: Option<String>, confirmed: Option<bool>) -> AppResult<()> {
+    if let Some(translation) = translation.as_deref() {
+        if translation.trim().is_empty() {
+            return Err(AppError::invalid_input("译文不能为空").with_key("error.translation_memory.empty"));
+        }
+    }
+
+    let confirmed = confirmed.or(translation.as_ref().map(|_| true));
+    let changed = crate::database::with_connection(|conn| {
+        conn.execute(
+            "UPDATE translation_memory
+             SET translation = COALESCE(qc_encrypt(?2), translation),
+                 confirmed = COALESCE(?3, confirmed),
+                 updated_at = ?4
+             WHERE id = ?1",
+            params![id, translation, confirmed.map(i64::from), now()],
+        )
+    })?;
+
+    if changed == 0 {
+        return Err(entry_not_found(id));
+    }
+    clear_prefilled();
+    Ok(())
+}
+
+pub fn delete_entry(id: i64) -> AppResult<()> {
+    let changed = crate::database::with_connection(|conn| {
+        conn.execute("DELETE FROM translation_memory WHERE id = ?1", params![id])
+    })?;
+
+    if changed == 0 {
+        return Err(entry_not_found(id));
+    }
+    clear_prefilled();
+    Ok(())
+}
+
+// 清空翻译记忆，返回删除的条数
+pub fn clear() -> AppResult<usize> {
+    let count = crate::database::with_connection(|conn| conn.execute("DELETE FROM translation_memory", []))?;
+    clear_prefilled();
+    Ok(count)
+}
+
+fn entry_not_found(id: i64) -> AppError {
+    AppError::not_found(format!("翻译记忆不存在: {}", id))
+        .with_key("error.translation_memory.not_found")
+        .with_context("id", id)
+}
+
+// =================== 预载 ===================
+
+// 查询预载的已确认译文，首次使用时从数据库加载
+fn prefilled(key: &MemoryKey) -> AppResult<Option<String>> {
+    if let Some(map) = PREFILLED.read().map_err(lock_error)?.as_ref() {
+        return Ok(map.get(key).cloned());
+    }
+
+    let map = load_confirmed()?;
+    let translation = map.get(key).cloned();
+    *PREFILLED.write().map_err(lock_error)? = Some(map);
+    Ok(translation)
+}
+
+fn load_confirmed() -> AppResult<PrefilledMap> {
+    crate::database::with_connection(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT qc_decrypt(source_text), target_language, model, prompt_hash, qc_decrypt(translation)
+             FROM translation_memory WHERE confirmed = 1",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                MemoryKey {
+                    source: row.get(0)?,
+                    target_language: row.get(1)?,
+                    model: row.get(2)?,
+                    prompt_hash: row.get(3)?,
+                },
+                row.get(4)?,
+            ))
+        })?;
+        rows.collect()
+    })
+}
+
+// 使预载的译文失效（修改记忆或锁定加密数据后调用）
+pub fn clear_prefilled() {
+    if let Ok(mut prefilled) = PREFILLED.write() {
+        *prefilled = None;
+    }
+}
+
+fn lock_error<T>(e: std::sync::PoisonError<T>) -> AppError {
+    AppError::internal(format!("获取翻译记忆缓存锁失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_source_text() {
+        assert_eq!(normalize_source("  Hello \t world  \r\n\r\n\r\nnext   line \n"), "Hello world\n\nnext line");
+        assert_eq!(
+            MemoryKey::new("Hello  world", "zh-CN", "m", "p"),
+            MemoryKey::new(" Hello world\n", "zh-CN", "m", "p")
+        );
+        assert_ne!(
+            MemoryKey::new("Hello", "zh-CN", "m", "p1").prompt_hash,
+            MemoryKey::new("Hello", "zh-CN", "m", "p2").prompt_hash
+        );
+    }
+
+    #[test]
+    fn measures_similarity() {
+        assert_eq!(similarity("", ""), 1.0);
+        assert_eq!(similarity("abc", "abc"), 1.0);
+        assert_eq!(similarity("abc", "xyz"), 0.0);
+        assert!((similarity("kitten", "sitting") - (1.0 - 3.0 / 7.0)).abs() < 1e-9);
+        assert!(similarity("今天天气很好", "今天天气不错") > 0.6);
+    }
+}
@@ -2,46 +2,116 @@ use image::GenericImageView;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{TrayIconBuilder, TrayIconEvent},
     AppHandle, Manager,
 };
 
 use once_cell::sync::OnceCell;
 
+use crate::i18n::{t, t_with};
+
 // 暴露需要动态更新文本的菜单项引用
 pub static TOGGLE_HOTKEYS_ITEM: OnceCell<tauri::menu::MenuItem<tauri::Wry>> = OnceCell::new();
 pub static TOGGLE_MONITOR_ITEM: OnceCell<tauri::menu::MenuItem<tauri::Wry>> = OnceCell::new();
+// 文案固定的菜单项及其文案键，切换语言时重新设置
+static STATIC_ITEMS: OnceCell<Vec<(&'static str, tauri::menu::MenuItem<tauri::Wry>)>> =
+    OnceCell::new();
+// 粘贴队列子菜单，队列变化时更新数量和勾选状态
+static PASTE_QUEUE_MENU: OnceCell<PasteQueueMenu> = OnceCell::new();
+
+struct PasteQueueMenu {
+    submenu: Submenu<tauri::Wry>,
+    enabled: CheckMenuItem<tauri::Wry>,
+    fifo: CheckMenuItem<tauri::Wry>,
+    lifo: CheckMenuItem<tauri::Wry>,
+    merge: MenuItem<tauri::Wry>,
+    clear: MenuItem<tauri::Wry>,
+}
+
+// 快捷键切换项的文案（enabled 为当前是否已启用）
+pub fn hotkeys_label(enabled: bool) -> String {
+    t(if enabled { "tray.hotkeys.disable" } else { "tray.hotkeys.enable" })
+}
+
+// 剪贴板监听切换项的文案
+pub fn monitor_label(enabled: bool) -> String {
+    t(if enabled { "tray.monitor.disable" } else { "tray.monitor.enable" })
+}
+
+fn paste_queue_title() -> String {
+    t_with(
+        "tray.queue.title",
+        &[("count", &crate::paste_queue::len().to_string())],
+    )
+}
+
+// 按队列当前状态刷新粘贴队列子菜单
+pub fn refresh_paste_queue_menu() {
+    let Some(menu) = PASTE_QUEUE_MENU.get() else {
+        return;
+    };
+    let enabled = crate::paste_queue::is_enabled();
+    let lifo = crate::paste_queue::QueueOrder::current() == crate::paste_queue::QueueOrder::Lifo;
+    let has_items = crate::paste_queue::len() > 0;
+
+    let _ = menu.submenu.set_text(paste_queue_title());
+    let _ = menu.enabled.set_text(t("tray.queue.enabled"));
+    let _ = menu.enabled.set_checked(enabled);
+    let _ = menu.fifo.set_text(t("tray.queue.fifo"));
+    let _ = menu.fifo.set_checked(!lifo);
+    let _ = menu.lifo.set_text(t("tray.queue.lifo"));
+    let _ = menu.lifo.set_checked(lifo);
+    let _ = menu.merge.set_text(t("tray.queue.merge"));
+    let _ = menu.merge.set_enabled(has_items);
+    let _ = menu.clear.set_text(t("tray.queue.clear"));
+    let _ = menu.clear.set_enabled(has_items);
+}
+
+fn hotkeys_enabled() -> bool {
+    let hook = crate::shortcut_interceptor::is_interception_enabled();
+    let poll = crate::key_state_monitor::is_polling_active();
+    hook || poll
+}
+
+// 按当前语言和状态刷新全部托盘文案
+pub fn refresh_labels(app: &AppHandle) {
+    if let Some(items) = STATIC_ITEMS.get() {
+        for (key, item) in items {
+            let _ = item.set_text(t(key));
+        }
+    }
+    if let Some(item) = TOGGLE_HOTKEYS_ITEM.get() {
+        let _ = item.set_text(hotkeys_label(hotkeys_enabled()));
+    }
+    if let Some(item) = TOGGLE_MONITOR_ITEM.get() {
+        let _ = item.set_text(monitor_label(crate::clipboard_history::is_monitoring_enabled()));
+    }
+    refresh_paste_queue_menu();
+    if let Some(tray) = app.tray_by_id("main-tray") {
+        let _ = tray.set_tooltip(Some(t("tray.tooltip")));
+    }
+}
 
 pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     // 创建托盘菜单
-    let toggle_item = MenuItem::with_id(app, "toggle", "显示/隐藏", true, None::<&str>)?;
+    let toggle_item = MenuItem::with_id(app, "toggle", t("tray.toggle"), true, None::<&str>)?;
     let separator = PredefinedMenuItem::separator(app)?;
-    let settings_item = MenuItem::with_id(app, "settings", "设置", true, None::<&str>)?;
-    let screenshot_item = MenuItem::with_id(app, "screenshot", "截屏", true, None::<&str>)?;
+    let settings_item = MenuItem::with_id(app, "settings", t("tray.settings"), true, None::<&str>)?;
+    let screenshot_item =
+        MenuItem::with_id(app, "screenshot", t("tray.screenshot"), true, None::<&str>)?;
     // 根据当前状态设置切换项的初始文本
-    let any_hotkeys_enabled = {
-        let hook = crate::shortcut_interceptor::is_interception_enabled();
-        let poll = crate::key_state_monitor::is_polling_active();
-        hook || poll
-    };
-    let hotkeys_label = if any_hotkeys_enabled {
-        "禁用快捷键"
-    } else {
-        "启用快捷键"
-    };
-    let monitor_label = if crate::clipboard_history::is_monitoring_enabled() {
-        "禁用剪贴板监听"
-    } else {
-        "启用剪贴板监听"
-    };
-
-    let toggle_hotkeys_item =
-        MenuItem::with_id(app, "toggle-hotkeys", hotkeys_label, true, None::<&str>)?;
+    let toggle_hotkeys_item = MenuItem::with_id(
+        app,
+        "toggle-hotkeys",
+        hotkeys_label(hotkeys_enabled()),
+        true,
+        None::<&str>,
+    )?;
     let toggle_monitor_item = MenuItem::with_id(
         app,
         "toggle-clipboard-monitor",
-        monitor_label,
+        monitor_label(crate::clipboard_history::is_monitoring_enabled()),
         true,
         None::<&str>,
     )?;
@@ -50,10 +120,84 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let _ = TOGGLE_HOTKEYS_ITEM.set(toggle_hotkeys_item.clone());
     let _ = TOGGLE_MONITOR_ITEM.set(toggle_monitor_item.clone());
 
+    // 粘贴队列子菜单
+    let queue_lifo =
+        crate::paste_queue::QueueOrder::current() == crate::paste_queue::QueueOrder::Lifo;
+    let queue_has_items = crate::paste_queue::len() > 0;
+    let queue_enabled_item = CheckMenuItem::with_id(
+        app,
+        "paste-queue-toggle",
+        t("tray.queue.enabled"),
+        true,
+        crate::paste_queue::is_enabled(),
+        None::<&str>,
+    )?;
+    let queue_fifo_item = CheckMenuItem::with_id(
+        app,
+        "paste-queue-fifo",
+        t("tray.queue.fifo"),
+        true,
+        !queue_lifo,
+        None::<&str>,
+    )?;
+    let queue_lifo_item = CheckMenuItem::with_id(
+        app,
+        "paste-queue-lifo",
+        t("tray.queue.lifo"),
+        true,
+        queue_lifo,
+        None::<&str>,
+    )?;
+    let queue_merge_item = MenuItem::with_id(
+        app,
+        "paste-queue-merge",
+        t("tray.queue.merge"),
+        queue_has_items,
+        None::<&str>,
+    )?;
+    let queue_clear_item = MenuItem::with_id(
+        app,
+        "paste-queue-clear",
+        t("tray.queue.clear"),
+        queue_has_items,
+        None::<&str>,
+    )?;
+    let queue_submenu = Submenu::with_id_and_items(
+        app,
+        "paste-queue",
+        paste_queue_title(),
+        true,
+        &[
+            &queue_enabled_item,
+            &PredefinedMenuItem::separator(app)?,
+            &queue_fifo_item,
+            &queue_lifo_item,
+            &PredefinedMenuItem::separator(app)?,
+            &queue_merge_item,
+            &queue_clear_item,
+        ],
+    )?;
+    let _ = PASTE_QUEUE_MENU.set(PasteQueueMenu {
+        submenu: queue_submenu.clone(),
+        enabled: queue_enabled_item,
+        fifo: queue_fifo_item,
+        lifo: queue_lifo_item,
+        merge: queue_merge_item,
+        clear: queue_clear_item,
+    });
+
     let separator2 = PredefinedMenuItem::separator(app)?;
     let separator3 = PredefinedMenuItem::separator(app)?;
-    let restart_item = MenuItem::with_id(app, "restart", "重启程序", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
+    let restart_item = MenuItem::with_id(app, "restart", t("tray.restart"), true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", t("tray.quit"), true, None::<&str>)?;
+
+    let _ = STATIC_ITEMS.set(vec![
+        ("tray.toggle", toggle_item.clone()),
+        ("tray.settings", settings_item.clone()),
+        ("tray.screenshot", screenshot_item.clone()),
+        ("tray.restart", restart_item.clone()),
+        ("tray.quit", quit_item.clone()),
+    ]);
 
     let menu = Menu::with_items(
         app,
@@ -65,6 +209,7 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
             &separator2,
             &toggle_hotkeys_item,
             &toggle_monitor_item,
+            &queue_submenu,
             &separator3,
             &restart_item,
             &quit_item,
@@ -86,7 +231,7 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     
     let _tray = TrayIconBuilder::with_id("main-tray")
         .menu(&menu)
-        .tooltip("快速剪贴板")
+        .tooltip(t("tray.tooltip"))
         .icon(icon)
         .show_menu_on_left_click(false)
         .on_tray_icon_event(move |_tray, event| {
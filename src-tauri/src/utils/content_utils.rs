@@ -30,3 +30,76 @@ pub fn generate_files_title(files_content: &str) -> String {
     // 解析失败时的回退标题
     "文件".to_string()
 }
+
+// 将HTML转换为纯文本（去除标签、脚本和样式，解码常见实体）
+pub fn html_to_plain_text(html: &str) -> String {
+    use once_cell::sync::Lazy;
+    use regex::Regex;
+
+    static SCRIPT_STYLE_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?is)<(script|style)[^>]*>.*?</(script|style)>").unwrap());
+    static COMMENT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
+    static BLOCK_TAG_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?i)<(br|/p|/div|/li|/tr|/h[1-6])[^>]*>").unwrap());
+    static CELL_TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)</t[dh]>").unwrap());
+    static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+    static BLANK_LINES_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\n[ \t]*\n[\s]*").unwrap());
+
+    let text = SCRIPT_STYLE_RE.replace_all(html, "");
+    let text = COMMENT_RE.replace_all(&text, "");
+    let text = BLOCK_TAG_RE.replace_all(&text, "\n");
+    let text = CELL_TAG_RE.replace_all(&text, "\t");
+    let text = TAG_RE.replace_all(&text, "");
+
+    let decoded = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+
+    BLANK_LINES_RE
+        .replace_all(decoded.trim(), "\n\n")
+        .to_string()
+}
+
+// 从 HTML Format（CF_HTML）数据中取出 SourceURL，头部为 "键:值" 行，在 HTML 内容之前
+// 目前只有 Windows 读取 HTML Format 时使用
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn extract_html_source_url(html_format: &str) -> Option<String> {
+    html_format
+        .lines()
+        .take_while(|line| !line.trim_start().starts_with('<'))
+        .find_map(|line| line.strip_prefix("SourceURL:"))
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_source_url_from_html_format_header() {
+        let html_format = "Version:0.9\r\n\
+                           StartHTML:0000000105\r\n\
+                           EndHTML:0000000199\r\n\
+                           StartFragment:0000000141\r\n\
+                           EndFragment:0000000163\r\n\
+                           SourceURL:https://example.com/page?a=1\r\n\
+                           <html><body><!--StartFragment--><b>hi</b><!--EndFragment--></body></html>";
+        assert_eq!(
+            extract_html_source_url(html_format).as_deref(),
+            Some("https://example.com/page?a=1")
+        );
+
+        // 没有 SourceURL，或只在 HTML 内容中出现时不算
+        assert_eq!(extract_html_source_url("Version:0.9\r\nSourceURL:\r\n<html></html>"), None);
+        assert_eq!(
+            extract_html_source_url("Version:0.9\r\n<p>\nSourceURL:https://example.com</p>"),
+            None
+        );
+    }
+}
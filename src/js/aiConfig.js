@@ -2,7 +2,7 @@
  * AI配置管理模块
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 import { emit, listen } from '@tauri-apps/api/event';
 
 /**
@@ -14,6 +14,7 @@ import { emit, listen } from '@tauri-apps/api/event';
  */
 const DEFAULT_AI_CONFIG = {
   enabled: false,
+  provider: 'openai',
   apiKey: '',
   model: 'Qwen/Qwen2-7B-Instruct',
   baseUrl: 'https://api.siliconflow.cn/v1',
@@ -54,8 +55,10 @@ export async function loadAIConfig(settings = null) {
       settings = await invoke('get_settings');
     }
 
+    const provider = settings.aiProvider || DEFAULT_AI_CONFIG.provider;
     currentAIConfig = {
-      enabled: !!(settings.aiApiKey && settings.aiApiKey.trim() !== ''),
+      enabled: provider === 'ollama' || !!(settings.aiApiKey && settings.aiApiKey.trim() !== ''),
+      provider,
       apiKey: settings.aiApiKey || '',
       model: settings.aiModel || DEFAULT_AI_CONFIG.model,
       baseUrl: settings.aiBaseUrl || DEFAULT_AI_CONFIG.baseUrl,
@@ -83,11 +86,15 @@ export async function saveAIConfig(config) {
     const settings = await invoke('get_settings');
 
     // 更新相关设置字段
+    if (config.provider !== undefined) {
+      settings.aiProvider = config.provider;
+    }
     if (config.apiKey !== undefined) {
       settings.aiApiKey = config.apiKey;
-      // 当API密钥变化时，自动更新enabled状态
-      currentAIConfig.enabled = !!(config.apiKey && config.apiKey.trim() !== '');
     }
+    // 当提供方或API密钥变化时，自动更新enabled状态
+    currentAIConfig.enabled = !providerRequiresApiKey(currentAIConfig.provider) ||
+      !!(currentAIConfig.apiKey && currentAIConfig.apiKey.trim() !== '');
     if (config.model !== undefined) {
       settings.aiModel = config.model;
     }
@@ -117,9 +124,10 @@ export function getCurrentAIConfig() {
  * 验证AI配置是否有效
  */
 export function isAIConfigValid(config = currentAIConfig) {
+  const hasApiKey = !providerRequiresApiKey(config.provider) ||
+    !!(config.apiKey && config.apiKey.trim() !== '');
   return !!(
-    config.apiKey &&
-    config.apiKey.trim() !== '' &&
+    hasApiKey &&
     config.model &&
     config.model.trim() !== '' &&
     config.baseUrl &&
@@ -131,6 +139,13 @@ export function isAIConfigValid(config = currentAIConfig) {
   );
 }
 
+/**
+ * 提供方是否需要API密钥（本地 Ollama 不需要）
+ */
+export function providerRequiresApiKey(provider) {
+  return provider !== 'ollama';
+}
+
 /**
  * 获取可用的AI模型列表
  */
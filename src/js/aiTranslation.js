@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 import { listen, emit } from '@tauri-apps/api/event';
 import {
   setIsAiTranslationEnabled,
@@ -21,7 +21,8 @@ let aiTranslationConfig = {
   translateOnCopy: false,
   translateOnPaste: false, // 默认关闭，等待从后端加载真实设置
   translationPrompt: '请将以下文本翻译成{target_language}，严格保持原文的所有格式、换行符、段落结构和空白字符，只返回翻译结果，不要添加任何解释或修改格式：',
-  inputSpeed: 50
+  inputSpeed: 50,
+  backend: 'ai'
 };
 
 /**
@@ -69,7 +70,8 @@ async function loadAiTranslationSettings() {
       translateOnCopy: settings.aiTranslateOnCopy === true, // 明确使用布尔值，避免默认值覆盖
       translateOnPaste: settings.aiTranslateOnPaste === true, // 明确使用布尔值，避免默认值覆盖
       translationPrompt: settings.aiTranslationPrompt || '请将以下文本翻译成{target_language}，严格保持原文的所有格式、换行符、段落结构和空白字符，只返回翻译结果，不要添加任何解释或修改格式：',
-      inputSpeed: settings.aiInputSpeed || 50
+      inputSpeed: settings.aiInputSpeed || 50,
+      backend: settings.translationBackend || 'ai'
     };
 
     // 更新UI状态
@@ -169,7 +171,8 @@ async function setupAiTranslationEventListeners() {
           translateOnCopy: newSettings.aiTranslateOnCopy === true, // 明确使用布尔值
           translateOnPaste: newSettings.aiTranslateOnPaste === true, // 明确使用布尔值
           translationPrompt: newSettings.aiTranslationPrompt || aiTranslationConfig.translationPrompt,
-          inputSpeed: newSettings.aiInputSpeed || aiTranslationConfig.inputSpeed
+          inputSpeed: newSettings.aiInputSpeed || aiTranslationConfig.inputSpeed,
+          backend: newSettings.translationBackend || aiTranslationConfig.backend
         };
 
         console.log('AI翻译配置已更新:', aiTranslationConfig);
@@ -508,9 +511,9 @@ function getTranslationErrorMessage(error) {
 /**
  * 复制时翻译文本并直接输入到目标位置
  */
-export async function translateAndInputOnCopy(text) {
+export async function translateAndInputOnCopy(text, context = 'copy') {
   // 检查是否应该进行翻译
-  const translationCheck = shouldTranslateText(text, 'copy');
+  const translationCheck = shouldTranslateText(text, context);
   if (!translationCheck.should) {
     console.log('跳过复制时翻译:', translationCheck.reason);
     return;
@@ -650,7 +653,10 @@ export function shouldTranslate(context = 'paste') {
     return false;
   }
 
-  if (context === 'copy') {
+  if (context === 'rule') {
+    // 剪贴板记录规则要求翻译
+    return true;
+  } else if (context === 'copy') {
     return aiTranslationConfig.translateOnCopy;
   } else if (context === 'paste') {
     return aiTranslationConfig.translateOnPaste;
@@ -673,9 +679,9 @@ export function isTextSuitableForTranslation(text) {
  * 检查翻译配置是否有效
  */
 export function isTranslationConfigValid() {
-  // 检查AI配置是否有效
+  // 检查AI配置是否有效（离线翻译不使用AI配置，由后端检查）
   const aiConfig = getCurrentAIConfig();
-  if (!aiConfig.enabled || !isAIConfigValid(aiConfig)) {
+  if (aiTranslationConfig.backend !== 'offline' && (!aiConfig.enabled || !isAIConfigValid(aiConfig))) {
     return false;
   }
 
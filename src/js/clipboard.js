@@ -1,4 +1,5 @@
-import { invoke, convertFileSrc } from '@tauri-apps/api/core';
+import { convertFileSrc } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 import { openUrl } from '@tauri-apps/plugin-opener';
 import { getCurrentSettings } from '../settings/js/settingsManager.js';
 import {
@@ -103,16 +104,23 @@ function generateClipboardItemHTML(item, index) {
   // 生成日期时间HTML - 优先使用created_at，如果为空则使用timestamp
   // 对于文件类型，时间戳会在文件HTML内部显示，所以这里不显示
   const timeValue = item.created_at || item.created_at;
-  const timestampHTML = contentType === 'file' ? '' : `<div class="clipboard-timestamp">${formatTimestamp(timeValue)}</div>`;
+  const sourceTooltip = formatSourceTooltip(item.source);
+  const sourceAttr = sourceTooltip ? ` title="${escapeHtml(sourceTooltip).replace(/"/g, '&quot;')}"` : '';
+  const timestampHTML = contentType === 'file' ? '' : `<div class="clipboard-timestamp"${sourceAttr}>${formatTimestamp(timeValue)}</div>`;
+
+  // 置顶标记
+  const pinHTML = item.pinned ? '<i class="ti ti-pinned-filled clipboard-pin-badge" title="已置顶"></i>' : '';
 
   // 组合完整的HTML
   const activeClass = index === activeItemIndex ? ' active' : '';
   const noShortcutClass = index >= 9 ? ' no-shortcut' : '';
+  const pinnedClass = item.pinned ? ' pinned' : '';
 
   return `
-    <div class="clipboard-item${activeClass}${noShortcutClass}" draggable="true" data-index="${index}">
+    <div class="clipboard-item${activeClass}${noShortcutClass}${pinnedClass}" draggable="true" data-index="${index}">
       ${timestampHTML}
       ${contentHTML}
+      ${pinHTML}
       ${numberHTML}
       ${shortcutHTML}
       ${actionsHTML}
@@ -122,6 +130,17 @@ function generateClipboardItemHTML(item, index) {
 
 
 
+// 来源提示：应用、窗口标题和网页地址
+function formatSourceTooltip(source) {
+  if (!source) return '';
+  const lines = [];
+  const app = [source.app, source.title].filter(Boolean).join(' — ');
+  if (app) lines.push(`来源: ${app}`);
+  if (source.url) lines.push(source.url);
+  if (source.selection === 'primary') lines.push('来自主选区（选中文本）');
+  return lines.join('\n');
+}
+
 // 生成图片HTML
 function generateImageHTML(item) {
   // 为图片元素生成唯一ID，用于后续异步加载
@@ -260,12 +279,20 @@ export async function writeClipboardImage(dataUrl) {
   }
 }
 
+// 新项目的插入位置：置顶的项目插入到最前面，其余插入到置顶项目之后
+function getInsertIndex(history, item) {
+  if (item.pinned) return 0;
+  const index = history.findIndex(existingItem => !existingItem.pinned);
+  return index === -1 ? history.length : index;
+}
+
 // 增量添加剪贴板项
 export function addClipboardItemIncremental(item, isNew) {
   // 如果是新增项，直接添加到数组开头
   if (isNew) {
     console.log('增量添加：新增项');
-    const newHistory = [item, ...clipboardHistory];
+    const newHistory = [...clipboardHistory];
+    newHistory.splice(getInsertIndex(newHistory, item), 0, item);
     setClipboardHistory(newHistory);
     window.clipboardHistory = newHistory;
   } else {
@@ -273,8 +300,7 @@ export function addClipboardItemIncremental(item, isNew) {
     const newHistory = clipboardHistory.filter(
       existingItem => existingItem.content !== item.content
     );
-    const afterLength = newHistory.length;
-    newHistory.unshift(item);
+    newHistory.splice(getInsertIndex(newHistory, item), 0, item);
     setClipboardHistory(newHistory);
     window.clipboardHistory = newHistory;
   }
@@ -373,6 +399,12 @@ export async function updateClipboardOrder(oldIndex, newIndex) {
       toIndex: originalNewIndex
     });
 
+    // 置顶的项目只能在置顶区域内移动，跨区域时以后端结果为准
+    if (Boolean(movedItem.pinned) !== Boolean(targetItem.pinned)) {
+      await refreshClipboardHistory();
+      return;
+    }
+
     const newHistory = [...clipboardHistory];
     const [removed] = newHistory.splice(originalOldIndex, 1);
     newHistory.splice(originalNewIndex, 0, removed);
@@ -519,15 +551,15 @@ function handleClipboardItemContextMenu(index, event) {
   showClipboardContextMenu(event, item, originalIndex);
 }
 
-// 处理剪贴板项目粘贴
-async function handleClipboardItemPaste(item, index, element = null) {
+// 处理剪贴板项目粘贴（mimeType 为空时按设置粘贴，否则只粘贴指定格式）
+async function handleClipboardItemPaste(item, index, element = null, mimeType = null) {
   try {
     if (element) element.classList.add('paste-loading');
     showNotification('正在粘贴...', 'info');
 
     // 调用后端统一粘贴接口
     await invoke('paste_content', { 
-      params: { clipboard_id: item.id } 
+      params: { clipboard_id: item.id, mime_type: mimeType } 
     });
     
     setActiveItem(index);
@@ -566,13 +598,24 @@ async function deleteClipboardItem(id) {
   }
 }
 
+// 切换剪贴板项目的置顶状态（列表通过 clipboard-item-pinned 事件刷新）
+async function toggleClipboardItemPin(item) {
+  try {
+    const pinned = await invoke('toggle_clipboard_item_pin', { id: item.id });
+    showNotification(pinned ? '已置顶' : '已取消置顶', 'success');
+  } catch (error) {
+    console.error('切换置顶状态失败:', error);
+    showNotification('操作失败', 'error');
+  }
+}
+
 // 清空剪贴板历史
 async function clearClipboardHistory() {
   const { showConfirmModal } = await import('./ui.js');
 
   showConfirmModal(
     '确认清空',
-    '确定要清空所有剪贴板历史记录吗？此操作不可撤销。',
+    '确定要清空所有剪贴板历史记录吗？置顶的项目会保留，此操作不可撤销。',
     async () => {
       try {
         await invoke('clear_clipboard_history');
@@ -587,10 +630,48 @@ async function clearClipboardHistory() {
   );
 }
 
+// 剪贴板格式的显示名称
+const FORMAT_LABELS = {
+  'text/plain': '纯文本',
+  'text/html': 'HTML',
+  'text/rtf': 'RTF',
+  'image/png': '图片',
+  'application/x-quickclipboard-files': '文件'
+};
+
+// 构建“选择格式粘贴”子菜单，只有一种格式时不显示
+async function buildFormatMenuItem(item, index) {
+  let formats = [];
+  try {
+    formats = await invoke('get_clipboard_item_formats', { id: item.id });
+  } catch (error) {
+    console.error('获取剪贴板格式失败:', error);
+  }
+  if (formats.length < 2) return null;
+
+  return {
+    icon: 'ti-clipboard-list',
+    text: '选择格式粘贴',
+    children: formats.map(format => ({
+      icon: format.primary ? 'ti-check' : undefined,
+      text: `${FORMAT_LABELS[format.mime_type] || format.mime_type}（${formatFileSize(format.size)}）`,
+      onClick: async () => {
+        await handleClipboardItemPaste(item, index, null, format.mime_type);
+      }
+    }))
+  };
+}
+
 // 显示剪贴板右键菜单
-function showClipboardContextMenu(event, item, index) {
+async function showClipboardContextMenu(event, item, index) {
   const menuItems = [];
 
+  // 保存了多种格式时可以选择要粘贴的格式
+  const formatMenuItem = await buildFormatMenuItem(item, index);
+  if (formatMenuItem) {
+    menuItems.push(formatMenuItem);
+  }
+
   // 直接使用后端返回的content_type字段
   const contentType = item.content_type || 'text';
 
@@ -663,6 +744,13 @@ function showClipboardContextMenu(event, item, index) {
 
   // 通用菜单项
   menuItems.push(
+    {
+      icon: item.pinned ? 'ti-pinned-off' : 'ti-pinned',
+      text: item.pinned ? '取消置顶' : '置顶',
+      onClick: async () => {
+        await toggleClipboardItemPin(item);
+      }
+    },
     {
       icon: 'ti-star',
       text: '添加到常用文本',
@@ -735,17 +823,15 @@ async function openTextEditor(item, index) {
 
 // 加载图片
 export async function loadImageById(imgElement, imageId) {
-  try {
-    const filePath = await invoke('get_image_file_path', { content: `image:${imageId}` });
-    const assetUrl = convertFileSrc(filePath, 'asset');
-    imgElement.src = assetUrl;
-  } catch (error) {
-    console.error('加载图片失败:', error);
+  // 通过 qcimage 协议读取，加密的图片在内存中解密
+  imgElement.onerror = () => {
+    console.error('加载图片失败:', imageId);
     imgElement.alt = '图片加载失败';
     imgElement.style.backgroundColor = '#ffebee';
     imgElement.style.color = '#c62828';
     imgElement.textContent = '图片加载失败';
-  }
+  };
+  imgElement.src = convertFileSrc(imageId, 'qcimage');
 }
 
 
@@ -235,19 +235,30 @@ export async function showContextMenu(event, options) {
       menuItems.push(createPluginSeparator());
     }
 
+    const toPluginItem = (item, id) => {
+      let icon = item.icon;
+      if (icon && icon.startsWith('ti-') && !icon.startsWith('ti ti-')) {
+        icon = 'ti ' + icon;
+      }
+
+      const pluginItem = createPluginMenuItem(item.id || id, item.text, {
+        icon: icon,
+        disabled: item.disabled || false
+      });
+      // 子菜单项
+      if (item.children && item.children.length > 0) {
+        pluginItem.children = item.children.map((child, childIndex) =>
+          toPluginItem(child, `${id}-${childIndex}`)
+        );
+      }
+      return pluginItem;
+    };
+
     options.items.forEach((item, index) => {
       if (item.type === 'separator') {
         menuItems.push(createPluginSeparator());
       } else {
-        let icon = item.icon;
-        if (icon && icon.startsWith('ti-') && !icon.startsWith('ti ti-')) {
-          icon = 'ti ' + icon;
-        }
-        
-        menuItems.push(createPluginMenuItem(item.id || `custom-${index}`, item.text, {
-          icon: icon,
-          disabled: item.disabled || false
-        }));
+        menuItems.push(toPluginItem(item, `custom-${index}`));
       }
     });
   }
@@ -317,16 +328,23 @@ export async function showContextMenu(event, options) {
     }
     // 处理自定义菜单项
     else if (options.items) {
-      // 查找匹配的自定义菜单项
-      let customItem = null;
-      for (let i = 0; i < options.items.length; i++) {
-        const item = options.items[i];
-        // 检查是否匹配
-        if ((item.id && item.id === result) || `custom-${i}` === result) {
-          customItem = item;
-          break;
+      // 查找匹配的自定义菜单项（包括子菜单项）
+      const findItem = (items, prefix) => {
+        for (let i = 0; i < items.length; i++) {
+          const item = items[i];
+          const id = `${prefix}-${i}`;
+          // 检查是否匹配
+          if ((item.id && item.id === result) || id === result) {
+            return item;
+          }
+          if (item.children) {
+            const child = findItem(item.children, id);
+            if (child) return child;
+          }
         }
-      }
+        return null;
+      };
+      const customItem = findItem(options.items, 'custom');
       
       if (customItem && customItem.onClick) {
         customItem.onClick();
@@ -1,5 +1,5 @@
 import { listen } from '@tauri-apps/api/event';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 import { LogicalPosition, LogicalSize } from '@tauri-apps/api/window';
 import {
   appWindow
@@ -13,7 +13,7 @@ export async function setupClipboardEventListener() {
   try {
     // 监听剪贴板新增项事件（增量更新）
     await listen('clipboard-item-added', async (event) => {
-      const { item, is_new } = event.payload;
+      const { item, is_new, translate } = event.payload;
       
       // 调用增量添加函数
       const { addClipboardItemIncremental } = await import('./clipboard.js');
@@ -32,9 +32,9 @@ export async function setupClipboardEventListener() {
           // 获取最新的剪贴板内容
           const clipboardText = await invoke('get_clipboard_text');
           if (clipboardText && clipboardText.trim()) {
-            // 动态导入AI翻译模块并执行复制时翻译
+            // 动态导入AI翻译模块并执行复制时翻译（记录规则要求翻译时不检查复制时翻译开关）
             const { translateAndInputOnCopy } = await import('./aiTranslation.js');
-            await translateAndInputOnCopy(clipboardText);
+            await translateAndInputOnCopy(clipboardText, translate ? 'rule' : 'copy');
           }
         } catch (error) {
           // 复制时翻译失败不应该影响正常的剪贴板功能
@@ -53,6 +53,11 @@ export async function setupClipboardEventListener() {
       addClipboardItemIncremental(item, false);
     });
 
+    // 置顶状态变化后位置随之改变，全量刷新
+    await listen('clipboard-item-pinned', async () => {
+      refreshClipboardHistory();
+    });
+
     // 全量刷新
     await listen('clipboard-changed', async () => {
       console.log('收到剪贴板变化通知（全量刷新）');
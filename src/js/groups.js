@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 import { setCurrentTab } from './config.js';
 import { showNotification } from './notificationManager.js';
 import { showConfirmModal, showAlertModal } from './ui.js';
@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 import { listen } from '@tauri-apps/api/event';
 import { togglePin } from './window.js';
 
@@ -1,4 +1,5 @@
-import { invoke, convertFileSrc } from '@tauri-apps/api/core';
+import { convertFileSrc } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 import { getCurrentSettings } from '../settings/js/settingsManager.js';
 import {
   quickTexts,
@@ -945,7 +946,7 @@ function showQuickTextContextMenu(event, text) {
 async function pinImageToScreen(text) {
   try {
     // 获取图片文件路径
-    const filePath = await window.__TAURI__.core.invoke('get_image_file_path', { 
+    const filePath = await invoke('get_image_file_path', { 
       content: text.content 
     });
     
@@ -955,7 +956,7 @@ async function pinImageToScreen(text) {
     }
     
     // 创建贴图窗口
-    await window.__TAURI__.core.invoke('pin_image_from_file', { 
+    await invoke('pin_image_from_file', { 
       filePath 
     });
     
@@ -1101,15 +1102,13 @@ window.addEventListener('format-mode-changed', (event) => {
 
 // 加载图片（用于虚拟列表的图片懒加载）
 export async function loadImageById(imgElement, imageId) {
-  try {
-    const filePath = await invoke('get_image_file_path', { content: `image:${imageId}` });
-    const assetUrl = convertFileSrc(filePath, 'asset');
-    imgElement.src = assetUrl;
-  } catch (error) {
-    console.error('加载图片失败:', error);
+  // 通过 qcimage 协议读取，加密的图片在内存中解密
+  imgElement.onerror = () => {
+    console.error('加载图片失败:', imageId);
     imgElement.alt = '图片加载失败';
     imgElement.style.backgroundColor = '#ffebee';
     imgElement.style.color = '#c62828';
     imgElement.textContent = '图片加载失败';
-  }
+  };
+  imgElement.src = convertFileSrc(imageId, 'qcimage');
 }
@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 import { listen } from '@tauri-apps/api/event';
 
 // 快捷键显示映射
@@ -116,6 +116,15 @@ export async function updateAllShortcutDisplays() {
     if (settings.numberShortcutsModifier) {
       updateShortcutDisplay('number-shortcut-display', settings.numberShortcutsModifier + '+1-9');
     }
+
+    // 数字快捷键置顶（未设置修饰键时隐藏）
+    const numberPinElement = document.getElementById('number-pin-shortcut-display');
+    if (numberPinElement) {
+      numberPinElement.style.display = settings.numberShortcutsPinModifier ? '' : 'none';
+      if (settings.numberShortcutsPinModifier) {
+        updateShortcutDisplay('number-pin-shortcut-display', settings.numberShortcutsPinModifier + '+1-9');
+      }
+    }
     
   } catch (error) {
     console.error('更新快捷键显示失败:', error);
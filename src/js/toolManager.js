@@ -1,5 +1,5 @@
 // 统一工具管理器 - 规范化工具按钮的注册、渲染和状态管理
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 import { setPasteWithFormat, getPasteWithFormat, setIsPinned, isPinned, setIsOneTimePaste, isOneTimePaste, setIsAiTranslationEnabled, isAiTranslationEnabled } from './config.js';
 import { openSettingsWindow } from './window.js';
 import { startScreenshot } from './screenshot.js';
@@ -1,6 +1,6 @@
 // 可展开工具面板模块
 import { setPasteWithFormat, getPasteWithFormat } from './config.js';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 
 let toolsPanelToggle = null;
 let toolsPanel = null;
@@ -49,11 +49,10 @@ export function processHTMLImages(htmlContent) {
 // 异步加载图片ID对应的图片
 export async function loadImageByIdForHTML(imgElement, imageId) {
   try {
-    const { invoke, convertFileSrc } = window.__TAURI__.core;
+    const { convertFileSrc } = window.__TAURI__.core;
     
-    // 使用文件路径
-    const filePath = await invoke('get_image_file_path', { content: `image:${imageId}` });
-    const assetUrl = convertFileSrc(filePath, 'asset');
+    // 通过 qcimage 协议读取，加密的图片在内存中解密
+    const assetUrl = convertFileSrc(imageId, 'qcimage');
     
     imgElement.src = assetUrl;
     imgElement.setAttribute('data-src', assetUrl);
@@ -0,0 +1,32 @@
+// 后端命令调用
+//
+// 后端命令返回的结构化错误 { code, key, message, context } 转换为 CommandError，
+// 拼接到字符串时显示 message，需要区分错误类别时判断 error.code
+
+import { invoke as tauriInvoke } from '@tauri-apps/api/core';
+
+export class CommandError extends Error {
+  constructor({ code, key, message, context }) {
+    super(message);
+    this.name = 'CommandError';
+    this.code = code;
+    this.key = key;
+    this.context = context || {};
+  }
+
+  toString() {
+    return this.message;
+  }
+}
+
+function isStructuredError(error) {
+  return error && typeof error === 'object' && typeof error.code === 'string' && typeof error.message === 'string';
+}
+
+export async function invoke(command, args, options) {
+  try {
+    return await tauriInvoke(command, args, options);
+  } catch (error) {
+    throw isStructuredError(error) ? new CommandError(error) : error;
+  }
+}
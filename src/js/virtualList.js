@@ -676,9 +676,9 @@ export class VirtualList {
   // 异步加载图片
   async loadImageById(imgElement, imageId) {
     try {
-      const { invoke, convertFileSrc } = await import('@tauri-apps/api/core');
-      const filePath = await invoke('get_image_file_path', { content: `image:${imageId}` });
-      const assetUrl = convertFileSrc(filePath, 'asset');
+      const { convertFileSrc } = await import('@tauri-apps/api/core');
+      // 通过 qcimage 协议读取，加密的图片在内存中解密
+      const assetUrl = convertFileSrc(imageId, 'qcimage');
 
       imgElement.setAttribute('data-src', assetUrl);
       imgElement.classList.remove('lazy'); 
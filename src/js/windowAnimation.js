@@ -1,7 +1,7 @@
 // ==================== 窗口动画模块 ====================
 // 负责处理窗口显示/隐藏的真实高度变化动画
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './utils/invoke.js';
 
 // 全局动画开关
 let animationEnabled = true;
@@ -19,7 +19,7 @@ import './js/fileIconUtils.js';
 import './js/utils/htmlProcessor.js';
 import { initNavigation, initShortcutsHelpPanel } from './js/navigation.js';
 import { initShortcutDisplay } from './js/shortcutDisplay.js';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './js/utils/invoke.js';
 import { listen } from '@tauri-apps/api/event';
 import {
   initDOMReferences,
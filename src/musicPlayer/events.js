@@ -1,7 +1,7 @@
 // 音频播放器事件处理
 import { playerState, setState } from './state.js';
 import { LIST_TYPES } from './constants.js';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 import { musicPanel, titleIcon, hidePanel, showPanel, highlightSelectedList, updateMusicItemFileStatus } from './ui.js';
 import { 
   togglePlayPause, 
@@ -1,6 +1,6 @@
 // 音乐文件夹管理模块
 import { open } from '@tauri-apps/plugin-dialog';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 import { FOLDERS_STORAGE_KEY } from './constants.js';
 import { isAudioFile, getFileName } from './audioExtractor.js';
 
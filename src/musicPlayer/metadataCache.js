@@ -1,5 +1,5 @@
 // 音频元数据缓存模块
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 
 // 元数据缓存
 const metadataCache = new Map();
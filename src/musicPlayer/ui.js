@@ -1,5 +1,5 @@
 // 音频播放器 UI 管理
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 import { playerState } from './state.js';
 import { escapeHtml } from './audioExtractor.js';
 
@@ -3,7 +3,7 @@
  * 处理贴图窗口的右键菜单
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 import { showContextMenuFromEvent, createMenuItem, createSeparator } from '../plugins/context_menu/index.js';
 import { getCurrentTheme, saveSettings, loadSettings } from './settings.js';
 
@@ -5,7 +5,8 @@
 
 import '@tabler/icons-webfont/dist/tabler-icons.min.css';
 import { getCurrentWindow } from '@tauri-apps/api/window';
-import { invoke, convertFileSrc } from '@tauri-apps/api/core';
+import { convertFileSrc } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 import { loadSettings, saveSettings } from './settings.js';
 import { createContextMenu } from './contextMenu.js';
 import { enterThumbnailMode, exitThumbnailMode } from './thumbnail.js';
@@ -99,7 +100,9 @@ import {
     try {
         const data = await invoke('get_pin_image_data');
         
-        if (data && data.file_path) {
+        if (data && data.image_id) {
+            img.src = convertFileSrc(data.image_id, 'qcimage');
+        } else if (data && data.file_path) {
             const assetUrl = convertFileSrc(data.file_path, 'asset');
             img.src = assetUrl;
         }
@@ -3,7 +3,7 @@
  * 负责保存和加载贴图窗口的设置
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 
 const STORAGE_KEY = 'pinImageSettings';
 
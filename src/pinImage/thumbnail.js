@@ -3,7 +3,7 @@
  * 处理窗口的缩略图模式切换
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 
 const THUMBNAIL_SIZE = 50;
 
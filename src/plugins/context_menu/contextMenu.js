@@ -5,7 +5,7 @@
 import '@tabler/icons-webfont/dist/tabler-icons.min.css';
 
 import { getCurrentWindow, LogicalSize } from '@tauri-apps/api/window';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../js/utils/invoke.js';
 document.addEventListener('contextmenu', event => event.preventDefault());
 const currentWindow = getCurrentWindow();
 const menuContainer = document.getElementById('menuContainer');
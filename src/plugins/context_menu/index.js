@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../js/utils/invoke.js';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 document.addEventListener('contextmenu', event => event.preventDefault());
 // 显示右键菜单
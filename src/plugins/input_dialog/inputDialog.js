@@ -3,7 +3,7 @@
  */
 
 import { getCurrentWindow } from '@tauri-apps/api/window';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../js/utils/invoke.js';
 document.addEventListener('contextmenu', event => event.preventDefault());
 const currentWindow = getCurrentWindow();
 const messageEl = document.getElementById('message');
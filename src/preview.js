@@ -1,7 +1,8 @@
 
 import '@tabler/icons-webfont/dist/tabler-icons.min.css';
 
-import { invoke, convertFileSrc } from '@tauri-apps/api/core';
+import { convertFileSrc } from '@tauri-apps/api/core';
+import { invoke } from './js/utils/invoke.js';
 import { listen } from '@tauri-apps/api/event';
 import { createFileIconElement } from './js/fileIconUtils.js';
 import { initDisableBrowserShortcuts } from './js/utils/disableBrowserShortcuts.js';
@@ -148,6 +149,18 @@ async function setupEventListeners() {
     }
   });
 
+  // 监听置顶状态变化（保持当前索引，由 preview-index-changed 定位到原项目）
+  await listen('clipboard-item-pinned', async () => {
+    if (currentDataSource.tab === 'clipboard') {
+      try {
+        clipboardHistory = (await invoke('get_clipboard_history')) || [];
+        renderPreviewItems();
+      } catch (error) {
+        console.error('获取剪贴板历史失败:', error);
+      }
+    }
+  });
+
   // 监听索引更新事件
   await listen('preview-index-changed', (event) => {
     const newIndex = event.payload.index;
@@ -453,21 +466,26 @@ function createPreviewItem(item, index, position = 'current') {
   previewItem.appendChild(typeIndicator);
   previewItem.appendChild(indexIndicator);
 
+  // 置顶标记（中键切换）
+  if (item.pinned) {
+    const pinIndicator = document.createElement('i');
+    pinIndicator.className = 'ti ti-pinned-filled pin-indicator';
+    previewItem.appendChild(pinIndicator);
+  }
+
   return previewItem;
 }
 
 
 // 根据图片ID加载图片
 async function loadImageById(imgElement, imageId) {
-  try {
-    const filePath = await invoke('get_image_file_path', { content: `image:${imageId}` });
-    const assetUrl = convertFileSrc(filePath, 'asset');
-    imgElement.src = assetUrl;
-  } catch (error) {
-    console.error('加载图片失败:', error);
+  // 通过 qcimage 协议读取，加密的图片在内存中解密
+  imgElement.onerror = () => {
+    console.error('加载图片失败:', imageId);
     imgElement.alt = '图片加载失败';
     imgElement.style.backgroundColor = '#333';
-  }
+  };
+  imgElement.src = convertFileSrc(imageId, 'qcimage');
 }
 
 // 处理滚动
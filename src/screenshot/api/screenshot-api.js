@@ -3,7 +3,7 @@
  * 负责与后端Tauri命令的通信
  */
 
-const { invoke } = window.__TAURI__.core;
+import { invoke } from '../../js/utils/invoke.js';
 
 export class ScreenshotAPI {
     /**
@@ -104,6 +104,13 @@ export class ScreenshotAPI {
         return await invoke('update_scrolling_panel_rect', { panel });
     }
 
+    /**
+     * 用截取的图片创建贴图窗口
+     */
+    static async createPinImageWindow(imageData, width, height, x, y) {
+        return await invoke('create_pin_image_window', { imageData, width, height, x, y });
+    }
+
     /**
      * 获取应用设置
      */
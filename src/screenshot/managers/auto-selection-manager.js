@@ -3,7 +3,7 @@
  * 使用 UI Automation API 实现智能元素识别
  */
 
-const { invoke } = window.__TAURI__.core;
+import { invoke } from '../../js/utils/invoke.js';
 const { listen } = window.__TAURI__.event;
 
 export class AutoSelectionManager {
@@ -35,7 +35,7 @@ import { ObjectTypeMapper } from './utils/object-type-mapper.js';
 import { SpecialToolHandler } from './utils/special-tool-handler.js';
 import { settingsManager } from './utils/settings-manager.js';
 
-const { invoke } = window.__TAURI__.core;
+import { invoke } from '../js/utils/invoke.js';
 const { listen } = window.__TAURI__.event;
 
 export class ScreenshotController {
@@ -2,7 +2,7 @@
  * 坐标和边界计算工具类
  * 提供各种坐标转换和边界计算的静态方法
  */
-const { invoke } = window.__TAURI__.core;
+import { invoke } from '../../js/utils/invoke.js';
 
 export class CoordinateUtils {
     /**
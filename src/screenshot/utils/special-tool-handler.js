@@ -122,13 +122,13 @@ export class SpecialToolHandler {
             const uint8Array = new Uint8Array(arrayBuffer);
             
             // 创建贴图窗口
-            await window.__TAURI__.core.invoke('create_pin_image_window', {
-                imageData: Array.from(uint8Array),
-                width: Math.round(selection.width),
-                height: Math.round(selection.height),
-                x: Math.round(selection.left),
-                y: Math.round(selection.top)
-            });
+            await ScreenshotAPI.createPinImageWindow(
+                Array.from(uint8Array),
+                Math.round(selection.width),
+                Math.round(selection.height),
+                Math.round(selection.left),
+                Math.round(selection.top)
+            );
             
             // 清除内容并关闭截屏窗口
             if (this.onClearAllContent) {
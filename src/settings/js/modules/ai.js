@@ -1,10 +1,10 @@
 /**
  * AI设置模块
  */
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../../js/utils/invoke.js';
 import { emit } from '@tauri-apps/api/event';
 import { showNotification } from '../../../js/notificationManager.js';
-import { getCurrentAIConfig, saveAIConfig } from '../../../js/aiConfig.js';
+import { getCurrentAIConfig, saveAIConfig, providerRequiresApiKey } from '../../../js/aiConfig.js';
 
 export class AIManager {
     constructor(settings, saveCallback) {
@@ -27,7 +27,7 @@ export class AIManager {
      * 绑定AI配置输入
      */
     bindAIConfigInputs() {
-        const inputs = ['ai-api-key', 'ai-model', 'ai-base-url'];
+        const inputs = ['ai-provider', 'ai-api-key', 'ai-model', 'ai-base-url'];
         inputs.forEach(id => {
             const element = document.getElementById(id);
             if (element) {
@@ -75,7 +75,7 @@ export class AIManager {
         // 页面加载时自动刷新
         setTimeout(() => {
             const aiConfig = getCurrentAIConfig();
-            if (aiConfig.apiKey && aiConfig.apiKey.trim() !== '') {
+            if (!providerRequiresApiKey(aiConfig.provider) || (aiConfig.apiKey && aiConfig.apiKey.trim() !== '')) {
                 this.refreshModelsList(true);
             }
         }, 1000);
@@ -95,7 +95,7 @@ export class AIManager {
             refreshBtn.innerHTML = '<i class="ti ti-loader ti-spin"></i>';
 
             const aiConfig = getCurrentAIConfig();
-            if (!aiConfig.apiKey || !aiConfig.baseUrl) {
+            if ((providerRequiresApiKey(aiConfig.provider) && !aiConfig.apiKey) || !aiConfig.baseUrl) {
                 throw new Error('请先设置API密钥和API地址');
             }
 
@@ -245,14 +245,7 @@ export class AIManager {
             checkbox.addEventListener('change', async (e) => {
                 try {
                     if (e.target.checked) {
-                        const aiConfig = getCurrentAIConfig();
-                        if (!aiConfig.apiKey || aiConfig.apiKey.trim() === '') {
-                            e.target.checked = false;
-                            showNotification('请先配置AI API密钥后再启用AI翻译功能', 'warning');
-                            document.getElementById('ai-config-section')?.scrollIntoView({ behavior: 'smooth' });
-                            return;
-                        }
-
+                        // 由后端按所选翻译后端检查（离线翻译不需要AI密钥）
                         const isConfigValid = await invoke('check_ai_translation_config');
                         if (!isConfigValid) {
                             e.target.checked = false;
@@ -1,7 +1,7 @@
 /**
  * 数据管理模块
  */
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../../js/utils/invoke.js';
 import { confirm } from '@tauri-apps/plugin-dialog';
 import { showNotification } from '../../../js/notificationManager.js';
 import { showLoading, hideLoading } from './loadingManager.js';
@@ -1,7 +1,7 @@
 /**
  * 音效设置模块
  */
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../../js/utils/invoke.js';
 import { showNotification } from '../../../js/notificationManager.js';
 
 export class SoundManager {
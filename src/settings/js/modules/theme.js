@@ -1,7 +1,7 @@
 /**
  * 主题设置模块
  */
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../../js/utils/invoke.js';
 import { applyBackgroundImage } from '../../../js/backgroundManager.js';
 import { setTheme } from '../../../js/themeManager.js';
 import { showNotification } from '../../../js/notificationManager.js';
@@ -4,7 +4,8 @@
 
 import '@tabler/icons-webfont/dist/tabler-icons.min.css';
 
-import { invoke, convertFileSrc } from '@tauri-apps/api/core';
+import { convertFileSrc } from '@tauri-apps/api/core';
+import { invoke } from '../../js/utils/invoke.js';
 import { getDominantColor, generateTitleBarColors, applyTitleBarColors, removeTitleBarColors } from '../../js/colorAnalyzer.js';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { emit, listen } from '@tauri-apps/api/event';
@@ -36,11 +37,13 @@ const defaultSettings = {
   showStartupNotification: true,
   historyLimit: 100,
   theme: 'light',
+  language: 'zh-CN',
   opacity: 0.9,
   backgroundImagePath: '',
   toggleShortcut: 'Win+V',
   numberShortcuts: true,
   numberShortcutsModifier: 'Ctrl',
+  numberShortcutsPinModifier: 'Ctrl+Shift',
   clipboardMonitor: true,
   ignoreDuplicates: true,
   saveImages: true,
@@ -79,6 +82,7 @@ const defaultSettings = {
   aiApiKey: '',
   aiModel: 'Qwen/Qwen2-7B-Instruct',
   aiBaseUrl: 'https://api.siliconflow.cn/v1',
+  aiProvider: 'openai',
   aiTargetLanguage: 'auto',
   aiTranslateOnCopy: false,
   aiTranslateOnPaste: true,
@@ -86,6 +90,14 @@ const defaultSettings = {
   aiInputSpeed: 50,
   aiNewlineMode: 'auto',
   aiOutputMode: 'stream',
+  aiMaxRetries: 3,
+  aiMaxConcurrentRequests: 2,
+  aiTokenBudgetPerMinute: 0,
+  translationBackend: 'ai',
+  offlineDictionaryPath: '',
+  offlineModelCommand: '',
+  translationMemoryEnabled: true,
+  translationMemoryPrefill: false,
   mouseMiddleButtonEnabled: true,
   mouseMiddleButtonModifier: 'None',
   windowPositionMode: 'smart',
@@ -99,9 +111,12 @@ const defaultSettings = {
   appFilterEnabled: false,
   appFilterMode: 'blacklist',
   appFilterList: [],
+  captureRules: [],
   imageDataPriorityApps: [],
   clipboardAnimationEnabled: true,
   pasteWithFormat: true,
+  pasteQueueOrder: 'fifo',
+  pasteQueueSeparator: '\n',
   sidebarHoverDelay: 0.5
 };
 
@@ -195,10 +210,12 @@ async function initializeUI() {
   setInputValue('start-hidden', settings.startHidden);
   setInputValue('run-as-admin', settings.runAsAdmin);
   setInputValue('show-startup-notification', settings.showStartupNotification);
+  setInputValue('language', settings.language || 'zh-CN');
   setInputValue('history-limit', settings.historyLimit);
   setInputValue('toggle-shortcut', settings.toggleShortcut || 'Win+V');
   setInputValue('number-shortcuts', settings.numberShortcuts);
   setInputValue('number-shortcuts-modifier', settings.numberShortcutsModifier || 'Ctrl');
+  setInputValue('number-shortcuts-pin-modifier', settings.numberShortcutsPinModifier ?? 'Ctrl+Shift');
   setInputValue('clipboard-monitor', settings.clipboardMonitor);
   setInputValue('ignore-duplicates', settings.ignoreDuplicates);
   setInputValue('save-images', settings.saveImages);
@@ -243,6 +260,7 @@ async function initializeUI() {
 
   // AI配置
   const aiConfig = getCurrentAIConfig();
+  setInputValue('ai-provider', aiConfig.provider);
   setInputValue('ai-api-key', aiConfig.apiKey);
   setInputValue('ai-model', aiConfig.model);
   setInputValue('ai-base-url', aiConfig.baseUrl);
@@ -256,6 +274,14 @@ async function initializeUI() {
   setInputValue('ai-input-speed', settings.aiInputSpeed);
   setInputValue('ai-newline-mode', settings.aiNewlineMode);
   setInputValue('ai-output-mode', settings.aiOutputMode);
+  setInputValue('ai-max-retries', settings.aiMaxRetries ?? 3);
+  setInputValue('ai-max-concurrent-requests', settings.aiMaxConcurrentRequests ?? 2);
+  setInputValue('ai-token-budget-per-minute', settings.aiTokenBudgetPerMinute ?? 0);
+  setInputValue('translation-backend', settings.translationBackend || 'ai');
+  setInputValue('offline-dictionary-path', settings.offlineDictionaryPath || '');
+  setInputValue('offline-model-command', settings.offlineModelCommand || '');
+  setInputValue('translation-memory-enabled', settings.translationMemoryEnabled !== false);
+  setInputValue('translation-memory-prefill', settings.translationMemoryPrefill === true);
 
   // 鼠标设置
   setInputValue('mouse-middle-button-enabled', settings.mouseMiddleButtonEnabled);
@@ -273,6 +299,8 @@ async function initializeUI() {
   setInputValue('edge-hide-offset', settings.edgeHideOffset !== undefined ? settings.edgeHideOffset : 3);
   setInputValue('auto-focus-search', settings.autoFocusSearch !== undefined ? settings.autoFocusSearch : false);
   setInputValue('sidebar-hover-delay', settings.sidebarHoverDelay !== undefined ? settings.sidebarHoverDelay : 0.5);
+  setInputValue('paste-queue-order', settings.pasteQueueOrder || 'fifo');
+  setInputValue('paste-queue-separator', escapeSeparator(settings.pasteQueueSeparator ?? '\n'));
 
   // 应用过滤设置
   setInputValue('app-filter-enabled', settings.appFilterEnabled || false);
@@ -344,6 +372,15 @@ async function initializeUI() {
 }
 
 // =================== 辅助函数 ===================
+// 分隔符中的换行和制表符在输入框中以 \n、\t 显示
+function escapeSeparator(value) {
+  return value.replace(/\\/g, '\\\\').replace(/\n/g, '\\n').replace(/\t/g, '\\t');
+}
+
+function unescapeSeparator(value) {
+  return value.replace(/\\([\\nt])/g, (_, ch) => (ch === 'n' ? '\n' : ch === 't' ? '\t' : '\\'));
+}
+
 function setInputValue(id, value) {
   const element = document.getElementById(id);
   if (!element) return;
@@ -466,8 +503,8 @@ async function closeSettingsWindow() {
 
 function bindBasicSettingEvents() {
   const settingInputs = [
-    'auto-start', 'start-hidden', 'show-startup-notification', 'history-limit',
-    'number-shortcuts', 'number-shortcuts-modifier', 'clipboard-monitor',
+    'auto-start', 'start-hidden', 'show-startup-notification', 'language', 'history-limit',
+    'number-shortcuts', 'number-shortcuts-modifier', 'number-shortcuts-pin-modifier', 'clipboard-monitor',
     'ignore-duplicates', 'save-images', 'show-image-preview',
     'sound-enabled', 'copy-sound-path', 'paste-sound-path',
     'preview-enabled', 'preview-shortcut', 'preview-items-count', 'preview-auto-paste',
@@ -477,9 +514,13 @@ function bindBasicSettingEvents() {
     'screenshot-magnifier-enabled', 'screenshot-hints-enabled', 'screenshot-color-include-format',
     'ai-target-language', 'ai-translate-on-copy', 'ai-translate-on-paste',
     'ai-translation-prompt', 'ai-input-speed', 'ai-newline-mode', 'ai-output-mode',
+    'ai-max-retries', 'ai-max-concurrent-requests', 'ai-token-budget-per-minute',
+    'translation-backend', 'offline-dictionary-path', 'offline-model-command',
+    'translation-memory-enabled', 'translation-memory-prefill',
     'mouse-middle-button-enabled', 'mouse-middle-button-modifier', 'clipboard-animation-enabled',
     'window-position-mode', 'remember-window-size', 'auto-scroll-to-top-on-show',
     'title-bar-position', 'edge-hide-enabled', 'edge-hide-offset', 'auto-focus-search', 'sidebar-hover-delay',
+    'paste-queue-order', 'paste-queue-separator',
     'image-data-priority-apps'
   ];
 
@@ -507,6 +548,8 @@ function bindBasicSettingEvents() {
             .filter(Boolean)
             .map(line => line.toLowerCase());
           settings.imageDataPriorityApps = lines;
+        } else if (id === 'paste-queue-separator') {
+          settings.pasteQueueSeparator = unescapeSeparator(element.value);
         } else {
           settings[key] = element.value;
         }
@@ -1,5 +1,5 @@
 // 设置管理器 - 主窗口的设置状态同步和应用
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../js/utils/invoke.js';
 import { listen } from '@tauri-apps/api/event';
 import { refreshClipboardHistory } from '../../js/clipboard.js';
 import { setPasteWithFormat } from '../../js/config.js';
@@ -1,7 +1,7 @@
 
 import '@tabler/icons-webfont/dist/tabler-icons.min.css';
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './js/utils/invoke.js';
 import { listen } from '@tauri-apps/api/event';
 import { Window } from '@tauri-apps/api/window';
 import { showNotification } from './js/notificationManager.js';
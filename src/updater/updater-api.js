@@ -2,7 +2,7 @@
  * 更新器 API 模块
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 import { fetch as tauriFetch } from '@tauri-apps/plugin-http';
 
 export const REPO_URL = 'https://github.com/mosheng1/UpdateTes';
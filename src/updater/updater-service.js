@@ -2,7 +2,7 @@
  * 更新检测服务
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../js/utils/invoke.js';
 import { fetchLatestRelease, getCurrentVersion, checkPortableMode } from './updater-api.js';
 import { compareVersions } from './updater-utils.js';
 import { showNotification } from '../js/notificationManager.js';